@@ -6,10 +6,13 @@ use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
+use crate::config::{ServerConfig, CONFIGURATION_SECTION};
 use crate::handlers::{
-    code_action, completion, document_highlight, document_symbol, document_sync, folding_range,
-    formatting, goto_definition, goto_implementation, goto_type_definition, hover, references,
-    rename, signature_help, workspace_symbol,
+    call_hierarchy, code_action, code_lens, completion, diagnostics, document_highlight,
+    document_link, document_symbol, document_sync, execute_command, folding_range, formatting,
+    goto_definition, goto_implementation, goto_type_definition, hover, linked_editing_range,
+    pull_diagnostics, references, rename, selection_range, signature_help, type_hierarchy,
+    workspace_symbol,
 };
 use crate::state::WorldState;
 
@@ -19,6 +22,40 @@ pub struct AlBackend {
 }
 
 impl AlBackend {
+    /// Request the `alLsp` settings section from the client. Settings it
+    /// returns replace those from `initializationOptions`; open documents are
+    /// re-diagnosed when anything changed.
+    async fn pull_configuration(&self) {
+        let item = ConfigurationItem {
+            scope_uri: None,
+            section: Some(CONFIGURATION_SECTION.to_string()),
+        };
+        match self.client.configuration(vec![item]).await {
+            Ok(values) => {
+                if self.state.apply_configuration_response(&values) {
+                    diagnostics::republish_open_diagnostics(&self.client, &self.state).await;
+                }
+            }
+            Err(e) => tracing::warn!("workspace/configuration request failed: {}", e),
+        }
+    }
+
+    /// Register the type hierarchy requests for AL documents. lsp-types has
+    /// no `typeHierarchyProvider` server capability, so it cannot be
+    /// advertised from `initialize`.
+    async fn register_type_hierarchy(&self) {
+        let registration = Registration {
+            id: "al-lsp-type-hierarchy".to_string(),
+            method: "textDocument/prepareTypeHierarchy".to_string(),
+            register_options: Some(serde_json::json!({
+                "documentSelector": [{ "language": "al" }],
+            })),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            tracing::warn!("type hierarchy registration failed: {}", e);
+        }
+    }
+
     fn schedule_workspace_index(&self, reason: &'static str) {
         self.state
             .workspace_index_pending
@@ -82,6 +119,10 @@ impl AlBackend {
                 tracing::info!("{}", msg);
                 client.log_message(MessageType::INFO, &msg).await;
 
+                // Cross-document diagnostics (e.g. unimplemented interface
+                // methods) may change now that more objects are known
+                diagnostics::republish_open_diagnostics(&client, &state).await;
+
                 if !state.workspace_index_pending.load(Ordering::Acquire) {
                     break;
                 }
@@ -137,6 +178,47 @@ impl LanguageServer for AlBackend {
 
         *self.state.workspace_roots.lock().unwrap() = roots;
 
+        if let Some(options) = &params.initialization_options {
+            *self.state.config.lock().unwrap() = ServerConfig::from_json(options);
+        }
+        let configuration_pull = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.configuration)
+            .unwrap_or(false);
+        self.state
+            .configuration_pull
+            .store(configuration_pull, Ordering::Release);
+        let pull_diagnostics = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .is_some_and(|text_document| text_document.diagnostic.is_some());
+        self.state
+            .pull_diagnostics
+            .store(pull_diagnostics, Ordering::Release);
+        let type_hierarchy_registration = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.type_hierarchy)
+            .and_then(|type_hierarchy| type_hierarchy.dynamic_registration)
+            .unwrap_or(false);
+        self.state
+            .type_hierarchy_registration
+            .store(type_hierarchy_registration, Ordering::Release);
+        let resource_rename = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.workspace_edit.as_ref())
+            .and_then(|workspace_edit| workspace_edit.resource_operations.as_ref())
+            .is_some_and(|operations| operations.contains(&ResourceOperationKind::Rename));
+        self.state
+            .resource_rename
+            .store(resource_rename, Ordering::Release);
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
@@ -148,6 +230,9 @@ impl LanguageServer for AlBackend {
                 document_symbol_provider: Some(OneOf::Left(true)),
                 references_provider: Some(OneOf::Left(true)),
                 document_highlight_provider: Some(OneOf::Left(true)),
+                linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(
+                    true,
+                )),
                 rename_provider: Some(OneOf::Right(RenameOptions {
                     prepare_provider: Some(true),
                     work_done_progress_options: WorkDoneProgressOptions {
@@ -170,11 +255,40 @@ impl LanguageServer for AlBackend {
                     },
                 }),
                 type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
                 implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                    DiagnosticOptions {
+                        identifier: Some("al-lsp".to_string()),
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: false,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                document_link_provider: Some(DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
                 folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
                 code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                code_lens_provider: Some(CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: execute_command::supported_commands(),
+                    work_done_progress_options: Default::default(),
+                }),
                 document_formatting_provider: Some(OneOf::Left(true)),
-                workspace_symbol_provider: Some(OneOf::Left(true)),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                    first_trigger_character: "\n".to_string(),
+                    more_trigger_character: Some(vec![";".to_string(), "d".to_string()]),
+                }),
+                workspace_symbol_provider: Some(OneOf::Right(WorkspaceSymbolOptions {
+                    work_done_progress_options: Default::default(),
+                    resolve_provider: Some(true),
+                })),
                 workspace: Some(WorkspaceServerCapabilities {
                     workspace_folders: Some(WorkspaceFoldersServerCapabilities {
                         supported: Some(true),
@@ -209,6 +323,16 @@ impl LanguageServer for AlBackend {
                     .await;
             }
         }
+        if self.state.configuration_pull.load(Ordering::Acquire) {
+            self.pull_configuration().await;
+        }
+        if self
+            .state
+            .type_hierarchy_registration
+            .load(Ordering::Acquire)
+        {
+            self.register_type_hierarchy().await;
+        }
         self.schedule_workspace_index("startup");
     }
 
@@ -235,11 +359,19 @@ impl LanguageServer for AlBackend {
             let uri = &change.uri;
             match change.typ {
                 FileChangeType::CREATED | FileChangeType::CHANGED => {
+                    if uri.path().ends_with("/app.json") {
+                        self.state.load_app_manifests();
+                        continue;
+                    }
                     // Reload from disk (updates existing or loads new file)
                     self.state.reload_file_from_disk(uri);
                 }
                 FileChangeType::DELETED => {
-                    self.state.documents.remove(uri);
+                    self.state.remove_document_symbols(uri);
+                    // A deleted directory takes all of its documents with it.
+                    if let Ok(path) = uri.to_file_path() {
+                        self.state.remove_documents_under(&path);
+                    }
                 }
                 _ => {}
             }
@@ -247,6 +379,7 @@ impl LanguageServer for AlBackend {
     }
 
     async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        let mut removed_paths = Vec::new();
         {
             let mut roots = self.state.workspace_roots.lock().unwrap();
 
@@ -254,6 +387,7 @@ impl LanguageServer for AlBackend {
             for removed in &params.event.removed {
                 if let Ok(path) = removed.uri.to_file_path() {
                     roots.retain(|r| r != &path);
+                    removed_paths.push(path);
                 }
             }
 
@@ -267,6 +401,12 @@ impl LanguageServer for AlBackend {
             }
         }
 
+        // Purge documents and index entries from removed folders
+        for path in &removed_paths {
+            let count = self.state.remove_documents_under(path);
+            tracing::info!("removed {} documents under {}", count, path.display());
+        }
+
         self.schedule_workspace_index("workspace folder change");
     }
 
@@ -309,6 +449,16 @@ impl LanguageServer for AlBackend {
         ))
     }
 
+    async fn linked_editing_range(
+        &self,
+        params: LinkedEditingRangeParams,
+    ) -> Result<Option<LinkedEditingRanges>> {
+        Ok(linked_editing_range::handle_linked_editing_range(
+            &self.state,
+            params,
+        ))
+    }
+
     async fn prepare_rename(
         &self,
         params: TextDocumentPositionParams,
@@ -348,14 +498,122 @@ impl LanguageServer for AlBackend {
         ))
     }
 
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        Ok(pull_diagnostics::handle_document_diagnostic(
+            &self.state,
+            params,
+        ))
+    }
+
+    async fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(type_hierarchy::handle_prepare_type_hierarchy(
+            &self.state,
+            params,
+        ))
+    }
+
+    async fn supertypes(
+        &self,
+        params: TypeHierarchySupertypesParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(type_hierarchy::handle_supertypes(&self.state, params))
+    }
+
+    async fn subtypes(
+        &self,
+        params: TypeHierarchySubtypesParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        Ok(type_hierarchy::handle_subtypes(&self.state, params))
+    }
+
+    async fn document_link(&self, params: DocumentLinkParams) -> Result<Option<Vec<DocumentLink>>> {
+        Ok(document_link::handle_document_link(&self.state, params))
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        Ok(call_hierarchy::handle_prepare_call_hierarchy(
+            &self.state,
+            params,
+        ))
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        Ok(call_hierarchy::handle_incoming_calls(&self.state, params))
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        Ok(call_hierarchy::handle_outgoing_calls(&self.state, params))
+    }
+
     async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
         Ok(folding_range::handle_folding_range(&self.state, params))
     }
 
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        Ok(selection_range::handle_selection_range(&self.state, params))
+    }
+
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         Ok(formatting::handle_formatting(&self.state, params))
     }
 
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        Ok(formatting::handle_range_formatting(&self.state, params))
+    }
+
+    async fn on_type_formatting(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        Ok(formatting::handle_on_type_formatting(&self.state, params))
+    }
+
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        let command = params.command.clone();
+        match execute_command::handle_execute_command(&self.state, params) {
+            Some(execute_command::CommandOutcome::Edit(edit)) => {
+                if let Err(err) = self.client.apply_edit(edit).await {
+                    tracing::warn!("failed to apply edit for {}: {}", command, err);
+                }
+                Ok(None)
+            }
+            Some(execute_command::CommandOutcome::Value(value)) => Ok(Some(value)),
+            None => Ok(None),
+        }
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        Ok(code_lens::handle_code_lens(&self.state, params))
+    }
+
+    async fn code_lens_resolve(&self, params: CodeLens) -> Result<CodeLens> {
+        Ok(code_lens::handle_code_lens_resolve(&self.state, params))
+    }
+
     async fn code_action(
         &self,
         params: CodeActionParams,
@@ -367,7 +625,16 @@ impl LanguageServer for AlBackend {
         &self,
         params: WorkspaceSymbolParams,
     ) -> Result<Option<Vec<SymbolInformation>>> {
-        Ok(workspace_symbol::handle_workspace_symbol(
+        // tower-lsp 0.20 types `workspace/symbol` as `SymbolInformation[]`, so
+        // locations are resolved here rather than by the client.
+        Ok(
+            workspace_symbol::handle_workspace_symbol(&self.state, params)
+                .map(|response| workspace_symbol::into_symbol_information(&self.state, response)),
+        )
+    }
+
+    async fn symbol_resolve(&self, params: WorkspaceSymbol) -> Result<WorkspaceSymbol> {
+        Ok(workspace_symbol::handle_workspace_symbol_resolve(
             &self.state,
             params,
         ))
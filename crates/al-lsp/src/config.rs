@@ -0,0 +1,134 @@
+use al_syntax::formatting::{BeginStyle, FormatOptions};
+
+/// Settings section requested through `workspace/configuration`; the VS Code
+/// extension contributes its settings under this prefix (`alLsp.*`).
+pub const CONFIGURATION_SECTION: &str = "alLsp";
+
+/// Server settings supplied by the client through `initializationOptions`
+/// or pulled with `workspace/configuration`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// Publish `WARNING` diagnostics as `ERROR` so strict CI gates fail on them.
+    pub treat_warnings_as_errors: bool,
+    /// Lowercase keywords when formatting documents.
+    pub normalize_keyword_case: bool,
+    /// Where the formatter puts `begin` after `then`, `else` and `do`.
+    pub begin_style: BeginStyle,
+    /// Keep one blank line between a `var` section and `begin` when formatting.
+    pub blank_line_before_begin: bool,
+    /// Longest run of blank lines the formatter keeps.
+    pub max_blank_lines: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            treat_warnings_as_errors: false,
+            normalize_keyword_case: false,
+            begin_style: BeginStyle::default(),
+            blank_line_before_begin: false,
+            max_blank_lines: FormatOptions::default().max_blank_lines,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Read settings from a JSON object. Keys may be camelCase (as sent by the
+    /// VS Code extension) or snake_case; unknown keys are ignored.
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        ServerConfig::default().merge_json(value)
+    }
+
+    /// Settings from `value`, keeping the current value of any key it omits.
+    pub fn merge_json(&self, value: &serde_json::Value) -> Self {
+        let flag = |camel: &str, snake: &str| {
+            value
+                .get(camel)
+                .or_else(|| value.get(snake))
+                .and_then(serde_json::Value::as_bool)
+        };
+        let begin_style = value
+            .get("beginStyle")
+            .or_else(|| value.get("begin_style"))
+            .and_then(serde_json::Value::as_str)
+            .and_then(|style| match style {
+                "sameLine" | "same_line" => Some(BeginStyle::SameLine),
+                "nextLine" | "next_line" => Some(BeginStyle::NextLine),
+                _ => None,
+            });
+        let max_blank_lines = value
+            .get("maxBlankLines")
+            .or_else(|| value.get("max_blank_lines"))
+            .and_then(serde_json::Value::as_u64)
+            .map(|lines| lines as usize);
+
+        ServerConfig {
+            treat_warnings_as_errors: flag("treatWarningsAsErrors", "treat_warnings_as_errors")
+                .unwrap_or(self.treat_warnings_as_errors),
+            normalize_keyword_case: flag("normalizeKeywordCase", "normalize_keyword_case")
+                .unwrap_or(self.normalize_keyword_case),
+            begin_style: begin_style.unwrap_or(self.begin_style),
+            blank_line_before_begin: flag("blankLineBeforeBegin", "blank_line_before_begin")
+                .unwrap_or(self.blank_line_before_begin),
+            max_blank_lines: max_blank_lines.unwrap_or(self.max_blank_lines),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_reads_camel_and_snake_case() {
+        let camel = serde_json::json!({ "treatWarningsAsErrors": true });
+        assert!(ServerConfig::from_json(&camel).treat_warnings_as_errors);
+
+        let snake = serde_json::json!({ "treat_warnings_as_errors": true });
+        assert!(ServerConfig::from_json(&snake).treat_warnings_as_errors);
+
+        let empty = serde_json::json!({});
+        assert_eq!(ServerConfig::from_json(&empty), ServerConfig::default());
+    }
+
+    #[test]
+    fn test_merge_json_keeps_omitted_keys() {
+        let base = ServerConfig {
+            treat_warnings_as_errors: true,
+            normalize_keyword_case: true,
+            begin_style: BeginStyle::NextLine,
+            blank_line_before_begin: true,
+            max_blank_lines: 3,
+        };
+        assert_eq!(base.merge_json(&serde_json::json!({})), base);
+        let off = serde_json::json!({ "treatWarningsAsErrors": false });
+        assert!(!base.merge_json(&off).treat_warnings_as_errors);
+        assert!(base.merge_json(&off).normalize_keyword_case);
+        assert!(base.merge_json(&off).blank_line_before_begin);
+        assert_eq!(base.merge_json(&off).begin_style, BeginStyle::NextLine);
+        assert_eq!(base.merge_json(&off).max_blank_lines, 3);
+    }
+
+    #[test]
+    fn test_begin_style_reads_known_values() {
+        let next = serde_json::json!({ "beginStyle": "nextLine" });
+        assert_eq!(
+            ServerConfig::from_json(&next).begin_style,
+            BeginStyle::NextLine
+        );
+        let unknown = serde_json::json!({ "beginStyle": "hanging" });
+        assert_eq!(
+            ServerConfig::from_json(&unknown).begin_style,
+            BeginStyle::SameLine
+        );
+    }
+
+    #[test]
+    fn test_max_blank_lines_defaults_to_formatter_default() {
+        assert_eq!(ServerConfig::default().max_blank_lines, 1);
+        let two = serde_json::json!({ "maxBlankLines": 2 });
+        assert_eq!(ServerConfig::from_json(&two).max_blank_lines, 2);
+        let negative = serde_json::json!({ "max_blank_lines": -1 });
+        assert_eq!(ServerConfig::from_json(&negative).max_blank_lines, 1);
+    }
+}
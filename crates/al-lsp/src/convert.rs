@@ -61,8 +61,10 @@ pub fn lsp_position_to_byte_offset(rope: &ropey::Rope, pos: Position) -> Option<
     let line_start = rope.line_to_byte(line);
     let line_slice = rope.line(line);
     let line_text = line_slice.to_string();
+    // Columns past the end of the line stop before its terminator
+    let line_text = line_text.trim_end_matches(['\n', '\r']);
     let utf16_col = pos.character as usize;
-    let line_byte = line_byte_for_utf16_col(&line_text, utf16_col);
+    let line_byte = line_byte_for_utf16_col(line_text, utf16_col);
     Some(line_start + line_byte)
 }
 
@@ -112,6 +114,19 @@ mod tests {
         assert_eq!(&text[b1..b2], "😊");
     }
 
+    #[test]
+    fn test_lsp_position_to_byte_offset_crlf() {
+        let text = "codeunit 50100 Test\r\n{\r\n    x := 1;\r\n}\r\n";
+        let rope = ropey::Rope::from_str(text);
+        let offset = |line, character| {
+            lsp_position_to_byte_offset(&rope, Position { line, character }).unwrap()
+        };
+        assert_eq!(&text[offset(2, 4)..offset(2, 11)], "x := 1;");
+        assert_eq!(offset(3, 0), text.find('}').unwrap());
+        // Past the end of a line: before `\r\n`, not inside it
+        assert_eq!(offset(2, 40), text.find(";\r\n").unwrap() + 1);
+    }
+
     #[test]
     fn test_ts_point_to_lsp_position_utf16() {
         let text = "a😊b\n";
@@ -0,0 +1,426 @@
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Location, Position, Range, ReferenceContext,
+    ReferenceParams, TextDocumentIdentifier, TextDocumentPositionParams, Url,
+};
+use tree_sitter::Node;
+
+use al_syntax::ast::{AlSymbol, AlSymbolKind};
+
+use crate::convert::ts_range_to_lsp_range;
+use crate::handlers::goto_definition::handle_goto_definition;
+use crate::handlers::references::handle_references;
+use crate::state::WorldState;
+
+/// Call hierarchy item for the procedure or trigger at the cursor: its
+/// declaration, or the procedure a call at the cursor resolves to.
+pub fn handle_prepare_call_hierarchy(
+    state: &WorldState,
+    params: CallHierarchyPrepareParams,
+) -> Option<Vec<CallHierarchyItem>> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let declared = {
+        let doc = state.documents.get(&uri)?;
+        find_callable(doc.symbols(), &|symbol| {
+            range_contains(
+                ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point),
+                position,
+            )
+        })
+        .map(|symbol| callable_item(&uri, symbol))
+    };
+    let item = declared.or_else(|| resolve_call(state, &uri, position))?;
+    Some(vec![item])
+}
+
+/// Procedures and triggers containing a call to the item's procedure, with
+/// the call sites grouped per caller. References that are not calls, such as
+/// the interface method an implementation refers to, are left out.
+pub fn handle_incoming_calls(
+    state: &WorldState,
+    params: CallHierarchyIncomingCallsParams,
+) -> Option<Vec<CallHierarchyIncomingCall>> {
+    let item = params.item;
+    let locations = handle_references(
+        state,
+        ReferenceParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: item.uri.clone(),
+                },
+                position: item.selection_range.start,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: ReferenceContext {
+                include_declaration: false,
+            },
+        },
+    )?;
+
+    let mut calls: Vec<CallHierarchyIncomingCall> = Vec::new();
+    for location in locations {
+        let Some(caller) = calling_item(state, &location) else {
+            continue;
+        };
+        match calls
+            .iter_mut()
+            .find(|call| call.from.uri == caller.uri && call.from.range == caller.range)
+        {
+            Some(call) => call.from_ranges.push(location.range),
+            None => calls.push(CallHierarchyIncomingCall {
+                from: caller,
+                from_ranges: vec![location.range],
+            }),
+        }
+    }
+    non_empty(calls)
+}
+
+/// Procedures called from the body of the item's procedure or trigger, with
+/// the call sites grouped per callee.
+pub fn handle_outgoing_calls(
+    state: &WorldState,
+    params: CallHierarchyOutgoingCallsParams,
+) -> Option<Vec<CallHierarchyOutgoingCall>> {
+    let item = params.item;
+    let call_names = {
+        let doc = state.documents.get(&item.uri)?;
+        let symbol = find_callable(doc.symbols(), &|symbol| {
+            ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point)
+                == item.selection_range
+        })?;
+        let node = doc
+            .tree()
+            .root_node()
+            .descendant_for_byte_range(symbol.start_byte, symbol.end_byte)?;
+        let mut names = Vec::new();
+        collect_call_names(node, &mut names);
+        names
+    };
+
+    let mut calls: Vec<CallHierarchyOutgoingCall> = Vec::new();
+    for range in call_names {
+        let Some(callee) = resolve_call(state, &item.uri, range.start) else {
+            continue;
+        };
+        match calls
+            .iter_mut()
+            .find(|call| call.to.uri == callee.uri && call.to.range == callee.range)
+        {
+            Some(call) => call.from_ranges.push(range),
+            None => calls.push(CallHierarchyOutgoingCall {
+                to: callee,
+                from_ranges: vec![range],
+            }),
+        }
+    }
+    non_empty(calls)
+}
+
+/// Names of the `function_call` and `method_call` nodes under `node`, in
+/// source order.
+fn collect_call_names(node: Node, names: &mut Vec<Range>) {
+    let name_field = match node.kind() {
+        "function_call" => Some("function"),
+        "method_call" => Some("method"),
+        _ => None,
+    };
+    if let Some(name) = name_field.and_then(|field| node.child_by_field_name(field)) {
+        names.push(ts_range_to_lsp_range(
+            name.start_position(),
+            name.end_position(),
+        ));
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_call_names(child, names);
+    }
+}
+
+/// Item for the procedure or trigger that goto-definition at `position`
+/// lands on, if any.
+fn resolve_call(state: &WorldState, uri: &Url, position: Position) -> Option<CallHierarchyItem> {
+    let response = handle_goto_definition(
+        state,
+        GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        },
+    )?;
+    let target = match response {
+        GotoDefinitionResponse::Scalar(location) => location,
+        GotoDefinitionResponse::Array(locations) => locations.into_iter().next()?,
+        GotoDefinitionResponse::Link(_) => return None,
+    };
+
+    let doc = state.documents.get(&target.uri)?;
+    let symbol = find_callable(doc.symbols(), &|symbol| {
+        range_contains(
+            ts_range_to_lsp_range(symbol.start_point, symbol.end_point),
+            target.range.start,
+        )
+    })?;
+    Some(callable_item(&target.uri, symbol))
+}
+
+/// Item for the innermost procedure or trigger containing the call at
+/// `location`. `None` unless `location` is the name of a `function_call` or
+/// the member name of a `method_call`.
+fn calling_item(state: &WorldState, location: &Location) -> Option<CallHierarchyItem> {
+    let doc = state.documents.get(&location.uri)?;
+    // Reference ranges carry byte columns, like tree-sitter points
+    let point = |position: Position| tree_sitter::Point {
+        row: position.line as usize,
+        column: position.character as usize,
+    };
+    let tree = doc.scan_tree();
+    let name = tree
+        .root_node()
+        .named_descendant_for_point_range(point(location.range.start), point(location.range.end))?;
+    let call = name.parent()?;
+    let name_field = match call.kind() {
+        "function_call" => "function",
+        "method_call" => "method",
+        _ => return None,
+    };
+    if call.child_by_field_name(name_field)?.id() != name.id() {
+        return None;
+    }
+
+    let symbol = find_callable(doc.symbols(), &|symbol| {
+        range_contains(
+            ts_range_to_lsp_range(symbol.start_point, symbol.end_point),
+            location.range.start,
+        )
+    })?;
+    Some(callable_item(&location.uri, symbol))
+}
+
+/// Innermost procedure or trigger among `symbols` and their children that
+/// satisfies `matches`.
+fn find_callable<'a>(
+    symbols: &'a [AlSymbol],
+    matches: &dyn Fn(&AlSymbol) -> bool,
+) -> Option<&'a AlSymbol> {
+    symbols.iter().find_map(|symbol| {
+        find_callable(&symbol.children, matches).or_else(|| {
+            (matches!(symbol.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger)
+                && matches(symbol))
+            .then_some(symbol)
+        })
+    })
+}
+
+fn callable_item(uri: &Url, symbol: &AlSymbol) -> CallHierarchyItem {
+    CallHierarchyItem {
+        name: symbol.name.clone(),
+        kind: symbol.kind.lsp_kind(),
+        tags: None,
+        detail: symbol.type_info.clone(),
+        uri: uri.clone(),
+        range: ts_range_to_lsp_range(symbol.start_point, symbol.end_point),
+        selection_range: ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point),
+        data: None,
+    }
+}
+
+fn range_contains(range: Range, position: Position) -> bool {
+    range.start <= position && position <= range.end
+}
+
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+
+    const INTERFACE: &str = r#"interface ICompute
+{
+    procedure Compute(): Integer;
+}"#;
+
+    const HELPER: &str = r#"codeunit 50100 Helper
+{
+    procedure Compute(): Integer
+    begin
+        exit(Double(2) + Double(3));
+    end;
+
+    local procedure Double(Value: Integer): Integer
+    begin
+        exit(Value * 2);
+    end;
+}"#;
+
+    const CONSUMER: &str = r#"codeunit 50101 Consumer
+{
+    procedure Run()
+    var
+        Computer: Interface ICompute;
+    begin
+        Computer.Compute();
+    end;
+}"#;
+
+    fn uri(path: &str) -> Url {
+        Url::parse(&format!("file:///test/{path}")).unwrap()
+    }
+
+    fn workspace() -> WorldState {
+        let state = WorldState::new();
+        for (path, source) in [
+            ("interface.al", INTERFACE),
+            ("helper.al", HELPER),
+            ("consumer.al", CONSUMER),
+        ] {
+            state
+                .documents
+                .insert(uri(path), DocumentState::new(source));
+        }
+        state
+    }
+
+    fn prepare(state: &WorldState, path: &str, position: Position) -> CallHierarchyItem {
+        let items = handle_prepare_call_hierarchy(
+            state,
+            CallHierarchyPrepareParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri: uri(path) },
+                    position,
+                },
+                work_done_progress_params: Default::default(),
+            },
+        )
+        .expect("expected a call hierarchy item");
+        assert_eq!(items.len(), 1);
+        items.into_iter().next().unwrap()
+    }
+
+    fn outgoing_calls(
+        state: &WorldState,
+        item: CallHierarchyItem,
+    ) -> Vec<CallHierarchyOutgoingCall> {
+        handle_outgoing_calls(
+            state,
+            CallHierarchyOutgoingCallsParams {
+                item,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .expect("expected outgoing calls")
+    }
+
+    #[test]
+    fn test_prepare_from_call_site_resolves_to_declaration() {
+        let state = workspace();
+        let item = prepare(&state, "helper.al", Position::new(4, 25));
+        assert_eq!(item.name, "Double");
+        assert_eq!(item.kind, lsp_types::SymbolKind::FUNCTION);
+        assert_eq!(item.selection_range.start, Position::new(7, 20));
+        assert_eq!(item.range.start.line, 7);
+        assert_eq!(item.range.end.line, 10);
+    }
+
+    #[test]
+    fn test_incoming_calls_group_call_sites_per_caller() {
+        let state = workspace();
+        let item = prepare(&state, "helper.al", Position::new(7, 20));
+
+        let incoming = handle_incoming_calls(
+            &state,
+            CallHierarchyIncomingCallsParams {
+                item,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .expect("expected incoming calls");
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].from.name, "Compute");
+        assert_eq!(
+            incoming[0].from_ranges,
+            vec![
+                Range::new(Position::new(4, 13), Position::new(4, 19)),
+                Range::new(Position::new(4, 25), Position::new(4, 31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_incoming_calls_leave_out_non_call_references() {
+        let state = workspace();
+        state.documents.insert(
+            uri("implementer.al"),
+            DocumentState::new(
+                r#"codeunit 50102 Implementer implements ICompute
+{
+    procedure Compute(): Integer
+    begin
+        exit(1);
+    end;
+
+    procedure Twice(): Integer
+    begin
+        exit(Compute() * 2);
+    end;
+}"#,
+            ),
+        );
+        let item = prepare(&state, "implementer.al", Position::new(2, 14));
+
+        let incoming = handle_incoming_calls(
+            &state,
+            CallHierarchyIncomingCallsParams {
+                item,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .expect("expected incoming calls");
+        let callers: Vec<(&str, &str)> = incoming
+            .iter()
+            .map(|call| (call.from.name.as_str(), call.from.uri.path()))
+            .collect();
+        assert_eq!(callers, vec![("Twice", "/test/implementer.al")]);
+        assert_eq!(
+            incoming[0].from_ranges,
+            vec![Range::new(Position::new(9, 13), Position::new(9, 20))]
+        );
+    }
+
+    #[test]
+    fn test_outgoing_calls_resolve_local_and_cross_document_callees() {
+        let state = workspace();
+        let outgoing = outgoing_calls(&state, prepare(&state, "helper.al", Position::new(2, 14)));
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].to.name, "Double");
+        assert_eq!(outgoing[0].to.uri.path(), "/test/helper.al");
+        assert_eq!(outgoing[0].from_ranges.len(), 2);
+
+        let outgoing = outgoing_calls(&state, prepare(&state, "consumer.al", Position::new(2, 14)));
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].to.name, "Compute");
+        assert_eq!(outgoing[0].to.uri.path(), "/test/interface.al");
+        assert_eq!(
+            outgoing[0].from_ranges,
+            vec![Range::new(Position::new(6, 17), Position::new(6, 24))]
+        );
+    }
+}
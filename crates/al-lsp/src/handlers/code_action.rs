@@ -1,12 +1,19 @@
 use lsp_types::{
-    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Range, TextEdit,
-    WorkspaceEdit,
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Diagnostic, NumberOrString,
+    Position, Range, TextEdit, Url, WorkspaceEdit,
 };
 use std::collections::HashMap;
 
-use al_syntax::navigation::node_at_offset;
+use al_syntax::ast::{extract_name, procedure_signature_text, AlObjectKind, AlSymbolKind};
+use al_syntax::diagnostics::{MISSING_SEMICOLON_CODE, UNUSED_VARIABLE_CODE};
+use al_syntax::document::DocumentState;
+use al_syntax::formatting::FormatOptions;
+use al_syntax::navigation::{is_write_reference, node_at_offset};
+use al_syntax::symbols::DocumentSymbolTable;
 
-use crate::convert::lsp_position_to_byte_offset;
+use crate::convert::{
+    lsp_position_to_byte_offset, ts_point_to_lsp_position_utf16, ts_range_to_lsp_range,
+};
 use crate::state::WorldState;
 
 pub fn handle_code_action(
@@ -18,21 +25,69 @@ pub fn handle_code_action(
 
     let doc = state.documents.get(&uri)?;
     let source = doc.source();
+    let options = state.format_options(&uri);
 
     let mut actions = Vec::new();
 
     // Toggle procedure visibility
-    if let Some(action) = toggle_visibility_action(&doc.tree, &source, &doc.rope, &uri, range) {
+    if let Some(action) = toggle_visibility_action(doc.tree(), &source, doc.rope(), &uri, range) {
+        actions.push(CodeActionOrCommand::CodeAction(action));
+    }
+
+    // Wrap a single-statement if/for/while body in begin..end
+    if let Some(action) =
+        wrap_in_block_action(doc.tree(), source, doc.rope(), &uri, range, &options)
+    {
+        actions.push(CodeActionOrCommand::CodeAction(action));
+    }
+
+    // Rewrite an `if`/`else if` chain of equality tests as a `case` statement
+    if let Some(action) =
+        if_chain_to_case_action(doc.tree(), source, doc.rope(), &uri, range, &options)
+    {
+        actions.push(CodeActionOrCommand::CodeAction(action));
+    }
+
+    // Sort an object's procedures by name
+    if let Some(action) = sort_procedures_action(doc.tree(), source, doc.rope(), &uri, range) {
         actions.push(CodeActionOrCommand::CodeAction(action));
     }
 
     // Extract procedure (only when there's a non-empty selection)
     if range.start != range.end {
-        if let Some(action) = extract_procedure_action(&doc.tree, &source, &doc.rope, &uri, range) {
+        if let Some(action) = extract_procedure_action(
+            doc.tree(),
+            &source,
+            &doc.symbol_table,
+            doc.rope(),
+            &uri,
+            range,
+            &options,
+        ) {
+            actions.push(CodeActionOrCommand::CodeAction(action));
+        }
+    }
+
+    // Remove variables flagged by the unused-variable hint
+    for diagnostic in &params.context.diagnostics {
+        if let Some(action) =
+            remove_unused_variable_action(doc.tree(), source, &uri, range, diagnostic)
+        {
+            actions.push(CodeActionOrCommand::CodeAction(action));
+        }
+        if let Some(action) = insert_semicolon_action(&uri, range, diagnostic) {
             actions.push(CodeActionOrCommand::CodeAction(action));
         }
     }
 
+    // Implement missing interface methods (looks up other documents)
+    drop(doc);
+    actions.extend(
+        implement_interface_actions(state, &uri, range)
+            .into_iter()
+            .map(CodeActionOrCommand::CodeAction),
+    );
+
     if actions.is_empty() {
         None
     } else {
@@ -54,7 +109,7 @@ fn find_ancestor<'a>(node: tree_sitter::Node<'a>, kind: &str) -> Option<tree_sit
 /// Detect the access modifier (local/internal/protected) before "procedure" keyword
 /// by examining the source text of the procedure node.
 /// Returns the modifier text and its byte range (start, end) if found.
-fn detect_access_modifier(
+pub(crate) fn detect_access_modifier(
     source: &str,
     proc_node: tree_sitter::Node,
 ) -> Option<(String, usize, usize)> {
@@ -84,6 +139,275 @@ fn detect_access_modifier(
     }
 }
 
+/// "Remove unused variable" quick fix for an unused-variable diagnostic that
+/// overlaps the requested range. Deletes the declaration's lines, or the
+/// whole `var` section when it was the only declaration. In a multi-name
+/// declaration (`A, B: Integer;`) only the name and its comma are removed.
+fn remove_unused_variable_action(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    uri: &Url,
+    range: Range,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    if diagnostic.code != Some(NumberOrString::String(UNUSED_VARIABLE_CODE.to_string())) {
+        return None;
+    }
+    if diagnostic.range.end < range.start || range.end < diagnostic.range.start {
+        return None;
+    }
+
+    let start = tree_sitter::Point {
+        row: diagnostic.range.start.line as usize,
+        column: diagnostic.range.start.character as usize,
+    };
+    let end = tree_sitter::Point {
+        row: diagnostic.range.end.line as usize,
+        column: diagnostic.range.end.character as usize,
+    };
+    let name_node = tree
+        .root_node()
+        .named_descendant_for_point_range(start, end)?;
+    let declaration = find_ancestor(name_node, "variable_declaration")?;
+    let name = extract_name(name_node, source);
+
+    let mut cursor = declaration.walk();
+    let names: Vec<tree_sitter::Node> = declaration
+        .children_by_field_name("name", &mut cursor)
+        .collect();
+    let edit_range = if names.len() > 1 {
+        let index = names.iter().position(|n| n.id() == name_node.id())?;
+        let (from, to) = match names.get(index + 1) {
+            Some(next) => (name_node.start_position(), next.start_position()),
+            None => (names[index - 1].end_position(), name_node.end_position()),
+        };
+        ts_range_to_lsp_range(from, to)
+    } else {
+        let var_section = declaration.parent().filter(|p| p.kind() == "var_section");
+        let mut cursor = declaration.walk();
+        let removed = match var_section {
+            Some(section)
+                if section
+                    .named_children(&mut cursor)
+                    .all(|child| child.id() == declaration.id()) =>
+            {
+                section
+            }
+            _ => declaration,
+        };
+        Range {
+            start: Position::new(removed.start_position().row as u32, 0),
+            end: Position::new(removed.end_position().row as u32 + 1, 0),
+        }
+    };
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: edit_range,
+            new_text: String::new(),
+        }],
+    );
+    Some(CodeAction {
+        title: format!("Remove unused variable `{name}`"),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+/// "Insert `;`" quick fix for a missing-semicolon diagnostic that overlaps the
+/// requested range. The diagnostic sits at the end of the unterminated statement.
+fn insert_semicolon_action(uri: &Url, range: Range, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    if diagnostic.code != Some(NumberOrString::String(MISSING_SEMICOLON_CODE.to_string())) {
+        return None;
+    }
+    if diagnostic.range.end < range.start || range.end < diagnostic.range.start {
+        return None;
+    }
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: Range {
+                start: diagnostic.range.end,
+                end: diagnostic.range.end,
+            },
+            new_text: ";".to_string(),
+        }],
+    );
+    Some(CodeAction {
+        title: "Insert `;`".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+/// "Implement interface" code actions.
+/// When the cursor is on the declaration line(s) of a codeunit with an
+/// `implements` clause, offer one action per interface that still has
+/// unimplemented methods. Each action inserts stub procedures before the
+/// codeunit's closing brace.
+fn implement_interface_actions(state: &WorldState, uri: &Url, range: Range) -> Vec<CodeAction> {
+    let Some((implemented, interfaces, insert_position, prefix)) = state
+        .documents
+        .get(uri)
+        .and_then(|doc| implements_clause_site(&doc, range))
+    else {
+        return Vec::new();
+    };
+
+    let unit = state.format_options(uri).indent_unit();
+    let mut actions = Vec::new();
+    for interface_name in interfaces {
+        let signatures = missing_interface_signatures(state, &interface_name, &implemented);
+        if signatures.is_empty() {
+            continue;
+        }
+
+        let mut new_text = prefix.to_string();
+        for signature in &signatures {
+            new_text.push_str(&format!(
+                "\n{unit}{signature}\n{unit}begin\n{unit}{unit}Error('Not implemented');\n{unit}end;\n"
+            ));
+        }
+
+        let mut changes = HashMap::new();
+        changes.insert(
+            uri.clone(),
+            vec![TextEdit {
+                range: Range {
+                    start: insert_position,
+                    end: insert_position,
+                },
+                new_text,
+            }],
+        );
+        actions.push(CodeAction {
+            title: format!("Implement interface `{interface_name}`"),
+            kind: Some(CodeActionKind::QUICKFIX),
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+    }
+    actions
+}
+
+/// For a cursor on the header of a codeunit, return the lowercased names of
+/// its procedures, its `implements` list, and where (plus with what line
+/// prefix) stubs should be inserted before its closing brace.
+fn implements_clause_site(
+    doc: &DocumentState,
+    range: Range,
+) -> Option<(Vec<String>, Vec<String>, Position, &'static str)> {
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), range.start)?;
+    let codeunit_node = find_ancestor(
+        node_at_offset(doc.tree(), byte_offset)?,
+        "codeunit_declaration",
+    )?;
+
+    // Only on the header, before the opening brace
+    let mut cursor = codeunit_node.walk();
+    let braces: Vec<tree_sitter::Node> = codeunit_node
+        .children(&mut cursor)
+        .filter(|child| matches!(child.kind(), "{" | "}"))
+        .collect();
+    let (Some(open_brace), Some(close_brace)) = (braces.first(), braces.last()) else {
+        return None;
+    };
+    if byte_offset > open_brace.start_byte() {
+        return None;
+    }
+
+    let codeunit = doc
+        .symbols()
+        .iter()
+        .find(|sym| sym.start_byte == codeunit_node.start_byte())?;
+    let implemented: Vec<String> = codeunit
+        .children
+        .iter()
+        .filter(|child| matches!(child.kind, AlSymbolKind::Procedure))
+        .map(|child| child.name.to_lowercase())
+        .collect();
+    let interfaces = codeunit.implements.clone();
+
+    // Stubs go on their own lines just above the closing brace
+    let source = doc.source();
+    let brace_point = close_brace.start_position();
+    let line_start = close_brace.start_byte() - brace_point.column;
+    let (insert_position, prefix) = if source[line_start..close_brace.start_byte()]
+        .trim()
+        .is_empty()
+    {
+        (Position::new(brace_point.row as u32, 0), "")
+    } else {
+        (
+            ts_point_to_lsp_position_utf16(doc.rope(), brace_point),
+            "\n",
+        )
+    };
+    Some((implemented, interfaces, insert_position, prefix))
+}
+
+/// Signatures (`procedure Name(...)[: Type]`) of the methods of
+/// `interface_name` whose names are not in `implemented` (lowercased).
+fn missing_interface_signatures(
+    state: &WorldState,
+    interface_name: &str,
+    implemented: &[String],
+) -> Vec<String> {
+    let key = (
+        AlObjectKind::Interface.label().to_ascii_lowercase(),
+        interface_name.to_ascii_lowercase(),
+    );
+    let Some(entries) = state.object_index.get(&key) else {
+        return Vec::new();
+    };
+    for indexed in entries.iter() {
+        let Some(other_doc) = state.documents.get(&indexed.uri) else {
+            continue;
+        };
+        let Some(interface) = other_doc
+            .symbol_table
+            .symbols
+            .iter()
+            .find(|object| object.start_byte == indexed.object_start_byte)
+        else {
+            continue;
+        };
+        let source = other_doc.source();
+        let tree = other_doc.scan_tree();
+        let root = tree.root_node();
+        return interface
+            .children
+            .iter()
+            .filter(|child| matches!(child.kind, AlSymbolKind::Procedure))
+            .filter(|child| !implemented.contains(&child.name.to_lowercase()))
+            .filter_map(|child| {
+                let method = root.descendant_for_byte_range(child.start_byte, child.end_byte)?;
+                procedure_signature_text(method, source)
+            })
+            .collect();
+    }
+    Vec::new()
+}
+
 /// Toggle procedure visibility code action.
 /// When cursor is on a procedure_declaration:
 /// - If it has local/internal/protected → offer "Remove '...' modifier"
@@ -155,15 +479,352 @@ fn toggle_visibility_action(
     })
 }
 
+/// "Wrap in begin..end" code action.
+/// When the cursor is on a statement that is the body of an `if`, `for` or
+/// `while` without a `begin..end` block, wrap it in one, indented one level
+/// past the control statement.
+fn wrap_in_block_action(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    rope: &ropey::Rope,
+    uri: &Url,
+    range: Range,
+    options: &FormatOptions,
+) -> Option<CodeAction> {
+    let byte_offset = lsp_position_to_byte_offset(rope, range.start)?;
+    let mut body = node_at_offset(tree, byte_offset)?;
+    let control = loop {
+        let parent = body.parent()?;
+        if matches!(
+            parent.kind(),
+            "if_statement" | "for_statement" | "while_statement"
+        ) && body.kind() != "block"
+        {
+            let mut cursor = parent.walk();
+            let is_body = ["consequence", "alternative", "body"].iter().any(|field| {
+                parent
+                    .children_by_field_name(field, &mut cursor)
+                    .any(|child| child.id() == body.id())
+            });
+            // `else if` chains are left alone
+            if is_body && !(body.kind() == "if_statement" && parent.kind() == "if_statement") {
+                break parent;
+            }
+        }
+        body = parent;
+    };
+    // Keywords aren't nodes; the edit starts right after `then`/`else`/`do`
+    let keyword_end = source[..body.start_byte()].trim_end().len();
+    let keyword_line = rope.byte_to_line(keyword_end);
+    let keyword_end = tree_sitter::Point {
+        row: keyword_line,
+        column: keyword_end - rope.line_to_byte(keyword_line),
+    };
+
+    let line_indent = |row: usize| -> &str {
+        let line = source.lines().nth(row).unwrap_or("");
+        &line[..line.len() - line.trim_start().len()]
+    };
+    let base_indent = line_indent(control.start_position().row);
+    let inner_indent = format!("{base_indent}{}", options.indent_unit());
+    let statement = &source[body.start_byte()..body.end_byte()];
+    let statement = reindent(
+        statement,
+        line_indent(body.start_position().row),
+        &inner_indent,
+        options,
+    );
+    let statement = statement.trim_start();
+
+    let edit_range = ts_range_to_lsp_range(keyword_end, body.end_position());
+    let new_text = format!(" begin\n{inner_indent}{statement};\n{base_indent}end");
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: edit_range,
+            new_text,
+        }],
+    );
+    Some(CodeAction {
+        title: "Wrap in begin..end".to_string(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// "Sort procedures alphabetically" code action.
+/// When the cursor is on an object's header, reorder its procedures by name
+/// (case-insensitive). Procedures trade places among the slots they occupy, so
+/// triggers, `var` sections and fields stay put, as does the spacing between
+/// members. Comments and attributes directly above a procedure move with it.
+fn sort_procedures_action(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    rope: &ropey::Rope,
+    uri: &Url,
+    range: Range,
+) -> Option<CodeAction> {
+    let byte_offset = lsp_position_to_byte_offset(rope, range.start)?;
+    let mut object = node_at_offset(tree, byte_offset)?;
+    while AlObjectKind::from_node_kind(object.kind()).is_none() {
+        object = object.parent()?;
+    }
+
+    // Only on the header, before the opening brace
+    let mut cursor = object.walk();
+    let open_brace = object
+        .children(&mut cursor)
+        .find(|child| child.kind() == "{")?;
+    if byte_offset > open_brace.start_byte() {
+        return None;
+    }
+
+    let mut cursor = object.walk();
+    let slots: Vec<(usize, usize, String)> = object
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "procedure_declaration")
+        .filter_map(|procedure| {
+            let name = extract_name(procedure.child_by_field_name("name")?, source);
+            Some((
+                procedure_span_start(procedure, source),
+                procedure.end_byte(),
+                name.to_lowercase(),
+            ))
+        })
+        .collect();
+    let mut sorted = slots.clone();
+    sorted.sort_by(|a, b| a.2.cmp(&b.2));
+    if sorted == slots {
+        return None;
+    }
+
+    // Rewrite from the first procedure to the last, keeping what lies between slots
+    let mut new_text = String::new();
+    for (idx, (slot, procedure)) in slots.iter().zip(&sorted).enumerate() {
+        if idx > 0 {
+            new_text.push_str(&source[slots[idx - 1].1..slot.0]);
+        }
+        new_text.push_str(&source[procedure.0..procedure.1]);
+    }
+    let point = |byte: usize| {
+        let row = rope.byte_to_line(byte);
+        tree_sitter::Point {
+            row,
+            column: byte - rope.line_to_byte(row),
+        }
+    };
+    let edit_range = ts_range_to_lsp_range(point(slots[0].0), point(slots[slots.len() - 1].1));
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: edit_range,
+            new_text,
+        }],
+    );
+    Some(CodeAction {
+        title: "Sort procedures alphabetically".to_string(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Start of a procedure together with the comments and attributes directly
+/// above it (no blank line in between). Attributes the parser could not
+/// attach to the procedure show up as `ERROR` siblings starting with `[`.
+fn procedure_span_start(procedure: tree_sitter::Node, source: &str) -> usize {
+    let mut start = procedure.start_byte();
+    let mut sibling = procedure.prev_sibling();
+    while let Some(node) = sibling {
+        let attached = match node.kind() {
+            "line_comment" | "block_comment" | "attribute" => true,
+            "ERROR" => source[node.start_byte()..].starts_with('['),
+            _ => false,
+        };
+        let gap = &source[node.end_byte()..start];
+        if !attached || gap.matches('\n').count() > 1 {
+            break;
+        }
+        start = node.start_byte();
+        sibling = node.prev_sibling();
+    }
+    // Take the indentation along when the span starts its line
+    let line_start = source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    if source[line_start..start].trim().is_empty() {
+        line_start
+    } else {
+        start
+    }
+}
+
+/// "Convert to case statement" code action.
+/// When the cursor is inside an `if`/`else if` chain whose conditions all
+/// compare the same expression with `=` against distinct values, rewrite the
+/// chain as `case <expr> of`, one branch per value plus an `else` branch for
+/// the final `else`.
+fn if_chain_to_case_action(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    rope: &ropey::Rope,
+    uri: &Url,
+    range: Range,
+    options: &FormatOptions,
+) -> Option<CodeAction> {
+    let byte_offset = lsp_position_to_byte_offset(rope, range.start)?;
+    let mut head = find_ancestor(node_at_offset(tree, byte_offset)?, "if_statement")?;
+    // Climb to the head of the chain when the cursor is on an `else if`
+    while let Some(parent) = head.parent() {
+        let is_alternative = parent.kind() == "if_statement"
+            && parent
+                .child_by_field_name("alternative")
+                .is_some_and(|alt| alt.id() == head.id());
+        if !is_alternative {
+            break;
+        }
+        head = parent;
+    }
+
+    let normalize = |node: tree_sitter::Node| -> String {
+        source[node.byte_range()]
+            .split_whitespace()
+            .collect::<String>()
+            .to_lowercase()
+    };
+
+    let mut subject: Option<tree_sitter::Node> = None;
+    let mut branches = Vec::new();
+    let mut else_branch = None;
+    let mut current = Some(head);
+    while let Some(if_stmt) = current {
+        let (left, value) = equality_operands(if_stmt.child_by_field_name("condition")?)?;
+        match subject {
+            Some(subject) if normalize(subject) != normalize(left) => return None,
+            Some(_) => {}
+            None => subject = Some(left),
+        }
+        // A bare nested `if` would capture the `else` of the generated `case`
+        let consequence = if_stmt.child_by_field_name("consequence")?;
+        if consequence.kind() == "if_statement" {
+            return None;
+        }
+        branches.push((value, consequence));
+
+        current = None;
+        if let Some(alternative) = if_stmt.child_by_field_name("alternative") {
+            if alternative.kind() == "if_statement" {
+                current = Some(alternative);
+            } else {
+                else_branch = Some(alternative);
+            }
+        }
+    }
+    if branches.len() < 2 {
+        return None;
+    }
+    let mut seen = std::collections::HashSet::new();
+    if !branches
+        .iter()
+        .all(|(value, _)| seen.insert(normalize(*value)))
+    {
+        return None;
+    }
+
+    let line_indent = |row: usize| -> &str {
+        let line = source.lines().nth(row).unwrap_or("");
+        &line[..line.len() - line.trim_start().len()]
+    };
+    let base_indent = line_indent(head.start_position().row);
+    let branch_indent = format!("{base_indent}{}", options.indent_unit());
+    let body_indent = format!("{branch_indent}{}", options.indent_unit());
+    let body = |statement: tree_sitter::Node| -> String {
+        let text = reindent(
+            &source[statement.byte_range()],
+            line_indent(statement.start_position().row),
+            &body_indent,
+            options,
+        );
+        format!("{body_indent}{};\n", text.trim_start())
+    };
+
+    let mut new_text = format!("case {} of\n", &source[subject?.byte_range()]);
+    for (value, consequence) in &branches {
+        new_text.push_str(&format!(
+            "{branch_indent}{}:\n",
+            &source[value.byte_range()]
+        ));
+        new_text.push_str(&body(*consequence));
+    }
+    if let Some(alternative) = else_branch {
+        new_text.push_str(&format!("{branch_indent}else\n"));
+        new_text.push_str(&body(alternative));
+    }
+    new_text.push_str(&format!("{base_indent}end"));
+
+    let mut changes = HashMap::new();
+    changes.insert(
+        uri.clone(),
+        vec![TextEdit {
+            range: ts_range_to_lsp_range(head.start_position(), head.end_position()),
+            new_text,
+        }],
+    );
+    Some(CodeAction {
+        title: "Convert to case statement".to_string(),
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Split an `<expr> = <value>` condition (optionally parenthesized) into its
+/// operands.
+fn equality_operands(
+    condition: tree_sitter::Node,
+) -> Option<(tree_sitter::Node, tree_sitter::Node)> {
+    let mut node = condition;
+    loop {
+        match node.kind() {
+            "primary_expression" | "parenthesized_expression" if node.named_child_count() == 1 => {
+                node = node.named_child(0)?;
+            }
+            "comparison_expression" => break,
+            _ => return None,
+        }
+    }
+    if node.child_by_field_name("operator")?.kind() != "=" {
+        return None;
+    }
+    Some((
+        node.child_by_field_name("left")?,
+        node.child_by_field_name("right")?,
+    ))
+}
+
 /// Extract procedure code action.
 /// When the user has a non-empty selection covering complete statements within a begin...end block,
 /// extract those statements into a new local procedure.
 fn extract_procedure_action(
     tree: &tree_sitter::Tree,
     source: &str,
+    symbol_table: &DocumentSymbolTable,
     rope: &ropey::Rope,
     uri: &lsp_types::Url,
     range: Range,
+    options: &FormatOptions,
 ) -> Option<CodeAction> {
     let start_offset = lsp_position_to_byte_offset(rope, range.start)?;
     let end_offset = lsp_position_to_byte_offset(rope, range.end)?;
@@ -211,15 +872,29 @@ fn extract_procedure_action(
     let extracted_text = &source[extract_start..extract_end];
 
     // Collect free variables: locals/params referenced in selection but declared outside it
-    let free_vars = collect_free_variables(tree, source, proc_node, extract_start, extract_end);
+    let free_vars = collect_free_variables(
+        tree,
+        source,
+        symbol_table,
+        proc_node,
+        extract_start,
+        extract_end,
+    );
 
-    // Build parameter list for the new procedure
+    // Build parameter list for the new procedure; variables written in the
+    // selection are passed by reference, read-only ones by value
     let params_str = if free_vars.is_empty() {
         String::new()
     } else {
         free_vars
             .iter()
-            .map(|(name, type_info)| format!("var {}: {}", name, type_info))
+            .map(|(name, type_info, written)| {
+                if *written {
+                    format!("var {}: {}", name, type_info)
+                } else {
+                    format!("{}: {}", name, type_info)
+                }
+            })
             .collect::<Vec<_>>()
             .join("; ")
     };
@@ -230,7 +905,7 @@ fn extract_procedure_action(
     } else {
         free_vars
             .iter()
-            .map(|(name, _)| name.as_str())
+            .map(|(name, _, _)| name.as_str())
             .collect::<Vec<_>>()
             .join(", ")
     };
@@ -243,17 +918,19 @@ fn extract_procedure_action(
     // Re-indent extracted text relative to the new procedure body
     let stmt_indent =
         &source[rope.line_to_byte(first_stmt.start_position().row)..first_stmt.start_byte()];
-    let new_body_indent = format!("{}    ", proc_indent);
-    let reindented = reindent(extracted_text, stmt_indent, &new_body_indent);
+    let new_body_indent = format!("{}{}", proc_indent, options.indent_unit());
+    let reindented = reindent(extracted_text, stmt_indent, &new_body_indent, options);
 
     // Build the new procedure text
+    let proc_name =
+        unique_procedure_name(symbol_table, proc_node.start_byte(), "ExtractedProcedure");
     let new_proc = format!(
-        "\n\n{}local procedure ExtractedProcedure({})\n{}begin\n{}\n{}end;",
-        proc_indent, params_str, proc_indent, reindented, proc_indent,
+        "\n\n{}local procedure {}({})\n{}begin\n{}{}\n{}end;",
+        proc_indent, proc_name, params_str, proc_indent, new_body_indent, reindented, proc_indent,
     );
 
     // Build the call replacement
-    let call_text = format!("ExtractedProcedure({});", args_str);
+    let call_text = format!("{}({});", proc_name, args_str);
 
     // Two edits:
     // 1. Replace selected statements with the call
@@ -288,21 +965,62 @@ fn extract_procedure_action(
     })
 }
 
+/// `base`, or `base` with the smallest numeric suffix from 2 up, that names no
+/// procedure of the object enclosing `byte_offset`.
+fn unique_procedure_name(
+    symbol_table: &DocumentSymbolTable,
+    byte_offset: usize,
+    base: &str,
+) -> String {
+    let existing: Vec<&str> = symbol_table
+        .symbols
+        .iter()
+        .filter(|object| {
+            matches!(object.kind, AlSymbolKind::Object(_))
+                && object.start_byte <= byte_offset
+                && byte_offset < object.end_byte
+        })
+        .flat_map(|object| &object.children)
+        .filter(|child| child.kind == AlSymbolKind::Procedure)
+        .map(|child| child.name.as_str())
+        .collect();
+
+    let mut name = base.to_string();
+    let mut suffix = 1;
+    while existing.iter().any(|used| used.eq_ignore_ascii_case(&name)) {
+        suffix += 1;
+        name = format!("{base}{suffix}");
+    }
+    name
+}
+
 /// Collect free variables referenced in the byte range [start..end] that are
-/// declared as parameters or local variables in the enclosing procedure.
+/// declared as parameters or local variables in the enclosing procedure,
+/// along with whether the range writes to them.
 fn collect_free_variables(
     tree: &tree_sitter::Tree,
     source: &str,
+    symbol_table: &DocumentSymbolTable,
     proc_node: tree_sitter::Node,
     start: usize,
     end: usize,
-) -> Vec<(String, String)> {
+) -> Vec<(String, String, bool)> {
     use al_syntax::ast::{extract_name, node_text};
     use std::collections::HashSet;
 
-    // Collect all identifiers used in the selection
+    // Collect all identifiers used in the selection, and those written to
     let mut used_names: HashSet<String> = HashSet::new();
-    collect_identifiers_in_range(tree.root_node(), source, start, end, &mut used_names);
+    let mut written_names: HashSet<String> = HashSet::new();
+    collect_identifiers_in_range(
+        tree,
+        tree.root_node(),
+        source,
+        symbol_table,
+        start,
+        end,
+        &mut used_names,
+        &mut written_names,
+    );
 
     // Collect parameter and variable declarations from the procedure
     let mut declared: Vec<(String, String)> = Vec::new();
@@ -342,20 +1060,29 @@ fn collect_free_variables(
     // Filter to those that are used in the selection but declared outside it
     declared
         .into_iter()
-        .filter(|(name, _)| {
+        .filter_map(|(name, type_info)| {
             let lower = name.to_lowercase();
-            used_names.contains(&lower)
+            if !used_names.contains(&lower) {
+                return None;
+            }
+            let written = written_names.contains(&lower);
+            Some((name, type_info, written))
         })
         .collect()
 }
 
-/// Recursively collect all identifier names used within a byte range.
+/// Recursively collect all identifier names used within a byte range, and
+/// separately those in a write position (see `is_write_reference`).
+#[allow(clippy::too_many_arguments)]
 fn collect_identifiers_in_range(
+    tree: &tree_sitter::Tree,
     node: tree_sitter::Node,
     source: &str,
+    symbol_table: &DocumentSymbolTable,
     start: usize,
     end: usize,
     names: &mut std::collections::HashSet<String>,
+    written: &mut std::collections::HashSet<String>,
 ) {
     if node.end_byte() < start || node.start_byte() > end {
         return;
@@ -365,22 +1092,43 @@ fn collect_identifiers_in_range(
         && node.start_byte() >= start
         && node.end_byte() <= end
     {
-        let name = al_syntax::ast::extract_name(node, source);
-        names.insert(name.to_lowercase());
+        let name = al_syntax::ast::extract_name(node, source).to_lowercase();
+        if is_write_reference(
+            tree,
+            source,
+            symbol_table,
+            node.start_position(),
+            node.end_position(),
+        ) {
+            written.insert(name.clone());
+        }
+        names.insert(name);
     }
 
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        collect_identifiers_in_range(child, source, start, end, names);
+        collect_identifiers_in_range(
+            tree,
+            child,
+            source,
+            symbol_table,
+            start,
+            end,
+            names,
+            written,
+        );
     }
 }
 
 /// Re-indent text by replacing old_indent prefix with new_indent on each line.
-fn reindent(text: &str, old_indent: &str, new_indent: &str) -> String {
+/// Indentation past old_indent is rewritten in the style of `options`.
+fn reindent(text: &str, old_indent: &str, new_indent: &str, options: &FormatOptions) -> String {
     text.lines()
         .map(|line| {
             if let Some(rest) = line.strip_prefix(old_indent) {
-                format!("{}{}", new_indent, rest)
+                let content = rest.trim_start();
+                let extra = relative_indent(&rest[..rest.len() - content.len()], options);
+                format!("{}{}{}", new_indent, extra, content)
             } else {
                 line.to_string()
             }
@@ -389,6 +1137,21 @@ fn reindent(text: &str, old_indent: &str, new_indent: &str) -> String {
         .join("\n")
 }
 
+/// `whitespace` re-expressed as indentation levels of `options`, with any
+/// remainder narrower than a level kept as spaces.
+fn relative_indent(whitespace: &str, options: &FormatOptions) -> String {
+    let tab_size = options.tab_size.max(1);
+    let width: usize = whitespace
+        .chars()
+        .map(|c| if c == '\t' { tab_size } else { 1 })
+        .sum();
+    format!(
+        "{}{}",
+        options.indent_unit().repeat(width / tab_size),
+        " ".repeat(width % tab_size)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -433,7 +1196,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on the procedure (line 2, col 14)
         let params = make_code_action_params(uri.clone(), (2, 14), (2, 14));
@@ -467,7 +1230,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on the procedure name (line 2, col 20)
         let params = make_code_action_params(uri.clone(), (2, 20), (2, 20));
@@ -512,7 +1275,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_code_action_params(uri.clone(), (2, 24), (2, 24));
         let result = handle_code_action(&state, params);
@@ -540,7 +1303,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on the variable (line 3, col 8)
         let params = make_code_action_params(uri.clone(), (3, 8), (3, 8));
@@ -578,7 +1341,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Select "X := 1;\n        Y := X + 1;" (lines 7-8)
         // Line 7: "        X := 1;"  (starts at col 8)
@@ -620,4 +1383,588 @@ mod tests {
             assert!(edits[1].new_text.contains("end;"));
         }
     }
+
+    /// The call and declaration line produced by extracting `start..end`.
+    fn extract_procedure_edits(
+        source: &str,
+        start: (u32, u32),
+        end: (u32, u32),
+    ) -> (String, String) {
+        let (call, procedure) =
+            extract_procedure_text(source, start, end, FormatOptions::default());
+        let declaration = procedure
+            .lines()
+            .find(|line| line.contains("local procedure"))
+            .unwrap()
+            .trim()
+            .to_string();
+        (call, declaration)
+    }
+
+    /// The call and procedure text produced by extracting `start..end`.
+    fn extract_procedure_text(
+        source: &str,
+        start: (u32, u32),
+        end: (u32, u32),
+        options: FormatOptions,
+    ) -> (String, String) {
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+        state.format_options.insert(uri.clone(), options);
+
+        let params = make_code_action_params(uri.clone(), start, end);
+        let actions = handle_code_action(&state, params).expect("expected code actions");
+        let action = actions
+            .iter()
+            .find_map(|a| match a {
+                CodeActionOrCommand::CodeAction(ca) if ca.title == "Extract procedure" => Some(ca),
+                _ => None,
+            })
+            .expect("expected extract procedure action");
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        (edits[0].new_text.clone(), edits[1].new_text.clone())
+    }
+
+    #[test]
+    fn test_extract_procedure_passes_read_only_variable_by_value() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+    begin
+        X := 1;
+        Message('%1', X);
+    end;
+}"#;
+        let (call, declaration) = extract_procedure_edits(source, (7, 8), (7, 25));
+        assert_eq!(call, "ExtractedProcedure(X);");
+        assert_eq!(
+            declaration,
+            "local procedure ExtractedProcedure(X: Integer)"
+        );
+    }
+
+    #[test]
+    fn test_extract_procedure_passes_written_variable_by_var() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+        Y: Integer;
+    begin
+        X := 1;
+        Y := X + 1;
+        Message('%1', Y);
+    end;
+}"#;
+        let (call, declaration) = extract_procedure_edits(source, (8, 8), (8, 19));
+        assert_eq!(call, "ExtractedProcedure(X, Y);");
+        assert_eq!(
+            declaration,
+            "local procedure ExtractedProcedure(X: Integer; var Y: Integer)"
+        );
+    }
+
+    #[test]
+    fn test_extract_procedure_indents_with_tabs() {
+        let source = "codeunit 50100 Test\n{\n\tprocedure DoWork()\n\tbegin\n\t\tif true then\n\t\t    Message('Hello');\n\tend;\n}";
+        let options = FormatOptions {
+            insert_spaces: false,
+            ..Default::default()
+        };
+        let (_, procedure) = extract_procedure_text(source, (4, 2), (5, 23), options);
+        assert_eq!(
+            procedure,
+            "\n\n\tlocal procedure ExtractedProcedure()\n\tbegin\n\t\tif true then\n\t\t\tMessage('Hello')\n\tend;"
+        );
+    }
+
+    #[test]
+    fn test_extract_procedure_avoids_existing_name() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        Message('Hello');
+    end;
+
+    local procedure ExtractedProcedure()
+    begin
+    end;
+}"#;
+        let (call, declaration) = extract_procedure_edits(source, (4, 8), (4, 25));
+        assert_eq!(call, "ExtractedProcedure2();");
+        assert_eq!(declaration, "local procedure ExtractedProcedure2()");
+    }
+
+    #[test]
+    fn test_implement_interface_inserts_stubs_for_missing_methods() {
+        let iface_source = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+    procedure SetAddress(NewAddress: Text);
+    procedure Reset();
+}"#;
+        let other_iface_source = r#"interface ILogger
+{
+    procedure Log(Message: Text);
+}"#;
+        let impl_source = r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider, ILogger
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/impl.al").unwrap();
+        let iface_uri = Url::parse("file:///test/iface.al").unwrap();
+        let state = WorldState::new();
+        // A closed interface document stays unloaded while its methods are read
+        state.upsert_document(iface_uri.clone(), DocumentState::new_unloaded(iface_source));
+        state.upsert_document(
+            Url::parse("file:///test/logger.al").unwrap(),
+            DocumentState::new(other_iface_source),
+        );
+        state.upsert_document(uri.clone(), DocumentState::new(impl_source));
+
+        let params = make_code_action_params(uri.clone(), (0, 20), (0, 20));
+        let actions = handle_code_action(&state, params).expect("expected code actions");
+        let implement: Vec<&CodeAction> = actions
+            .iter()
+            .filter_map(|a| match a {
+                CodeActionOrCommand::CodeAction(ca)
+                    if ca.title.starts_with("Implement interface") =>
+                {
+                    Some(ca)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(implement.len(), 2, "one action per interface");
+        assert!(state.documents.get(&iface_uri).unwrap().is_unloaded());
+
+        let edits = |action: &CodeAction| {
+            action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri].clone()
+        };
+        let address = edits(implement[0]);
+        assert_eq!(implement[0].title, "Implement interface `IAddressProvider`");
+        assert_eq!(address.len(), 1);
+        assert_eq!(address[0].range.start, Position::new(5, 0));
+        assert_eq!(
+            address[0]
+                .new_text
+                .matches("Error('Not implemented');")
+                .count(),
+            2,
+            "GetAddress is already implemented: {}",
+            address[0].new_text
+        );
+        assert_eq!(
+            address[0].new_text,
+            "\n    procedure SetAddress(NewAddress: Text)\n    begin\n        Error('Not implemented');\n    end;\n\n    procedure Reset()\n    begin\n        Error('Not implemented');\n    end;\n"
+        );
+
+        let logger = edits(implement[1]);
+        assert_eq!(
+            logger[0]
+                .new_text
+                .matches("procedure Log(Message: Text)")
+                .count(),
+            1
+        );
+
+        // Not offered inside the codeunit body
+        let params = make_code_action_params(uri, (2, 14), (2, 14));
+        let inside = handle_code_action(&state, params).unwrap_or_default();
+        assert!(!inside.iter().any(|a| matches!(
+            a,
+            CodeActionOrCommand::CodeAction(ca) if ca.title.starts_with("Implement interface")
+        )));
+    }
+
+    fn remove_unused_variable_edit(source: &str, name: &str) -> TextEdit {
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        let doc = DocumentState::new(source);
+        let diagnostic = doc
+            .diagnostics
+            .iter()
+            .find(|d| d.message == format!("`{name}` is declared but never used"))
+            .cloned()
+            .expect("unused-variable diagnostic");
+        state.documents.insert(uri.clone(), doc);
+
+        let position = (
+            diagnostic.range.start.line,
+            diagnostic.range.start.character,
+        );
+        let mut params = make_code_action_params(uri.clone(), position, position);
+        params.context.diagnostics = vec![diagnostic];
+        let actions = handle_code_action(&state, params).expect("expected code actions");
+        let action = actions
+            .iter()
+            .find_map(|a| match a {
+                CodeActionOrCommand::CodeAction(ca)
+                    if ca.title == format!("Remove unused variable `{name}`") =>
+                {
+                    Some(ca)
+                }
+                _ => None,
+            })
+            .expect("expected remove unused variable action");
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(edits.len(), 1);
+        edits[0].clone()
+    }
+
+    #[test]
+    fn test_remove_unused_variable_keeps_other_declarations() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Used: Integer;
+        Leftover: Text;
+    begin
+        Used := 1;
+    end;
+}"#;
+        let edit = remove_unused_variable_edit(source, "Leftover");
+        assert_eq!(edit.range.start, Position::new(5, 0));
+        assert_eq!(edit.range.end, Position::new(6, 0));
+        assert_eq!(edit.new_text, "");
+    }
+
+    #[test]
+    fn test_remove_sole_unused_variable_drops_var_section() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Leftover: Text;
+    begin
+    end;
+}"#;
+        let edit = remove_unused_variable_edit(source, "Leftover");
+        assert_eq!(edit.range.start, Position::new(3, 0));
+        assert_eq!(edit.range.end, Position::new(5, 0));
+        assert_eq!(edit.new_text, "");
+    }
+
+    #[test]
+    fn test_insert_missing_semicolon() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        x: Integer;
+    begin
+        x := 1
+        x := 2;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        let doc = DocumentState::new(source);
+        let diagnostic = doc
+            .diagnostics
+            .iter()
+            .find(|d| d.message == "Missing ';' after statement")
+            .cloned()
+            .expect("missing-semicolon diagnostic");
+        state.documents.insert(uri.clone(), doc);
+
+        let mut params = make_code_action_params(uri.clone(), (6, 14), (6, 14));
+        params.context.diagnostics = vec![diagnostic];
+        let actions = handle_code_action(&state, params).expect("expected code actions");
+        let action = actions
+            .iter()
+            .find_map(|a| match a {
+                CodeActionOrCommand::CodeAction(ca) if ca.title == "Insert `;`" => Some(ca),
+                _ => None,
+            })
+            .expect("expected insert semicolon action");
+        let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start, Position::new(6, 14));
+        assert_eq!(edits[0].range.end, Position::new(6, 14));
+        assert_eq!(edits[0].new_text, ";");
+    }
+
+    /// Apply the code action titled `title` at `position`, if offered.
+    fn apply_action(source: &str, position: (u32, u32), title: &str) -> Option<String> {
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let params = make_code_action_params(uri.clone(), position, position);
+        let actions = handle_code_action(&state, params).unwrap_or_default();
+        let action = actions.iter().find_map(|a| match a {
+            CodeActionOrCommand::CodeAction(ca) if ca.title == title => Some(ca),
+            _ => None,
+        })?;
+        let edit = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri][0];
+
+        let rope = ropey::Rope::from_str(source);
+        let start = lsp_position_to_byte_offset(&rope, edit.range.start).unwrap();
+        let end = lsp_position_to_byte_offset(&rope, edit.range.end).unwrap();
+        Some(format!(
+            "{}{}{}",
+            &source[..start],
+            edit.new_text,
+            &source[end..]
+        ))
+    }
+
+    #[test]
+    fn test_sort_procedures_alphabetically() {
+        let source = r#"codeunit 50100 Test
+{
+    var
+        Counter: Integer;
+
+    procedure Zeta()
+    begin
+    end;
+
+    trigger OnRun()
+    begin
+    end;
+
+    // Runs first
+    local procedure beta()
+    begin
+    end;
+
+
+    procedure Alpha()
+    begin
+    end;
+}"#;
+        let sorted = apply_action(source, (0, 10), "Sort procedures alphabetically")
+            .expect("expected sort action");
+        assert_eq!(
+            sorted,
+            r#"codeunit 50100 Test
+{
+    var
+        Counter: Integer;
+
+    procedure Alpha()
+    begin
+    end;
+
+    trigger OnRun()
+    begin
+    end;
+
+    // Runs first
+    local procedure beta()
+    begin
+    end;
+
+
+    procedure Zeta()
+    begin
+    end;
+}"#
+        );
+
+        // Already sorted, and not offered inside the object body
+        assert!(apply_action(&sorted, (0, 10), "Sort procedures alphabetically").is_none());
+        assert!(apply_action(source, (6, 6), "Sort procedures alphabetically").is_none());
+    }
+
+    #[test]
+    fn test_sort_procedures_moves_comments_and_attributes() {
+        let source = r#"codeunit 50100 Test
+{
+    /// Documented
+    [Scope('OnPrem')]
+    procedure Second()
+    begin
+    end;
+
+    procedure First()
+    begin
+    end;
+}"#;
+        let sorted = apply_action(source, (0, 10), "Sort procedures alphabetically")
+            .expect("expected sort action");
+        assert_eq!(
+            sorted,
+            r#"codeunit 50100 Test
+{
+    procedure First()
+    begin
+    end;
+
+    /// Documented
+    [Scope('OnPrem')]
+    procedure Second()
+    begin
+    end;
+}"#
+        );
+    }
+
+    fn wrap_in_block(source: &str, position: (u32, u32)) -> String {
+        apply_action(source, position, "Wrap in begin..end").expect("expected wrap action")
+    }
+
+    #[test]
+    fn test_wrap_if_body_in_begin_end() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Boolean)
+    var
+        y: Integer;
+    begin
+        if x then
+            y := 1;
+        if x then y := 2 else y := 3;
+    end;
+}"#;
+        let wrapped = wrap_in_block(source, (7, 12));
+        assert_eq!(
+            wrapped,
+            r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Boolean)
+    var
+        y: Integer;
+    begin
+        if x then begin
+            y := 1;
+        end;
+        if x then y := 2 else y := 3;
+    end;
+}"#
+        );
+        assert!(!al_parser::parse(&wrapped).unwrap().root_node().has_error());
+
+        let wrapped = wrap_in_block(source, (8, 18));
+        assert!(
+            wrapped.contains(
+                "        if x then begin\n            y := 2;\n        end else y := 3;\n"
+            ),
+            "got:\n{wrapped}"
+        );
+        assert!(!al_parser::parse(&wrapped).unwrap().root_node().has_error());
+    }
+
+    #[test]
+    fn test_no_wrap_for_block_body() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Boolean)
+    var
+        y: Integer;
+    begin
+        if x then begin
+            y := 1;
+        end;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+        let params = make_code_action_params(uri, (7, 12), (7, 12));
+        let actions = handle_code_action(&state, params).unwrap_or_default();
+        assert!(!actions.iter().any(|a| matches!(
+            a,
+            CodeActionOrCommand::CodeAction(ca) if ca.title == "Wrap in begin..end"
+        )));
+    }
+
+    #[test]
+    fn test_convert_if_chain_to_case() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Integer)
+    var
+        y: Integer;
+    begin
+        if x = 1 then
+            y := 10
+        else if x = 2 then begin
+            y := 20;
+            y += 1;
+        end else if (x = 3) then
+            y := 30
+        else
+            y := 0;
+    end;
+}"#;
+        // Offered from the head of the chain and from an `else if`
+        let converted = apply_action(source, (6, 9), "Convert to case statement")
+            .expect("expected convert action");
+        assert_eq!(
+            apply_action(source, (11, 25), "Convert to case statement").as_ref(),
+            Some(&converted)
+        );
+        assert_eq!(
+            converted,
+            r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Integer)
+    var
+        y: Integer;
+    begin
+        case x of
+            1:
+                y := 10;
+            2:
+                begin
+                    y := 20;
+                    y += 1;
+                end;
+            3:
+                y := 30;
+            else
+                y := 0;
+        end;
+    end;
+}"#
+        );
+        assert!(!al_parser::parse(&converted)
+            .unwrap()
+            .root_node()
+            .has_error());
+    }
+
+    #[test]
+    fn test_no_case_conversion_for_mixed_conditions() {
+        let different_subject = r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Integer; z: Integer)
+    begin
+        if x = 1 then
+            Message('a')
+        else if z = 2 then
+            Message('b');
+    end;
+}"#;
+        let not_equality = r#"codeunit 50100 Test
+{
+    procedure DoWork(x: Integer)
+    begin
+        if x = 1 then
+            Message('a')
+        else if x <> 2 then
+            Message('b');
+    end;
+}"#;
+        for source in [different_subject, not_equality] {
+            assert!(apply_action(source, (4, 9), "Convert to case statement").is_none());
+        }
+    }
 }
@@ -0,0 +1,173 @@
+use lsp_types::{
+    CodeLens, CodeLensParams, Command, Position, ReferenceContext, ReferenceParams,
+    TextDocumentIdentifier, TextDocumentPositionParams, Url,
+};
+
+use al_syntax::ast::{AlSymbol, AlSymbolKind};
+
+use crate::convert::ts_range_to_lsp_range;
+use crate::handlers::references::handle_references;
+use crate::state::WorldState;
+
+/// Client-side command that opens the references peek view
+/// (arguments: document URI, position, locations).
+pub const SHOW_REFERENCES_COMMAND: &str = "al-lsp.showReferences";
+
+/// Emit an unresolved lens on every object and procedure declaration.
+/// Reference counts are filled in by `handle_code_lens_resolve`, so only
+/// lenses the client actually displays pay for a reference search.
+pub fn handle_code_lens(state: &WorldState, params: CodeLensParams) -> Option<Vec<CodeLens>> {
+    let uri = params.text_document.uri;
+    let doc = state.documents.get(&uri)?;
+
+    let mut lenses = Vec::new();
+    for object in doc.symbols() {
+        if !matches!(object.kind, AlSymbolKind::Object(_)) || object.name.trim().is_empty() {
+            continue;
+        }
+        lenses.push(unresolved_lens(&uri, object));
+        for child in &object.children {
+            if matches!(child.kind, AlSymbolKind::Procedure) {
+                lenses.push(unresolved_lens(&uri, child));
+            }
+        }
+    }
+
+    Some(lenses)
+}
+
+/// Count references to the lens's symbol and attach the show-references command.
+pub fn handle_code_lens_resolve(state: &WorldState, mut lens: CodeLens) -> CodeLens {
+    let Some((uri, position)) = lens
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value::<(Url, Position)>(data).ok())
+    else {
+        return lens;
+    };
+
+    let locations = handle_references(
+        state,
+        ReferenceParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: ReferenceContext {
+                include_declaration: false,
+            },
+        },
+    )
+    .unwrap_or_default();
+
+    let title = match locations.len() {
+        1 => "1 reference".to_string(),
+        count => format!("{count} references"),
+    };
+    lens.command = Some(Command {
+        title,
+        command: SHOW_REFERENCES_COMMAND.to_string(),
+        arguments: Some(vec![
+            serde_json::json!(uri),
+            serde_json::json!(position),
+            serde_json::json!(locations),
+        ]),
+    });
+    lens
+}
+
+fn unresolved_lens(uri: &Url, symbol: &AlSymbol) -> CodeLens {
+    let range = ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point);
+    CodeLens {
+        range,
+        command: None,
+        data: Some(serde_json::json!([uri, range.start])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+
+    #[test]
+    fn test_code_lens_count_matches_interface_method_references() {
+        let iface_source = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+}"#;
+        let impl_source = r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+
+    procedure HelloWorld()
+    var
+        AddressProvider: Interface IAddressProvider;
+    begin
+        AddressProvider.GetAddress();
+    end;
+
+    procedure HelloAgain()
+    var
+        OtherProvider: Interface IAddressProvider;
+    begin
+        OtherProvider.GetAddress();
+    end;
+}"#;
+        let iface_uri = Url::parse("file:///test/iface.al").unwrap();
+        let impl_uri = Url::parse("file:///test/impl.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
+        state
+            .documents
+            .insert(impl_uri, DocumentState::new(impl_source));
+
+        let lenses = handle_code_lens(
+            &state,
+            CodeLensParams {
+                text_document: TextDocumentIdentifier {
+                    uri: iface_uri.clone(),
+                },
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .expect("expected code lenses");
+        assert_eq!(lenses.len(), 2, "interface object and its method");
+        assert!(lenses.iter().all(|lens| lens.command.is_none()));
+
+        let method_lens = lenses
+            .into_iter()
+            .find(|lens| lens.range.start == Position::new(2, 14))
+            .expect("lens on GetAddress");
+        let resolved = handle_code_lens_resolve(&state, method_lens);
+        let command = resolved.command.expect("resolved command");
+
+        let references = handle_references(
+            &state,
+            ReferenceParams {
+                text_document_position: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri: iface_uri },
+                    position: Position::new(2, 14),
+                },
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+                context: ReferenceContext {
+                    include_declaration: false,
+                },
+            },
+        )
+        .unwrap_or_default();
+        assert_eq!(references.len(), 2, "two call sites: {references:?}");
+        assert_eq!(command.title, "2 references");
+        assert_eq!(command.command, SHOW_REFERENCES_COMMAND);
+        assert_eq!(command.arguments.unwrap()[2], serde_json::json!(references));
+    }
+}
@@ -1,21 +1,23 @@
 use std::collections::HashSet;
 
 use lsp_types::{
-    CompletionItem, CompletionItemKind, CompletionParams, CompletionResponse, Documentation,
-    MarkupContent, MarkupKind, Url,
+    CompletionItem, CompletionItemKind, CompletionList, CompletionParams, CompletionResponse,
+    Documentation, InsertTextFormat, MarkupContent, MarkupKind, Url,
 };
 
-use al_syntax::ast::{extract_name, AlSymbol, AlSymbolKind};
+use al_syntax::ast::{extract_name, AlObjectKind, AlSymbol, AlSymbolKind};
+use al_syntax::builtins::{
+    builtin_method_return_type, builtin_object_kind_from_name, literal_values_for_property,
+    methods_for_object_kind, missing_methods_for_object_kind, object_triggers,
+    procedure_attributes, properties_for_scope,
+};
 use al_syntax::document::DocumentState;
+use al_syntax::formatting::{indent_snippet, FormatOptions};
 use al_syntax::navigation::{
-    extract_type_object_name, identifier_context_at_offset, node_at_offset,
+    extract_type_object_name, extract_value_type_name, identifier_context_at_offset, node_at_offset,
 };
-use al_syntax::symbols::al_keywords;
+use al_syntax::symbols::{al_keywords, format_type_info};
 
-use crate::builtins::{
-    builtin_method_return_type, builtin_object_kind_from_name, literal_values_for_property,
-    methods_for_object_kind, missing_methods_for_object_kind, properties_for_scope,
-};
 use crate::convert::lsp_position_to_byte_offset;
 use crate::handlers::events::event_subscriber_completion_items;
 use crate::state::WorldState;
@@ -56,17 +58,37 @@ pub fn handle_completion(
     let position = params.text_document_position.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source_arc();
     let source_ref = source.as_ref();
-    let prefix_lower = extract_prefix(source_ref, byte_offset).to_ascii_lowercase();
+    let prefix = extract_prefix(source_ref, byte_offset);
+    let prefix_lower = prefix.to_ascii_lowercase();
+    let in_quotes = source_ref[..byte_offset - prefix.len()].ends_with('"');
+    let in_string_or_comment = is_inside_string_or_comment(doc.tree(), byte_offset);
     let member_access_context = is_member_access_context(source_ref, byte_offset);
-    let enum_context = enum_context_at_offset(&doc.tree, source_ref, byte_offset);
+    let enum_context = enum_context_at_offset(doc.tree(), source_ref, byte_offset);
     let where_value_context =
-        where_value_completion_context_at_offset(&doc.tree, source_ref, byte_offset);
+        where_value_completion_context_at_offset(doc.tree(), source_ref, byte_offset);
     let property_context =
-        property_completion_context_at_offset(&doc.tree, source_ref, byte_offset);
+        property_completion_context_at_offset(doc.tree(), source_ref, byte_offset);
     let dot_target = dot_target_at_offset(state, &doc, source_ref, byte_offset);
+    let attribute_name_context =
+        attribute_name_context_at_offset(doc.tree(), source_ref, byte_offset);
+    let type_keyword_context = type_keyword_context_at_offset(source_ref, byte_offset);
+    let options = state.format_options(&uri);
+    let mut trigger_items = object_member_context_at_offset(&doc, source_ref, byte_offset)
+        .map(|object| {
+            collect_trigger_snippet_completions(object, source_ref, byte_offset, &options)
+        })
+        .unwrap_or_default();
+    if let Some(scope) = snippet_scope_at_offset(&doc, source_ref, byte_offset) {
+        trigger_items.extend(collect_template_snippet_completions(
+            scope,
+            source_ref,
+            byte_offset,
+            &options,
+        ));
+    }
 
     drop(doc);
 
@@ -78,6 +100,28 @@ pub fn handle_completion(
         }
     }
 
+    // Event names are completed inside their quotes above; any other string
+    // or comment (e.g. a `.` typed in a label) offers nothing.
+    if in_string_or_comment {
+        return None;
+    }
+
+    if let Some((object_kind, quoted)) = type_keyword_context {
+        let object_items =
+            collect_type_object_completions(state, object_kind, quoted, &prefix_lower);
+        if object_items.is_empty() {
+            return None;
+        }
+        return Some(CompletionResponse::Array(object_items));
+    }
+
+    if attribute_name_context {
+        let attribute_items = collect_attribute_name_completions(&prefix_lower);
+        if !attribute_items.is_empty() {
+            return Some(CompletionResponse::Array(attribute_items));
+        }
+    }
+
     if let Some(enum_context) = enum_context {
         if let Some((_type_info, option_members)) =
             resolve_option_members_from_context(state, &uri, &enum_context)
@@ -88,11 +132,14 @@ pub fn handle_completion(
             }
         }
         if let Some(enum_name) = resolve_enum_name_from_context(state, &uri, &enum_context) {
-            let enum_items = collect_enum_value_completions(state, &enum_name, &prefix_lower);
+            let enum_items =
+                collect_enum_value_completions(state, &enum_name, &prefix_lower, in_quotes);
             if !enum_items.is_empty() {
                 return Some(CompletionResponse::Array(enum_items));
             }
         }
+        // After `Qualifier::` only values make sense; no symbols or keywords
+        return None;
     }
 
     if let Some((object_kind, object_name)) = dot_target {
@@ -102,6 +149,7 @@ pub fn handle_completion(
             &object_kind,
             &object_name,
             &prefix_lower,
+            in_quotes,
         );
         if !object_items.is_empty() {
             return Some(CompletionResponse::Array(object_items));
@@ -130,55 +178,63 @@ pub fn handle_completion(
             }
         };
         if !property_items.is_empty() {
-            return Some(CompletionResponse::Array(property_items));
+            let mut items = trigger_items;
+            items.extend(property_items);
+            return Some(CompletionResponse::Array(items));
         }
     }
 
     let doc = state.documents.get(&uri)?;
-    let mut items = Vec::new();
+    let mut items = trigger_items;
 
-    // Add reachable symbols.
-    let reachable = doc.symbol_table.reachable_symbols(byte_offset);
-    for sym in reachable {
-        if !matches_prefix_ci(&sym.name, &prefix_lower) {
+    // Reachable symbols rank above keywords; a keyword spelled like a symbol
+    // (case-insensitively) is left out.
+    let mut seen = HashSet::new();
+    let mut symbol_items = Vec::new();
+    for sym in doc.symbol_table.reachable_symbols(byte_offset) {
+        if !matches_prefix_ci(&sym.name, &prefix_lower) || !seen.insert(sym.name.to_lowercase()) {
             continue;
         }
-
-        if !push_completion_item(
-            &mut items,
-            CompletionItem {
-                label: sym.name.clone(),
-                kind: Some(completion_item_kind(sym.kind)),
-                detail: sym.type_info.clone(),
-                ..Default::default()
-            },
-        ) {
-            break;
-        }
+        symbol_items.push(CompletionItem {
+            label: sym.name.clone(),
+            kind: Some(completion_item_kind(sym.kind)),
+            detail: sym.type_info.as_deref().map(format_type_info),
+            insert_text: quoted_insert_text(&sym.name, in_quotes),
+            sort_text: Some(format!("1{}", sym.name.to_lowercase())),
+            ..Default::default()
+        });
     }
 
-    // Add keywords.
+    let mut keyword_items = Vec::new();
     for &kw in al_keywords() {
-        if !matches_prefix_ci(kw, &prefix_lower) {
+        if !matches_prefix_ci(kw, &prefix_lower) || !seen.insert(kw.to_lowercase()) {
             continue;
         }
-
-        if !push_completion_item(
-            &mut items,
-            CompletionItem {
-                label: kw.to_string(),
-                kind: Some(CompletionItemKind::KEYWORD),
-                ..Default::default()
-            },
-        ) {
-            break;
-        }
+        keyword_items.push(CompletionItem {
+            label: kw.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            sort_text: Some(format!("2{}", kw.to_lowercase())),
+            ..Default::default()
+        });
     }
 
+    symbol_items.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+    keyword_items.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+    items.extend(symbol_items);
+    items.extend(keyword_items);
+
     if items.is_empty() {
         return None;
     }
 
+    if items.len() > MAX_COMPLETION_ITEMS {
+        items.truncate(MAX_COMPLETION_ITEMS);
+        return Some(CompletionResponse::List(CompletionList {
+            is_incomplete: true,
+            items,
+        }));
+    }
+
     Some(CompletionResponse::Array(items))
 }
 
@@ -209,6 +265,22 @@ fn matches_prefix_ci(candidate: &str, prefix_lower: &str) -> bool {
     true
 }
 
+/// Whether `byte_offset` lies inside a string literal or comment, rather than
+/// on one of its delimiters.
+fn is_inside_string_or_comment(tree: &tree_sitter::Tree, byte_offset: usize) -> bool {
+    let Some(node) = node_at_offset(tree, byte_offset) else {
+        return false;
+    };
+    match node.kind() {
+        "string_literal" | "block_comment" => {
+            node.start_byte() < byte_offset && byte_offset < node.end_byte()
+        }
+        // A line comment runs to the end of the line, so its end is still inside.
+        "line_comment" => node.start_byte() < byte_offset,
+        _ => false,
+    }
+}
+
 fn is_member_access_context(source: &str, byte_offset: usize) -> bool {
     let bytes = source.as_bytes();
     if bytes.is_empty() || byte_offset == 0 {
@@ -251,9 +323,16 @@ fn collect_object_member_completions(
     object_kind: &str,
     object_name: &str,
     prefix_lower: &str,
+    in_quotes: bool,
 ) -> Vec<CompletionItem> {
     if object_kind.eq_ignore_ascii_case("table") {
-        return collect_record_variable_completions(state, caller_uri, object_name, prefix_lower);
+        return collect_record_variable_completions(
+            state,
+            caller_uri,
+            object_name,
+            prefix_lower,
+            in_quotes,
+        );
     }
 
     let mut items = Vec::new();
@@ -279,7 +358,8 @@ fn collect_object_member_completions(
                         CompletionItem {
                             label: child.name.clone(),
                             kind: Some(completion_item_kind(child.kind)),
-                            detail: child.type_info.clone(),
+                            detail: child.type_info.as_deref().map(format_type_info),
+                            insert_text: quoted_insert_text(&child.name, in_quotes),
                             ..Default::default()
                         },
                     ) {
@@ -325,11 +405,15 @@ enum TableProcedureVisibility {
     Hidden,
 }
 
+/// Members of a `Record` of `table_name`: its fields (with their type as
+/// detail), the table procedures visible from `caller_uri`, and the built-in
+/// record methods.
 fn collect_record_variable_completions(
     state: &WorldState,
     caller_uri: &Url,
     table_name: &str,
     prefix_lower: &str,
+    in_quotes: bool,
 ) -> Vec<CompletionItem> {
     let mut items = Vec::new();
     let mut seen = HashSet::new();
@@ -365,7 +449,8 @@ fn collect_record_variable_completions(
                     CompletionItem {
                         label: child.name.clone(),
                         kind: Some(completion_item_kind(child.kind)),
-                        detail: child.type_info.clone(),
+                        detail: child.type_info.as_deref().map(format_type_info),
+                        insert_text: quoted_insert_text(&child.name, in_quotes),
                         ..Default::default()
                     },
                 ) {
@@ -464,6 +549,7 @@ fn collect_enum_value_completions(
     state: &WorldState,
     enum_name: &str,
     prefix_lower: &str,
+    in_quotes: bool,
 ) -> Vec<CompletionItem> {
     let mut items = Vec::new();
     let mut seen = HashSet::new();
@@ -490,7 +576,8 @@ fn collect_enum_value_completions(
                     CompletionItem {
                         label: child.name.clone(),
                         kind: Some(CompletionItemKind::ENUM_MEMBER),
-                        detail: child.type_info.clone(),
+                        detail: child.type_info.as_deref().map(format_type_info),
+                        insert_text: quoted_insert_text(&child.name, in_quotes),
                         ..Default::default()
                     },
                 ) {
@@ -559,6 +646,281 @@ fn collect_property_name_completions(scope: &str, prefix_lower: &str) -> Vec<Com
     items
 }
 
+fn collect_attribute_name_completions(prefix_lower: &str) -> Vec<CompletionItem> {
+    let mut items = Vec::new();
+    for attribute in procedure_attributes() {
+        if !matches_prefix_ci(attribute.name, prefix_lower) {
+            continue;
+        }
+        if !push_completion_item(
+            &mut items,
+            CompletionItem {
+                label: attribute.name.to_string(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some(attribute.signature.to_string()),
+                documentation: Some(Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: attribute.summary.to_string(),
+                })),
+                ..Default::default()
+            },
+        ) {
+            break;
+        }
+    }
+    items
+}
+
+/// Snippets declaring the object-level triggers `object` does not declare
+/// yet, laid out for the cursor's column and the document's indentation style.
+fn collect_trigger_snippet_completions(
+    object: &AlSymbol,
+    source: &str,
+    byte_offset: usize,
+    options: &FormatOptions,
+) -> Vec<CompletionItem> {
+    let prefix = extract_prefix(source, byte_offset);
+    let prefix_lower = prefix.to_ascii_lowercase();
+    let line_prefix = line_prefix_at_offset(source, byte_offset);
+    let line_prefix = &line_prefix[..line_prefix.len() - prefix.len()];
+    if !line_prefix.trim().is_empty() {
+        return Vec::new();
+    }
+    let AlSymbolKind::Object(object_kind) = object.kind else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    for trigger in object_triggers(object_kind.label()) {
+        let declared = object.children.iter().any(|child| {
+            matches!(child.kind, AlSymbolKind::Trigger)
+                && child.name.eq_ignore_ascii_case(trigger.name)
+        });
+        if declared
+            || !(matches_prefix_ci("trigger", &prefix_lower)
+                || matches_prefix_ci(trigger.name, &prefix_lower))
+        {
+            continue;
+        }
+        let body = format!("trigger {}\nbegin\n\t$0\nend;", trigger.signature);
+        items.push(CompletionItem {
+            label: format!("trigger {}", trigger.name),
+            kind: Some(CompletionItemKind::SNIPPET),
+            detail: Some(format!("trigger {}", trigger.signature)),
+            documentation: Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: trigger.summary.to_string(),
+            })),
+            filter_text: Some(format!("trigger {}", trigger.name)),
+            insert_text: Some(indent_snippet(&body, line_prefix, options)),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        });
+    }
+    items
+}
+
+/// Where a scaffolding snippet can be inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SnippetScope {
+    /// Outside any object declaration.
+    TopLevel,
+    /// Between member declarations of an object body.
+    ObjectBody,
+    /// Inside a procedure or trigger.
+    CodeBlock,
+}
+
+/// A scaffolding snippet; `body` is indented with one tab per level and
+/// re-indented for the cursor by [`indent_snippet`].
+struct TemplateSnippet {
+    label: &'static str,
+    detail: &'static str,
+    scope: SnippetScope,
+    body: &'static str,
+}
+
+const TEMPLATE_SNIPPETS: &[TemplateSnippet] = &[
+    TemplateSnippet {
+        label: "codeunit",
+        detail: "Codeunit object",
+        scope: SnippetScope::TopLevel,
+        body: "codeunit ${1:50100} ${2:MyCodeunit}\n{\n\ttrigger OnRun()\n\tbegin\n\t\t$0\n\tend;\n}",
+    },
+    TemplateSnippet {
+        label: "table",
+        detail: "Table object with a field",
+        scope: SnippetScope::TopLevel,
+        body: "table ${1:50100} ${2:MyTable}\n{\n\tfields\n\t{\n\t\tfield(${3:1}; ${4:\"No.\"}; ${5:Code[20]})\n\t\t{\n\t\t\t$0\n\t\t}\n\t}\n}",
+    },
+    TemplateSnippet {
+        label: "page",
+        detail: "Page object",
+        scope: SnippetScope::TopLevel,
+        body: "page ${1:50100} ${2:MyPage}\n{\n\tPageType = ${3:Card};\n\tSourceTable = ${4:MyTable};\n\n\tlayout\n\t{\n\t\tarea(Content)\n\t\t{\n\t\t\t$0\n\t\t}\n\t}\n}",
+    },
+    TemplateSnippet {
+        label: "procedure",
+        detail: "Procedure declaration",
+        scope: SnippetScope::ObjectBody,
+        body: "procedure ${1:Name}(${2})\nbegin\n\t$0\nend;",
+    },
+    TemplateSnippet {
+        label: "if",
+        detail: "if ... then begin ... end",
+        scope: SnippetScope::CodeBlock,
+        body: "if ${1:Condition} then begin\n\t$0\nend;",
+    },
+];
+
+/// The snippet scope at `byte_offset`, when the cursor starts a line (only
+/// the word being typed precedes it).
+fn snippet_scope_at_offset(
+    doc: &DocumentState,
+    source: &str,
+    byte_offset: usize,
+) -> Option<SnippetScope> {
+    let line_prefix = line_prefix_at_offset(source, byte_offset);
+    let prefix_len = extract_prefix(source, byte_offset).len();
+    if !line_prefix[..line_prefix.len() - prefix_len]
+        .trim()
+        .is_empty()
+    {
+        return None;
+    }
+    if object_member_context_at_offset(doc, source, byte_offset).is_some() {
+        return Some(SnippetScope::ObjectBody);
+    }
+
+    let contains = |sym: &AlSymbol| sym.start_byte <= byte_offset && byte_offset <= sym.end_byte;
+    let Some(object) = doc.symbol_table.symbols.iter().find(|sym| contains(sym)) else {
+        return Some(SnippetScope::TopLevel);
+    };
+    object
+        .children
+        .iter()
+        .any(|child| {
+            matches!(child.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger) && contains(child)
+        })
+        .then_some(SnippetScope::CodeBlock)
+}
+
+/// Scaffolding snippets for `scope` whose keyword matches the word being typed.
+fn collect_template_snippet_completions(
+    scope: SnippetScope,
+    source: &str,
+    byte_offset: usize,
+    options: &FormatOptions,
+) -> Vec<CompletionItem> {
+    let prefix = extract_prefix(source, byte_offset);
+    let prefix_lower = prefix.to_ascii_lowercase();
+    let line_prefix = line_prefix_at_offset(source, byte_offset);
+    let line_prefix = &line_prefix[..line_prefix.len() - prefix.len()];
+
+    TEMPLATE_SNIPPETS
+        .iter()
+        .filter(|snippet| snippet.scope == scope && matches_prefix_ci(snippet.label, &prefix_lower))
+        .map(|snippet| CompletionItem {
+            label: snippet.label.to_string(),
+            kind: Some(CompletionItemKind::SNIPPET),
+            detail: Some(snippet.detail.to_string()),
+            insert_text: Some(indent_snippet(snippet.body, line_prefix, options)),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// The object whose body contains `byte_offset`, when the cursor sits
+/// between member declarations (where a trigger or procedure can start).
+fn object_member_context_at_offset<'a>(
+    doc: &'a DocumentState,
+    source: &str,
+    byte_offset: usize,
+) -> Option<&'a AlSymbol> {
+    let word_start = byte_offset - extract_prefix(source, byte_offset).len();
+    let mut node = node_at_offset(doc.tree(), word_start)?;
+    while matches!(node.kind(), "identifier" | "ERROR") {
+        node = node.parent()?;
+    }
+    AlObjectKind::from_node_kind(node.kind())?;
+    let body_start = node.start_byte() + source[node.start_byte()..node.end_byte()].find('{')?;
+    if word_start <= body_start || word_start >= node.end_byte() {
+        return None;
+    }
+    doc.symbol_table
+        .symbols
+        .iter()
+        .find(|object| object.start_byte == node.start_byte())
+}
+
+/// When the cursor follows a type keyword in a declaration (`Cust: Record |`,
+/// `Mgt: Codeunit "Sales|`), the object kind it names and whether the name
+/// being typed already has an opening quote.
+fn type_keyword_context_at_offset(
+    source: &str,
+    byte_offset: usize,
+) -> Option<(AlObjectKind, bool)> {
+    let before = &source[..byte_offset.min(source.len())];
+    let before = &before[..before.len() - extract_prefix(source, byte_offset).len()];
+    let (before, quoted) = match before.strip_suffix('"') {
+        Some(rest) => (rest, true),
+        None => (before, false),
+    };
+    let trimmed = before.trim_end_matches([' ', '\t']);
+    if trimmed.len() == before.len() {
+        return None;
+    }
+    let keyword_start = trimmed
+        .rfind(|c: char| !c.is_ascii_alphanumeric())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let kind = AlObjectKind::from_type_keyword(&trimmed[keyword_start..])?;
+    trimmed[..keyword_start]
+        .trim_end()
+        .ends_with(':')
+        .then_some((kind, quoted))
+}
+
+/// Objects of `object_kind` declared in any loaded document, quoted where
+/// the name requires it.
+fn collect_type_object_completions(
+    state: &WorldState,
+    object_kind: AlObjectKind,
+    quoted: bool,
+    prefix_lower: &str,
+) -> Vec<CompletionItem> {
+    let mut items = Vec::new();
+    let mut seen = HashSet::new();
+    for (_, symbol) in state.objects_of_kind(object_kind) {
+        if !matches_prefix_ci(&symbol.name, prefix_lower)
+            || !seen.insert(symbol.name.to_lowercase())
+        {
+            continue;
+        }
+        let insert_text =
+            quoted_insert_text(&symbol.name, quoted).unwrap_or_else(|| symbol.name.clone());
+        let kind = if object_kind == AlObjectKind::Interface {
+            CompletionItemKind::INTERFACE
+        } else {
+            CompletionItemKind::CLASS
+        };
+        if !push_completion_item(
+            &mut items,
+            CompletionItem {
+                label: symbol.name.clone(),
+                kind: Some(kind),
+                detail: Some(format!("{} {}", object_kind.label(), symbol.name)),
+                insert_text: Some(insert_text),
+                ..Default::default()
+            },
+        ) {
+            return items;
+        }
+    }
+    items
+}
+
 fn collect_object_name_value_completions(
     state: &WorldState,
     object_kind: &str,
@@ -693,6 +1055,27 @@ fn collect_where_value_expression_completions(prefix_lower: &str) -> Vec<Complet
     items
 }
 
+/// Value names declared by the enum objects named `enum_name`, in declaration order.
+pub(crate) fn enum_value_names(state: &WorldState, enum_name: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    visit_matching_objects(
+        state,
+        "enum",
+        enum_name,
+        |_target_uri, _doc, object_symbol| {
+            names.extend(
+                object_symbol
+                    .children
+                    .iter()
+                    .filter(|child| matches!(child.kind, AlSymbolKind::EnumValue))
+                    .map(|child| child.name.clone()),
+            );
+            false
+        },
+    );
+    names
+}
+
 pub(crate) fn resolve_enum_name_from_context(
     state: &WorldState,
     uri: &lsp_types::Url,
@@ -710,7 +1093,7 @@ pub(crate) fn resolve_enum_name_from_context(
             let doc = state.documents.get(uri)?;
             let source = doc.source();
             let ctx = identifier_context_at_offset(
-                &doc.tree,
+                doc.tree(),
                 &source,
                 &doc.symbol_table,
                 *qualifier_byte_offset,
@@ -730,7 +1113,7 @@ pub(crate) fn resolve_enum_name_from_context(
             let doc = state.documents.get(uri)?;
             let source = doc.source();
             let ctx = identifier_context_at_offset(
-                &doc.tree,
+                doc.tree(),
                 &source,
                 &doc.symbol_table,
                 *object_byte_offset,
@@ -803,7 +1186,7 @@ pub(crate) fn resolve_option_members_from_context(
             let doc = state.documents.get(uri)?;
             let source = doc.source();
             let ctx = identifier_context_at_offset(
-                &doc.tree,
+                doc.tree(),
                 &source,
                 &doc.symbol_table,
                 *qualifier_byte_offset,
@@ -872,6 +1255,16 @@ fn find_table_field_enum_type(
     None
 }
 
+/// AL-quoted insert text for names that are not plain identifiers (`"My Codeunit"`).
+/// `None` when the name needs no quotes or the cursor already follows a `"`.
+fn quoted_insert_text(name: &str, in_quotes: bool) -> Option<String> {
+    if in_quotes || is_simple_identifier_name(name) {
+        None
+    } else {
+        Some(format!("\"{name}\""))
+    }
+}
+
 fn is_simple_identifier_name(name: &str) -> bool {
     let mut chars = name.chars();
     let Some(first) = chars.next() else {
@@ -985,7 +1378,7 @@ fn dot_target_at_offset(
     byte_offset: usize,
 ) -> Option<(String, String)> {
     if let Some((object_node, scope_byte)) =
-        object_node_for_member_access(&doc.tree, source, byte_offset)
+        object_node_for_member_access(doc.tree(), source, byte_offset)
     {
         if let Some(target) =
             resolve_object_type_from_expression(state, doc, source, object_node, scope_byte, 0)
@@ -1013,7 +1406,7 @@ pub(crate) fn member_access_target_at_offset(
     source: &str,
     byte_offset: usize,
 ) -> Option<MemberAccessTarget> {
-    let node = node_at_offset(&doc.tree, byte_offset)?;
+    let node = node_at_offset(doc.tree(), byte_offset)?;
     if !matches!(node.kind(), "identifier" | "quoted_identifier") {
         return None;
     }
@@ -1194,7 +1587,7 @@ fn resolve_object_type_from_symbol_name(
         .or_else(|| fallback_local_variable_type_from_source(source, object_name, scope_byte))
         .or_else(|| fallback_procedure_return_type_from_source(source, object_name));
     let type_info = type_info_owned.as_deref()?;
-    let (object_kind, object_name) = extract_type_object_name(type_info)?;
+    let (object_kind, object_name) = extract_value_type_name(type_info)?;
     Some((object_kind.to_string(), object_name.to_string()))
 }
 
@@ -1259,7 +1652,7 @@ pub(crate) fn resolve_object_type_from_expression(
             )?;
             let member_type =
                 find_object_member_type(state, &object_kind, &object_name, &method_name, true)?;
-            let (member_kind, member_name) = extract_type_object_name(&member_type)?;
+            let (member_kind, member_name) = extract_value_type_name(&member_type)?;
             Some((member_kind.to_string(), member_name.to_string()))
         }
         "member_access" => {
@@ -1276,13 +1669,72 @@ pub(crate) fn resolve_object_type_from_expression(
             )?;
             let member_type =
                 find_object_member_type(state, &object_kind, &object_name, &member_name, false)?;
-            let (member_kind, member_name) = extract_type_object_name(&member_type)?;
+            let (member_kind, member_name) = extract_value_type_name(&member_type)?;
             Some((member_kind.to_string(), member_name.to_string()))
         }
         _ => None,
     }
 }
 
+/// Declared type of `expr_node`: a variable's type, a procedure call's return
+/// type, or the type of the member reached by one member access or method
+/// call (`GetCustomer().Name` is the type of the `Name` field).
+pub(crate) fn infer_expression_type(
+    state: &WorldState,
+    doc: &DocumentState,
+    source: &str,
+    expr_node: tree_sitter::Node<'_>,
+    scope_byte: usize,
+) -> Option<String> {
+    let expr_node = unwrap_primary_expression(expr_node);
+    match expr_node.kind() {
+        "identifier" | "quoted_identifier" | "function_call" => {
+            let name_node = if expr_node.kind() == "function_call" {
+                expr_node
+                    .child_by_field_name("function")
+                    .or_else(|| expr_node.child_by_field_name("name"))?
+            } else {
+                expr_node
+            };
+            doc.symbol_table
+                .lookup_in_scope(&extract_name(name_node, source), scope_byte)
+                .into_iter()
+                .find(|sym| {
+                    matches!(
+                        sym.kind,
+                        AlSymbolKind::Variable
+                            | AlSymbolKind::Parameter
+                            | AlSymbolKind::Field
+                            | AlSymbolKind::Procedure
+                    )
+                })
+                .and_then(|sym| sym.type_info.clone())
+        }
+        "member_access" | "method_call" => {
+            let is_method_call = expr_node.kind() == "method_call";
+            let object_node = expr_node.child_by_field_name("object")?;
+            let member_node =
+                expr_node.child_by_field_name(if is_method_call { "method" } else { "member" })?;
+            let (object_kind, object_name) = resolve_object_type_from_expression(
+                state,
+                doc,
+                source,
+                object_node,
+                expr_node.start_byte(),
+                0,
+            )?;
+            find_object_member_type(
+                state,
+                &object_kind,
+                &object_name,
+                &extract_name(member_node, source),
+                is_method_call,
+            )
+        }
+        _ => None,
+    }
+}
+
 fn fallback_procedure_return_type_from_source(
     source: &str,
     procedure_name: &str,
@@ -1502,22 +1954,21 @@ where
         }
     }
 
-    if visited {
-        return false;
-    }
-
+    // Objects missing from the index are found by scanning; extensions
+    // (`tableextension X extends Customer`) are visited after the objects
+    // they extend, since their members belong to the base object too
     for entry in state.documents.iter() {
         let doc = entry.value();
         for object_symbol in &doc.symbol_table.symbols {
             let AlSymbolKind::Object(kind) = object_symbol.kind else {
                 continue;
             };
-            if !kind.label().eq_ignore_ascii_case(object_kind)
-                || !object_symbol.name.eq_ignore_ascii_case(object_name)
+            let is_object = !visited
+                && kind.label().eq_ignore_ascii_case(object_kind)
+                && object_symbol.name.eq_ignore_ascii_case(object_name);
+            if (is_object || extends_object(kind, object_symbol, object_kind, object_name))
+                && visit(entry.key(), doc, object_symbol)
             {
-                continue;
-            }
-            if visit(entry.key(), doc, object_symbol) {
                 return true;
             }
         }
@@ -1526,6 +1977,22 @@ where
     false
 }
 
+/// Whether `symbol`, an object of `kind`, extends the `object_kind` object
+/// named `object_name`.
+pub(crate) fn extends_object(
+    kind: AlObjectKind,
+    symbol: &AlSymbol,
+    object_kind: &str,
+    object_name: &str,
+) -> bool {
+    kind.extended_kind()
+        .is_some_and(|base| base.label().eq_ignore_ascii_case(object_kind))
+        && symbol
+            .extends
+            .as_deref()
+            .is_some_and(|base| base.eq_ignore_ascii_case(object_name))
+}
+
 fn supports_symbol_object_lookup(object_kind: &str) -> bool {
     matches!(
         object_kind.to_ascii_lowercase().as_str(),
@@ -1775,6 +2242,90 @@ fn property_completion_context_at_offset(
     None
 }
 
+/// Returns true when the cursor is on the name of an attribute decorating a
+/// procedure, e.g. `[Integ|` on the line above `procedure OnFoo()`.
+fn attribute_name_context_at_offset(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    byte_offset: usize,
+) -> bool {
+    for probe in [
+        byte_offset.min(source.len()),
+        byte_offset.saturating_sub(1).min(source.len()),
+    ] {
+        let Some(node) = node_at_offset(tree, probe) else {
+            continue;
+        };
+        let mut current = Some(node);
+        while let Some(n) = current {
+            match n.kind() {
+                "attribute" => {
+                    let mut cursor = n.walk();
+                    let name = n
+                        .named_children(&mut cursor)
+                        .find(|child| child.kind() == "identifier");
+                    return name.is_none_or(|name| byte_offset <= name.end_byte());
+                }
+                "ERROR" if is_attribute_decoration_error(n, source, byte_offset) => {
+                    return true;
+                }
+                "attribute_arguments" | "block" | "var_section" => break,
+                _ => {}
+            }
+            current = n.parent();
+        }
+    }
+    false
+}
+
+/// An unfinished attribute such as `[Integ` does not parse as an `attribute`
+/// node; it surfaces as an ERROR in the object body or procedure header.
+fn is_attribute_decoration_error(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+    byte_offset: usize,
+) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    let decoration_position = AlObjectKind::from_node_kind(parent.kind()).is_some()
+        || matches!(
+            parent.kind(),
+            "procedure_declaration" | "trigger_declaration"
+        );
+    if !decoration_position || byte_offset <= node.start_byte() {
+        return false;
+    }
+    let end = byte_offset.min(node.end_byte()).min(source.len());
+    let Some(text) = source.get(node.start_byte()..end) else {
+        return false;
+    };
+    let Some(bracket) = text.rfind('[') else {
+        return false;
+    };
+    text[bracket + 1..]
+        .trim_start()
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Returns the attribute name when the cursor is on it, e.g. `[Integ|rationEvent(...)]`.
+pub(crate) fn attribute_name_at_offset(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    byte_offset: usize,
+) -> Option<String> {
+    let node = node_at_offset(tree, byte_offset)?;
+    if node.kind() != "identifier" {
+        return None;
+    }
+    let parent = node.parent()?;
+    if parent.kind() != "attribute" {
+        return None;
+    }
+    Some(extract_name(node, source))
+}
+
 pub(crate) fn enum_context_at_offset(
     tree: &tree_sitter::Tree,
     source: &str,
@@ -2063,7 +2614,7 @@ fn find_ancestor_of_kind<'a>(
     None
 }
 
-fn enum_name_from_enum_value_declaration(
+pub(crate) fn enum_name_from_enum_value_declaration(
     enum_value_decl: tree_sitter::Node<'_>,
     source: &str,
 ) -> Option<String> {
@@ -2136,6 +2687,92 @@ mod tests {
         (line, character)
     }
 
+    #[test]
+    fn test_completion_dedupes_keywords_and_ranks_symbols_first() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        "Begin": Integer;
+        EntryNo: Integer;
+    begin
+        beg
+        e
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "        beg");
+        let items = items_from(
+            handle_completion(&state, make_completion_params(uri.clone(), line, character))
+                .expect("expected completions"),
+        );
+        let begins: Vec<_> = items
+            .iter()
+            .filter(|item| item.label.eq_ignore_ascii_case("begin"))
+            .collect();
+        assert_eq!(begins.len(), 1, "got: {begins:?}");
+        assert_eq!(begins[0].kind, Some(CompletionItemKind::VARIABLE));
+
+        let (line, character) = cursor_after(source, "        e");
+        let items = items_from(
+            handle_completion(&state, make_completion_params(uri, line, character))
+                .expect("expected completions"),
+        );
+        assert_eq!(items[0].label, "EntryNo");
+        let first_keyword = items
+            .iter()
+            .position(|item| item.kind == Some(CompletionItemKind::KEYWORD))
+            .expect("expected keywords");
+        assert!(items[first_keyword..]
+            .iter()
+            .all(|item| item.kind == Some(CompletionItemKind::KEYWORD)));
+        assert!(items
+            .windows(2)
+            .all(|pair| pair[0].sort_text <= pair[1].sort_text));
+    }
+
+    #[test]
+    fn test_completion_suppressed_inside_strings_and_comments() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        FileName: Label 'file.txt';
+        Customer: Record Customer;
+    begin
+        // Customer.
+        Message('Customer.');
+        Cust
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        for marker in ["'file.", "// Customer.", "Message('Customer."] {
+            let (line, character) = cursor_after(source, marker);
+            assert!(
+                handle_completion(&state, make_completion_params(uri.clone(), line, character))
+                    .is_none(),
+                "expected no completion after {marker:?}"
+            );
+        }
+
+        let (line, character) = cursor_after(source, "        Cust");
+        let items = items_from(
+            handle_completion(&state, make_completion_params(uri, line, character))
+                .expect("code outside strings still completes"),
+        );
+        assert!(items.iter().any(|item| item.label == "Customer"));
+    }
+
     #[test]
     fn test_completion_dot_record_variable_cross_doc() {
         let table_source = r#"table 18 Customer
@@ -2155,39 +2792,92 @@ mod tests {
 {
     procedure DoWork()
     var
-        Cust: Record Customer;
+        Cust: Record Customer;
+    begin
+        Cust.
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        // Cursor after `Cust.` (line 6, col 13)
+        let params = make_completion_params(codeunit_uri, 6, 13);
+        let result = handle_completion(&state, params);
+        assert!(result.is_some(), "expected completion result");
+        let labels: Vec<String> = items_from(result.unwrap())
+            .into_iter()
+            .map(|i| i.label)
+            .collect();
+        assert!(
+            labels.iter().any(|l| l == "Name"),
+            "expected table field in completion, got: {labels:?}"
+        );
+        assert!(
+            labels.iter().any(|l| l == "HasAddress"),
+            "expected table procedure in completion, got: {labels:?}"
+        );
+    }
+
+    #[test]
+    fn test_completion_dot_record_variable_offers_fields_with_types() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20])
+        {
+        }
+        field(2; Name; Text[100])
+        {
+        }
+    }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Rec: Record Customer;
     begin
-        Cust.
+        Rec.
     end;
 }"#;
-        let table_uri = Url::parse("file:///test/customer.al").unwrap();
         let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
-
         let state = WorldState::new();
-        state
-            .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
         state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
+            Url::parse("file:///test/customer.al").unwrap(),
+            DocumentState::new(table_source),
         );
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
-        // Cursor after `Cust.` (line 6, col 13)
-        let params = make_completion_params(codeunit_uri, 6, 13);
-        let result = handle_completion(&state, params);
-        assert!(result.is_some(), "expected completion result");
-        let labels: Vec<String> = items_from(result.unwrap())
-            .into_iter()
-            .map(|i| i.label)
-            .collect();
-        assert!(
-            labels.iter().any(|l| l == "Name"),
-            "expected table field in completion, got: {labels:?}"
-        );
-        assert!(
-            labels.iter().any(|l| l == "HasAddress"),
-            "expected table procedure in completion, got: {labels:?}"
+        let (line, character) = cursor_after(codeunit_source, "        Rec.");
+        let items = items_from(
+            handle_completion(
+                &state,
+                make_completion_params(codeunit_uri, line, character),
+            )
+            .expect("expected completion result"),
         );
+        let field = |label: &str| {
+            items
+                .iter()
+                .find(|item| item.label == label)
+                .unwrap_or_else(|| panic!("expected field {label}"))
+        };
+        assert_eq!(field("No.").kind, Some(CompletionItemKind::FIELD));
+        assert_eq!(field("No.").detail.as_deref(), Some("Code[20]"));
+        assert_eq!(field("No.").insert_text.as_deref(), Some("\"No.\""));
+        assert_eq!(field("Name").kind, Some(CompletionItemKind::FIELD));
+        assert_eq!(field("Name").detail.as_deref(), Some("Text[100]"));
     }
 
     #[test]
@@ -2219,11 +2909,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor after `Na` in `Cust.Na` (line 6, col 15)
         let params = make_completion_params(codeunit_uri, 6, 15);
@@ -2272,17 +2961,16 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_after(codeunit_source, "Cust.\"Doc");
         let doc = state.documents.get(&codeunit_uri).unwrap();
         let source_text = doc.source();
         let byte_offset =
-            crate::convert::lsp_position_to_byte_offset(&doc.rope, Position { line, character })
+            crate::convert::lsp_position_to_byte_offset(doc.rope(), Position { line, character })
                 .unwrap();
         assert_eq!(
             parse_object_before_member_fragment(source_text, byte_offset),
@@ -2295,14 +2983,76 @@ mod tests {
         let params = make_completion_params(codeunit_uri, line, character);
         let result = handle_completion(&state, params);
         assert!(result.is_some(), "expected completion result");
-        let labels: Vec<String> = items_from(result.unwrap())
-            .into_iter()
-            .map(|i| i.label)
-            .collect();
-        assert!(
-            labels.iter().any(|l| l == "Document Type"),
-            "expected quoted field completion, got: {labels:?}"
+        let items = items_from(result.unwrap());
+        let item = items
+            .iter()
+            .find(|i| i.label == "Document Type")
+            .unwrap_or_else(|| panic!("expected quoted field completion, got: {items:?}"));
+        // The opening quote is already typed
+        assert_eq!(item.insert_text, None);
+    }
+
+    #[test]
+    fn test_completion_quotes_names_with_spaces() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        "My Codeunit": Integer;
+        MyCounter: Integer;
+    begin
+        My
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "begin\n        My");
+        let items = items_from(
+            handle_completion(&state, make_completion_params(uri, line, character)).unwrap(),
+        );
+        let quoted = items
+            .iter()
+            .find(|i| i.label == "My Codeunit")
+            .unwrap_or_else(|| panic!("expected My Codeunit, got: {items:?}"));
+        assert_eq!(quoted.insert_text.as_deref(), Some("\"My Codeunit\""));
+        let plain = items.iter().find(|i| i.label == "MyCounter").unwrap();
+        assert_eq!(plain.insert_text, None);
+    }
+
+    #[test]
+    fn test_completion_offers_only_enclosing_procedure_locals() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure First()
+    var
+        Temp: Integer;
+    begin
+        Te
+    end;
+
+    procedure Second()
+    var
+        Temp: Text;
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "begin\n        Te");
+        let items = items_from(
+            handle_completion(&state, make_completion_params(uri, line, character)).unwrap(),
         );
+        let temps: Vec<_> = items.iter().filter(|i| i.label == "Temp").collect();
+        assert_eq!(temps.len(), 1, "got: {items:?}");
+        assert_eq!(temps[0].detail.as_deref(), Some("Integer"));
     }
 
     #[test]
@@ -2345,11 +3095,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_after(codeunit_source, "Cust.");
         let params = make_completion_params(codeunit_uri, line, character);
@@ -2405,11 +3154,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_after(codeunit_source, "Cust.");
         let params = make_completion_params(codeunit_uri, line, character);
@@ -2452,11 +3200,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_after(codeunit_source, "RecParam.");
         let params = make_completion_params(codeunit_uri, line, character);
@@ -2495,7 +3242,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_completion_params(uri, 10, 15);
         let result = handle_completion(&state, params);
@@ -2533,7 +3280,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_completion_params(uri, 10, 15);
         let result = handle_completion(&state, params);
@@ -2571,7 +3318,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Cust.Tab");
         let params = make_completion_params(uri, line, character);
@@ -2602,7 +3349,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Tags.");
         let params = make_completion_params(uri, line, character);
@@ -2637,7 +3384,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Tags.");
         let params = make_completion_params(uri, line, character);
@@ -2672,7 +3419,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Txt.");
         let params = make_completion_params(uri, line, character);
@@ -2705,7 +3452,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Guid.");
         let params = make_completion_params(uri, line, character);
@@ -2736,7 +3483,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "D.");
         let params = make_completion_params(uri, line, character);
@@ -2771,7 +3518,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "StreamIn.");
         let params = make_completion_params(uri, line, character);
@@ -2806,7 +3553,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "RecIdentifier.");
         let params = make_completion_params(uri, line, character);
@@ -2841,7 +3588,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "AnyValue.");
         let params = make_completion_params(uri, line, character);
@@ -2876,7 +3623,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "SessionCfg.");
         let params = make_completion_params(uri, line, character);
@@ -2911,7 +3658,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Notice.");
         let params = make_completion_params(uri, line, character);
@@ -2946,7 +3693,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "ProgressDlg.");
         let params = make_completion_params(uri, line, character);
@@ -2981,7 +3728,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "ModInfo.");
         let params = make_completion_params(uri, line, character);
@@ -3014,7 +3761,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Session.");
         let params = make_completion_params(uri, line, character);
@@ -3049,7 +3796,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "SecretVal.");
         let params = make_completion_params(uri, line, character);
@@ -3094,7 +3841,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (page_line, page_char) = cursor_after(source, "PageVar.");
         let page_labels: Vec<String> = items_from(
@@ -3238,7 +3985,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "E.");
         let labels: Vec<String> = items_from(
@@ -3269,7 +4016,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Choice.");
         let labels: Vec<String> = items_from(
@@ -3308,7 +4055,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (req_line, req_char) = cursor_after(source, "ReqPage.");
         let req_labels: Vec<String> = items_from(
@@ -3418,7 +4165,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (db_line, db_char) = cursor_after(source, "Database.");
         let db_result = handle_completion(
@@ -3462,7 +4209,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (si_line, si_char) = cursor_after(source, "SessionInformation.");
         let si_result = handle_completion(
@@ -3519,7 +4266,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (fpb_line, fpb_char) = cursor_after(source, "FPB.");
         let fpb_labels: Vec<String> = items_from(
@@ -3604,7 +4351,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (na_line, na_char) = cursor_after(source, "NavApp.");
         let na_labels: Vec<String> = items_from(
@@ -3662,7 +4409,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (m_line, m_char) = cursor_after(source, "\n        M.");
         let m_labels: Vec<String> = items_from(
@@ -3713,7 +4460,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "MS.Item(1).");
         let result = handle_completion(&state, make_completion_params(uri, line, character));
@@ -3741,7 +4488,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Version.Create('1.0.0.0').");
         let result = handle_completion(&state, make_completion_params(uri, line, character));
@@ -3771,7 +4518,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "RecIdentifier.GetRecord().");
         let params = make_completion_params(uri, line, character);
@@ -3802,7 +4549,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "RecIdentifier.GetRecord.");
         let params = make_completion_params(uri, line, character);
@@ -3833,7 +4580,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Stamp.Date().");
         let params = make_completion_params(uri, line, character);
@@ -3864,7 +4611,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Stamp.Date.");
         let params = make_completion_params(uri, line, character);
@@ -3895,7 +4642,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Tags.Keys().");
         let params = make_completion_params(uri, line, character);
@@ -3926,7 +4673,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Tags.Keys.");
         let params = make_completion_params(uri, line, character);
@@ -3965,7 +4712,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "HelperFunc.");
         let doc = state.documents.get(&uri).unwrap();
@@ -3975,7 +4722,7 @@ codeunit 50100 Test
             Some("Interface \"Demo IFunctions\"".to_string())
         );
         let byte_offset =
-            crate::convert::lsp_position_to_byte_offset(&doc.rope, Position { line, character })
+            crate::convert::lsp_position_to_byte_offset(doc.rope(), Position { line, character })
                 .unwrap();
         assert_eq!(source_text.as_bytes()[byte_offset - 1], b'.');
         assert_eq!(
@@ -4035,7 +4782,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "HelperFunc().");
         let doc = state.documents.get(&uri).unwrap();
@@ -4045,7 +4792,7 @@ codeunit 50100 Test
             Some("Interface \"Demo IFunctions\"".to_string())
         );
         let byte_offset =
-            crate::convert::lsp_position_to_byte_offset(&doc.rope, Position { line, character })
+            crate::convert::lsp_position_to_byte_offset(doc.rope(), Position { line, character })
                 .unwrap();
         assert_eq!(source_text.as_bytes()[byte_offset - 1], b'.');
         assert_eq!(
@@ -4095,7 +4842,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_completion_params(uri, 4, 17);
         let result = handle_completion(&state, params);
@@ -4123,7 +4870,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "PAGE.");
         let params = make_completion_params(uri, line, character);
@@ -4152,7 +4899,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "PAGE.");
         let params = make_completion_params(uri, line, character);
@@ -4193,7 +4940,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "CurrentState.");
         let params = make_completion_params(uri, line, character);
@@ -4226,7 +4973,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_completion_params(uri, 4, 15);
         let result = handle_completion(&state, params);
@@ -4256,7 +5003,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_completion_params(uri, 6, 10);
         let result = handle_completion(&state, params);
@@ -4304,7 +5051,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `MyEnum::` on line 16, col 21
         let params = make_completion_params(uri, 16, 21);
@@ -4324,6 +5071,65 @@ codeunit 50100 Test
         );
     }
 
+    #[test]
+    fn test_completion_enum_values_after_quoted_enum_name() {
+        let source = r#"enum 50100 "Shipping Method"
+{
+    value(0; Standard)
+    {
+    }
+    value(1; "Next Day")
+    {
+    }
+}
+
+codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+    begin
+        X := "Shipping Method"::;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/all.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        // Cursor after `"Shipping Method"::` on line 16
+        let params = make_completion_params(uri, 16, 32);
+        let labels: Vec<String> = items_from(handle_completion(&state, params).unwrap())
+            .into_iter()
+            .map(|i| i.label)
+            .collect();
+        assert_eq!(labels, vec!["Standard", "Next Day"]);
+    }
+
+    #[test]
+    fn test_completion_after_double_colon_without_enum() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+    begin
+        X := X::;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/all.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        // Cursor after `X::`: no enum values, and no symbols or keywords either
+        let params = make_completion_params(uri, 6, 16);
+        let result = handle_completion(&state, params);
+        assert!(result.is_none(), "expected no completions, got: {result:?}");
+    }
+
     #[test]
     fn test_completion_enum_values_prefix_filter() {
         let source = r#"enum 50100 MyEnum
@@ -4349,7 +5155,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `Fi` on line 16, col 23
         let params = make_completion_params(uri, 16, 23);
@@ -4406,14 +5212,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri, DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri, DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor after `::` on line 6, col 30
         let params = make_completion_params(codeunit_uri, 6, 30);
@@ -4448,7 +5253,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Choice::");
         let labels: Vec<String> = items_from(
@@ -4486,7 +5291,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (global_line, global_character) = cursor_after(source, "GlobalChoice.");
         let global_labels: Vec<String> = items_from(
@@ -4535,7 +5340,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (global_line, global_character) = cursor_after(source, "GlobalChoice::");
         let global_labels: Vec<String> = items_from(
@@ -4587,7 +5392,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (param_enum_line, param_enum_char) = cursor_after(source, "OptionParameter::");
         let param_enum_labels: Vec<String> = items_from(
@@ -4670,7 +5475,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (enum_line, enum_char) = cursor_after(source, "GlobalChoice::");
         let enum_labels: Vec<String> = items_from(
@@ -4720,7 +5525,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (enum_line, enum_char) = cursor_after(source, "ActionKind::");
         let enum_labels: Vec<String> = items_from(
@@ -4784,7 +5589,7 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "CurrPage.Host.");
         let labels: Vec<String> = items_from(
@@ -4847,14 +5652,13 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri, DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri, DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_after(codeunit_source, "\"KDS Display/Printing\"::");
         let params = make_completion_params(codeunit_uri, line, character);
@@ -4895,10 +5699,10 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(publisher_uri, DocumentState::new(publisher_source).unwrap());
+            .insert(publisher_uri, DocumentState::new(publisher_source));
         state.documents.insert(
             subscriber_uri.clone(),
-            DocumentState::new(subscriber_source).unwrap(),
+            DocumentState::new(subscriber_source),
         );
 
         let (line, character) = cursor_after(subscriber_source, "ObjectType::Codeunit, ");
@@ -4936,7 +5740,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Codeunit::\"My Publisher\", '");
         let params = make_completion_params(uri, line, character);
@@ -4962,7 +5766,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `Da` on line 2
         let params = make_completion_params(uri, 2, 6);
@@ -4988,7 +5792,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `Si` on line 2
         let params = make_completion_params(uri, 2, 6);
@@ -5014,7 +5818,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `=` on line 2
         let params = make_completion_params(uri, 2, 25);
@@ -5040,7 +5844,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `=` on line 2
         let params = make_completion_params(uri, 2, 21);
@@ -5072,7 +5876,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `Ca` on line 6
         let params = make_completion_params(uri, 6, 14);
@@ -5104,7 +5908,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_completion_params(uri, 6, 14);
         let result = handle_completion(&state, params);
@@ -5135,7 +5939,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor after `=` on line 6
         let params = make_completion_params(uri, 6, 26);
@@ -5171,10 +5975,10 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
+            .insert(table_uri, DocumentState::new(table_source));
         state
             .documents
-            .insert(owner_uri.clone(), DocumentState::new(owner_source).unwrap());
+            .insert(owner_uri.clone(), DocumentState::new(owner_source));
 
         // Cursor after `=` on line 6
         let params = make_completion_params(owner_uri, 6, 28);
@@ -5206,7 +6010,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "TableNo = ");
         let params = make_completion_params(uri, line, character);
@@ -5240,7 +6044,7 @@ codeunit 50101 "My Subscriber"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "\"Object Type\" = C");
         let params = make_completion_params(uri, line, character);
@@ -5259,4 +6063,223 @@ codeunit 50101 "My Subscriber"
             "did not expect FIELD/FILTER for C prefix, got: {labels:?}"
         );
     }
+
+    #[test]
+    fn test_completion_attribute_names_above_procedure() {
+        let source = r#"codeunit 50100 Test
+{
+    [Int
+    procedure OnFoo()
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "[Int");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        assert!(result.is_some(), "expected completion result");
+        let labels: Vec<String> = items_from(result.unwrap())
+            .into_iter()
+            .map(|i| i.label)
+            .collect();
+        assert!(
+            labels.iter().any(|l| l == "IntegrationEvent"),
+            "expected IntegrationEvent attribute, got: {labels:?}"
+        );
+        assert!(
+            labels.iter().any(|l| l == "InternalEvent"),
+            "expected InternalEvent attribute, got: {labels:?}"
+        );
+        assert!(
+            !labels.iter().any(|l| l == "TryFunction"),
+            "did not expect TryFunction for Int prefix, got: {labels:?}"
+        );
+    }
+
+    #[test]
+    fn test_completion_attribute_names_after_open_bracket() {
+        let source = r#"codeunit 50100 Test
+{
+    [
+    procedure OnFoo()
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "[");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        assert!(result.is_some(), "expected completion result");
+        let items = items_from(result.unwrap());
+        assert!(
+            items.iter().any(|i| i.label == "EventSubscriber"),
+            "expected EventSubscriber attribute"
+        );
+        assert!(
+            items
+                .iter()
+                .all(|i| i.kind == Some(CompletionItemKind::FUNCTION)),
+            "expected only attribute items after `[`"
+        );
+    }
+
+    #[test]
+    fn test_completion_trigger_snippet_indented_to_cursor_column() {
+        let source =
+            "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n    end;\n\n    tri\n}";
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+        state.format_options.insert(
+            uri.clone(),
+            FormatOptions {
+                tab_size: 2,
+                insert_spaces: true,
+                ..Default::default()
+            },
+        );
+
+        let (line, character) = cursor_after(source, "    tri");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        let items = items_from(result.expect("expected completion result"));
+        let on_run = items
+            .iter()
+            .find(|i| i.label == "trigger OnRun")
+            .expect("expected OnRun trigger snippet");
+        assert_eq!(on_run.insert_text_format, Some(InsertTextFormat::SNIPPET));
+        assert_eq!(
+            on_run.insert_text.as_deref(),
+            Some("trigger OnRun()\n    begin\n      $0\n    end;")
+        );
+    }
+
+    #[test]
+    fn test_completion_record_type_offers_tables() {
+        let objects = r#"table 50100 Customer
+{
+}
+
+table 50101 "Sales Header"
+{
+}
+
+codeunit 50102 "Customer Mgt."
+{
+}"#;
+        let source = "codeunit 50103 Test\n{\n    var\n        Cust: Record \n}";
+        let state = WorldState::new();
+        state.documents.insert(
+            Url::parse("file:///test/objects.al").unwrap(),
+            DocumentState::new(objects),
+        );
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "Record ");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        let items = items_from(result.expect("expected completion result"));
+        let mut labels: Vec<_> = items.iter().map(|i| i.label.as_str()).collect();
+        labels.sort_unstable();
+        assert_eq!(labels, vec!["Customer", "Sales Header"]);
+        let sales_header = items.iter().find(|i| i.label == "Sales Header").unwrap();
+        assert_eq!(
+            sales_header.insert_text.as_deref(),
+            Some("\"Sales Header\"")
+        );
+    }
+
+    #[test]
+    fn test_completion_interface_type_offers_interfaces() {
+        let source = r#"interface "Shipping Provider"
+{
+    procedure Ship();
+}
+
+table 50100 Shipment
+{
+}
+
+codeunit 50101 Test
+{
+    var
+        Provider: Interface "Ship
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "Interface \"Ship");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        let items = items_from(result.expect("expected completion result"));
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label, "Shipping Provider");
+        assert_eq!(items[0].kind, Some(CompletionItemKind::INTERFACE));
+        assert_eq!(items[0].insert_text.as_deref(), Some("Shipping Provider"));
+    }
+
+    #[test]
+    fn test_completion_procedure_snippet_inside_codeunit_body() {
+        let source = "codeunit 50100 Test\n{\n    proc\n}";
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "    proc");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        let items = items_from(result.expect("expected completion result"));
+        let procedure = items
+            .iter()
+            .find(|i| i.label == "procedure" && i.kind == Some(CompletionItemKind::SNIPPET))
+            .expect("expected procedure snippet");
+        assert_eq!(
+            procedure.insert_text_format,
+            Some(InsertTextFormat::SNIPPET)
+        );
+        assert_eq!(
+            procedure.insert_text.as_deref(),
+            Some("procedure ${1:Name}(${2})\n    begin\n        $0\n    end;")
+        );
+        assert!(
+            !items
+                .iter()
+                .any(|i| i.label == "codeunit" && i.kind == Some(CompletionItemKind::SNIPPET)),
+            "object snippets belong to the top level"
+        );
+    }
+
+    #[test]
+    fn test_completion_object_snippets_at_top_level_only() {
+        let source = "codeunit 50100 Test\n{\n}\n\ncod";
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "\ncod");
+        let result = handle_completion(&state, make_completion_params(uri, line, character));
+        let items = items_from(result.expect("expected completion result"));
+        let snippets: Vec<_> = items
+            .iter()
+            .filter(|i| i.kind == Some(CompletionItemKind::SNIPPET))
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(snippets, vec!["codeunit"]);
+    }
 }
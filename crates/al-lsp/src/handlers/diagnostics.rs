@@ -1,12 +1,20 @@
-use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range, Url};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, Url};
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use tower_lsp::Client;
 
 use al_syntax::ast::{extract_name, node_text, AlObjectKind, AlSymbol, AlSymbolKind};
+use al_syntax::diagnostics::{
+    extract_duplicate_field_id_diagnostics, extract_exit_type_diagnostics,
+    extract_length_diagnostics,
+};
 use al_syntax::document::DocumentState;
 use al_syntax::navigation::node_at_offset;
+use al_syntax::symbols::format_type_info;
 
-use crate::handlers::completion::resolve_object_type_from_expression;
+use crate::handlers::completion::{
+    enum_value_names, parse_option_members_from_type_info, resolve_object_type_from_expression,
+};
 use crate::state::WorldState;
 
 pub async fn publish_diagnostics(
@@ -15,22 +23,101 @@ pub async fn publish_diagnostics(
     uri: &Url,
     doc: &DocumentState,
 ) {
-    let mut diagnostics = doc.diagnostics.clone();
-    // Semantic member diagnostics are expensive and low-value while syntax is broken.
-    if diagnostics.is_empty() {
-        diagnostics.extend(collect_semantic_member_diagnostics(state, uri, doc));
+    if state.pull_diagnostics.load(Ordering::Acquire) {
+        return;
     }
+    let diagnostics = collect_diagnostics(state, uri, doc);
     client
         .publish_diagnostics(uri.clone(), diagnostics, None)
         .await;
 }
 
-pub async fn publish_syntax_diagnostics(client: &Client, uri: &Url, doc: &DocumentState) {
+/// Re-publish full diagnostics for every open document, e.g. after the
+/// server settings changed. Clients that pull diagnostics are asked to
+/// refresh instead.
+pub async fn republish_open_diagnostics(client: &Client, state: &WorldState) {
+    if state.pull_diagnostics.load(Ordering::Acquire) {
+        if let Err(e) = client.workspace_diagnostic_refresh().await {
+            tracing::warn!("workspace/diagnostic/refresh failed: {}", e);
+        }
+        return;
+    }
+    let uris: Vec<Url> = state.open_documents.iter().map(|uri| uri.clone()).collect();
+    for uri in uris {
+        if let Some(doc) = state.documents.get(&uri) {
+            publish_diagnostics(client, state, &uri, &doc).await;
+        }
+    }
+}
+
+pub async fn publish_syntax_diagnostics(
+    client: &Client,
+    state: &WorldState,
+    uri: &Url,
+    doc: &DocumentState,
+) {
+    if state.pull_diagnostics.load(Ordering::Acquire) {
+        return;
+    }
+    let mut diagnostics = doc.diagnostics.clone();
+    apply_severity_overrides(state, &mut diagnostics);
     client
-        .publish_diagnostics(uri.clone(), doc.diagnostics.clone(), None)
+        .publish_diagnostics(uri.clone(), diagnostics, None)
         .await;
 }
 
+/// All diagnostics for a document, with configured severity overrides applied.
+pub(crate) fn collect_diagnostics(
+    state: &WorldState,
+    uri: &Url,
+    doc: &DocumentState,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = doc.diagnostics.clone();
+    // Semantic member diagnostics are expensive and low-value while syntax is broken.
+    if !diagnostics
+        .iter()
+        .any(|d| d.severity == Some(DiagnosticSeverity::ERROR))
+    {
+        diagnostics.extend(collect_semantic_member_diagnostics(state, uri, doc));
+        diagnostics.extend(collect_interface_signature_diagnostics(state, doc));
+        diagnostics.extend(collect_unimplemented_interface_diagnostics(state, doc));
+        diagnostics.extend(collect_object_id_diagnostics(state, uri, doc));
+        diagnostics.extend(collect_case_exhaustiveness_diagnostics(state, doc));
+        diagnostics.extend(extract_exit_type_diagnostics(doc.tree(), doc.source()));
+        diagnostics.extend(extract_duplicate_field_id_diagnostics(
+            doc.tree(),
+            doc.source(),
+        ));
+        diagnostics.extend(extract_length_diagnostics(
+            doc.tree(),
+            doc.source(),
+            &doc.symbol_table,
+        ));
+    }
+    apply_severity_overrides(state, &mut diagnostics);
+    diagnostics
+}
+
+/// Promote warnings to errors when `treat_warnings_as_errors` is set. The
+/// original severity is kept in the diagnostic `code` as `warning` (or
+/// `warning:<code>` when the diagnostic already had one).
+pub(crate) fn apply_severity_overrides(state: &WorldState, diagnostics: &mut [Diagnostic]) {
+    if !state.config.lock().unwrap().treat_warnings_as_errors {
+        return;
+    }
+    for diagnostic in diagnostics {
+        if diagnostic.severity != Some(DiagnosticSeverity::WARNING) {
+            continue;
+        }
+        diagnostic.severity = Some(DiagnosticSeverity::ERROR);
+        diagnostic.code = Some(NumberOrString::String(match diagnostic.code.take() {
+            Some(NumberOrString::String(code)) => format!("warning:{code}"),
+            Some(NumberOrString::Number(code)) => format!("warning:{code}"),
+            None => "warning".to_string(),
+        }));
+    }
+}
+
 fn collect_semantic_member_diagnostics(
     state: &WorldState,
     caller_uri: &Url,
@@ -51,12 +138,302 @@ fn collect_semantic_member_diagnostics(
         caller_uri,
         doc,
         &source,
-        doc.tree.root_node(),
+        doc.tree().root_node(),
         &mut diagnostics,
     );
     diagnostics
 }
 
+/// Flag procedures of `implements` codeunits whose parameter or return types
+/// differ from the interface method they implement. Interfaces are looked up
+/// across all open documents; types are compared as `type_info` strings.
+fn collect_interface_signature_diagnostics(
+    state: &WorldState,
+    doc: &DocumentState,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for object in &doc.symbol_table.symbols {
+        if object.implements.is_empty() {
+            continue;
+        }
+        for procedure in &object.children {
+            if !matches!(procedure.kind, AlSymbolKind::Procedure) {
+                continue;
+            }
+            for interface_name in &object.implements {
+                let Some(expected) =
+                    interface_method_signature(state, interface_name, &procedure.name)
+                else {
+                    continue;
+                };
+                if expected == procedure_signature(procedure) {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    range: ts_range_to_lsp_range(
+                        procedure.name_start_point,
+                        procedure.name_end_point,
+                    ),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("al-lsp".to_string()),
+                    message: format!(
+                        "signature does not match interface method '{}.{}'",
+                        interface_name, procedure.name
+                    ),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Flag `implements` codeunits that lack a procedure for some method of an
+/// interface they implement. Interfaces that are not in any open document
+/// are skipped.
+fn collect_unimplemented_interface_diagnostics(
+    state: &WorldState,
+    doc: &DocumentState,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for object in &doc.symbol_table.symbols {
+        if object.kind != AlSymbolKind::Object(AlObjectKind::Codeunit) {
+            continue;
+        }
+        for interface_name in &object.implements {
+            for method in interface_method_names(state, interface_name) {
+                let implemented = object.children.iter().any(|child| {
+                    matches!(child.kind, AlSymbolKind::Procedure)
+                        && child.name.eq_ignore_ascii_case(&method)
+                });
+                if implemented {
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    range: ts_range_to_lsp_range(object.name_start_point, object.name_end_point),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("al-lsp".to_string()),
+                    message: format!(
+                        "codeunit does not implement interface method '{}.{}'",
+                        interface_name, method
+                    ),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Names of the methods declared by `interface_name`, from the first open
+/// document that declares it.
+fn interface_method_names(state: &WorldState, interface_name: &str) -> Vec<String> {
+    state
+        .objects_of_kind(AlObjectKind::Interface)
+        .into_iter()
+        .find(|(_, interface)| interface.name.to_lowercase() == interface_name.to_lowercase())
+        .map(|(_, interface)| {
+            interface
+                .children
+                .iter()
+                .filter(|child| matches!(child.kind, AlSymbolKind::Procedure))
+                .map(|child| child.name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Flag object declarations whose ID lies outside the `idRanges` of the
+/// document's `app.json`. No-op when the workspace has no manifest.
+fn collect_object_id_diagnostics(
+    state: &WorldState,
+    uri: &Url,
+    doc: &DocumentState,
+) -> Vec<Diagnostic> {
+    let Some(manifest) = state.app_manifest_for(uri) else {
+        return Vec::new();
+    };
+
+    let mut diagnostics = Vec::new();
+    let root = doc.tree().root_node();
+    let mut cursor = root.walk();
+    for object in root.named_children(&mut cursor) {
+        if AlObjectKind::from_node_kind(object.kind()).is_none() {
+            continue;
+        }
+        let mut object_cursor = object.walk();
+        let Some(id_node) = object
+            .named_children(&mut object_cursor)
+            .next()
+            .filter(|node| node.kind() == "integer_literal")
+        else {
+            continue;
+        };
+        let Ok(id) = node_text(id_node, doc.source()).parse::<i64>() else {
+            continue;
+        };
+        if manifest.allows_object_id(id) {
+            continue;
+        }
+        diagnostics.push(Diagnostic {
+            range: ts_range_to_lsp_range(id_node.start_position(), id_node.end_position()),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some("al-lsp".to_string()),
+            message: format!("Object ID {id} is outside the allowed range(s)"),
+            ..Default::default()
+        });
+    }
+    diagnostics
+}
+
+/// Hint at enum and option values a `case` statement without an `else`
+/// branch does not handle. Only cases whose branch labels are all
+/// `Type::Value` expressions are checked.
+fn collect_case_exhaustiveness_diagnostics(
+    state: &WorldState,
+    doc: &DocumentState,
+) -> Vec<Diagnostic> {
+    let source = doc.source();
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![doc.tree().root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+        if node.kind() != "case_statement" {
+            continue;
+        }
+        let Some(subject) = node.child_by_field_name("expression") else {
+            continue;
+        };
+
+        let mut cursor = node.walk();
+        let branches: Vec<_> = node
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "case_branch")
+            .collect();
+        let Some(last_branch) = branches.last() else {
+            continue;
+        };
+        let rest = node_text(node, source)[last_branch.end_byte() - node.start_byte()..]
+            .trim_start_matches(|c: char| c == ';' || c.is_whitespace());
+        if rest
+            .get(..4)
+            .is_some_and(|word| word.eq_ignore_ascii_case("else"))
+        {
+            continue;
+        }
+
+        let Some(handled) = case_branch_value_names(&branches, source) else {
+            continue;
+        };
+        let Some(values) = case_subject_values(state, doc, source, subject, node.start_byte())
+        else {
+            continue;
+        };
+        for value in values {
+            if value.is_empty() || handled.iter().any(|name| name.eq_ignore_ascii_case(&value)) {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                range: ts_range_to_lsp_range(node.start_position(), subject.end_position()),
+                severity: Some(DiagnosticSeverity::HINT),
+                source: Some("al-lsp".to_string()),
+                message: format!("case does not handle value '{value}'"),
+                ..Default::default()
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Value names of all `Type::Value` branch labels, or `None` when a label is
+/// any other expression.
+fn case_branch_value_names(
+    branches: &[tree_sitter::Node<'_>],
+    source: &str,
+) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    for branch in branches {
+        let mut cursor = branch.walk();
+        let children: Vec<_> = branch.named_children(&mut cursor).collect();
+        // The last named child is the branch statement.
+        for label in &children[..children.len().saturating_sub(1)] {
+            let label = unwrap_primary_expression(*label);
+            if label.kind() != "qualified_enum_value" {
+                return None;
+            }
+            let value = label.named_child(label.named_child_count().checked_sub(1)?)?;
+            names.push(extract_name(value, source));
+        }
+    }
+    Some(names)
+}
+
+/// Values of the enum or option type of a `case` subject.
+fn case_subject_values(
+    state: &WorldState,
+    doc: &DocumentState,
+    source: &str,
+    subject: tree_sitter::Node<'_>,
+    scope_byte: usize,
+) -> Option<Vec<String>> {
+    let unwrapped = unwrap_primary_expression(subject);
+    if matches!(unwrapped.kind(), "identifier" | "quoted_identifier") {
+        let name = extract_name(unwrapped, source);
+        let option_members = doc
+            .symbol_table
+            .lookup_in_scope(&name, scope_byte)
+            .into_iter()
+            .find(|sym| matches!(sym.kind, AlSymbolKind::Variable | AlSymbolKind::Parameter))
+            .and_then(|sym| sym.type_info.as_deref())
+            .and_then(parse_option_members_from_type_info);
+        if option_members.is_some() {
+            return option_members;
+        }
+    }
+
+    let (object_kind, enum_name) =
+        resolve_object_type_from_expression(state, doc, source, subject, scope_byte, 0)?;
+    if !object_kind.eq_ignore_ascii_case("enum") {
+        return None;
+    }
+    let values = enum_value_names(state, &enum_name);
+    (!values.is_empty()).then_some(values)
+}
+
+/// Normalized parameter types and return type of a procedure symbol.
+type ProcedureSignature = (Vec<String>, Option<String>);
+
+fn procedure_signature(procedure: &AlSymbol) -> ProcedureSignature {
+    let normalize = |type_info: &Option<String>| {
+        type_info.as_deref().map(|t| {
+            format_type_info(t)
+                .split_whitespace()
+                .collect::<String>()
+                .to_ascii_lowercase()
+        })
+    };
+    let parameters = procedure
+        .parameters()
+        .map(|param| normalize(&param.type_info).unwrap_or_default())
+        .collect();
+    (parameters, normalize(&procedure.type_info))
+}
+
+fn interface_method_signature(
+    state: &WorldState,
+    interface_name: &str,
+    method_name: &str,
+) -> Option<ProcedureSignature> {
+    state.documents.iter().find_map(|entry| {
+        entry
+            .value()
+            .symbol_table
+            .find_interface_method(interface_name, method_name)
+            .map(procedure_signature)
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 fn collect_member_diagnostics_recursive(
     state: &WorldState,
@@ -386,7 +763,7 @@ fn validate_member_access(
         return;
     }
 
-    let caller_object = enclosing_object_context(node_at_offset(&doc.tree, scope_byte), source);
+    let caller_object = enclosing_object_context(node_at_offset(doc.tree(), scope_byte), source);
     let object_key = (
         target_kind.to_ascii_lowercase(),
         target_object_name.to_ascii_lowercase(),
@@ -535,7 +912,7 @@ fn ts_range_to_lsp_range(start: tree_sitter::Point, end: tree_sitter::Point) ->
 }
 
 fn is_known_builtin_method(object_kind: &str, method_name: &str) -> bool {
-    crate::builtins::is_known_builtin_method(object_kind, method_name)
+    al_syntax::builtins::is_known_builtin_method(object_kind, method_name)
 }
 
 fn is_known_system_table_field(name: &str) -> bool {
@@ -610,7 +987,7 @@ fn is_same_object(caller: Option<&ObjectContext>, target_kind: &str, target_name
 }
 
 fn procedure_access_modifier(doc: &DocumentState, source: &str, sym: &AlSymbol) -> ProcedureAccess {
-    let Some(mut node) = node_at_offset(&doc.tree, sym.start_byte) else {
+    let Some(mut node) = node_at_offset(doc.tree(), sym.start_byte) else {
         return ProcedureAccess::Public;
     };
 
@@ -746,7 +1123,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -783,7 +1160,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -817,7 +1194,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -850,7 +1227,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -881,7 +1258,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -909,7 +1286,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -950,7 +1327,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -985,7 +1362,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1029,7 +1406,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1073,7 +1450,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let doc = state.documents.get(&uri).unwrap();
         assert!(
@@ -1103,7 +1480,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         assert!(
             doc.diagnostics.is_empty(),
@@ -1146,7 +1523,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1194,7 +1571,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1226,7 +1603,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1253,7 +1630,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1280,7 +1657,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1307,7 +1684,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1339,7 +1716,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1374,7 +1751,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1418,7 +1795,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1469,7 +1846,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1513,7 +1890,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1559,7 +1936,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1603,7 +1980,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1638,7 +2015,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1671,7 +2048,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1704,7 +2081,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1736,7 +2113,7 @@ codeunit 50101 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1780,7 +2157,7 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1822,7 +2199,7 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1855,7 +2232,7 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1882,7 +2259,7 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1915,7 +2292,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1949,7 +2326,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -1980,11 +2357,10 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(target_uri, DocumentState::new(target_source).unwrap());
-        state.documents.insert(
-            caller_uri.clone(),
-            DocumentState::new(caller_source).unwrap(),
-        );
+            .insert(target_uri, DocumentState::new(target_source));
+        state
+            .documents
+            .insert(caller_uri.clone(), DocumentState::new(caller_source));
         let doc = state.documents.get(&caller_uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &caller_uri, &doc);
         assert!(
@@ -2015,7 +2391,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
         let doc = state.documents.get(&uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &uri, &doc);
         assert!(
@@ -2046,11 +2422,10 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(target_uri, DocumentState::new(target_source).unwrap());
-        state.documents.insert(
-            caller_uri.clone(),
-            DocumentState::new(caller_source).unwrap(),
-        );
+            .insert(target_uri, DocumentState::new(target_source));
+        state
+            .documents
+            .insert(caller_uri.clone(), DocumentState::new(caller_source));
         let doc = state.documents.get(&caller_uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &caller_uri, &doc);
         assert!(
@@ -2081,11 +2456,10 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(target_uri, DocumentState::new(target_source).unwrap());
-        state.documents.insert(
-            caller_uri.clone(),
-            DocumentState::new(caller_source).unwrap(),
-        );
+            .insert(target_uri, DocumentState::new(target_source));
+        state
+            .documents
+            .insert(caller_uri.clone(), DocumentState::new(caller_source));
         let doc = state.documents.get(&caller_uri).unwrap();
         let diags = collect_semantic_member_diagnostics(&state, &caller_uri, &doc);
         assert!(
@@ -2093,4 +2467,249 @@ codeunit 50100 Test
             "did not expect internal-access diagnostic, got: {diags:?}"
         );
     }
+
+    #[test]
+    fn test_warnings_published_as_errors_when_configured() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    begin
+        Missing := 1;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let doc = state.documents.get(&uri).unwrap();
+        let diags = collect_diagnostics(&state, &uri, &doc);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diags[0].code, None);
+
+        state.config.lock().unwrap().treat_warnings_as_errors = true;
+        let diags = collect_diagnostics(&state, &uri, &doc);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            diags[0].code,
+            Some(NumberOrString::String("warning".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_configuration_response_updates_warning_severity() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    begin
+        Missing := 1;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        // A client without settings for the section answers `null`.
+        assert!(!state.apply_configuration_response(&[serde_json::Value::Null]));
+        let response = [serde_json::json!({ "treatWarningsAsErrors": true })];
+        assert!(state.apply_configuration_response(&response));
+        assert!(!state.apply_configuration_response(&response));
+
+        let doc = state.documents.get(&uri).unwrap();
+        let diags = collect_diagnostics(&state, &uri, &doc);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+    }
+
+    #[test]
+    fn test_interface_method_signature_mismatch() {
+        let iface_source = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+    procedure SetAddress(NewAddress: Text[100]);
+}"#;
+        let impl_source = r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Integer
+    begin
+    end;
+
+    procedure SetAddress(NewAddress: Text [100])
+    begin
+    end;
+}"#;
+        let iface_uri = Url::parse("file:///test/iface.al").unwrap();
+        let impl_uri = Url::parse("file:///test/impl.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(iface_uri, DocumentState::new(iface_source));
+        state
+            .documents
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
+
+        let doc = state.documents.get(&impl_uri).unwrap();
+        let diags = collect_diagnostics(&state, &impl_uri, &doc);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            diags[0].message,
+            "signature does not match interface method 'IAddressProvider.GetAddress'"
+        );
+        assert_eq!(diags[0].range.start, Position::new(2, 14));
+    }
+
+    fn interface_diagnostics(impl_source: &str) -> Vec<Diagnostic> {
+        let iface_source = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+    procedure Reset();
+}"#;
+        let iface_uri = Url::parse("file:///test/iface.al").unwrap();
+        let impl_uri = Url::parse("file:///test/impl.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(iface_uri, DocumentState::new(iface_source));
+        state
+            .documents
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
+
+        let doc = state.documents.get(&impl_uri).unwrap();
+        collect_diagnostics(&state, &impl_uri, &doc)
+    }
+
+    #[test]
+    fn test_fully_implemented_interface() {
+        let diags = interface_diagnostics(
+            r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+
+    procedure reset()
+    begin
+    end;
+}"#,
+        );
+        assert!(diags.is_empty(), "{diags:?}");
+    }
+
+    #[test]
+    fn test_partially_implemented_interface() {
+        let diags = interface_diagnostics(
+            r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider, IUnknown
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+}"#,
+        );
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            diags[0].message,
+            "codeunit does not implement interface method 'IAddressProvider.Reset'"
+        );
+        assert_eq!(diags[0].range.start, Position::new(0, 15));
+        assert_eq!(diags[0].range.end, Position::new(0, 37));
+    }
+
+    #[test]
+    fn test_object_id_outside_app_json_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = r#"codeunit 50100 InRange
+{
+}
+
+codeunit 60000 OutOfRange
+{
+}"#;
+        let uri = Url::from_file_path(dir.path().join("src").join("objects.al")).unwrap();
+        let state = WorldState::new();
+        *state.workspace_roots.lock().unwrap() = vec![dir.path().to_path_buf()];
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        // Without an app.json there is nothing to check against.
+        state.load_app_manifests();
+        let doc = state.documents.get(&uri).unwrap();
+        assert!(collect_diagnostics(&state, &uri, &doc).is_empty());
+        drop(doc);
+
+        std::fs::write(
+            dir.path().join("app.json"),
+            r#"{ "name": "Test", "idRanges": [{ "from": 50100, "to": 50149 }] }"#,
+        )
+        .unwrap();
+        state.load_app_manifests();
+        let doc = state.documents.get(&uri).unwrap();
+        let diags = collect_diagnostics(&state, &uri, &doc);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diags[0].message,
+            "Object ID 60000 is outside the allowed range(s)"
+        );
+        assert_eq!(diags[0].range.start, Position::new(4, 9));
+    }
+
+    const CASE_ENUM_SOURCE: &str = r#"enum 50100 TicketStatus
+{
+    value(0; Open) { }
+    value(1; InProgress) { }
+    value(2; Closed) { }
+}
+
+codeunit 50101 Tickets
+{
+    procedure Describe(Status: Enum TicketStatus)
+    begin
+        case Status of
+            TicketStatus::Open:
+                exit;
+            TicketStatus::InProgress:
+                exit;
+        end;
+    end;
+}"#;
+
+    #[test]
+    fn test_case_missing_enum_value_is_hinted() {
+        let uri = Url::parse("file:///test/tickets.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(CASE_ENUM_SOURCE));
+        let doc = state.documents.get(&uri).unwrap();
+        let diags = collect_case_exhaustiveness_diagnostics(&state, &doc);
+        assert_eq!(diags.len(), 1, "got: {diags:?}");
+        assert_eq!(diags[0].message, "case does not handle value 'Closed'");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(diags[0].range.start, Position::new(11, 8));
+    }
+
+    #[test]
+    fn test_case_with_else_is_not_hinted() {
+        let source = CASE_ENUM_SOURCE.replace(
+            "                exit;\n        end;",
+            "                exit;\n            else\n                exit;\n        end;",
+        );
+        assert!(source.contains("else"));
+        let uri = Url::parse("file:///test/tickets.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(&source));
+        let doc = state.documents.get(&uri).unwrap();
+        let diags = collect_case_exhaustiveness_diagnostics(&state, &doc);
+        assert!(diags.is_empty(), "got: {diags:?}");
+    }
 }
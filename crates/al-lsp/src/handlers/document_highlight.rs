@@ -1,6 +1,6 @@
 use lsp_types::{DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams};
 
-use al_syntax::navigation::find_all_references;
+use al_syntax::navigation::{find_all_references, is_write_reference};
 
 use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range_utf16};
 use crate::state::WorldState;
@@ -13,11 +13,11 @@ pub fn handle_document_highlight(
     let position = params.text_document_position_params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
 
     // Always include declaration for highlight
-    let refs = find_all_references(&doc.tree, &source, &doc.symbol_table, byte_offset, true);
+    let refs = find_all_references(doc.tree(), &source, &doc.symbol_table, byte_offset, true);
 
     if refs.is_empty() {
         return None;
@@ -26,16 +26,130 @@ pub fn handle_document_highlight(
     let highlights: Vec<DocumentHighlight> = refs
         .into_iter()
         .map(|(start, end)| {
-            let range = ts_range_to_lsp_range_utf16(&doc.rope, start, end);
-            // Classify as Write if the node is on the left side of an assignment.
-            // For simplicity, we mark all as Read — a more precise classification
-            // would require checking the parent node type at each reference site.
+            let range = ts_range_to_lsp_range_utf16(doc.rope(), start, end);
+            let kind = if is_write_reference(doc.tree(), source, &doc.symbol_table, start, end) {
+                DocumentHighlightKind::WRITE
+            } else {
+                DocumentHighlightKind::READ
+            };
             DocumentHighlight {
                 range,
-                kind: Some(DocumentHighlightKind::READ),
+                kind: Some(kind),
             }
         })
         .collect();
 
     Some(highlights)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
+
+    fn make_highlight_params(uri: Url, line: u32, character: u32) -> DocumentHighlightParams {
+        DocumentHighlightParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: Position { line, character },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    fn highlights_for(
+        source: &str,
+        line: u32,
+        character: u32,
+    ) -> Vec<(u32, DocumentHighlightKind)> {
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+        let mut highlights: Vec<(u32, DocumentHighlightKind)> =
+            handle_document_highlight(&state, make_highlight_params(uri, line, character))
+                .expect("expected highlights")
+                .into_iter()
+                .map(|h| (h.range.start.line, h.kind.unwrap()))
+                .collect();
+        highlights.sort_by_key(|(line, _)| *line);
+        highlights
+    }
+
+    const SOURCE: &str = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+        Y: Integer;
+    begin
+        X := 1;
+        Y := X;
+        Y += X;
+        Bump(X);
+        Show(Y);
+    end;
+
+    procedure Bump(var Value: Integer)
+    begin
+    end;
+
+    procedure Show(Value: Integer)
+    begin
+    end;
+}"#;
+
+    #[test]
+    fn test_highlight_assignment_target_is_write() {
+        let highlights = highlights_for(SOURCE, 7, 8);
+        assert_eq!(
+            highlights,
+            vec![
+                (4, DocumentHighlightKind::READ),
+                (7, DocumentHighlightKind::WRITE),
+                (8, DocumentHighlightKind::READ),
+                (9, DocumentHighlightKind::READ),
+                (10, DocumentHighlightKind::WRITE),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlight_compound_assignment_target_is_write() {
+        let highlights = highlights_for(SOURCE, 8, 8);
+        assert_eq!(
+            highlights,
+            vec![
+                (5, DocumentHighlightKind::READ),
+                (8, DocumentHighlightKind::WRITE),
+                (9, DocumentHighlightKind::WRITE),
+                (11, DocumentHighlightKind::READ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlight_object_name_and_self_references() {
+        let source = r#"codeunit 50100 Selfie
+{
+    procedure Run()
+    var
+        Other: Codeunit Selfie;
+    begin
+        Codeunit.Run(Codeunit::Selfie);
+    end;
+}"#;
+        let highlights = highlights_for(source, 0, 17);
+        assert_eq!(
+            highlights,
+            vec![
+                (0, DocumentHighlightKind::READ),
+                (4, DocumentHighlightKind::READ),
+                (6, DocumentHighlightKind::READ),
+            ]
+        );
+    }
+}
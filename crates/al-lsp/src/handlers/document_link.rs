@@ -0,0 +1,141 @@
+use lsp_types::{DocumentLink, DocumentLinkParams};
+
+use crate::convert::ts_range_to_lsp_range_utf16;
+use crate::handlers::goto_definition::{
+    find_object_declarations, type_target_from_type_identifier,
+};
+use crate::state::WorldState;
+
+/// Link object names in type declarations (`Record Customer`, `Codeunit "Sales-Post"`)
+/// to the declaring object. Targets carry an `#L<line>` fragment for the object's
+/// first line; names that resolve to no object, or to several, get no link.
+pub fn handle_document_link(
+    state: &WorldState,
+    params: DocumentLinkParams,
+) -> Option<Vec<DocumentLink>> {
+    let uri = params.text_document.uri;
+    let doc = state.documents.get(&uri)?;
+    let source = doc.source();
+
+    let mut type_names = Vec::new();
+    collect_type_names(doc.tree().root_node(), source, &mut type_names);
+    let type_names: Vec<_> = type_names
+        .into_iter()
+        .map(|(node, kind, name)| {
+            let range =
+                ts_range_to_lsp_range_utf16(doc.rope(), node.start_position(), node.end_position());
+            (range, kind, name)
+        })
+        .collect();
+    drop(doc);
+
+    let links: Vec<DocumentLink> = type_names
+        .into_iter()
+        .filter_map(|(range, kind, name)| {
+            let locations = find_object_declarations(state, &uri, kind, &name);
+            if locations.len() != 1 {
+                return None;
+            }
+            let location = &locations[0];
+            let mut target = location.uri.clone();
+            target.set_fragment(Some(&format!("L{}", location.range.start.line + 1)));
+            Some(DocumentLink {
+                range,
+                target: Some(target),
+                tooltip: Some(format!("Go to {kind} {name}")),
+                data: None,
+            })
+        })
+        .collect();
+
+    if links.is_empty() {
+        return None;
+    }
+
+    Some(links)
+}
+
+fn collect_type_names<'a>(
+    node: tree_sitter::Node<'a>,
+    source: &str,
+    result: &mut Vec<(tree_sitter::Node<'a>, &'static str, String)>,
+) {
+    if let Some((kind, name)) = type_target_from_type_identifier(node, source) {
+        result.push((node, kind, name));
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_type_names(child, source, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{Position, TextDocumentIdentifier, Url};
+
+    fn links_for(source: &str, objects: &[(&str, &str)]) -> Vec<DocumentLink> {
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        for (path, object_source) in objects {
+            state.documents.insert(
+                Url::parse(&format!("file:///test/{path}")).unwrap(),
+                DocumentState::new(object_source),
+            );
+        }
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+        handle_document_link(
+            &state,
+            DocumentLinkParams {
+                text_document: TextDocumentIdentifier { uri },
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_document_link_record_type_to_table() {
+        let links = links_for(
+            r#"codeunit 50100 Test
+{
+    var
+        Cust: Record Customer;
+        Missing: Record Unknown;
+}"#,
+            &[("customer.al", "\n\ntable 18 Customer\n{\n}")],
+        );
+        assert_eq!(links.len(), 1, "got: {links:?}");
+        assert_eq!(links[0].range.start, Position::new(3, 21));
+        assert_eq!(links[0].range.end, Position::new(3, 29));
+        assert_eq!(
+            links[0].target.as_ref().map(Url::as_str),
+            Some("file:///test/customer.al#L3")
+        );
+    }
+
+    #[test]
+    fn test_document_link_codeunit_type_to_codeunit() {
+        let links = links_for(
+            r#"codeunit 50100 Test
+{
+    procedure Run(Provider: Codeunit AddressProvider)
+    begin
+    end;
+}"#,
+            &[("provider.al", "codeunit 50101 AddressProvider\n{\n}")],
+        );
+        assert_eq!(links.len(), 1, "got: {links:?}");
+        assert_eq!(links[0].range.start, Position::new(2, 37));
+        assert_eq!(
+            links[0].target.as_ref().map(Url::as_str),
+            Some("file:///test/provider.al#L1")
+        );
+    }
+}
@@ -45,7 +45,10 @@ fn al_symbol_to_document_symbol(sym: &AlSymbol) -> Option<DocumentSymbol> {
         }
     };
 
-    let detail = sym.type_info.clone();
+    let detail = match sym.object_id {
+        Some(id) => sym.type_info.as_ref().map(|t| format!("{t} {id}")),
+        None => sym.type_info.clone(),
+    };
 
     Some(DocumentSymbol {
         name: sym.name.clone(),
@@ -70,6 +73,8 @@ mod tests {
             kind: AlSymbolKind::Object(AlObjectKind::Codeunit),
             type_info: Some("codeunit".to_string()),
             implements: Vec::new(),
+            object_id: None,
+            extends: None,
             start_byte: 0,
             end_byte: 0,
             start_point: tree_sitter::Point::new(0, 0),
@@ -85,4 +90,14 @@ mod tests {
         let sym = make_symbol("");
         assert!(al_symbol_to_document_symbol(&sym).is_none());
     }
+
+    #[test]
+    fn test_document_symbol_detail_includes_object_id() {
+        let sym = AlSymbol {
+            object_id: Some(50100),
+            ..make_symbol("Test")
+        };
+        let doc_sym = al_symbol_to_document_symbol(&sym).unwrap();
+        assert_eq!(doc_sym.detail.as_deref(), Some("codeunit 50100"));
+    }
 }
@@ -17,11 +17,10 @@ pub async fn handle_did_open(
     let uri = params.text_document.uri;
     let text = params.text_document.text;
 
-    if let Some(doc) = DocumentState::new(&text) {
-        state.upsert_document(uri.clone(), doc);
-        if let Some(doc_ref) = state.documents.get(&uri) {
-            publish_diagnostics(client, state, &uri, &doc_ref).await;
-        }
+    state.open_documents.insert(uri.clone());
+    state.upsert_document(uri.clone(), DocumentState::new(&text));
+    if let Some(doc_ref) = state.documents.get(&uri) {
+        publish_diagnostics(client, state, &uri, &doc_ref).await;
     }
 }
 
@@ -41,10 +40,10 @@ pub async fn handle_did_change(
             if change.range.is_some() {
                 // Incremental edit path for low-latency typing diagnostics.
                 if let Some(range) = change.range {
-                    let start_offset = offset_from_position(&doc.rope, range.start);
-                    let end_offset = offset_from_position(&doc.rope, range.end);
-                    let start_point = point_from_position(&doc.rope, range.start);
-                    let old_end_point = point_from_position(&doc.rope, range.end);
+                    let start_offset = offset_from_position(doc.rope(), range.start);
+                    let end_offset = offset_from_position(doc.rope(), range.end);
+                    let start_point = point_from_position(doc.rope(), range.start);
+                    let old_end_point = point_from_position(doc.rope(), range.end);
                     if let (Some(start), Some(end), Some(start_point), Some(old_end_point)) =
                         (start_offset, end_offset, start_point, old_end_point)
                     {
@@ -61,10 +60,12 @@ pub async fn handle_did_change(
                         // Fallback: apply best-effort range replacement and do a full reparse.
                         let mut source = doc.source().to_string();
                         let start = start_offset
-                            .unwrap_or_else(|| offset_from_position_clamped(&doc.rope, range.start))
+                            .unwrap_or_else(|| {
+                                offset_from_position_clamped(doc.rope(), range.start)
+                            })
                             .min(source.len());
                         let mut end = end_offset
-                            .unwrap_or_else(|| offset_from_position_clamped(&doc.rope, range.end))
+                            .unwrap_or_else(|| offset_from_position_clamped(doc.rope(), range.end))
                             .min(source.len());
                         if end < start {
                             end = start;
@@ -83,6 +84,7 @@ pub async fn handle_did_change(
     }
 
     if changed {
+        state.invalidate_document_caches(&uri);
         if needs_reindex {
             state.reindex_document(&uri);
         }
@@ -90,18 +92,23 @@ pub async fn handle_did_change(
             if has_full_sync_change {
                 publish_diagnostics(client, state, &uri, &doc_ref).await;
             } else {
-                publish_syntax_diagnostics(client, &uri, &doc_ref).await;
+                publish_syntax_diagnostics(client, state, &uri, &doc_ref).await;
             }
         }
     }
 }
 
-pub async fn handle_did_close(_state: &WorldState, params: DidCloseTextDocumentParams) {
+pub async fn handle_did_close(state: &WorldState, params: DidCloseTextDocumentParams) {
     // Don't remove the document — it may be needed for cross-document features
     // (rename, references, go-to-definition). The workspace scanner loaded it from
     // disk, and closing a tab shouldn't discard that knowledge.
     // The file watcher (didChangeWatchedFiles) handles actual deletions.
-    let _ = &params.text_document.uri;
+    // Its rope and parse tree are dropped until a feature needs them again.
+    let uri = params.text_document.uri;
+    state.open_documents.remove(&uri);
+    if let Some(mut doc) = state.documents.get_mut(&uri) {
+        doc.unload();
+    }
 }
 
 fn offset_from_position(rope: &ropey::Rope, pos: lsp_types::Position) -> Option<usize> {
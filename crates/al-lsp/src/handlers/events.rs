@@ -133,6 +133,33 @@ pub(crate) fn event_publisher_target_at_offset(
     })
 }
 
+/// When the cursor is on the name of a procedure carrying an
+/// `[EventSubscriber(...)]` attribute, the event it subscribes to.
+pub(crate) fn event_subscriber_procedure_target_at_offset(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    byte_offset: usize,
+) -> Option<EventTarget> {
+    let node = node_at_offset(tree, byte_offset)?;
+    let proc_node = find_ancestor_of_kind(node, "procedure_declaration")?;
+    let proc_name_node = proc_node.child_by_field_name("name")?;
+
+    // Restrict to cursor on procedure name.
+    if byte_offset < proc_name_node.start_byte() || byte_offset > proc_name_node.end_byte() {
+        return None;
+    }
+
+    let (owner_kind, owner_name) = enclosing_object_info(proc_node, source)?;
+    procedure_attribute_nodes(proc_node)
+        .into_iter()
+        .filter(|attr| {
+            attribute_name(*attr, source)
+                .is_some_and(|name| name.eq_ignore_ascii_case("EventSubscriber"))
+        })
+        .find_map(|attr| parse_event_subscriber_attribute(attr, source, &owner_kind, &owner_name))
+        .map(|subscriber| subscriber.target)
+}
+
 pub(crate) fn find_event_publishers(
     state: &WorldState,
     target: &EventTarget,
@@ -142,7 +169,7 @@ pub(crate) fn find_event_publishers(
     for entry in state.documents.iter() {
         let doc = entry.value();
         let source = doc.source();
-        for publisher in collect_event_publishers_in_tree(&doc.tree, &source) {
+        for publisher in collect_event_publishers_in_tree(&doc.scan_tree(), &source) {
             if !event_target_matches(&publisher.target, target) {
                 continue;
             }
@@ -166,7 +193,7 @@ pub(crate) fn find_event_subscriber_usages(
     for entry in state.documents.iter() {
         let doc = entry.value();
         let source = doc.source();
-        for subscriber in collect_event_subscribers_in_tree(&doc.tree, &source) {
+        for subscriber in collect_event_subscribers_in_tree(&doc.scan_tree(), &source) {
             if !event_target_matches(&subscriber.target, target) {
                 continue;
             }
@@ -191,7 +218,7 @@ pub(crate) fn find_event_invocation_usages(
         let doc = entry.value();
         let source = doc.source();
         for (start, end) in
-            collect_event_invocations_in_tree(&doc.tree, &source, &doc.symbol_table, target)
+            collect_event_invocations_in_tree(&doc.scan_tree(), &source, &doc.symbol_table, target)
         {
             locations.push(EventInvocationUsage {
                 uri: entry.key().clone(),
@@ -472,7 +499,7 @@ fn collect_event_publisher_names_for_object(
 
     let mut collect_from_doc = |doc: &al_syntax::document::DocumentState| {
         let source = doc.source();
-        for publisher in collect_event_publishers_in_tree(&doc.tree, source) {
+        for publisher in collect_event_publishers_in_tree(doc.tree(), source) {
             if !publisher
                 .target
                 .object_kind
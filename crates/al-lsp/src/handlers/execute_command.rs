@@ -0,0 +1,588 @@
+use lsp_types::{
+    CreateFile, CreateFileOptions, DocumentChangeOperation, DocumentChanges, ExecuteCommandParams,
+    Location, OneOf, OptionalVersionedTextDocumentIdentifier, Position, Range, ResourceOp,
+    TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use al_syntax::ast::{
+    extract_name, procedure_signature_text, AlObjectKind, AlSymbol, AlSymbolKind,
+};
+use al_syntax::navigation::extract_type_object_name;
+
+use crate::convert::{ts_point_to_lsp_position_utf16, ts_range_to_lsp_range};
+use crate::handlers::code_action::detect_access_modifier;
+use crate::handlers::goto_definition::object_reference_property_value;
+use crate::state::WorldState;
+
+/// Generate an interface from a codeunit's public procedures.
+/// Arguments: `[codeunitUri]`.
+pub const EXTRACT_INTERFACE_COMMAND: &str = "al-lsp.extractInterface";
+
+/// List codeunits, tables and pages no other object references.
+/// Returns the candidates' locations; takes no arguments.
+pub const FIND_UNUSED_OBJECTS_COMMAND: &str = "al-lsp.findUnusedObjects";
+
+/// Return the symbol tables of all indexed documents as [`DocumentSymbolDump`]s;
+/// takes no arguments.
+pub const DUMP_SYMBOLS_COMMAND: &str = "al-lsp.dumpSymbols";
+
+/// All commands advertised through `execute_command_provider`.
+pub fn supported_commands() -> Vec<String> {
+    vec![
+        EXTRACT_INTERFACE_COMMAND.to_string(),
+        FIND_UNUSED_OBJECTS_COMMAND.to_string(),
+        DUMP_SYMBOLS_COMMAND.to_string(),
+    ]
+}
+
+/// What running a server command produced.
+#[derive(Debug)]
+pub enum CommandOutcome {
+    /// An edit the backend applies through `workspace/applyEdit`.
+    Edit(WorkspaceEdit),
+    /// A value returned to the client as the command result.
+    Value(serde_json::Value),
+}
+
+pub fn handle_execute_command(
+    state: &WorldState,
+    params: ExecuteCommandParams,
+) -> Option<CommandOutcome> {
+    match params.command.as_str() {
+        EXTRACT_INTERFACE_COMMAND => {
+            let uri = params
+                .arguments
+                .first()
+                .and_then(|arg| serde_json::from_value::<Url>(arg.clone()).ok())?;
+            extract_interface(state, &uri).map(CommandOutcome::Edit)
+        }
+        FIND_UNUSED_OBJECTS_COMMAND => serde_json::to_value(find_unused_objects(state))
+            .ok()
+            .map(CommandOutcome::Value),
+        DUMP_SYMBOLS_COMMAND => serde_json::to_value(dump_symbols(state))
+            .ok()
+            .map(CommandOutcome::Value),
+        _ => None,
+    }
+}
+
+/// Create `<IName>.Interface.al` next to the codeunit with the signatures of
+/// its public procedures, and add the interface to the codeunit's
+/// `implements` clause.
+fn extract_interface(state: &WorldState, uri: &Url) -> Option<WorkspaceEdit> {
+    let doc = state.documents.get(uri)?;
+    let source = doc.source();
+
+    let root = doc.tree().root_node();
+    let mut cursor = root.walk();
+    let codeunit = root
+        .named_children(&mut cursor)
+        .find(|node| node.kind() == "codeunit_declaration")?;
+
+    let mut name_node = None;
+    let mut implements_node = None;
+    let mut signatures = Vec::new();
+    let mut cursor = codeunit.walk();
+    for child in codeunit.named_children(&mut cursor) {
+        match child.kind() {
+            "identifier" | "quoted_identifier" if name_node.is_none() => name_node = Some(child),
+            "implements_clause" => implements_node = Some(child),
+            "procedure_declaration" if detect_access_modifier(source, child).is_none() => {
+                signatures.extend(procedure_signature_text(child, source));
+            }
+            _ => {}
+        }
+    }
+
+    let codeunit_name = extract_name(name_node?, source);
+    let interface_name = format!("I{codeunit_name}");
+    let quoted_name = if interface_name.contains(' ') {
+        format!("\"{interface_name}\"")
+    } else {
+        interface_name.clone()
+    };
+
+    let mut interface_text = format!("interface {quoted_name}\n{{\n");
+    for signature in &signatures {
+        interface_text.push_str(&format!("    {signature};\n"));
+    }
+    interface_text.push_str("}\n");
+
+    let file_name = format!("{}.Interface.al", interface_name.replace(' ', ""));
+    let interface_uri = uri.join(&file_name).ok()?;
+
+    let implements_edit = match implements_node {
+        Some(clause) => {
+            let text = &source[clause.start_byte()..clause.end_byte()];
+            if text
+                .split([',', ' ', '\n', '\t', '\r'])
+                .any(|name| name.trim_matches('"').eq_ignore_ascii_case(&interface_name))
+            {
+                None
+            } else {
+                let end = ts_point_to_lsp_position_utf16(doc.rope(), clause.end_position());
+                Some(insert_at(end, format!(", {quoted_name}")))
+            }
+        }
+        None => {
+            let end = ts_point_to_lsp_position_utf16(doc.rope(), name_node?.end_position());
+            Some(insert_at(end, format!(" implements {quoted_name}")))
+        }
+    };
+
+    let mut operations = vec![
+        DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+            uri: interface_uri.clone(),
+            options: Some(CreateFileOptions {
+                overwrite: Some(false),
+                ignore_if_exists: Some(false),
+            }),
+            annotation_id: None,
+        })),
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier {
+                uri: interface_uri,
+                version: None,
+            },
+            edits: vec![OneOf::Left(insert_at(Position::new(0, 0), interface_text))],
+        }),
+    ];
+    if let Some(edit) = implements_edit {
+        operations.push(DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version: None,
+            },
+            edits: vec![OneOf::Left(edit)],
+        }));
+    }
+
+    Some(WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(operations)),
+        ..Default::default()
+    })
+}
+
+/// Codeunits, tables and pages that no other object in the workspace
+/// references through a variable, parameter or return type, an `implements`
+/// clause, an object-reference property (`RunObject`, `CardPageID`, ...),
+/// an `extends` target, or a `Codeunit::`/`Page::`/`Database::` literal.
+///
+/// Tables and pages with triggers, and codeunits with event subscribers,
+/// are invoked by the platform and never reported.
+fn find_unused_objects(state: &WorldState) -> Vec<Location> {
+    let mut referenced: HashSet<(String, String)> = HashSet::new();
+    let mut candidates = Vec::new();
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        let source = doc.source();
+        let tree = doc.scan_tree();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for object in root.named_children(&mut cursor) {
+            let Some(kind) = AlObjectKind::from_node_kind(object.kind()) else {
+                continue;
+            };
+            let Some(symbol) = doc
+                .symbols()
+                .iter()
+                .find(|sym| sym.start_byte == object.start_byte())
+            else {
+                continue;
+            };
+            let own = (kind.label().to_string(), symbol.name.to_lowercase());
+
+            let mut dependencies = Vec::new();
+            collect_object_dependencies(object, source, &mut dependencies);
+            referenced.extend(dependencies.into_iter().filter(|dep| *dep != own));
+
+            let platform_invoked = match kind {
+                AlObjectKind::Table | AlObjectKind::Page => {
+                    has_descendant(object, "trigger_declaration")
+                }
+                AlObjectKind::Codeunit => source[object.start_byte()..object.end_byte()]
+                    .to_ascii_lowercase()
+                    .contains("[eventsubscriber"),
+                _ => continue,
+            };
+            if !platform_invoked {
+                candidates.push((
+                    own,
+                    Location {
+                        uri: entry.key().clone(),
+                        range: ts_range_to_lsp_range(
+                            symbol.name_start_point,
+                            symbol.name_end_point,
+                        ),
+                    },
+                ));
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(key, _)| !referenced.contains(key))
+        .map(|(_, location)| location)
+        .collect()
+}
+
+/// One document's symbols in the `al-lsp.dumpSymbols` result.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentSymbolDump {
+    pub uri: Url,
+    pub symbols: Vec<SymbolDump>,
+}
+
+/// Serializable view of an [`AlSymbol`], with positions as LSP ranges.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolDump {
+    pub name: String,
+    /// `procedure`, `field`, ... or the object kind (`codeunit`, `table`, ...).
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_info: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub implements: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    pub range: Range,
+    pub selection_range: Range,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SymbolDump>,
+}
+
+impl From<&AlSymbol> for SymbolDump {
+    fn from(symbol: &AlSymbol) -> Self {
+        let kind = match symbol.kind {
+            AlSymbolKind::Object(kind) => kind.label(),
+            AlSymbolKind::Procedure => "procedure",
+            AlSymbolKind::Trigger => "trigger",
+            AlSymbolKind::Variable => "variable",
+            AlSymbolKind::Parameter => "parameter",
+            AlSymbolKind::Field => "field",
+            AlSymbolKind::Key => "key",
+            AlSymbolKind::EnumValue => "enumValue",
+        };
+        SymbolDump {
+            name: symbol.name.clone(),
+            kind: kind.to_string(),
+            type_info: symbol.type_info.clone(),
+            object_id: symbol.object_id,
+            implements: symbol.implements.clone(),
+            extends: symbol.extends.clone(),
+            range: ts_range_to_lsp_range(symbol.start_point, symbol.end_point),
+            selection_range: ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point),
+            children: symbol.children.iter().map(SymbolDump::from).collect(),
+        }
+    }
+}
+
+/// Symbol tables of all indexed documents, ordered by URI.
+fn dump_symbols(state: &WorldState) -> Vec<DocumentSymbolDump> {
+    let mut documents: Vec<DocumentSymbolDump> = state
+        .documents
+        .iter()
+        .map(|entry| DocumentSymbolDump {
+            uri: entry.key().clone(),
+            symbols: entry
+                .value()
+                .symbols()
+                .iter()
+                .map(SymbolDump::from)
+                .collect(),
+        })
+        .collect();
+    documents.sort_by(|a, b| a.uri.as_str().cmp(b.uri.as_str()));
+    documents
+}
+
+/// Outgoing object references of `node` as lowercased `(kind, name)` pairs.
+fn collect_object_dependencies(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+    out: &mut Vec<(String, String)>,
+) {
+    let mut push = |kind: &str, name: &str| {
+        out.push((
+            kind.to_string(),
+            name.trim().trim_matches('"').to_lowercase(),
+        ));
+    };
+    match node.kind() {
+        "variable_declaration" | "parameter" | "return_type" => {
+            let mut cursor = node.walk();
+            let type_node = node
+                .child_by_field_name("type")
+                .or_else(|| node.named_children(&mut cursor).last());
+            if let Some((kind, name)) = type_node
+                .and_then(|t| extract_type_object_name(&source[t.start_byte()..t.end_byte()]))
+            {
+                push(kind, name);
+            }
+        }
+        "implements_clause" => {
+            let mut cursor = node.walk();
+            for name in node.named_children(&mut cursor) {
+                push("interface", &extract_name(name, source));
+            }
+            return;
+        }
+        "property" => {
+            if let Some((kind, name, _)) = object_reference_property_value(node, source) {
+                push(kind, &name);
+            }
+        }
+        "qualified_enum_value" => {
+            let text = &source[node.start_byte()..node.end_byte()];
+            if let Some((qualifier, name)) = text.split_once("::") {
+                let kind = match qualifier.trim().to_ascii_lowercase().as_str() {
+                    "codeunit" => Some("codeunit"),
+                    "page" => Some("page"),
+                    "report" => Some("report"),
+                    "database" => Some("table"),
+                    "xmlport" => Some("xmlport"),
+                    "query" => Some("query"),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    push(kind, name);
+                }
+            }
+        }
+        "table_extension_declaration" | "page_extension_declaration" => {
+            let mut cursor = node.walk();
+            let target = node
+                .named_children(&mut cursor)
+                .filter(|child| matches!(child.kind(), "identifier" | "quoted_identifier"))
+                .nth(1);
+            if let Some(target) = target {
+                let kind = if node.kind() == "table_extension_declaration" {
+                    "table"
+                } else {
+                    "page"
+                };
+                push(kind, &extract_name(target, source));
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_object_dependencies(child, source, out);
+    }
+}
+
+fn has_descendant(node: tree_sitter::Node<'_>, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .any(|child| child.kind() == kind || has_descendant(child, kind));
+    found
+}
+
+fn insert_at(position: Position, new_text: String) -> TextEdit {
+    TextEdit {
+        range: Range {
+            start: position,
+            end: position,
+        },
+        new_text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+
+    #[test]
+    fn test_extract_interface_from_codeunit_public_procedures() {
+        let source = r#"codeunit 50200 CompanyAddressProvider
+{
+    procedure GetAddress(Country: Code[10]; var Lines: List of [Text]): Text
+    begin
+    end;
+
+    local procedure Helper()
+    begin
+    end;
+
+    internal procedure Hidden(): Integer
+    begin
+    end;
+
+    procedure Reset()
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///project/src/CompanyAddressProvider.Codeunit.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let Some(CommandOutcome::Edit(edit)) = handle_execute_command(
+            &state,
+            ExecuteCommandParams {
+                command: EXTRACT_INTERFACE_COMMAND.to_string(),
+                arguments: vec![serde_json::json!(uri)],
+                work_done_progress_params: Default::default(),
+            },
+        ) else {
+            panic!("expected workspace edit");
+        };
+
+        let Some(DocumentChanges::Operations(operations)) = edit.document_changes else {
+            panic!("expected document change operations");
+        };
+        let interface_uri =
+            Url::parse("file:///project/src/ICompanyAddressProvider.Interface.al").unwrap();
+        assert!(matches!(
+            &operations[0],
+            DocumentChangeOperation::Op(ResourceOp::Create(create)) if create.uri == interface_uri
+        ));
+
+        let DocumentChangeOperation::Edit(interface_edit) = &operations[1] else {
+            panic!("expected interface text edit");
+        };
+        let OneOf::Left(text_edit) = &interface_edit.edits[0] else {
+            panic!("expected plain text edit");
+        };
+        assert_eq!(
+            text_edit.new_text,
+            "interface ICompanyAddressProvider\n{\n    procedure GetAddress(Country: Code[10]; var Lines: List of [Text]): Text;\n    procedure Reset();\n}\n"
+        );
+
+        let DocumentChangeOperation::Edit(codeunit_edit) = &operations[2] else {
+            panic!("expected implements edit");
+        };
+        assert_eq!(codeunit_edit.text_document.uri, uri);
+        let OneOf::Left(implements) = &codeunit_edit.edits[0] else {
+            panic!("expected plain text edit");
+        };
+        assert_eq!(implements.range.start, Position::new(0, 37));
+        assert_eq!(implements.new_text, " implements ICompanyAddressProvider");
+    }
+
+    #[test]
+    fn test_find_unused_objects_reports_only_unreferenced_codeunits() {
+        let helper_source = r#"codeunit 50100 "Address Helper"
+{
+    procedure Format(): Text
+    begin
+    end;
+}
+
+codeunit 50101 "Orphan Helper"
+{
+    procedure Nothing()
+    begin
+    end;
+}"#;
+        let user_source = r#"table 50200 "Address Consumer"
+{
+    trigger OnInsert()
+    var
+        Helper: Codeunit "Address Helper";
+    begin
+        Helper.Format();
+    end;
+}"#;
+        let helper_uri = Url::parse("file:///project/src/Helpers.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(helper_uri.clone(), DocumentState::new(helper_source));
+        state.documents.insert(
+            Url::parse("file:///project/src/Consumer.al").unwrap(),
+            DocumentState::new(user_source),
+        );
+
+        let Some(CommandOutcome::Value(value)) = handle_execute_command(
+            &state,
+            ExecuteCommandParams {
+                command: FIND_UNUSED_OBJECTS_COMMAND.to_string(),
+                arguments: Vec::new(),
+                work_done_progress_params: Default::default(),
+            },
+        ) else {
+            panic!("expected command result");
+        };
+        let unused: Vec<Location> = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            unused,
+            vec![Location {
+                uri: helper_uri,
+                range: Range::new(Position::new(7, 15), Position::new(7, 30)),
+            }],
+            "only the orphan is unused; the consumer table has a trigger"
+        );
+    }
+
+    #[test]
+    fn test_dump_symbols_round_trips_through_json() {
+        let source = r#"table 50100 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+    }
+
+    procedure Validate(Strict: Boolean)
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///project/src/Customer.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let Some(CommandOutcome::Value(value)) = handle_execute_command(
+            &state,
+            ExecuteCommandParams {
+                command: DUMP_SYMBOLS_COMMAND.to_string(),
+                arguments: Vec::new(),
+                work_done_progress_params: Default::default(),
+            },
+        ) else {
+            panic!("expected command result");
+        };
+        let dump: Vec<DocumentSymbolDump> =
+            serde_json::from_value(value.clone()).expect("dump parses back");
+        assert_eq!(dump.len(), 1);
+        assert_eq!(dump[0].uri, uri);
+
+        let table = &dump[0].symbols[0];
+        assert_eq!(table.name, "Customer");
+        assert_eq!(table.kind, "table");
+        assert_eq!(table.object_id, Some(50100));
+        assert_eq!(
+            table.selection_range,
+            Range::new(Position::new(0, 12), Position::new(0, 20))
+        );
+
+        let field = table
+            .children
+            .iter()
+            .find(|child| child.kind == "field")
+            .expect("field in dump");
+        assert_eq!(field.name, "No.");
+        assert_eq!(field.type_info.as_deref(), Some("Code[20]"));
+        let procedure = table
+            .children
+            .iter()
+            .find(|child| child.kind == "procedure")
+            .expect("procedure in dump");
+        assert_eq!(procedure.name, "Validate");
+        assert_eq!(procedure.range.start, Position::new(7, 4));
+        assert!(value[0]["symbols"][0].get("selectionRange").is_some());
+    }
+}
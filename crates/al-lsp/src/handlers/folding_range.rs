@@ -11,7 +11,7 @@ pub fn handle_folding_range(
     let uri = params.text_document.uri;
     let doc = state.documents.get(&uri)?;
 
-    let areas = collect_folding_ranges(&doc.tree);
+    let areas = collect_folding_ranges(doc.tree(), doc.source());
 
     if areas.is_empty() {
         return None;
@@ -28,7 +28,7 @@ pub fn handle_folding_range(
                 FoldingAreaKind::Comment => FoldingRangeKind::Comment,
                 FoldingAreaKind::Region => FoldingRangeKind::Region,
             }),
-            collapsed_text: None,
+            collapsed_text: area.collapsed_text,
         })
         .collect();
 
@@ -1,9 +1,25 @@
-use lsp_types::{DocumentFormattingParams, Position, Range, TextEdit};
+use lsp_types::{
+    DocumentFormattingParams, DocumentOnTypeFormattingParams, DocumentRangeFormattingParams,
+    FormattingOptions, Position, Range, TextEdit,
+};
 
 use al_syntax::formatting::{self, FormatOptions};
 
 use crate::state::WorldState;
 
+/// Formatter options from the client's request options and the server config.
+fn format_options(state: &WorldState, options: &FormattingOptions) -> FormatOptions {
+    let config = state.config.lock().unwrap();
+    FormatOptions {
+        tab_size: options.tab_size as usize,
+        insert_spaces: options.insert_spaces,
+        normalize_keyword_case: config.normalize_keyword_case,
+        begin_style: config.begin_style,
+        blank_line_before_begin: config.blank_line_before_begin,
+        max_blank_lines: config.max_blank_lines,
+    }
+}
+
 pub fn handle_formatting(
     state: &WorldState,
     params: DocumentFormattingParams,
@@ -11,22 +27,20 @@ pub fn handle_formatting(
     let uri = params.text_document.uri;
     let doc = state.documents.get(&uri)?;
 
-    let source = doc.rope.to_string();
-    let options = FormatOptions {
-        tab_size: params.options.tab_size as usize,
-        insert_spaces: params.options.insert_spaces,
-    };
+    let source = doc.rope().to_string();
+    let options = format_options(state, &params.options);
+    state.format_options.insert(uri.clone(), options.clone());
 
-    let formatted = formatting::format_document(&doc.tree, &source, &options);
+    let formatted = formatting::format_document(doc.tree(), &source, &options);
 
     if formatted == source {
         return None;
     }
 
     // Return a single edit replacing the entire document.
-    let line_count = doc.rope.len_lines();
+    let line_count = doc.rope().len_lines();
     let last_line = if line_count > 0 { line_count - 1 } else { 0 };
-    let last_col = doc.rope.line(last_line).len_chars();
+    let last_col = doc.rope().line(last_line).len_chars();
 
     Some(vec![TextEdit {
         range: Range {
@@ -42,3 +56,162 @@ pub fn handle_formatting(
         new_text: formatted,
     }])
 }
+
+pub fn handle_range_formatting(
+    state: &WorldState,
+    params: DocumentRangeFormattingParams,
+) -> Option<Vec<TextEdit>> {
+    let uri = params.text_document.uri;
+    let doc = state.documents.get(&uri)?;
+
+    let source = doc.rope().to_string();
+    let options = format_options(state, &params.options);
+    state.format_options.insert(uri.clone(), options.clone());
+
+    let start_line = params.range.start.line as usize;
+    let mut end_line = params.range.end.line as usize;
+    // A selection ending at the start of a line does not include that line.
+    if params.range.end.character == 0 && end_line > start_line {
+        end_line -= 1;
+    }
+
+    let edits: Vec<TextEdit> =
+        formatting::format_range(doc.tree(), &source, &options, start_line, end_line)
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: Range {
+                    start: line_start_position(doc.rope(), edit.start_line),
+                    end: line_start_position(doc.rope(), edit.end_line),
+                },
+                new_text: edit.new_text,
+            })
+            .collect();
+
+    if edits.is_empty() {
+        return None;
+    }
+    Some(edits)
+}
+
+pub fn handle_on_type_formatting(
+    state: &WorldState,
+    params: DocumentOnTypeFormattingParams,
+) -> Option<Vec<TextEdit>> {
+    let uri = params.text_document_position.text_document.uri;
+    let doc = state.documents.get(&uri)?;
+
+    let source = doc.rope().to_string();
+    let options = format_options(state, &params.options);
+    state.format_options.insert(uri.clone(), options.clone());
+    let line = params.text_document_position.position.line as usize;
+
+    let edits: Vec<TextEdit> = formatting::format_on_type(doc.tree(), &source, &options, line)
+        .into_iter()
+        .map(|edit| TextEdit {
+            // Leading whitespace is ASCII, so byte length equals UTF-16 length.
+            range: Range {
+                start: Position::new(edit.line as u32, 0),
+                end: Position::new(edit.line as u32, edit.current_len as u32),
+            },
+            new_text: edit.indent,
+        })
+        .collect();
+
+    if edits.is_empty() {
+        return None;
+    }
+    Some(edits)
+}
+
+/// Position of the start of `line`, or the end of the document past the last line.
+fn line_start_position(rope: &ropey::Rope, line: usize) -> Position {
+    if line < rope.len_lines() {
+        return Position {
+            line: line as u32,
+            character: 0,
+        };
+    }
+    let last_line = rope.len_lines().saturating_sub(1);
+    Position {
+        line: last_line as u32,
+        character: rope.line(last_line).len_utf16_cu() as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{FormattingOptions, TextDocumentIdentifier, Url};
+
+    #[test]
+    fn test_range_formatting_confines_edits_to_selection() {
+        let source = "codeunit 50100 Test\n{\nprocedure First()\nbegin\nMessage('a');\nend;\n\nprocedure Second()\nbegin\nMessage('b');\nend;\n}";
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let params = DocumentRangeFormattingParams {
+            text_document: TextDocumentIdentifier { uri },
+            range: Range {
+                start: Position::new(7, 0),
+                end: Position::new(11, 0),
+            },
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: true,
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+        };
+        let edits = handle_range_formatting(&state, params).expect("expected edits");
+        assert!(
+            edits
+                .iter()
+                .all(|e| e.range.start.line >= 7 && e.range.end.line <= 11),
+            "edits escaped the selection: {edits:?}"
+        );
+        assert!(edits
+            .iter()
+            .any(|e| e.new_text == "    procedure Second()\n"));
+        assert!(edits
+            .iter()
+            .any(|e| e.new_text == "        Message('b');\n"));
+    }
+
+    #[test]
+    fn test_on_type_formatting_dedents_end() {
+        let source = "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n        Message('a');\n        end;\n}";
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let params = DocumentOnTypeFormattingParams {
+            text_document_position: lsp_types::TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: Position::new(5, 12),
+            },
+            ch: ";".to_string(),
+            options: FormattingOptions {
+                tab_size: 4,
+                insert_spaces: true,
+                ..Default::default()
+            },
+        };
+        let edits = handle_on_type_formatting(&state, params).expect("expected edits");
+        assert_eq!(
+            edits,
+            vec![TextEdit {
+                range: Range {
+                    start: Position::new(5, 0),
+                    end: Position::new(5, 8),
+                },
+                new_text: "    ".to_string(),
+            }]
+        );
+    }
+}
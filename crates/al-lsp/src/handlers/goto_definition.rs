@@ -1,21 +1,36 @@
-use lsp_types::{GotoDefinitionParams, GotoDefinitionResponse, Location};
+use lsp_types::{GotoDefinitionParams, GotoDefinitionResponse, Location, Range, Url};
 use tree_sitter::Point;
 
-use al_syntax::ast::{extract_name, AlSymbol, AlSymbolKind};
+use al_syntax::ast::{
+    extract_name, node_text, split_namespace_qualifier, AlObjectKind, AlSymbol, AlSymbolKind,
+};
 use al_syntax::navigation::{
-    extract_type_object_name, identifier_context_at_offset, node_at_offset, resolve_at_offset,
+    codeunit_method_call_at_offset, extract_type_namespace, extract_type_object_name,
+    identifier_context_at_offset, interface_method_call_at_offset, node_at_offset,
+    resolve_at_offset,
 };
 
 use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range};
 use crate::handlers::completion::{
-    enum_value_target_at_offset, enum_value_usage_at_offset, member_access_target_at_offset,
-    resolve_option_members_from_context,
+    enum_value_target_at_offset, enum_value_usage_at_offset, extends_object,
+    member_access_target_at_offset, resolve_option_members_from_context,
 };
 use crate::handlers::events::{
-    event_invocation_target_at_offset, event_subscriber_context_at_offset, find_event_publishers,
+    event_invocation_target_at_offset, event_subscriber_context_at_offset,
+    event_subscriber_procedure_target_at_offset, find_event_publishers,
 };
 use crate::state::WorldState;
 
+/// Where a jump to `symbol` lands: its name, or the whole declaration when
+/// the symbol has no name node.
+fn symbol_target_range(symbol: &AlSymbol) -> Range {
+    if symbol.name_start_point == symbol.name_end_point {
+        ts_range_to_lsp_range(symbol.start_point, symbol.end_point)
+    } else {
+        ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point)
+    }
+}
+
 fn to_definition_response(locations: Vec<Location>) -> Option<GotoDefinitionResponse> {
     if locations.is_empty() {
         return None;
@@ -28,12 +43,56 @@ fn to_definition_response(locations: Vec<Location>) -> Option<GotoDefinitionResp
     Some(GotoDefinitionResponse::Array(locations))
 }
 
-fn find_object_declarations(
+/// Declarations of the `object_kind` object named `object_name` that a
+/// reference in `from` can mean (see [`WorldState::object_visible_from`]).
+pub(crate) fn find_object_declarations(
+    state: &WorldState,
+    from: &Url,
+    object_kind: &str,
+    object_name: &str,
+) -> Vec<Location> {
+    let mut locations = find_all_object_declarations(state, object_kind, object_name);
+    locations.retain(|location| {
+        state.object_visible_from(from, &location.uri, object_kind, object_name)
+    });
+    locations
+}
+
+fn find_all_object_declarations(
     state: &WorldState,
     object_kind: &str,
     object_name: &str,
 ) -> Vec<Location> {
     let mut locations = Vec::new();
+
+    // Prefer the workspace object index; fall back to scanning documents
+    // that were loaded without going through it.
+    let object_key = (
+        object_kind.to_ascii_lowercase(),
+        object_name.to_ascii_lowercase(),
+    );
+    if let Some(indexed_entries) = state.object_index.get(&object_key) {
+        for indexed in indexed_entries.iter() {
+            let Some(doc) = state.documents.get(&indexed.uri) else {
+                continue;
+            };
+            if let Some(symbol) = doc
+                .symbol_table
+                .symbols
+                .iter()
+                .find(|symbol| symbol.start_byte == indexed.object_start_byte)
+            {
+                locations.push(Location {
+                    uri: indexed.uri.clone(),
+                    range: symbol_target_range(symbol),
+                });
+            }
+        }
+    }
+    if !locations.is_empty() {
+        return locations;
+    }
+
     for entry in state.documents.iter() {
         for symbol in &entry.value().symbol_table.symbols {
             if let AlSymbolKind::Object(kind) = symbol.kind {
@@ -42,7 +101,7 @@ fn find_object_declarations(
                 {
                     locations.push(Location {
                         uri: entry.key().clone(),
-                        range: ts_range_to_lsp_range(symbol.start_point, symbol.end_point),
+                        range: symbol_target_range(symbol),
                     });
                 }
             }
@@ -72,7 +131,7 @@ fn find_enum_value_declarations(
                 {
                     locations.push(Location {
                         uri: entry.key().clone(),
-                        range: ts_range_to_lsp_range(child.start_point, child.end_point),
+                        range: symbol_target_range(child),
                     });
                 }
             }
@@ -88,7 +147,8 @@ fn find_inline_option_member_declaration(
     qualifier_byte_offset: usize,
     value_name: &str,
 ) -> Option<Location> {
-    let ctx = identifier_context_at_offset(&doc.tree, source, &doc.symbol_table, qualifier_byte_offset)?;
+    let ctx =
+        identifier_context_at_offset(doc.tree(), source, &doc.symbol_table, qualifier_byte_offset)?;
     let sym = ctx.symbol?;
     let decl_start = sym.start_byte.min(source.len());
     let decl_end = sym.end_byte.min(source.len());
@@ -141,6 +201,7 @@ fn point_at_offset(source: &str, offset: usize) -> Option<Point> {
 
 fn find_object_member_declarations(
     state: &WorldState,
+    from: &Url,
     object_kind: &str,
     object_name: &str,
     member_name: &str,
@@ -155,9 +216,12 @@ fn find_object_member_declarations(
             let AlSymbolKind::Object(kind) = symbol.kind else {
                 continue;
             };
-            if !kind.label().eq_ignore_ascii_case(object_kind)
-                || !symbol.name.eq_ignore_ascii_case(object_name)
-            {
+            let is_object = kind.label().eq_ignore_ascii_case(object_kind)
+                && symbol.name.eq_ignore_ascii_case(object_name);
+            if !is_object && !extends_object(kind, symbol, object_kind, object_name) {
+                continue;
+            }
+            if !state.object_visible_from(from, &uri, object_kind, object_name) {
                 continue;
             }
 
@@ -172,11 +236,13 @@ fn find_object_member_declarations(
                 found_symbol_member = true;
                 locations.push(Location {
                     uri: uri.clone(),
-                    range: ts_range_to_lsp_range(child.start_point, child.end_point),
+                    range: symbol_target_range(child),
                 });
             }
 
-            if !is_method_call && kind.label().eq_ignore_ascii_case("table") && !found_symbol_member
+            if !is_method_call
+                && matches!(kind, AlObjectKind::Table | AlObjectKind::TableExtension)
+                && !found_symbol_member
             {
                 if let Some(location) = find_table_field_location(&uri, source, symbol, member_name)
                 {
@@ -278,18 +344,20 @@ fn point_from_relative_byte(base: Point, text: &str, rel: usize) -> Point {
     point
 }
 
-fn type_target_from_type_identifier(
+pub(crate) fn type_target_from_type_identifier(
     node: tree_sitter::Node<'_>,
     source: &str,
 ) -> Option<(&'static str, String)> {
     if !matches!(node.kind(), "identifier" | "quoted_identifier") {
         return None;
     }
-    let parent = node.parent()?;
+    // `Record MyCompany.Sales.Customer`: only the last segment names the object.
+    let type_name = type_name_node(node);
+    let parent = type_name.parent()?;
     match parent.kind() {
         "record_type" => {
             let table = parent.child_by_field_name("table")?;
-            if table.id() == node.id() {
+            if table.id() == type_name.id() {
                 Some(("table", extract_name(node, source)))
             } else {
                 None
@@ -297,37 +365,80 @@ fn type_target_from_type_identifier(
         }
         "codeunit_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("codeunit", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("codeunit", extract_name(node, source)))
         }
         "page_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("page", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("page", extract_name(node, source)))
         }
         "report_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("report", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("report", extract_name(node, source)))
         }
         "query_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("query", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("query", extract_name(node, source)))
         }
         "xmlport_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("xmlport", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("xmlport", extract_name(node, source)))
         }
         "enum_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("enum", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("enum", extract_name(node, source)))
         }
         "interface_type" => {
             let name = parent.child_by_field_name("name")?;
-            (name.id() == node.id()).then(|| ("interface", extract_name(node, source)))
+            (name.id() == type_name.id()).then(|| ("interface", extract_name(node, source)))
         }
         _ => None,
     }
 }
 
-fn interface_target_from_implements_identifier(
+/// The node that fills a type's name field: the enclosing `qualified_name`
+/// when `node` is its final segment, otherwise `node` itself (namespace
+/// segments therefore never match a type field).
+fn type_name_node(node: tree_sitter::Node<'_>) -> tree_sitter::Node<'_> {
+    match node.parent() {
+        Some(parent)
+            if parent.kind() == "qualified_name"
+                && parent
+                    .named_child(parent.named_child_count().saturating_sub(1))
+                    .is_some_and(|last| last.id() == node.id()) =>
+        {
+            parent
+        }
+        _ => node,
+    }
+}
+
+/// Namespace qualifier written before the type name under `node`, if any.
+pub(crate) fn type_target_namespace(node: tree_sitter::Node<'_>, source: &str) -> Option<String> {
+    let parent = node.parent().filter(|p| p.kind() == "qualified_name")?;
+    let (qualifier, _) = split_namespace_qualifier(node_text(parent, source));
+    qualifier.map(|q| q.split_whitespace().collect())
+}
+
+/// Narrow `locations` to documents declaring one of `namespaces`, keeping
+/// every location when none of them match (or no namespace applies).
+pub(crate) fn prefer_namespaces(
+    state: &WorldState,
+    locations: Vec<Location>,
+    namespaces: &[String],
+) -> Vec<Location> {
+    let preferred: Vec<Location> = locations
+        .iter()
+        .filter(|location| state.document_in_namespaces(&location.uri, namespaces))
+        .cloned()
+        .collect();
+    if preferred.is_empty() {
+        locations
+    } else {
+        preferred
+    }
+}
+
+pub(crate) fn interface_target_from_implements_identifier(
     node: tree_sitter::Node<'_>,
     source: &str,
 ) -> Option<(&'static str, String)> {
@@ -341,6 +452,88 @@ fn interface_target_from_implements_identifier(
     None
 }
 
+/// Object kind referenced by a property whose value names an object, or
+/// `None` for `RunObject`, where the kind is spelled out in the value.
+fn object_reference_property_kind(property_name: &str) -> Option<Option<&'static str>> {
+    let lower = property_name.to_ascii_lowercase();
+    match lower.as_str() {
+        "runobject" => Some(None),
+        "cardpageid" | "lookuppageid" | "drilldownpageid" => Some(Some("page")),
+        "sourcetable" => Some(Some("table")),
+        _ => None,
+    }
+}
+
+/// Object `(kind, name)` referenced by the value of `RunObject`,
+/// `CardPageID`, `LookupPageID`, `DrillDownPageID` or `SourceTable` at
+/// `byte_offset`.
+fn object_reference_property_target_at_offset(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    byte_offset: usize,
+) -> Option<(&'static str, String)> {
+    let node = node_at_offset(tree, byte_offset)?;
+    let property = find_ancestor_of_kind(node, "property")?;
+    let (kind, name, value_range) = object_reference_property_value(property, source)?;
+    if byte_offset < value_range.start || byte_offset > value_range.end {
+        return None;
+    }
+    Some((kind, name))
+}
+
+/// Object `(kind, name)` named by an object-reference `property`, plus the
+/// byte range of its value.
+///
+/// The value is read from the property text rather than the tree, since
+/// `Page "Customer Card"` is not a single property value node.
+pub(crate) fn object_reference_property_value(
+    property: tree_sitter::Node<'_>,
+    source: &str,
+) -> Option<(&'static str, String, std::ops::Range<usize>)> {
+    let prop_name_node = property.child_by_field_name("name")?;
+    let fixed_kind = object_reference_property_kind(&extract_name(prop_name_node, source))?;
+
+    let property_text = &source[property.start_byte()..property.end_byte()];
+    let value_start = property.start_byte() + property_text.find('=')? + 1;
+    let value = source[value_start..property.end_byte()]
+        .trim_end()
+        .trim_end_matches(';')
+        .trim();
+    if value.is_empty() {
+        return None;
+    }
+    let value_offset = value_start + source[value_start..].find(value)?;
+
+    let (kind, name) = match fixed_kind {
+        Some(kind) => (kind, value),
+        None => {
+            let (kind_word, name) = value.split_once(char::is_whitespace)?;
+            let kind = match kind_word.to_ascii_lowercase().as_str() {
+                "page" => "page",
+                "report" => "report",
+                "codeunit" => "codeunit",
+                "xmlport" => "xmlport",
+                "query" => "query",
+                _ => return None,
+            };
+            (kind, name.trim())
+        }
+    };
+
+    let name = name
+        .strip_prefix('"')
+        .and_then(|n| n.strip_suffix('"'))
+        .unwrap_or(name);
+    if name.is_empty() {
+        return None;
+    }
+    Some((
+        kind,
+        name.to_string(),
+        value_offset..value_offset + value.len(),
+    ))
+}
+
 #[derive(Debug, Clone)]
 enum TableRelationNavTarget {
     Table(String),
@@ -479,9 +672,9 @@ pub fn handle_goto_definition(
     let position = params.text_document_position_params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
-    if let Some(usage) = enum_value_usage_at_offset(&doc.tree, &source, byte_offset) {
+    if let Some(usage) = enum_value_usage_at_offset(doc.tree(), &source, byte_offset) {
         if let Some((_, members)) = resolve_option_members_from_context(state, &uri, &usage.context)
         {
             if members
@@ -506,7 +699,7 @@ pub fn handle_goto_definition(
             }
         }
     }
-    let enum_target = enum_value_target_at_offset(state, &uri, &doc.tree, &source, byte_offset);
+    let enum_target = enum_value_target_at_offset(state, &uri, doc.tree(), &source, byte_offset);
 
     if let Some((enum_name, value_name)) = enum_target {
         drop(doc);
@@ -517,20 +710,21 @@ pub fn handle_goto_definition(
         }
         let doc = state.documents.get(&uri)?;
         let source = doc.source();
-        let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-        let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+        let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+        let range = symbol_target_range(resolved.symbol);
         return Some(GotoDefinitionResponse::Scalar(Location {
             uri: uri.clone(),
             range,
         }));
     }
 
-    if let Some(event_ctx) = event_subscriber_context_at_offset(&doc.tree, &source, byte_offset) {
+    if let Some(event_ctx) = event_subscriber_context_at_offset(doc.tree(), &source, byte_offset) {
         if event_ctx.arg_index == 1 {
             if let Some((object_kind, object_name)) = event_ctx.object_ref {
                 drop(doc);
                 if let Some(resp) = to_definition_response(find_object_declarations(
                     state,
+                    &uri,
                     &object_kind,
                     &object_name,
                 )) {
@@ -539,9 +733,8 @@ pub fn handle_goto_definition(
                 let doc = state.documents.get(&uri)?;
                 let source = doc.source();
                 let resolved =
-                    resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-                let range =
-                    ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+                    resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+                let range = symbol_target_range(resolved.symbol);
                 return Some(GotoDefinitionResponse::Scalar(Location {
                     uri: uri.clone(),
                     range,
@@ -565,9 +758,8 @@ pub fn handle_goto_definition(
                 let doc = state.documents.get(&uri)?;
                 let source = doc.source();
                 let resolved =
-                    resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-                let range =
-                    ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+                    resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+                let range = symbol_target_range(resolved.symbol);
                 return Some(GotoDefinitionResponse::Scalar(Location {
                     uri: uri.clone(),
                     range,
@@ -576,8 +768,12 @@ pub fn handle_goto_definition(
         }
     }
 
+    // Event raises and subscriber procedure names both lead to the publisher.
     let invocation_target =
-        event_invocation_target_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset);
+        event_invocation_target_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
+            .or_else(|| {
+                event_subscriber_procedure_target_at_offset(doc.tree(), source, byte_offset)
+            });
     let doc = if let Some(target) = invocation_target {
         drop(doc);
         let locations: Vec<Location> = find_event_publishers(state, &target)
@@ -596,13 +792,29 @@ pub fn handle_goto_definition(
     };
     let source = doc.source();
 
-    if let Some(target) = table_relation_nav_target_at_offset(&doc.tree, &source, byte_offset) {
+    if let Some((object_kind, object_name)) =
+        object_reference_property_target_at_offset(doc.tree(), source, byte_offset)
+    {
+        if let Some(resp) = to_definition_response(find_object_declarations(
+            state,
+            &uri,
+            object_kind,
+            &object_name,
+        )) {
+            return Some(resp);
+        }
+    }
+
+    if let Some(target) = table_relation_nav_target_at_offset(doc.tree(), &source, byte_offset) {
         drop(doc);
         match target {
             TableRelationNavTarget::Table(table_name) => {
-                if let Some(resp) =
-                    to_definition_response(find_object_declarations(state, "table", &table_name))
-                {
+                if let Some(resp) = to_definition_response(find_object_declarations(
+                    state,
+                    &uri,
+                    "table",
+                    &table_name,
+                )) {
                     return Some(resp);
                 }
             }
@@ -612,6 +824,7 @@ pub fn handle_goto_definition(
             } => {
                 if let Some(resp) = to_definition_response(find_object_member_declarations(
                     state,
+                    &uri,
                     "table",
                     &table_name,
                     &field_name,
@@ -623,18 +836,54 @@ pub fn handle_goto_definition(
         }
         let doc = state.documents.get(&uri)?;
         let source = doc.source();
-        let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-        let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+        let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+        let range = symbol_target_range(resolved.symbol);
         return Some(GotoDefinitionResponse::Scalar(Location {
             uri: uri.clone(),
             range,
         }));
     }
 
+    // Method calls on interface- and codeunit-typed variables jump to the
+    // called method, as in the references handler.
+    let method_call_target =
+        interface_method_call_at_offset(doc.tree(), source, &doc.symbol_table, byte_offset)
+            .map(|(interface_name, method_name)| (true, interface_name, method_name))
+            .or_else(|| {
+                codeunit_method_call_at_offset(doc.tree(), source, &doc.symbol_table, byte_offset)
+                    .map(|(codeunit_name, method_name)| (false, codeunit_name, method_name))
+            });
+    let doc = if let Some((is_interface, object_name, method_name)) = method_call_target {
+        drop(doc);
+        let mut locations = Vec::new();
+        for entry in state.documents.iter() {
+            let symbol_table = &entry.value().symbol_table;
+            let method = if is_interface {
+                symbol_table.find_interface_method(&object_name, &method_name)
+            } else {
+                symbol_table.find_object_procedure(&object_name, &method_name)
+            };
+            if let Some(method) = method {
+                locations.push(Location {
+                    uri: entry.key().clone(),
+                    range: symbol_target_range(method),
+                });
+            }
+        }
+        if let Some(resp) = to_definition_response(locations) {
+            return Some(resp);
+        }
+        state.documents.get(&uri)?
+    } else {
+        doc
+    };
+    let source = doc.source();
+
     if let Some(target) = member_access_target_at_offset(state, &doc, &source, byte_offset) {
         drop(doc);
         if let Some(resp) = to_definition_response(find_object_member_declarations(
             state,
+            &uri,
             &target.object_kind,
             &target.object_name,
             &target.member_name,
@@ -644,21 +893,24 @@ pub fn handle_goto_definition(
         }
         let doc = state.documents.get(&uri)?;
         let source = doc.source();
-        let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-        let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+        let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+        let range = symbol_target_range(resolved.symbol);
         return Some(GotoDefinitionResponse::Scalar(Location {
             uri: uri.clone(),
             range,
         }));
     }
 
-    let id_ctx = identifier_context_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset);
+    let id_ctx = identifier_context_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset);
 
-    // Only attempt interface→impl navigation when the cursor is on a Procedure identifier.
-    let cursor_on_procedure = id_ctx
-        .as_ref()
-        .and_then(|ctx| ctx.symbol)
-        .is_some_and(|sym| matches!(sym.kind, AlSymbolKind::Procedure));
+    // Only attempt impl→interface navigation when the cursor is on a procedure's
+    // declared name; calls inside an implementation resolve normally.
+    let cursor_on_procedure = id_ctx.as_ref().is_some_and(|ctx| {
+        ctx.is_definition
+            && ctx
+                .symbol
+                .is_some_and(|sym| matches!(sym.kind, AlSymbolKind::Procedure))
+    });
 
     if cursor_on_procedure {
         // Check if cursor is on a procedure inside a codeunit that implements interfaces.
@@ -680,10 +932,7 @@ pub fn handle_goto_definition(
                     {
                         locations.push(Location {
                             uri: entry.key().clone(),
-                            range: ts_range_to_lsp_range(
-                                method_sym.start_point,
-                                method_sym.end_point,
-                            ),
+                            range: symbol_target_range(method_sym),
                         });
                     }
                 }
@@ -695,11 +944,10 @@ pub fn handle_goto_definition(
 
             // If no interface method found, fall through to normal resolution.
             let doc = state.documents.get(&uri)?;
-            let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+            let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
             let source = doc.source();
-            let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-            let range =
-                ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+            let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+            let range = symbol_target_range(resolved.symbol);
             return Some(GotoDefinitionResponse::Scalar(Location {
                 uri: uri.clone(),
                 range,
@@ -707,17 +955,21 @@ pub fn handle_goto_definition(
         }
     }
 
-    let mut symbol_type_target: Option<(String, String)> = None;
-    let mut node_type_target: Option<(String, String)> = None;
+    let mut symbol_type_target: Option<(String, String, Vec<String>)> = None;
+    let mut node_type_target: Option<(String, String, Vec<String>)> = None;
     let mut implements_type_target: Option<(String, String)> = None;
     if let Some(ctx) = id_ctx {
+        let namespaces = &doc.symbol_table.namespaces;
         // Variable/parameter usage: jump to the declared object type (Record/Codeunit/etc).
         if let Some(sym) = ctx.symbol {
             if matches!(sym.kind, AlSymbolKind::Variable | AlSymbolKind::Parameter) {
                 if let Some(type_info) = sym.type_info.as_deref() {
                     if let Some((object_kind, object_name)) = extract_type_object_name(type_info) {
-                        symbol_type_target =
-                            Some((object_kind.to_string(), object_name.to_string()));
+                        symbol_type_target = Some((
+                            object_kind.to_string(),
+                            object_name.to_string(),
+                            namespaces.candidates(extract_type_namespace(type_info)),
+                        ));
                     }
                 }
             }
@@ -727,7 +979,12 @@ pub fn handle_goto_definition(
         if let Some((object_kind, object_name)) =
             type_target_from_type_identifier(ctx.node, &source)
         {
-            node_type_target = Some((object_kind.to_string(), object_name));
+            let qualifier = type_target_namespace(ctx.node, source);
+            node_type_target = Some((
+                object_kind.to_string(),
+                object_name,
+                namespaces.candidates(qualifier.as_deref()),
+            ));
         }
 
         // Cursor on an interface name inside an object `implements` clause.
@@ -738,17 +995,17 @@ pub fn handle_goto_definition(
         }
     }
 
-    if let Some((object_kind, object_name)) = symbol_type_target {
+    if let Some((object_kind, object_name, namespaces)) = symbol_type_target {
         drop(doc);
-        if let Some(resp) =
-            to_definition_response(find_object_declarations(state, &object_kind, &object_name))
+        let locations = find_object_declarations(state, &uri, &object_kind, &object_name);
+        if let Some(resp) = to_definition_response(prefer_namespaces(state, locations, &namespaces))
         {
             return Some(resp);
         }
         let doc = state.documents.get(&uri)?;
         let source = doc.source();
-        let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-        let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+        let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+        let range = symbol_target_range(resolved.symbol);
         return Some(GotoDefinitionResponse::Scalar(Location {
             uri: uri.clone(),
             range,
@@ -757,41 +1014,44 @@ pub fn handle_goto_definition(
 
     if let Some((object_kind, object_name)) = implements_type_target {
         drop(doc);
-        if let Some(resp) =
-            to_definition_response(find_object_declarations(state, &object_kind, &object_name))
-        {
+        if let Some(resp) = to_definition_response(find_object_declarations(
+            state,
+            &uri,
+            &object_kind,
+            &object_name,
+        )) {
             return Some(resp);
         }
         let doc = state.documents.get(&uri)?;
         let source = doc.source();
-        let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-        let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+        let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+        let range = symbol_target_range(resolved.symbol);
         return Some(GotoDefinitionResponse::Scalar(Location {
             uri: uri.clone(),
             range,
         }));
     }
 
-    if let Some((object_kind, object_name)) = node_type_target {
+    if let Some((object_kind, object_name, namespaces)) = node_type_target {
         drop(doc);
-        if let Some(resp) =
-            to_definition_response(find_object_declarations(state, &object_kind, &object_name))
+        let locations = find_object_declarations(state, &uri, &object_kind, &object_name);
+        if let Some(resp) = to_definition_response(prefer_namespaces(state, locations, &namespaces))
         {
             return Some(resp);
         }
         let doc = state.documents.get(&uri)?;
         let source = doc.source();
-        let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-        let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+        let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+        let range = symbol_target_range(resolved.symbol);
         return Some(GotoDefinitionResponse::Scalar(Location {
             uri: uri.clone(),
             range,
         }));
     }
 
-    let resolved = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
+    let resolved = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
 
-    let range = ts_range_to_lsp_range(resolved.symbol.start_point, resolved.symbol.end_point);
+    let range = symbol_target_range(resolved.symbol);
 
     Some(GotoDefinitionResponse::Scalar(Location {
         uri: uri.clone(),
@@ -835,6 +1095,172 @@ mod tests {
         (line, character)
     }
 
+    #[test]
+    fn test_object_resolution_stays_within_workspace_root() {
+        let codeunit_source = r#"codeunit 50100 X
+{
+    procedure DoWork()
+    begin
+    end;
+}"#;
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+    }
+}"#;
+        let caller_source = r#"codeunit 50101 Caller
+{
+    procedure Run()
+    var
+        Cust: Record Customer;
+    begin
+    end;
+}"#;
+        let state = WorldState::new();
+        *state.workspace_roots.lock().unwrap() =
+            vec!["/ws/app-a".into(), "/ws/app-b".into(), "/ws/app-c".into()];
+        let doc_uri = |path: &str| Url::parse(&format!("file:///ws/{path}")).unwrap();
+        for root in ["app-a", "app-b"] {
+            state.upsert_document(
+                doc_uri(&format!("{root}/X.Codeunit.al")),
+                DocumentState::new(codeunit_source),
+            );
+            state.upsert_document(
+                doc_uri(&format!("{root}/Customer.Table.al")),
+                DocumentState::new(table_source),
+            );
+        }
+        let caller_a = doc_uri("app-a/Caller.Codeunit.al");
+        let caller_c = doc_uri("app-c/Caller.Codeunit.al");
+        state.upsert_document(caller_a.clone(), DocumentState::new(caller_source));
+        state.upsert_document(caller_c.clone(), DocumentState::new(caller_source));
+
+        assert_eq!(state.root_for_uri(&caller_a), Some("/ws/app-a".into()));
+        assert_eq!(state.root_for_uri(&doc_uri("other/Y.al")), None);
+
+        let declaring = |from: &Url| {
+            let mut uris: Vec<Url> = find_object_declarations(&state, from, "codeunit", "X")
+                .into_iter()
+                .map(|location| location.uri)
+                .collect();
+            uris.sort();
+            uris
+        };
+        assert_eq!(declaring(&caller_a), vec![doc_uri("app-a/X.Codeunit.al")]);
+        assert_eq!(
+            declaring(&doc_uri("app-b/Other.al")),
+            vec![doc_uri("app-b/X.Codeunit.al")]
+        );
+        // A root without its own `X` may reach either app.
+        assert_eq!(declaring(&caller_c).len(), 2);
+
+        let (line, character) = cursor_on(caller_source, "Customer;");
+        let locs = locations_from(
+            handle_goto_definition(&state, make_goto_params(caller_a, line, character))
+                .expect("expected the table"),
+        );
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].uri, doc_uri("app-a/Customer.Table.al"));
+    }
+
+    #[test]
+    fn test_goto_definition_record_field_from_table_extension() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+    }
+}"#;
+        let extension_source = r#"tableextension 50100 "Customer Ext" extends Customer
+{
+    fields
+    {
+        field(50100; LoyaltyPoints; Integer) { }
+    }
+}"#;
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Cust: Record Customer;
+    begin
+        Cust.LoyaltyPoints := 1;
+    end;
+}"#;
+        let extension_uri = Url::parse("file:///test/customer_ext.al").unwrap();
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state.documents.insert(
+            Url::parse("file:///test/customer.al").unwrap(),
+            DocumentState::new(table_source),
+        );
+        state
+            .documents
+            .insert(extension_uri.clone(), DocumentState::new(extension_source));
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_on(source, "LoyaltyPoints :=");
+        let locs = locations_from(
+            handle_goto_definition(&state, make_goto_params(uri, line, character))
+                .expect("expected the extension field"),
+        );
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].uri, extension_uri);
+        assert_eq!(locs[0].range.start, Position::new(4, 21));
+    }
+
+    #[test]
+    fn test_goto_definition_lands_on_object_and_procedure_names() {
+        let helper_source = r#"table 50101 "Address Helper"
+{
+    procedure FormatAddress(): Text
+    begin
+    end;
+}"#;
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Helper: Record "Address Helper";
+    begin
+        Helper.FormatAddress();
+    end;
+}"#;
+        let helper_uri = Url::parse("file:///test/helper.al").unwrap();
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(helper_uri.clone(), DocumentState::new(helper_source));
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_on(source, "Address Helper\";");
+        let locs = locations_from(
+            handle_goto_definition(&state, make_goto_params(uri.clone(), line, character + 1))
+                .expect("expected object definition"),
+        );
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].uri, helper_uri);
+        assert_eq!(locs[0].range.start, Position::new(0, 12));
+        assert_eq!(locs[0].range.end, Position::new(0, 28));
+
+        let (line, character) = cursor_on(source, "FormatAddress();");
+        let locs = locations_from(
+            handle_goto_definition(&state, make_goto_params(uri, line, character))
+                .expect("expected procedure definition"),
+        );
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].range.start, Position::new(2, 14));
+        assert_eq!(locs[0].range.end, Position::new(2, 27));
+    }
+
     #[test]
     fn test_goto_definition_impl_procedure_to_interface_method_cross_doc() {
         // Cursor on GetAddress in a codeunit that implements IAddressProvider.
@@ -855,10 +1281,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl_uri.clone(), DocumentState::new(impl_source).unwrap());
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
 
         // Cursor on "GetAddress" in the codeunit (line 2, col 14)
         let params = make_goto_params(impl_uri.clone(), 2, 14);
@@ -894,7 +1320,7 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "GetAddress" in the codeunit (line 7, col 14)
         let params = make_goto_params(uri.clone(), 7, 14);
@@ -912,6 +1338,43 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         );
     }
 
+    #[test]
+    fn test_goto_definition_call_inside_impl_procedure_resolves_to_callee() {
+        let source = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+}
+
+codeunit 50200 CompanyAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Text
+    begin
+        exit(FormatAddress());
+    end;
+
+    local procedure FormatAddress(): Text
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/all.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        // Cursor on "FormatAddress" in the call inside GetAddress (line 9, col 13)
+        let params = make_goto_params(uri.clone(), 9, 13);
+        let locs = locations_from(
+            handle_goto_definition(&state, params).expect("expected FormatAddress to resolve"),
+        );
+        assert_eq!(
+            locs.iter().map(|l| l.range.start).collect::<Vec<_>>(),
+            vec![Position::new(12, 20)],
+            "expected the FormatAddress declaration, not the interface method"
+        );
+    }
+
     #[test]
     fn test_goto_definition_non_interface_procedure_unchanged() {
         // Cursor on a regular procedure that is NOT an interface implementation.
@@ -932,7 +1395,7 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "Hello" in the call Hello() (line 8, col 8)
         let params = make_goto_params(uri.clone(), 8, 8);
@@ -965,7 +1428,7 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, mut character) = cursor_on(source, "Choice::\"Second Value\"");
         character += "Choice::".len() as u32;
@@ -996,7 +1459,7 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (param_line, mut param_char) = cursor_on(source, "OptionParameter::Alpha");
         param_char += "OptionParameter::".len() as u32;
@@ -1041,7 +1504,7 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, mut character) = cursor_on(source, "GlobalChoice::Alpha");
         character += "GlobalChoice::".len() as u32;
@@ -1085,7 +1548,7 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "CurrPage.Host.Invoke");
         let character = character + "CurrPage.Host.".len() as u32;
@@ -1118,11 +1581,10 @@ page 50100 "Dummy Host"
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri.clone(), DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri.clone(), DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor on `Cust` in `Cust.FindFirst()` (line 6, col 8)
         let params = make_goto_params(codeunit_uri.clone(), 6, 8);
@@ -1135,6 +1597,241 @@ page 50100 "Dummy Host"
         );
     }
 
+    #[test]
+    fn test_goto_definition_record_field_same_document() {
+        let source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+        field(2; Name; Text[100]) { }
+    }
+}
+
+codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Customer: Record Customer;
+    begin
+        Customer.Name := 'A';
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_on(source, "Name := 'A'");
+        let params = make_goto_params(uri.clone(), line, character + 1);
+        let locs = locations_from(
+            handle_goto_definition(&state, params).expect("expected field declaration"),
+        );
+        assert_eq!(locs.len(), 1, "{locs:?}");
+        assert_eq!(locs[0].uri, uri);
+        assert_eq!(locs[0].range.start.line, 5);
+    }
+
+    #[test]
+    fn test_goto_definition_record_field_cross_document() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+        field(2; Name; Text[100]) { }
+    }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure DoWork(var Customer: Record Customer): Code[20]
+    begin
+        exit(Customer."No.");
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri.clone(), DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        let (line, character) = cursor_on(codeunit_source, "\"No.\")");
+        let params = make_goto_params(codeunit_uri, line, character + 1);
+        let locs = locations_from(
+            handle_goto_definition(&state, params).expect("expected field declaration"),
+        );
+        assert_eq!(locs.len(), 1, "{locs:?}");
+        assert_eq!(locs[0].uri, table_uri);
+        assert_eq!(locs[0].range.start.line, 4);
+    }
+
+    #[test]
+    fn test_goto_definition_global_temporary_record_field_in_two_procedures() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+        field(2; Name; Text[100]) { }
+    }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    var
+        MyTempCust: Record Customer temporary;
+
+    procedure Fill()
+    begin
+        MyTempCust.Name := 'A';
+    end;
+
+    procedure Show(): Text
+    begin
+        exit(MyTempCust."No.");
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri.clone(), DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        let line_of = |marker: &str| {
+            table_source[..table_source.find(marker).unwrap()]
+                .bytes()
+                .filter(|&b| b == b'\n')
+                .count() as u32
+        };
+
+        for (marker, field_marker) in [
+            ("Name := 'A'", "Name; Text"),
+            ("\"No.\");", "\"No.\"; Code"),
+        ] {
+            let (line, character) = cursor_on(codeunit_source, marker);
+            let params = make_goto_params(codeunit_uri.clone(), line, character + 1);
+            let result = handle_goto_definition(&state, params);
+            assert!(
+                result.is_some(),
+                "expected goto-definition result for {marker}"
+            );
+            let locs = locations_from(result.unwrap());
+            assert!(
+                locs.iter()
+                    .any(|l| l.uri == table_uri && l.range.start.line == line_of(field_marker)),
+                "expected navigation to field {field_marker} in table, got: {locs:?}"
+            );
+        }
+
+        let (line, character) = cursor_on(codeunit_source, "MyTempCust.Name");
+        let params = lsp_types::request::GotoTypeDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: codeunit_uri.clone(),
+                },
+                position: Position { line, character },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let result =
+            crate::handlers::goto_type_definition::handle_goto_type_definition(&state, params);
+        let locs = locations_from(result.expect("expected goto-type-definition result"));
+        assert!(
+            locs.iter().any(|l| l.uri == table_uri),
+            "expected goto-type to reach the Customer table, got: {locs:?}"
+        );
+    }
+
+    fn goto_locations(documents: &[(&str, &str)], path: &str, marker: &str) -> Vec<Location> {
+        let state = WorldState::new();
+        for (doc_path, source) in documents {
+            state.documents.insert(
+                Url::parse(&format!("file:///test/{doc_path}")).unwrap(),
+                DocumentState::new(source),
+            );
+        }
+        let source = documents
+            .iter()
+            .find(|(doc_path, _)| *doc_path == path)
+            .map(|(_, source)| *source)
+            .unwrap();
+        let (line, character) = cursor_on(source, marker);
+        let uri = Url::parse(&format!("file:///test/{path}")).unwrap();
+        handle_goto_definition(&state, make_goto_params(uri, line, character))
+            .map(locations_from)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_goto_definition_codeunit_qualified_call_to_procedure() {
+        let target = r#"codeunit 50101 CompanyAddressProvider2
+{
+    procedure HelloWorld2()
+    begin
+    end;
+}"#;
+        let caller = r#"codeunit 50100 Caller
+{
+    procedure Run()
+    var
+        CompanyAddressProvider2: Codeunit CompanyAddressProvider2;
+    begin
+        CompanyAddressProvider2.HelloWorld2();
+    end;
+
+    procedure HelloWorld2()
+    begin
+    end;
+}"#;
+        let locs = goto_locations(
+            &[("provider.al", target), ("caller.al", caller)],
+            "caller.al",
+            "HelloWorld2();",
+        );
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].uri.path(), "/test/provider.al");
+        assert_eq!(locs[0].range.start.line, 2);
+    }
+
+    #[test]
+    fn test_goto_definition_interface_qualified_call_to_method() {
+        let iface = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+}"#;
+        let caller = r#"codeunit 50100 Caller
+{
+    procedure Run()
+    var
+        Provider: Interface IAddressProvider;
+    begin
+        Provider.GetAddress();
+    end;
+
+    procedure GetAddress(): Text
+    begin
+    end;
+}"#;
+        let locs = goto_locations(
+            &[("iface.al", iface), ("caller.al", caller)],
+            "caller.al",
+            "GetAddress();",
+        );
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].uri.path(), "/test/iface.al");
+        assert_eq!(locs[0].range.start.line, 2);
+    }
+
     #[test]
     fn test_goto_definition_member_on_procedure_return_interface() {
         let source = r#"interface "Demo IFunctions"
@@ -1158,7 +1855,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on NumberPad in HelperFunc.NumberPad(...)
         let (line, character) = cursor_on(source, "NumberPad('9', 9)");
@@ -1198,7 +1895,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "NumberPad('9', 9)");
         let params = make_goto_params(uri, line, character);
@@ -1233,11 +1930,10 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(table_uri.clone(), DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri.clone(), DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor on `Customer` in `Cust: Record Customer;` (line 4, col 21)
         let params = make_goto_params(codeunit_uri.clone(), 4, 21);
@@ -1250,6 +1946,67 @@ codeunit 50100 Test
         );
     }
 
+    #[test]
+    fn test_goto_definition_namespaced_type_prefers_declaring_namespace() {
+        let sales_source = r#"namespace MyCompany.Sales;
+
+table 50100 Customer
+{
+}"#;
+        let service_source = r#"namespace MyCompany.Service;
+
+table 50200 Customer
+{
+}"#;
+        let codeunit_source = r#"namespace MyCompany.App;
+
+using MyCompany.Service;
+
+codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Cust: Record MyCompany.Sales.Customer;
+        Other: Record Customer;
+    begin
+        Cust.Init();
+    end;
+}"#;
+        let sales_uri = Url::parse("file:///test/sales.al").unwrap();
+        let service_uri = Url::parse("file:///test/service.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(sales_uri.clone(), DocumentState::new(sales_source));
+        state
+            .documents
+            .insert(service_uri.clone(), DocumentState::new(service_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        let goto = |marker: &str| {
+            let (line, character) = cursor_on(codeunit_source, marker);
+            let result = handle_goto_definition(
+                &state,
+                make_goto_params(codeunit_uri.clone(), line, character),
+            )
+            .expect("expected goto-definition result");
+            locations_from(result)
+                .into_iter()
+                .map(|l| l.uri)
+                .collect::<Vec<_>>()
+        };
+
+        // Qualified type name and a variable declared with it
+        assert_eq!(goto("Customer;"), vec![sales_uri.clone()]);
+        assert_eq!(goto("Cust.Init"), vec![sales_uri]);
+        // Unqualified name resolves through `using`
+        assert_eq!(goto("Customer;\n    begin"), vec![service_uri]);
+    }
+
     #[test]
     fn test_goto_definition_enum_value_from_qualified_usage() {
         let source = r#"enum 50100 MyEnum
@@ -1276,7 +2033,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on `First` in `MyEnum::First` (line 16, col 21)
         let params = make_goto_params(uri, 16, 21);
@@ -1292,6 +2049,56 @@ codeunit 50100 Test
         );
     }
 
+    const PRIORITY_ENUM: &str = r#"enum 50100 Priority
+{
+    value(0; Low)
+    {
+    }
+    value(1; High)
+    {
+    }
+}"#;
+
+    const PRIORITY_USAGE: &str = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        P: Enum Priority;
+    begin
+        P := Priority::High;
+        P := Priority::Urgent;
+    end;
+}"#;
+
+    fn goto_priority_usage(marker: &str) -> Option<GotoDefinitionResponse> {
+        let enum_uri = Url::parse("file:///test/priority.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(enum_uri, DocumentState::new(PRIORITY_ENUM));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(PRIORITY_USAGE));
+        let (line, character) = cursor_on(PRIORITY_USAGE, marker);
+        handle_goto_definition(&state, make_goto_params(codeunit_uri, line, character))
+    }
+
+    #[test]
+    fn test_goto_definition_enum_value_cross_document() {
+        let result = goto_priority_usage("High;");
+        let locs = locations_from(result.expect("expected enum value declaration"));
+        assert_eq!(locs.len(), 1, "got: {locs:?}");
+        assert_eq!(locs[0].uri.path(), "/test/priority.al");
+        assert_eq!(locs[0].range.start.line, 5);
+    }
+
+    #[test]
+    fn test_goto_definition_unknown_enum_value_has_no_target() {
+        let result = goto_priority_usage("Urgent;");
+        assert!(result.is_none(), "expected no definition, got: {result:?}");
+    }
+
     #[test]
     fn test_goto_definition_enum_value_from_record_field_qualified_usage() {
         let enum_source = r#"enum 50100 "Document Type"
@@ -1328,14 +2135,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri.clone(), DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri.clone(), DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor on `Order` in `Rec."Document Type"::Order` (line 6, col 47)
         let params = make_goto_params(codeunit_uri, 6, 47);
@@ -1371,13 +2177,12 @@ codeunit 50100 Test
         let publisher_uri = Url::parse("file:///test/publisher.al").unwrap();
         let subscriber_uri = Url::parse("file:///test/subscriber.al").unwrap();
         let state = WorldState::new();
-        state.documents.insert(
-            publisher_uri.clone(),
-            DocumentState::new(publisher_source).unwrap(),
-        );
+        state
+            .documents
+            .insert(publisher_uri.clone(), DocumentState::new(publisher_source));
         state.documents.insert(
             subscriber_uri.clone(),
-            DocumentState::new(subscriber_source).unwrap(),
+            DocumentState::new(subscriber_source),
         );
 
         let (line, character) = cursor_on(subscriber_source, "OnAfterPost");
@@ -1395,6 +2200,43 @@ codeunit 50100 Test
         );
     }
 
+    #[test]
+    fn test_goto_definition_event_subscriber_procedure_to_publisher() {
+        let publisher_source = r#"codeunit 50100 "My Publisher"
+{
+    [BusinessEvent(false)]
+    procedure OnAfterPost()
+    begin
+    end;
+}"#;
+        let subscriber_source = r#"codeunit 50101 "My Subscriber"
+{
+    [EventSubscriber(ObjectType::Codeunit, Codeunit::"My Publisher", 'OnAfterPost', '', false, false)]
+    local procedure HandleOnAfterPost()
+    begin
+    end;
+}"#;
+        let publisher_uri = Url::parse("file:///test/publisher.al").unwrap();
+        let subscriber_uri = Url::parse("file:///test/subscriber.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(publisher_uri.clone(), DocumentState::new(publisher_source));
+        state.documents.insert(
+            subscriber_uri.clone(),
+            DocumentState::new(subscriber_source),
+        );
+
+        let (line, character) = cursor_on(subscriber_source, "HandleOnAfterPost");
+        let params = make_goto_params(subscriber_uri, line, character);
+        let locs = locations_from(
+            handle_goto_definition(&state, params).expect("expected publisher location"),
+        );
+        assert_eq!(locs.len(), 1, "{locs:?}");
+        assert_eq!(locs[0].uri, publisher_uri);
+        assert_eq!(locs[0].range.start, Position::new(3, 14));
+    }
+
     #[test]
     fn test_goto_definition_event_invocation_to_publisher_declaration() {
         let source = r#"codeunit 50100 "My Publisher"
@@ -1413,7 +2255,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "OnAfterPost();");
         let params = make_goto_params(uri, line, character);
@@ -1447,10 +2289,10 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(enum_uri.clone(), DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri.clone(), DocumentState::new(enum_source));
 
         let (line, character) = cursor_on(enum_source, "\"Dummy Device Action\"");
         let params = make_goto_params(enum_uri, line, character);
@@ -1467,6 +2309,44 @@ codeunit 50100 Test
         );
     }
 
+    #[test]
+    fn test_goto_definition_page_properties_reference_objects() {
+        let card_source = r#"page 50101 "Customer Card"
+{
+}"#;
+        let list_source = r#"page 50100 "Customer List"
+{
+    CardPageID = "Customer Card";
+
+    actions
+    {
+        area(Processing)
+        {
+            action(Open)
+            {
+                RunObject = Page "Customer Card";
+            }
+        }
+    }
+}"#;
+        let card_uri = Url::parse("file:///test/card.al").unwrap();
+        let list_uri = Url::parse("file:///test/list.al").unwrap();
+
+        let state = WorldState::new();
+        state.upsert_document(card_uri.clone(), DocumentState::new(card_source));
+        state.upsert_document(list_uri.clone(), DocumentState::new(list_source));
+
+        for marker in ["\"Customer Card\";\n\n", "Page \"Customer Card\""] {
+            let (line, character) = cursor_on(list_source, marker);
+            let params = make_goto_params(list_uri.clone(), line, character + 6);
+            let result = handle_goto_definition(&state, params);
+            let locs = locations_from(result.expect("expected object declaration"));
+            assert_eq!(locs.len(), 1, "{marker}: {locs:?}");
+            assert_eq!(locs[0].uri, card_uri);
+            assert_eq!(locs[0].range.start.line, 0);
+        }
+    }
+
     #[test]
     fn test_goto_definition_tablerelation_table_target_from_member_access() {
         let ref_table_source = r#"table 50100 "Dummy Ref"
@@ -1490,14 +2370,12 @@ codeunit 50100 Test
         let ref_uri = Url::parse("file:///test/ref-table.al").unwrap();
         let target_uri = Url::parse("file:///test/target-table.al").unwrap();
         let state = WorldState::new();
-        state.documents.insert(
-            ref_uri.clone(),
-            DocumentState::new(ref_table_source).unwrap(),
-        );
-        state.documents.insert(
-            target_uri.clone(),
-            DocumentState::new(target_table_source).unwrap(),
-        );
+        state
+            .documents
+            .insert(ref_uri.clone(), DocumentState::new(ref_table_source));
+        state
+            .documents
+            .insert(target_uri.clone(), DocumentState::new(target_table_source));
 
         let (line, character) = cursor_on(ref_table_source, "\"Dummy Target\".\"No.\" WHERE");
         let params = make_goto_params(ref_uri, line, character + 1);
@@ -1537,14 +2415,12 @@ codeunit 50100 Test
         let ref_uri = Url::parse("file:///test/ref-table.al").unwrap();
         let target_uri = Url::parse("file:///test/target-table.al").unwrap();
         let state = WorldState::new();
-        state.documents.insert(
-            ref_uri.clone(),
-            DocumentState::new(ref_table_source).unwrap(),
-        );
-        state.documents.insert(
-            target_uri.clone(),
-            DocumentState::new(target_table_source).unwrap(),
-        );
+        state
+            .documents
+            .insert(ref_uri.clone(), DocumentState::new(ref_table_source));
+        state
+            .documents
+            .insert(target_uri.clone(), DocumentState::new(target_table_source));
 
         let (line, character) = cursor_on(ref_table_source, "\"No.\" WHERE");
         let params = make_goto_params(ref_uri, line, character + 1);
@@ -1583,14 +2459,12 @@ codeunit 50100 Test
         let ref_uri = Url::parse("file:///test/ref-table.al").unwrap();
         let target_uri = Url::parse("file:///test/target-table.al").unwrap();
         let state = WorldState::new();
-        state.documents.insert(
-            ref_uri.clone(),
-            DocumentState::new(ref_table_source).unwrap(),
-        );
-        state.documents.insert(
-            target_uri.clone(),
-            DocumentState::new(target_table_source).unwrap(),
-        );
+        state
+            .documents
+            .insert(ref_uri.clone(), DocumentState::new(ref_table_source));
+        state
+            .documents
+            .insert(target_uri.clone(), DocumentState::new(target_table_source));
 
         let (line, character) = cursor_on(ref_table_source, "\"Dummy Config\"");
         let params = make_goto_params(ref_uri, line, character + 1);
@@ -1646,14 +2520,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri, DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri, DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri.clone(), DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri.clone(), DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_on(codeunit_source, "\"KDS Display/Printing\" = HospType");
         let params = make_goto_params(codeunit_uri, line, character + 1);
@@ -1717,14 +2590,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri.clone(), DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri.clone(), DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_on(codeunit_source, "\"On Item Added\"");
         let params = make_goto_params(codeunit_uri, line, character + 1);
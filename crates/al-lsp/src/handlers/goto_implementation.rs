@@ -15,7 +15,7 @@ pub fn handle_goto_implementation(
     let position = params.text_document_position_params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
 
     // Only meaningful when cursor is on an interface method.
     let (interface_name, method_name) = doc.symbol_table.interface_method_at(byte_offset)?;
@@ -23,19 +23,14 @@ pub fn handle_goto_implementation(
     let method_name = method_name.to_string();
     drop(doc); // Release the DashMap ref before iterating
 
-    let mut locations = Vec::new();
-    for entry in state.documents.iter() {
-        let other_doc = entry.value();
-        let impls = other_doc
-            .symbol_table
-            .find_implementation_procedures(&interface_name, &method_name);
-        for proc_sym in impls {
-            locations.push(Location {
-                uri: entry.key().clone(),
-                range: ts_range_to_lsp_range(proc_sym.start_point, proc_sym.end_point),
-            });
-        }
-    }
+    let locations: Vec<Location> = state
+        .interface_implementations(&interface_name, &method_name)
+        .into_iter()
+        .map(|implementation| Location {
+            uri: implementation.uri,
+            range: ts_range_to_lsp_range(implementation.range.0, implementation.range.1),
+        })
+        .collect();
 
     if locations.is_empty() {
         return None;
@@ -93,10 +88,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl_uri.clone(), DocumentState::new(impl_source).unwrap());
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
 
         // Cursor on "GetAddress" in the interface (line 2, col 14)
         let params = make_impl_params(iface_uri.clone(), 2, 14);
@@ -138,13 +133,13 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl1_uri.clone(), DocumentState::new(impl1_source).unwrap());
+            .insert(impl1_uri.clone(), DocumentState::new(impl1_source));
         state
             .documents
-            .insert(impl2_uri.clone(), DocumentState::new(impl2_source).unwrap());
+            .insert(impl2_uri.clone(), DocumentState::new(impl2_source));
 
         let params = make_impl_params(iface_uri.clone(), 2, 14);
         let result = handle_goto_implementation(&state, params);
@@ -176,7 +171,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_impl_params(uri.clone(), 2, 14);
         let result = handle_goto_implementation(&state, params);
@@ -1,9 +1,12 @@
 use lsp_types::{request::GotoTypeDefinitionParams, GotoDefinitionResponse, Location};
 
 use al_syntax::ast::AlSymbolKind;
-use al_syntax::navigation::{extract_type_object_name, identifier_context_at_offset};
+use al_syntax::navigation::{
+    extract_type_object_name, identifier_context_at_offset, node_at_offset,
+};
 
 use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range};
+use crate::handlers::completion::infer_expression_type;
 use crate::state::WorldState;
 
 pub fn handle_goto_type_definition(
@@ -14,14 +17,20 @@ pub fn handle_goto_type_definition(
     let position = params.text_document_position_params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
 
-    let ctx = identifier_context_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-    let sym = ctx.symbol?;
-
-    let type_info = sym.type_info.as_deref()?;
-    let (object_kind, object_name) = extract_type_object_name(type_info)?;
+    let type_info = match call_or_member_expression_at_offset(doc.tree(), byte_offset) {
+        Some(expr) => infer_expression_type(state, &doc, source, expr, expr.start_byte())?,
+        // Variables and parameters resolve to their declared type; procedures,
+        // including calls without `()`, to their return type
+        None => {
+            let ctx =
+                identifier_context_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+            ctx.symbol?.type_info.clone()?
+        }
+    };
+    let (object_kind, object_name) = extract_type_object_name(&type_info)?;
 
     // Search in same document first
     for obj in &doc.symbol_table.symbols {
@@ -60,3 +69,136 @@ pub fn handle_goto_type_definition(
 
     None
 }
+
+/// The call or member access whose callee or member name is under the cursor,
+/// e.g. `GetCustomer()` or `GetCustomer().Name`.
+fn call_or_member_expression_at_offset(
+    tree: &tree_sitter::Tree,
+    byte_offset: usize,
+) -> Option<tree_sitter::Node<'_>> {
+    let node = node_at_offset(tree, byte_offset)?;
+    if !matches!(node.kind(), "identifier" | "quoted_identifier") {
+        return None;
+    }
+    let parent = node.parent()?;
+    let name_field = match parent.kind() {
+        "member_access" => "member",
+        "method_call" => "method",
+        "function_call" => "function",
+        _ => return None,
+    };
+    (parent.child_by_field_name(name_field)?.id() == node.id()).then_some(parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
+
+    use crate::test_support::extract_cursor;
+
+    fn goto_type(state: &WorldState, uri: &Url, position: Position) -> Option<Location> {
+        let params = GotoTypeDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        match handle_goto_type_definition(state, params)? {
+            GotoDefinitionResponse::Scalar(location) => Some(location),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_goto_type_definition_through_call_and_member_chain() {
+        let (source, position) = extract_cursor(
+            r#"enum 50100 CustomerStatus
+{
+    value(0; Active) { }
+}
+
+table 50101 Customer
+{
+    fields
+    {
+        field(1; Status; Enum CustomerStatus) { }
+    }
+}
+
+codeunit 50102 Test
+{
+    procedure Run()
+    begin
+        if GetCustomer().⟨cursor⟩Status = CustomerStatus::Active then
+            exit;
+    end;
+
+    local procedure GetCustomer(): Record Customer
+    var
+        Cust: Record Customer;
+    begin
+        exit(Cust);
+    end;
+}"#,
+        );
+        let uri = Url::parse("file:///test/all.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(&source));
+
+        let location = goto_type(&state, &uri, position).expect("expected enum type location");
+        assert_eq!(
+            location.range.start.line, 0,
+            "expected the CustomerStatus enum"
+        );
+
+        let call = Position::new(
+            position.line,
+            position.character - "GetCustomer().".len() as u32,
+        );
+        let location = goto_type(&state, &uri, call).expect("expected table type location");
+        assert_eq!(location.range.start.line, 5, "expected the Customer table");
+    }
+
+    #[test]
+    fn test_goto_type_definition_procedure_return_type() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Cust: Record Customer;
+    begin
+        Cust := GetCustomer();
+        Cust := GetCustomer;
+    end;
+
+    local procedure GetCustomer(): Record Customer
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+        state.documents.insert(
+            table_uri.clone(),
+            DocumentState::new("table 18 Customer\n{\n}"),
+        );
+
+        for marker in ["GetCustomer()", "GetCustomer;", "GetCustomer(): Record"] {
+            let (marked, position) =
+                extract_cursor(&source.replacen(marker, &format!("⟨cursor⟩{marker}"), 1));
+            assert_eq!(marked, source);
+            let location = goto_type(&state, &uri, position)
+                .unwrap_or_else(|| panic!("expected the Customer table from {marker}"));
+            assert_eq!(location.uri, table_uri, "from {marker}");
+        }
+    }
+}
@@ -1,29 +1,61 @@
 use lsp_types::{Hover, HoverContents, HoverParams, MarkupContent, MarkupKind};
 
-use al_syntax::ast::AlSymbolKind;
-use al_syntax::navigation::{identifier_at_offset, resolve_at_offset};
+use al_syntax::ast::{AlSymbol, AlSymbolKind};
+use al_syntax::builtins::{find_builtin_method, find_procedure_attribute};
+use al_syntax::navigation::{identifier_at_offset, node_at_offset, resolve_at_offset};
 use al_syntax::symbols::format_hover;
 
-use crate::builtins::find_builtin_method;
 use crate::convert::lsp_position_to_byte_offset;
 use crate::handlers::completion::{
-    enum_value_target_at_offset, find_table_field_type, member_access_target_at_offset,
-    option_value_target_at_offset,
+    attribute_name_at_offset, enum_value_target_at_offset, find_table_field_type,
+    member_access_target_at_offset, option_value_target_at_offset,
 };
 use crate::handlers::events::{event_subscriber_context_at_offset, find_event_publishers};
-use crate::state::WorldState;
+use crate::handlers::goto_definition::{type_target_from_type_identifier, type_target_namespace};
+use crate::state::{HoverCache, WorldState};
 
 pub fn handle_hover(state: &WorldState, params: HoverParams) -> Option<Hover> {
+    let uri = params
+        .text_document_position_params
+        .text_document
+        .uri
+        .clone();
+    let position = params.text_document_position_params.position;
+
+    let (source, byte_offset) = {
+        let doc = state.documents.get(&uri)?;
+        let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
+        (doc.source_arc(), byte_offset)
+    };
+
+    if let Some(cached) = state
+        .hover_cache
+        .get_mut(&uri)
+        .and_then(|mut cache| cache.get(&source, byte_offset))
+    {
+        return cached;
+    }
+
+    let hover = compute_hover(state, params);
+    state
+        .hover_cache
+        .entry(uri)
+        .or_insert_with(|| HoverCache::new(source.clone()))
+        .insert(source, byte_offset, hover.clone());
+    hover
+}
+
+fn compute_hover(state: &WorldState, params: HoverParams) -> Option<Hover> {
     let uri = params.text_document_position_params.text_document.uri;
     let position = params.text_document_position_params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source_arc();
     let source_ref = source.as_ref();
-    let enum_target = enum_value_target_at_offset(state, &uri, &doc.tree, source_ref, byte_offset);
+    let enum_target = enum_value_target_at_offset(state, &uri, doc.tree(), source_ref, byte_offset);
     let option_target =
-        option_value_target_at_offset(state, &uri, &doc.tree, source_ref, byte_offset);
+        option_value_target_at_offset(state, &uri, doc.tree(), source_ref, byte_offset);
     drop(doc);
 
     if let Some((enum_name, value_name)) = enum_target {
@@ -63,7 +95,7 @@ pub fn handle_hover(state: &WorldState, params: HoverParams) -> Option<Hover> {
 
     let doc = state.documents.get(&uri)?;
     let source = doc.source();
-    if let Some(ctx) = event_subscriber_context_at_offset(&doc.tree, &source, byte_offset) {
+    if let Some(ctx) = event_subscriber_context_at_offset(doc.tree(), &source, byte_offset) {
         if ctx.arg_index >= 2 {
             if let Some(target) = ctx.target {
                 drop(doc);
@@ -87,8 +119,54 @@ pub fn handle_hover(state: &WorldState, params: HoverParams) -> Option<Hover> {
     let doc = state.documents.get(&uri)?;
     let source = doc.source();
 
+    if let Some(attribute_name) = attribute_name_at_offset(doc.tree(), &source, byte_offset) {
+        if let Some(attribute) = find_procedure_attribute(&attribute_name) {
+            let mut value = format!(
+                "```al\n{}\n```\n\n{}\n\n[Microsoft Learn]({})",
+                attribute.signature, attribute.summary, attribute.docs_url
+            );
+            if !attribute.params.is_empty() {
+                value.push_str("\n\nParameters:");
+                for param in attribute.params {
+                    value.push_str(&format!("\n- `{}`: {}", param.label, param.documentation));
+                }
+            }
+            return Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value,
+                }),
+                range: None,
+            });
+        }
+    }
+
+    // Type name in a declaration (`Record MyCompany.Sales.Customer`): describe
+    // the declaring object, preferring the referenced namespace.
+    let type_target = node_at_offset(doc.tree(), byte_offset).and_then(|node| {
+        let (object_kind, object_name) = type_target_from_type_identifier(node, source)?;
+        let qualifier = type_target_namespace(node, source);
+        let namespaces = doc.symbol_table.namespaces.candidates(qualifier.as_deref());
+        Some((object_kind, object_name, namespaces))
+    });
+    if let Some((object_kind, object_name, namespaces)) = type_target {
+        drop(doc);
+        if let Some(value) = object_declaration_hover(state, object_kind, &object_name, &namespaces)
+        {
+            return Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value,
+                }),
+                range: None,
+            });
+        }
+    }
+    let doc = state.documents.get(&uri)?;
+    let source = doc.source();
+
     // First try to resolve to a definition
-    if let Some(resolved) = resolve_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset) {
+    if let Some(resolved) = resolve_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset) {
         return Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
@@ -154,8 +232,8 @@ pub fn handle_hover(state: &WorldState, params: HoverParams) -> Option<Hover> {
     }
 
     // If we're on a definition itself, show its hover
-    let name = identifier_at_offset(&doc.tree, &source, byte_offset)?;
-    let symbols = doc.symbol_table.lookup(&name);
+    let name = identifier_at_offset(doc.tree(), &source, byte_offset)?;
+    let symbols = doc.symbol_table.lookup_in_scope(&name, byte_offset);
     let sym = symbols.into_iter().next()?;
 
     Some(Hover {
@@ -167,6 +245,67 @@ pub fn handle_hover(state: &WorldState, params: HoverParams) -> Option<Hover> {
     })
 }
 
+/// Hover text for the declaration of an object, preferring one declared in
+/// `namespaces` when several documents declare the same name.
+fn object_declaration_hover(
+    state: &WorldState,
+    object_kind: &str,
+    object_name: &str,
+    namespaces: &[String],
+) -> Option<String> {
+    let mut fallback = None;
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        let Some(object) = doc.symbol_table.symbols.iter().find(|object| {
+            matches!(object.kind, AlSymbolKind::Object(kind) if kind.label() == object_kind)
+                && object.name.eq_ignore_ascii_case(object_name)
+        }) else {
+            continue;
+        };
+        let in_namespace = doc
+            .symbol_table
+            .namespaces
+            .namespace
+            .as_deref()
+            .is_some_and(|ns| namespaces.iter().any(|c| c.eq_ignore_ascii_case(ns)));
+        if in_namespace {
+            return Some(object_hover(object));
+        }
+        fallback.get_or_insert_with(|| object_hover(object));
+    }
+    fallback
+}
+
+/// An object's declaration line plus a count of its fields, procedures and
+/// enum values, e.g. `2 fields, 1 procedure`.
+fn object_hover(object: &AlSymbol) -> String {
+    let mut hover = format_hover(object);
+    let counts: Vec<String> = [
+        (AlSymbolKind::Field, "field"),
+        (AlSymbolKind::Procedure, "procedure"),
+        (AlSymbolKind::EnumValue, "value"),
+    ]
+    .into_iter()
+    .filter_map(|(kind, noun)| {
+        let count = object
+            .children
+            .iter()
+            .filter(|child| child.kind == kind)
+            .count();
+        match count {
+            0 => None,
+            1 => Some(format!("1 {noun}")),
+            _ => Some(format!("{count} {noun}s")),
+        }
+    })
+    .collect();
+    if !counts.is_empty() {
+        hover.push_str("\n\n");
+        hover.push_str(&counts.join(", "));
+    }
+    hover
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +333,88 @@ mod tests {
         (line, character)
     }
 
+    #[test]
+    fn test_hover_on_namespaced_type_reference() {
+        let table_source = r#"namespace MyCompany.Sales;
+
+table 50100 Customer
+{
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Cust: Record MyCompany.Sales.Customer;
+    begin
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        let (line, character) = cursor_on(codeunit_source, "Customer;");
+        let hover = handle_hover(&state, make_hover_params(codeunit_uri, line, character))
+            .expect("expected hover");
+        let HoverContents::Markup(markup) = hover.contents else {
+            panic!("expected markup hover");
+        };
+        assert!(
+            markup.value.contains("table") && markup.value.contains("Customer"),
+            "got: {}",
+            markup.value
+        );
+    }
+
+    #[test]
+    fn test_hover_on_type_reference_describes_object() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+        field(2; Name; Text[100]) { }
+    }
+
+    procedure Greet()
+    begin
+    end;
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Cust: Record Customer;
+    begin
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        let (line, character) = cursor_on(codeunit_source, "tomer;");
+        let hover = handle_hover(&state, make_hover_params(codeunit_uri, line, character))
+            .expect("expected hover");
+        let HoverContents::Markup(markup) = hover.contents else {
+            panic!("expected markup hover");
+        };
+        assert_eq!(
+            markup.value,
+            "```al\n(table 18) Customer\n```\n\n2 fields, 1 procedure"
+        );
+    }
+
     #[test]
     fn test_hover_on_qualified_enum_value() {
         let source = r#"enum 50100 MyEnum
@@ -216,7 +437,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on `First` in `MyEnum::First` (line 13)
         let params = make_hover_params(uri, 13, 21);
@@ -269,14 +490,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri, DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri, DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor on `Order` in `Rec."Document Type"::Order` (line 6).
         let params = make_hover_params(codeunit_uri, 6, 47);
@@ -308,7 +528,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, mut character) = cursor_on(source, "Choice::\"Second Value\"");
         character += "Choice::".len() as u32;
@@ -340,7 +560,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (param_line, mut param_char) = cursor_on(source, "OptionParameter::Alpha");
         param_char += "OptionParameter::".len() as u32;
@@ -389,7 +609,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, mut character) = cursor_on(source, "GlobalChoice::Alpha");
         character += "GlobalChoice::".len() as u32;
@@ -446,14 +666,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri, DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri, DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_on(codeunit_source, "\"KDS Display/Printing\" = HospType");
         let params = make_hover_params(codeunit_uri, line, character + 1);
@@ -513,14 +732,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri, DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri, DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         let (line, character) = cursor_on(codeunit_source, "\"On Item Added\"");
         let params = make_hover_params(codeunit_uri, line, character + 1);
@@ -552,7 +770,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "FindFirst");
         let params = make_hover_params(uri, line, character + 1);
@@ -585,7 +803,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "Contains");
         let params = make_hover_params(uri, line, character + 1);
@@ -618,7 +836,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "Date.DayOfWeek");
         let params = make_hover_params(uri, line, character + 1);
@@ -650,7 +868,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "GetLanguageId");
         let params = make_hover_params(uri, line, character + 1);
@@ -683,7 +901,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "Send");
         let params = make_hover_params(uri, line, character + 1);
@@ -713,7 +931,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "Commit");
         let params = make_hover_params(uri, line, character + 1);
@@ -743,7 +961,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "Abs");
         let params = make_hover_params(uri, line, character + 1);
@@ -776,7 +994,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "OpenEdit");
         let params = make_hover_params(uri, line, character + 1);
@@ -809,7 +1027,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "SaveAsPdf");
         let params = make_hover_params(uri, line, character + 1);
@@ -844,7 +1062,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_on(source, "SetSelectionFilter");
         let params = make_hover_params(uri, line, character + 1);
@@ -863,4 +1081,130 @@ codeunit 50100 Test
             content.value
         );
     }
+
+    #[test]
+    fn test_hover_on_procedure_attribute() {
+        let source = r#"codeunit 50100 Test
+{
+    [IntegrationEvent(false, false)]
+    procedure OnFoo()
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_on(source, "IntegrationEvent");
+        let hover = handle_hover(&state, make_hover_params(uri, line, character + 3));
+        assert!(hover.is_some(), "expected hover result");
+        let HoverContents::Markup(content) = hover.unwrap().contents else {
+            panic!("expected markdown hover");
+        };
+        assert!(
+            content
+                .value
+                .contains("[IntegrationEvent(IncludeSender: Boolean")
+                && content.value.contains("GlobalVarAccess"),
+            "expected attribute hover, got: {}",
+            content.value
+        );
+    }
+
+    #[test]
+    fn test_hover_cache_reuses_result_until_reparse() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Counter: Integer;
+    begin
+        Counter := 1;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_on(source, "Counter := 1");
+        let first = handle_hover(&state, make_hover_params(uri.clone(), line, character))
+            .expect("expected hover on variable");
+
+        // Poison the cached entry: a repeated hover must be served from it.
+        let sentinel = Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: "cached".to_string(),
+            }),
+            range: None,
+        };
+        {
+            let source = state.documents.get(&uri).unwrap().source_arc();
+            let byte_offset = source.find("Counter := 1").unwrap();
+            state.hover_cache.get_mut(&uri).unwrap().insert(
+                source,
+                byte_offset,
+                Some(sentinel.clone()),
+            );
+        }
+        let second = handle_hover(&state, make_hover_params(uri.clone(), line, character));
+        assert_eq!(second, Some(sentinel));
+
+        // Reparsing the document invalidates the cache.
+        state
+            .documents
+            .get_mut(&uri)
+            .unwrap()
+            .reparse_full(&source.replace("Counter := 1", "Counter := 2"));
+        let third = handle_hover(&state, make_hover_params(uri, line, character));
+        assert_eq!(third, Some(first));
+    }
+    #[test]
+    fn test_hover_cache_drops_results_when_another_document_changes() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+    }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Cust: Record Customer;
+    begin
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state.upsert_document(table_uri.clone(), DocumentState::new(table_source));
+        state.upsert_document(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        let (line, character) = cursor_on(codeunit_source, "tomer;");
+        let hover_text = || {
+            let hover = handle_hover(
+                &state,
+                make_hover_params(codeunit_uri.clone(), line, character),
+            )
+            .expect("expected hover");
+            let HoverContents::Markup(markup) = hover.contents else {
+                panic!("expected markup hover");
+            };
+            markup.value
+        };
+        assert!(hover_text().ends_with("1 field"), "{}", hover_text());
+
+        let edited = table_source.replace(
+            "Code[20]) { }",
+            "Code[20]) { }\n        field(2; Name; Text[100]) { }",
+        );
+        state.upsert_document(table_uri, DocumentState::new(&edited));
+        assert!(hover_text().ends_with("2 fields"), "{}", hover_text());
+    }
 }
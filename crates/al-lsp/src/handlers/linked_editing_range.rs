@@ -0,0 +1,125 @@
+use lsp_types::{LinkedEditingRangeParams, LinkedEditingRanges};
+
+use al_syntax::ast::AlSymbolKind;
+use al_syntax::navigation::{find_all_references, identifier_context_at_offset};
+
+use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range_utf16};
+use crate::state::WorldState;
+
+/// Plain AL identifiers and double-quoted names.
+const IDENTIFIER_WORD_PATTERN: &str = r#""[^"\r\n]*"|[A-Za-z_][A-Za-z0-9_]*"#;
+
+pub fn handle_linked_editing_range(
+    state: &WorldState,
+    params: LinkedEditingRangeParams,
+) -> Option<LinkedEditingRanges> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let doc = state.documents.get(&uri)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
+    let source = doc.source();
+
+    // Same restriction as prepare-rename: triggers and object names are not linked
+    let ctx = identifier_context_at_offset(doc.tree(), source, &doc.symbol_table, byte_offset)?;
+    if matches!(
+        ctx.symbol?.kind,
+        AlSymbolKind::Trigger | AlSymbolKind::Object(_)
+    ) {
+        return None;
+    }
+
+    let refs = find_all_references(doc.tree(), source, &doc.symbol_table, byte_offset, true);
+    if refs.len() < 2 {
+        return None;
+    }
+
+    // Linked ranges must hold identical text; AL names are case-insensitive and
+    // may be quoted, so differing spellings fall back to a regular rename
+    let byte = |point: tree_sitter::Point| doc.rope().line_to_byte(point.row) + point.column;
+    let texts: Vec<&str> = refs
+        .iter()
+        .map(|(start, end)| &source[byte(*start)..byte(*end)])
+        .collect();
+    if texts.iter().any(|text| *text != texts[0]) {
+        return None;
+    }
+
+    Some(LinkedEditingRanges {
+        ranges: refs
+            .into_iter()
+            .map(|(start, end)| ts_range_to_lsp_range_utf16(doc.rope(), start, end))
+            .collect(),
+        word_pattern: Some(IDENTIFIER_WORD_PATTERN.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::extract_cursor;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
+
+    fn linked_lines(marked: &str) -> Option<Vec<(u32, u32)>> {
+        let (source, position) = extract_cursor(marked);
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(&source));
+        let params = LinkedEditingRangeParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position,
+            },
+            work_done_progress_params: Default::default(),
+        };
+        let ranges = handle_linked_editing_range(&state, params)?;
+        assert!(ranges.word_pattern.is_some());
+        let mut starts: Vec<(u32, u32)> = ranges
+            .ranges
+            .iter()
+            .map(|r| {
+                assert_eq!(r.end, Position::new(r.start.line, r.start.character + 7));
+                (r.start.line, r.start.character)
+            })
+            .collect();
+        starts.sort();
+        Some(starts)
+    }
+
+    #[test]
+    fn test_linked_editing_local_variable() {
+        let ranges = linked_lines(
+            r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Counter: Integer;
+    begin
+        ⟨cursor⟩Counter := 1;
+        Message('%1', Counter);
+    end;
+}"#,
+        );
+        assert_eq!(ranges, Some(vec![(4, 8), (6, 8), (7, 22)]));
+    }
+
+    #[test]
+    fn test_linked_editing_skips_differing_spellings() {
+        let ranges = linked_lines(
+            r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Counter: Integer;
+    begin
+        ⟨cursor⟩Counter := 1;
+        Message('%1', counter);
+    end;
+}"#,
+        );
+        assert_eq!(ranges, None);
+    }
+}
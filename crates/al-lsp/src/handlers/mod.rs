@@ -1,17 +1,25 @@
+pub mod call_hierarchy;
 pub mod code_action;
+pub mod code_lens;
 pub mod completion;
 pub mod diagnostics;
 pub mod document_highlight;
+pub mod document_link;
 pub mod document_symbol;
 pub mod document_sync;
 pub mod events;
+pub mod execute_command;
 pub mod folding_range;
 pub mod formatting;
 pub mod goto_definition;
 pub mod goto_implementation;
 pub mod goto_type_definition;
 pub mod hover;
+pub mod linked_editing_range;
 pub mod references;
 pub mod rename;
+pub mod pull_diagnostics;
+pub mod selection_range;
 pub mod signature_help;
+pub mod type_hierarchy;
 pub mod workspace_symbol;
@@ -0,0 +1,100 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use lsp_types::{
+    Diagnostic, DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult,
+    FullDocumentDiagnosticReport, RelatedFullDocumentDiagnosticReport,
+    RelatedUnchangedDocumentDiagnosticReport, UnchangedDocumentDiagnosticReport,
+};
+
+use crate::handlers::diagnostics::collect_diagnostics;
+use crate::state::WorldState;
+
+/// Answer a `textDocument/diagnostic` pull with the document's full diagnostics.
+/// The result ID is a hash of the diagnostics, so a client re-requesting with
+/// the ID it already holds gets an `Unchanged` report.
+pub fn handle_document_diagnostic(
+    state: &WorldState,
+    params: DocumentDiagnosticParams,
+) -> DocumentDiagnosticReportResult {
+    let uri = params.text_document.uri;
+    let items = match state.documents.get(&uri) {
+        Some(doc) => collect_diagnostics(state, &uri, &doc),
+        None => Vec::new(),
+    };
+    let result_id = diagnostics_result_id(&items);
+
+    let report = if params.previous_result_id.as_deref() == Some(result_id.as_str()) {
+        DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+            related_documents: None,
+            unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport { result_id },
+        })
+    } else {
+        DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+            related_documents: None,
+            full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                result_id: Some(result_id),
+                items,
+            },
+        })
+    };
+    DocumentDiagnosticReportResult::Report(report)
+}
+
+fn diagnostics_result_id(items: &[Diagnostic]) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(items)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{DiagnosticSeverity, TextDocumentIdentifier, Url};
+
+    fn pull(
+        state: &WorldState,
+        uri: &Url,
+        previous_result_id: Option<String>,
+    ) -> DocumentDiagnosticReport {
+        let params = DocumentDiagnosticParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            identifier: None,
+            previous_result_id,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        match handle_document_diagnostic(state, params) {
+            DocumentDiagnosticReportResult::Report(report) => report,
+            other => panic!("expected a report, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_pull_diagnostics_reports_syntax_error() {
+        let uri = Url::parse("file:///test/broken.al").unwrap();
+        let state = WorldState::new();
+        state.documents.insert(
+            uri.clone(),
+            DocumentState::new("codeunit 50100 Test\n{\n    procedure Run(\n}"),
+        );
+
+        let DocumentDiagnosticReport::Full(full) = pull(&state, &uri, None) else {
+            panic!("expected a full report");
+        };
+        let report = full.full_document_diagnostic_report;
+        assert!(report
+            .items
+            .iter()
+            .any(|d| d.severity == Some(DiagnosticSeverity::ERROR)));
+
+        let result_id = report.result_id.expect("expected a result id");
+        assert!(matches!(
+            pull(&state, &uri, Some(result_id)),
+            DocumentDiagnosticReport::Unchanged(_)
+        ));
+    }
+}
@@ -1,22 +1,26 @@
-use lsp_types::{Location, ReferenceParams};
+use lsp_types::{Location, ReferenceParams, Url};
 
-use al_syntax::ast::AlSymbolKind;
+use al_syntax::ast::{extract_name, AlObjectKind, AlSymbolKind};
 use al_syntax::navigation::{
-    codeunit_method_call_at_offset, find_all_references, find_codeunit_method_calls,
-    find_interface_method_calls, identifier_context_at_offset, interface_method_call_at_offset,
+    codeunit_method_call_at_offset, extract_type_object_name, find_all_references,
+    identifier_context_at_offset, interface_method_call_at_offset, node_at_offset, CallSiteKind,
 };
 
 use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range};
 use crate::handlers::completion::{
     enum_value_target_at_offset, enum_value_usage_at_offset, enum_value_usages_in_tree,
-    resolve_enum_name_from_context, resolve_option_members_from_context, EnumContext,
+    resolve_enum_name_from_context, resolve_object_type_from_expression,
+    resolve_option_members_from_context, EnumContext,
 };
 use crate::handlers::events::{
     event_invocation_target_at_offset, event_publisher_target_at_offset,
     event_subscriber_context_at_offset, find_event_invocation_usages, find_event_publishers,
     find_event_subscriber_usages,
 };
-use crate::state::WorldState;
+use crate::handlers::goto_definition::{
+    find_object_declarations, type_target_from_type_identifier,
+};
+use crate::state::{IndexedCallSite, WorldState};
 
 fn point_at_offset(source: &str, offset: usize) -> Option<tree_sitter::Point> {
     if offset > source.len() {
@@ -35,7 +39,8 @@ fn inline_option_member_location(
     qualifier_byte_offset: usize,
     value_name: &str,
 ) -> Option<Location> {
-    let ctx = identifier_context_at_offset(&doc.tree, source, &doc.symbol_table, qualifier_byte_offset)?;
+    let ctx =
+        identifier_context_at_offset(doc.tree(), source, &doc.symbol_table, qualifier_byte_offset)?;
     let sym = ctx.symbol?;
     let decl_start = sym.start_byte.min(source.len());
     let decl_end = sym.end_byte.min(source.len());
@@ -70,16 +75,49 @@ fn inline_option_member_location(
     None
 }
 
+/// Names of every variable and parameter declared with the object type
+/// `object_kind`/`object_name` (e.g. all `Record Customer` declarations).
+fn collect_typed_declarations(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+    object_kind: &str,
+    object_name: &str,
+    out: &mut Vec<(tree_sitter::Point, tree_sitter::Point)>,
+) {
+    if matches!(node.kind(), "variable_declaration" | "parameter") {
+        let matches_type = node
+            .child_by_field_name("type")
+            .and_then(|type_node| {
+                extract_type_object_name(&source[type_node.start_byte()..type_node.end_byte()])
+            })
+            .is_some_and(|(kind, name)| {
+                kind == object_kind && name.eq_ignore_ascii_case(object_name)
+            });
+        if matches_type {
+            let mut cursor = node.walk();
+            for name_node in node.children_by_field_name("name", &mut cursor) {
+                out.push((name_node.start_position(), name_node.end_position()));
+            }
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_typed_declarations(child, source, object_kind, object_name, out);
+    }
+}
+
 pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<Vec<Location>> {
     let uri = params.text_document_position.text_document.uri;
     let position = params.text_document_position.position;
     let include_declaration = params.context.include_declaration;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
 
-    if let Some(usage) = enum_value_usage_at_offset(&doc.tree, &source, byte_offset) {
+    if let Some(usage) = enum_value_usage_at_offset(doc.tree(), &source, byte_offset) {
         if let Some((_type_info, members)) =
             resolve_option_members_from_context(state, &uri, &usage.context)
         {
@@ -93,7 +131,7 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
                 } = usage.context
                 {
                     let target_ctx = identifier_context_at_offset(
-                        &doc.tree,
+                        doc.tree(),
                         &source,
                         &doc.symbol_table,
                         qualifier_byte_offset,
@@ -113,7 +151,7 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
                         }
                     }
 
-                    for other_usage in enum_value_usages_in_tree(&doc.tree, &source) {
+                    for other_usage in enum_value_usages_in_tree(doc.tree(), &source) {
                         if !other_usage.value_name.eq_ignore_ascii_case(&usage.value_name) {
                             continue;
                         }
@@ -136,7 +174,7 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
                             continue;
                         };
                         let Some(other_ctx) = identifier_context_at_offset(
-                            &doc.tree,
+                            doc.tree(),
                             &source,
                             &doc.symbol_table,
                             other_qualifier_byte_offset,
@@ -168,7 +206,7 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
     }
 
     if let Some((enum_name, value_name)) =
-        enum_value_target_at_offset(state, &uri, &doc.tree, &source, byte_offset)
+        enum_value_target_at_offset(state, &uri, doc.tree(), &source, byte_offset)
     {
         drop(doc);
         let mut locations = Vec::new();
@@ -200,7 +238,7 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
                 }
             }
 
-            for usage in enum_value_usages_in_tree(&other_doc.tree, &other_source) {
+            for usage in enum_value_usages_in_tree(&other_doc.scan_tree(), &other_source) {
                 if !usage.value_name.eq_ignore_ascii_case(&value_name) {
                     continue;
                 }
@@ -225,11 +263,11 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
         return Some(locations);
     }
 
-    let event_target = event_subscriber_context_at_offset(&doc.tree, &source, byte_offset)
+    let event_target = event_subscriber_context_at_offset(doc.tree(), &source, byte_offset)
         .and_then(|ctx| (ctx.arg_index >= 2).then_some(ctx.target).flatten())
-        .or_else(|| event_publisher_target_at_offset(&doc.tree, &source, byte_offset))
+        .or_else(|| event_publisher_target_at_offset(doc.tree(), &source, byte_offset))
         .or_else(|| {
-            event_invocation_target_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)
+            event_invocation_target_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
         });
 
     let doc = if let Some(target) = event_target {
@@ -274,18 +312,78 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
     };
     let source = doc.source();
 
+    // Cursor on the object name in a type position (e.g. `Record Customer`):
+    // find every variable and parameter declared with that type.
+    if let Some((object_kind, object_name)) = node_at_offset(doc.tree(), byte_offset)
+        .and_then(|node| type_target_from_type_identifier(node, source))
+    {
+        drop(doc);
+
+        let target = object_target(state, &uri, object_kind, &object_name);
+        let mut locations = Vec::new();
+        for entry in state.documents.iter() {
+            let other_doc = entry.value();
+            if !reaches_target(
+                state,
+                entry.key(),
+                target.as_ref(),
+                object_kind,
+                &object_name,
+            ) {
+                continue;
+            }
+
+            if include_declaration {
+                for object in &other_doc.symbol_table.symbols {
+                    if let AlSymbolKind::Object(kind) = object.kind {
+                        if kind.label() == object_kind
+                            && object.name.eq_ignore_ascii_case(&object_name)
+                        {
+                            locations.push(Location {
+                                uri: entry.key().clone(),
+                                range: ts_range_to_lsp_range(
+                                    object.name_start_point,
+                                    object.name_end_point,
+                                ),
+                            });
+                        }
+                    }
+                }
+            }
+
+            let mut declarations = Vec::new();
+            collect_typed_declarations(
+                other_doc.scan_tree().root_node(),
+                other_doc.source(),
+                object_kind,
+                &object_name,
+                &mut declarations,
+            );
+            for (start, end) in declarations {
+                locations.push(Location {
+                    uri: entry.key().clone(),
+                    range: ts_range_to_lsp_range(start, end),
+                });
+            }
+        }
+
+        if locations.is_empty() {
+            return None;
+        }
+        return Some(locations);
+    }
+
     // First, check if the cursor is on the method part of an interface-typed method call
     // (e.g. `AddressProvider.GetAddress()` where AddressProvider is `Interface IAddressProvider`).
     // If so, treat this as a reference query on the interface method itself.
     if let Some((interface_name, method_name)) =
-        interface_method_call_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)
+        interface_method_call_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
     {
         drop(doc); // Release the DashMap ref before iterating
 
         let mut locations = Vec::new();
         for entry in state.documents.iter() {
             let other_doc = entry.value();
-            let other_source = other_doc.source();
 
             // Include the interface method definition if requested
             if include_declaration {
@@ -299,23 +397,15 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
                     });
                 }
             }
-
-            // Find all call sites on interface-typed variables
-            let calls = find_interface_method_calls(
-                &other_doc.tree,
-                &other_source,
-                &other_doc.symbol_table,
-                &interface_name,
-                &method_name,
-            );
-            for (start, end) in calls {
-                locations.push(Location {
-                    uri: entry.key().clone(),
-                    range: ts_range_to_lsp_range(start, end),
-                });
-            }
         }
 
+        // Find all call sites on interface-typed variables
+        locations.extend(call_site_locations(state.method_call_sites(
+            CallSiteKind::Interface,
+            &interface_name,
+            &method_name,
+        )));
+
         if locations.is_empty() {
             return None;
         }
@@ -325,14 +415,23 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
     // Check if cursor is on the method part of a codeunit-typed method call
     // (e.g. `CompanyAddressProvider2.HelloWorld2()` where CompanyAddressProvider2 is `Codeunit CompanyAddressProvider2`).
     if let Some((codeunit_name, method_name)) =
-        codeunit_method_call_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)
+        codeunit_method_call_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
     {
         drop(doc);
 
+        let target = object_target(state, &uri, "codeunit", &codeunit_name);
         let mut locations = Vec::new();
         for entry in state.documents.iter() {
             let other_doc = entry.value();
-            let other_source = other_doc.source();
+            if !reaches_target(
+                state,
+                entry.key(),
+                target.as_ref(),
+                "codeunit",
+                &codeunit_name,
+            ) {
+                continue;
+            }
 
             // Include the procedure definition in the target codeunit
             if include_declaration {
@@ -349,23 +448,25 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
                     });
                 }
             }
-
-            // Find all call sites on codeunit-typed variables
-            let calls = find_codeunit_method_calls(
-                &other_doc.tree,
-                &other_source,
-                &other_doc.symbol_table,
-                &codeunit_name,
-                &method_name,
-            );
-            for (start, end) in calls {
-                locations.push(Location {
-                    uri: entry.key().clone(),
-                    range: ts_range_to_lsp_range(start, end),
-                });
-            }
         }
 
+        // Find all call sites on codeunit-typed variables
+        locations.extend(call_site_locations(
+            state
+                .method_call_sites(CallSiteKind::Codeunit, &codeunit_name, &method_name)
+                .into_iter()
+                .filter(|site| {
+                    reaches_target(
+                        state,
+                        &site.uri,
+                        target.as_ref(),
+                        "codeunit",
+                        &codeunit_name,
+                    )
+                })
+                .collect(),
+        ));
+
         if locations.is_empty() {
             return None;
         }
@@ -373,7 +474,7 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
     }
 
     let refs = find_all_references(
-        &doc.tree,
+        doc.tree(),
         &source,
         &doc.symbol_table,
         byte_offset,
@@ -390,10 +491,35 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
 
     // Determine what the cursor is actually on. Only enter interface/impl paths
     // when the cursor is on a Procedure identifier.
+    let cursor_symbol =
+        identifier_context_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
+            .and_then(|ctx| ctx.symbol);
     let cursor_on_procedure =
-        identifier_context_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)
-            .and_then(|ctx| ctx.symbol)
-            .is_some_and(|sym| matches!(sym.kind, AlSymbolKind::Procedure));
+        cursor_symbol.is_some_and(|sym| matches!(sym.kind, AlSymbolKind::Procedure));
+
+    // A table field is also used as `Cust.Name` through variables of the table's type
+    let table_field = cursor_symbol
+        .filter(|sym| matches!(sym.kind, AlSymbolKind::Field))
+        .and_then(|field| {
+            let table = doc.symbol_table.symbols.iter().find(|object| {
+                object.kind == AlSymbolKind::Object(AlObjectKind::Table)
+                    && object.start_byte <= field.start_byte
+                    && field.end_byte <= object.end_byte
+            })?;
+            Some((table.name.clone(), field.name.clone()))
+        });
+    if let Some((table_name, field_name)) = table_field {
+        drop(doc);
+        for location in field_member_usages(state, &table_name, &field_name) {
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+        if locations.is_empty() {
+            return None;
+        }
+        return Some(locations);
+    }
 
     if !cursor_on_procedure {
         // Not on a procedure identifier — return standard same-document references only.
@@ -422,23 +548,11 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
     // If cursor is on an interface method, search all documents for call sites
     // where a variable typed as this interface invokes this method.
     if let Some((interface_name, method_name)) = iface_method {
-        for entry in state.documents.iter() {
-            let other_doc = entry.value();
-            let other_source = other_doc.source();
-            let calls = find_interface_method_calls(
-                &other_doc.tree,
-                &other_source,
-                &other_doc.symbol_table,
-                &interface_name,
-                &method_name,
-            );
-            for (start, end) in calls {
-                locations.push(Location {
-                    uri: entry.key().clone(),
-                    range: ts_range_to_lsp_range(start, end),
-                });
-            }
-        }
+        locations.extend(call_site_locations(state.method_call_sites(
+            CallSiteKind::Interface,
+            &interface_name,
+            &method_name,
+        )));
     }
 
     // If cursor is on a procedure inside a codeunit that implements interfaces,
@@ -463,36 +577,120 @@ pub fn handle_references(state: &WorldState, params: ReferenceParams) -> Option<
     // If cursor is on a procedure inside any codeunit, find cross-document call sites
     // where a variable typed as this codeunit calls this method.
     if let Some((object_name, method_name)) = cu_proc {
-        for entry in state.documents.iter() {
-            let other_doc = entry.value();
-            let other_source = other_doc.source();
-            let calls = find_codeunit_method_calls(
-                &other_doc.tree,
-                &other_source,
-                &other_doc.symbol_table,
-                &object_name,
-                &method_name,
-            );
-            for (start, end) in calls {
+        locations.extend(call_site_locations(state.method_call_sites(
+            CallSiteKind::Codeunit,
+            &object_name,
+            &method_name,
+        )));
+    }
+
+    if locations.is_empty() {
+        return None;
+    }
+
+    Some(locations)
+}
+
+/// `X.<field_name>` member accesses, across all documents, whose `X` is of
+/// type `Record <table_name>`.
+fn field_member_usages(state: &WorldState, table_name: &str, field_name: &str) -> Vec<Location> {
+    let field_lower = field_name.to_lowercase();
+    let mut locations = Vec::new();
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        // Cheap text check first; closed documents would otherwise be re-parsed
+        if !doc.source().to_lowercase().contains(&field_lower) {
+            continue;
+        }
+        let tree = doc.scan_tree();
+        let mut members = Vec::new();
+        collect_field_members(tree.root_node(), doc.source(), field_name, &mut members);
+        for (object_node, member_node) in members {
+            let Some((object_kind, object_name)) = resolve_object_type_from_expression(
+                state,
+                doc,
+                doc.source(),
+                object_node,
+                member_node.start_byte(),
+                0,
+            ) else {
+                continue;
+            };
+            if object_kind == "table" && object_name.eq_ignore_ascii_case(table_name) {
                 locations.push(Location {
                     uri: entry.key().clone(),
-                    range: ts_range_to_lsp_range(start, end),
+                    range: ts_range_to_lsp_range(
+                        member_node.start_position(),
+                        member_node.end_position(),
+                    ),
                 });
             }
         }
     }
+    locations
+}
 
-    if locations.is_empty() {
-        return None;
+/// `(object, member)` nodes of the `member_access` expressions under `node`
+/// whose member is named `field_name`.
+fn collect_field_members<'a>(
+    node: tree_sitter::Node<'a>,
+    source: &str,
+    field_name: &str,
+    out: &mut Vec<(tree_sitter::Node<'a>, tree_sitter::Node<'a>)>,
+) {
+    if node.kind() == "member_access" {
+        if let (Some(object), Some(member)) = (
+            node.child_by_field_name("object"),
+            node.child_by_field_name("member"),
+        ) {
+            if extract_name(member, source).eq_ignore_ascii_case(field_name) {
+                out.push((object, member));
+            }
+        }
     }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_field_members(child, source, field_name, out);
+    }
+}
 
-    Some(locations)
+/// The document declaring the object a reference in `from` resolves to.
+fn object_target(
+    state: &WorldState,
+    from: &Url,
+    object_kind: &str,
+    object_name: &str,
+) -> Option<Url> {
+    find_object_declarations(state, from, object_kind, object_name)
+        .into_iter()
+        .next()
+        .map(|location| location.uri)
+}
+
+/// Whether references in `uri` resolve to the object declared in `target`,
+/// which holds for every document when the object is not declared anywhere.
+fn reaches_target(
+    state: &WorldState,
+    uri: &Url,
+    target: Option<&Url>,
+    object_kind: &str,
+    object_name: &str,
+) -> bool {
+    target.is_none_or(|target| state.object_visible_from(uri, target, object_kind, object_name))
+}
+
+fn call_site_locations(sites: Vec<IndexedCallSite>) -> impl Iterator<Item = Location> {
+    sites.into_iter().map(|site| Location {
+        uri: site.uri,
+        range: ts_range_to_lsp_range(site.start, site.end),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::state::WorldState;
+    use crate::test_support::extract_cursor;
     use al_syntax::document::DocumentState;
     use lsp_types::{
         Position, ReferenceContext, ReferenceParams, TextDocumentIdentifier,
@@ -519,6 +717,63 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_object_references_stay_within_workspace_root() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+    }
+}"#;
+        let (caller_source, cursor) = extract_cursor(
+            r#"codeunit 50101 Caller
+{
+    procedure Run()
+    var
+        Cust: Record Cust⟨cursor⟩omer;
+    begin
+    end;
+}"#,
+        );
+        let state = WorldState::new();
+        *state.workspace_roots.lock().unwrap() = vec!["/ws/app-a".into(), "/ws/app-b".into()];
+        let doc_uri = |path: &str| Url::parse(&format!("file:///ws/{path}")).unwrap();
+        for root in ["app-a", "app-b"] {
+            state.upsert_document(
+                doc_uri(&format!("{root}/Customer.Table.al")),
+                DocumentState::new(table_source),
+            );
+            state.upsert_document(
+                doc_uri(&format!("{root}/Caller.Codeunit.al")),
+                DocumentState::new(&caller_source),
+            );
+        }
+
+        let locations = handle_references(
+            &state,
+            make_ref_params(
+                doc_uri("app-a/Caller.Codeunit.al"),
+                cursor.line,
+                cursor.character,
+                true,
+            ),
+        )
+        .expect("expected references");
+        let mut uris: Vec<&str> = locations
+            .iter()
+            .map(|location| location.uri.path())
+            .collect();
+        uris.sort();
+        assert_eq!(
+            uris,
+            vec![
+                "/ws/app-a/Caller.Codeunit.al",
+                "/ws/app-a/Customer.Table.al"
+            ]
+        );
+    }
+
     fn full_example_source() -> &'static str {
         r#"interface IAddressProvider
 {
@@ -580,10 +835,10 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl_uri.clone(), DocumentState::new(impl_source).unwrap());
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
 
         // Cursor on "GetAddress" in the codeunit (line 2, col 14), include declaration
         let params = make_ref_params(impl_uri.clone(), 2, 14, true);
@@ -609,7 +864,7 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "GetAddress" in the interface (line 2, col 14), include declaration
         let params = make_ref_params(uri.clone(), 2, 14, true);
@@ -658,10 +913,10 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl_uri.clone(), DocumentState::new(impl_source).unwrap());
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
 
         // Cursor on "GetAddress" in the interface (line 2, col 14)
         let params = make_ref_params(iface_uri.clone(), 2, 14, true);
@@ -677,6 +932,47 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         );
     }
 
+    #[test]
+    fn test_references_into_unloaded_document() {
+        let iface_source = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+}"#;
+        let impl_source = r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+
+    procedure HelloWorld()
+    var
+        AddressProvider: Interface IAddressProvider;
+    begin
+        AddressProvider.GetAddress();
+    end;
+}"#;
+        let iface_uri = Url::parse("file:///test/iface.al").unwrap();
+        let impl_uri = Url::parse("file:///test/impl.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
+        let mut impl_doc = DocumentState::new(impl_source);
+        impl_doc.unload();
+        state.documents.insert(impl_uri.clone(), impl_doc);
+
+        // Cursor on "GetAddress" in the interface (line 2, col 14)
+        let params = make_ref_params(iface_uri, 2, 14, false);
+        let locs = handle_references(&state, params).expect("expected references");
+
+        assert!(
+            locs.iter()
+                .any(|l| l.uri == impl_uri && l.range.start == Position::new(10, 24)),
+            "expected the call site in the unloaded document, got: {locs:?}"
+        );
+    }
+
     #[test]
     fn test_references_variable_in_impl_codeunit_no_interface_leak() {
         // Cursor on ExampleAddressLbl inside a codeunit that implements an interface.
@@ -688,7 +984,7 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "ExampleAddressLbl" in the var declaration (line 9, col 8)
         let params = make_ref_params(uri.clone(), 9, 8, true);
@@ -721,7 +1017,7 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "GetAddress" in the first codeunit's procedure (line 7, col 14)
         let params = make_ref_params(uri.clone(), 7, 14, true);
@@ -758,7 +1054,7 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "GetAddress" in `IAddressProvider.GetAddress()` (line 35, col 25)
         // Line 35 (0-indexed) = `        IAddressProvider.GetAddress();`
@@ -805,7 +1101,8 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
     fn test_references_on_interface_method_call_without_parentheses() {
         // Cursor on GetAddress in `IAddressProvider.GetAddress;` should behave the same
         // as `IAddressProvider.GetAddress()` and resolve to the interface method.
-        let source = r#"interface IAddressProvider
+        let (source, cursor) = extract_cursor(
+            r#"interface IAddressProvider
 {
     procedure GetAddress(): Text;
 }
@@ -820,18 +1117,17 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
     var
         IAddressProvider: Interface IAddressProvider;
     begin
-        IAddressProvider.GetAddress;
+        IAddressProvider.⟨cursor⟩GetAddress;
     end;
-}"#;
+}"#,
+        );
         let uri = Url::parse("file:///test/all.al").unwrap();
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(&source));
 
-        // `IAddressProvider.GetAddress;` line (0-indexed) = 15.
-        // 8 spaces + `IAddressProvider.` = 25, so GetAddress starts at col 25.
-        let params = make_ref_params(uri, 15, 25, true);
+        let params = make_ref_params(uri, cursor.line, cursor.character, true);
         let result = handle_references(&state, params);
 
         assert!(
@@ -904,7 +1200,7 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "HelloWorld2" in the procedure declaration (line 34)
         // Line 34: "    procedure HelloWorld2()"
@@ -981,7 +1277,7 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on "HelloWorld2" in the call site (line 20)
         // Line 20: "        CompanyAddressProvider2.HelloWorld2();"
@@ -1008,13 +1304,14 @@ codeunit 50201 CompanyAddressProvider2 implements IAddressProvider
     fn test_references_from_codeunit_method_call_site_without_parentheses() {
         // Cursor on HelloWorld2 in the call site CompanyAddressProvider2.HelloWorld2;
         // should still resolve to the procedure definition.
-        let source = r#"codeunit 50200 CompanyAddressProvider
+        let (source, cursor) = extract_cursor(
+            r#"codeunit 50200 CompanyAddressProvider
 {
     procedure HelloWorld()
     var
         CompanyAddressProvider2: codeunit CompanyAddressProvider2;
     begin
-        CompanyAddressProvider2.HelloWorld2;
+        CompanyAddressProvider2.⟨cursor⟩HelloWorld2;
     end;
 }
 
@@ -1023,16 +1320,15 @@ codeunit 50201 CompanyAddressProvider2
     procedure HelloWorld2()
     begin
     end;
-}"#;
+}"#,
+        );
         let uri = Url::parse("file:///test/all.al").unwrap();
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(&source));
 
-        // `CompanyAddressProvider2.HelloWorld2;` line (0-indexed) = 6.
-        // 8 spaces + `CompanyAddressProvider2.` = 32, so HelloWorld2 starts at col 32.
-        let params = make_ref_params(uri.clone(), 6, 32, true);
+        let params = make_ref_params(uri.clone(), cursor.line, cursor.character, true);
         let result = handle_references(&state, params);
 
         assert!(
@@ -1047,6 +1343,113 @@ codeunit 50201 CompanyAddressProvider2
         );
     }
 
+    #[test]
+    fn test_references_from_record_type_finds_typed_variables_across_documents() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; Name; Text[100]) { }
+    }
+}"#;
+        let first_source = r#"codeunit 50100 First
+{
+    var
+        Cust: Record Customer;
+        Vend: Record Vendor;
+
+    procedure Run()
+    begin
+        Cust.Name := 'A';
+    end;
+}"#;
+        let second_source = r#"codeunit 50101 Second
+{
+    procedure Show(var OtherCust: Record "Customer" temporary)
+    begin
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let first_uri = Url::parse("file:///test/first.al").unwrap();
+        let second_uri = Url::parse("file:///test/second.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(first_uri.clone(), DocumentState::new(first_source));
+        state
+            .documents
+            .insert(second_uri.clone(), DocumentState::new(second_source));
+
+        // Cursor on `Customer` in `Cust: Record Customer;` (line 3, col 22)
+        let params = make_ref_params(first_uri.clone(), 3, 22, false);
+        let locs = handle_references(&state, params).expect("expected typed declarations");
+
+        assert_eq!(locs.len(), 2, "got: {locs:?}");
+        assert!(locs
+            .iter()
+            .any(|l| l.uri == first_uri && l.range.start == Position::new(3, 8)));
+        assert!(locs
+            .iter()
+            .any(|l| l.uri == second_uri && l.range.start == Position::new(2, 23)));
+    }
+
+    #[test]
+    fn test_references_table_field_includes_member_access() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; Name; Text[100]) { }
+    }
+
+    trigger OnInsert()
+    begin
+        Name := '';
+    end;
+}"#;
+        let codeunit_source = r#"codeunit 50100 Greeter
+{
+    procedure Greet()
+    var
+        Cust: Record Customer;
+        Vend: Record Vendor;
+    begin
+        Cust.Name := 'A';
+        Vend.Name := 'B';
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/customer.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/greeter.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri.clone(), DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        // Cursor on the field declaration `Name` (line 4, col 17)
+        let params = make_ref_params(table_uri.clone(), 4, 17, true);
+        let locs = handle_references(&state, params).expect("expected field references");
+
+        let mut found: Vec<(&str, Position)> =
+            locs.iter().map(|l| (l.uri.path(), l.range.start)).collect();
+        found.sort_by_key(|(path, pos)| (*path, pos.line, pos.character));
+        assert_eq!(
+            found,
+            vec![
+                ("/test/customer.al", Position::new(4, 17)),
+                ("/test/customer.al", Position::new(9, 8)),
+                ("/test/greeter.al", Position::new(7, 13)),
+            ]
+        );
+    }
+
     #[test]
     fn test_references_enum_value_from_qualified_usage() {
         let source = r#"enum 50100 MyEnum
@@ -1073,7 +1476,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on `First` in `MyEnum::First` (line 16, col 21)
         let params = make_ref_params(uri, 16, 21, true);
@@ -1134,14 +1537,13 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(enum_uri.clone(), DocumentState::new(enum_source).unwrap());
+            .insert(enum_uri.clone(), DocumentState::new(enum_source));
         state
             .documents
-            .insert(table_uri, DocumentState::new(table_source).unwrap());
-        state.documents.insert(
-            codeunit_uri.clone(),
-            DocumentState::new(codeunit_source).unwrap(),
-        );
+            .insert(table_uri, DocumentState::new(table_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
 
         // Cursor on `Order` in `Rec."Document Type"::Order` (line 6, col 47)
         let params = make_ref_params(codeunit_uri, 6, 47, true);
@@ -1179,7 +1581,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on `First` in enum declaration `value(0; First)` (line 2).
         let params = make_ref_params(uri, 2, 13, true);
@@ -1218,7 +1620,7 @@ codeunit 50100 Test
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let params = make_ref_params(uri, 7, 36, true);
         let result = handle_references(&state, params);
@@ -1266,13 +1668,12 @@ codeunit 50100 Test
         let publisher_uri = Url::parse("file:///test/publisher.al").unwrap();
         let subscriber_uri = Url::parse("file:///test/subscriber.al").unwrap();
         let state = WorldState::new();
-        state.documents.insert(
-            publisher_uri.clone(),
-            DocumentState::new(publisher_source).unwrap(),
-        );
+        state
+            .documents
+            .insert(publisher_uri.clone(), DocumentState::new(publisher_source));
         state.documents.insert(
             subscriber_uri.clone(),
-            DocumentState::new(subscriber_source).unwrap(),
+            DocumentState::new(subscriber_source),
         );
 
         // Cursor on publisher procedure name `OnAfterPost` (line 3).
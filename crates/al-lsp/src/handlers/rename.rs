@@ -1,15 +1,31 @@
 use lsp_types::{
-    PrepareRenameResponse, RenameParams, TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
+    DocumentChangeOperation, DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier,
+    PrepareRenameResponse, RenameFile, RenameParams, ResourceOp, TextDocumentEdit,
+    TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
 };
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 
-use al_syntax::ast::AlSymbolKind;
+use al_syntax::ast::{extract_name, AlSymbolKind};
 use al_syntax::navigation::{
-    codeunit_method_call_at_offset, find_all_references, find_codeunit_method_calls,
-    find_interface_method_calls, identifier_context_at_offset, interface_method_call_at_offset,
+    codeunit_method_call_at_offset, find_all_references, identifier_context_at_offset,
+    interface_method_call_at_offset, node_at_offset, CallSiteKind,
 };
+use al_syntax::symbols::DocumentSymbolTable;
 
 use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range};
+use crate::handlers::completion::{
+    enum_name_from_enum_value_declaration, enum_value_target_at_offset, enum_value_usages_in_tree,
+    resolve_enum_name_from_context,
+};
+use crate::handlers::events::{
+    event_publisher_target_at_offset, find_event_invocation_usages, find_event_publishers,
+    find_event_subscriber_usages, EventTarget,
+};
+use crate::handlers::goto_definition::{
+    find_object_declarations, interface_target_from_implements_identifier,
+    object_reference_property_value, type_target_from_type_identifier,
+};
 use crate::state::WorldState;
 
 pub fn handle_prepare_rename(
@@ -20,35 +36,48 @@ pub fn handle_prepare_rename(
     let position = params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
 
+    // Allow prepare-rename on enum values (declaration or `Enum::Value` usage)
+    if enum_value_target_at_offset(state, &uri, doc.tree(), source, byte_offset).is_some() {
+        let node = al_syntax::navigation::node_at_offset(doc.tree(), byte_offset)?;
+        let range = ts_range_to_lsp_range(node.start_position(), node.end_position());
+        return Some(PrepareRenameResponse::Range(range));
+    }
+
     // Allow prepare-rename on interface method calls
-    if interface_method_call_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset).is_some()
+    if interface_method_call_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
+        .is_some()
     {
-        let node = al_syntax::navigation::node_at_offset(&doc.tree, byte_offset)?;
+        let node = al_syntax::navigation::node_at_offset(doc.tree(), byte_offset)?;
         let range = ts_range_to_lsp_range(node.start_position(), node.end_position());
         return Some(PrepareRenameResponse::Range(range));
     }
 
     // Allow prepare-rename on codeunit method calls
-    if codeunit_method_call_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset).is_some()
+    if codeunit_method_call_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset).is_some()
     {
-        let node = al_syntax::navigation::node_at_offset(&doc.tree, byte_offset)?;
+        let node = al_syntax::navigation::node_at_offset(doc.tree(), byte_offset)?;
+        let range = ts_range_to_lsp_range(node.start_position(), node.end_position());
+        return Some(PrepareRenameResponse::Range(range));
+    }
+
+    // Allow prepare-rename on object names and references to them
+    if object_target_at_offset(doc.tree(), source, &doc.symbol_table, byte_offset).is_some() {
+        let node = al_syntax::navigation::node_at_offset(doc.tree(), byte_offset)?;
         let range = ts_range_to_lsp_range(node.start_position(), node.end_position());
         return Some(PrepareRenameResponse::Range(range));
     }
 
-    let ctx = identifier_context_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
+    let ctx = identifier_context_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
 
-    // Don't allow renaming triggers or object names (too complex for simple rename)
-    if let Some(sym) = ctx.symbol {
-        match sym.kind {
-            AlSymbolKind::Trigger | AlSymbolKind::Object(_) => {
-                return None;
-            }
-            _ => {}
-        }
+    // Don't allow renaming triggers
+    if ctx
+        .symbol
+        .is_some_and(|sym| matches!(sym.kind, AlSymbolKind::Trigger))
+    {
+        return None;
     }
 
     let range = ts_range_to_lsp_range(ctx.node.start_position(), ctx.node.end_position());
@@ -83,12 +112,20 @@ pub fn handle_rename(state: &WorldState, params: RenameParams) -> Option<Workspa
     let new_name = params.new_name;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
 
+    // Case 0: Enum value (declaration or `Enum::Value` usage) — workspace-wide
+    if let Some((enum_name, value_name)) =
+        enum_value_target_at_offset(state, &uri, doc.tree(), source, byte_offset)
+    {
+        drop(doc);
+        return Some(rename_enum_value(state, &enum_name, &value_name, &new_name));
+    }
+
     // Case 1a: Interface-typed method call (e.g. `AddressProvider.GetAddress()`)
     if let Some((interface_name, method_name)) =
-        interface_method_call_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)
+        interface_method_call_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
     {
         drop(doc);
         return Some(rename_interface_method(
@@ -101,7 +138,7 @@ pub fn handle_rename(state: &WorldState, params: RenameParams) -> Option<Workspa
 
     // Case 1b: Codeunit-typed method call (e.g. `MyCodeunit.HelloWorld()`)
     if let Some((codeunit_name, method_name)) =
-        codeunit_method_call_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)
+        codeunit_method_call_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)
     {
         drop(doc);
         return Some(rename_codeunit_procedure(
@@ -112,16 +149,36 @@ pub fn handle_rename(state: &WorldState, params: RenameParams) -> Option<Workspa
         ));
     }
 
-    let ctx = identifier_context_at_offset(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
+    // Case 1c: Object name (declaration, type reference, `implements` entry) —
+    // workspace-wide, renaming the object's file when it is named after it
+    if let Some((object_kind, object_name)) =
+        object_target_at_offset(doc.tree(), source, &doc.symbol_table, byte_offset)
+    {
+        drop(doc);
+        return Some(rename_object(
+            state,
+            &uri,
+            object_kind,
+            &object_name,
+            &new_name,
+        ));
+    }
 
-    // Don't allow renaming triggers or object names
-    if let Some(sym) = ctx.symbol {
-        match sym.kind {
-            AlSymbolKind::Trigger | AlSymbolKind::Object(_) => {
-                return None;
-            }
-            _ => {}
-        }
+    let ctx = identifier_context_at_offset(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+
+    // Don't allow renaming triggers
+    if ctx
+        .symbol
+        .is_some_and(|sym| matches!(sym.kind, AlSymbolKind::Trigger))
+    {
+        return None;
+    }
+
+    // Case 1d: Event publisher procedure — publisher, raisers and the event-name
+    // argument of every `[EventSubscriber(...)]` across the workspace
+    if let Some(target) = event_publisher_target_at_offset(doc.tree(), source, byte_offset) {
+        drop(doc);
+        return Some(rename_event_publisher(state, &target, &new_name));
     }
 
     // Check if cursor is on a procedure identifier for cross-document cases
@@ -178,7 +235,7 @@ pub fn handle_rename(state: &WorldState, params: RenameParams) -> Option<Workspa
     }
 
     // Case 4: Regular symbol (variables, parameters, fields) — single-document
-    let refs = find_all_references(&doc.tree, &source, &doc.symbol_table, byte_offset, true);
+    let refs = find_all_references(doc.tree(), &source, &doc.symbol_table, byte_offset, true);
     drop(doc);
 
     if refs.is_empty() {
@@ -212,7 +269,6 @@ fn rename_interface_method(
 
     for entry in state.documents.iter() {
         let doc = entry.value();
-        let source = doc.source();
 
         // Rename interface method definition
         if let Some(method_sym) = doc
@@ -229,34 +285,25 @@ fn rename_interface_method(
                 ),
             );
         }
+    }
 
-        // Rename implementation procedures
-        let impl_procs = doc
-            .symbol_table
-            .find_implementation_procedures(interface_name, method_name);
-        for proc_sym in impl_procs {
-            insert_edit(
-                &mut changes,
-                entry.key().clone(),
-                make_rename_edit(proc_sym.name_start_point, proc_sym.name_end_point, new_name),
-            );
-        }
+    // Rename implementation procedures
+    for implementation in state.interface_implementations(interface_name, method_name) {
+        let (start, end) = implementation.name_range;
+        insert_edit(
+            &mut changes,
+            implementation.uri,
+            make_rename_edit(start, end, new_name),
+        );
+    }
 
-        // Rename call sites on interface-typed variables
-        let calls = find_interface_method_calls(
-            &doc.tree,
-            &source,
-            &doc.symbol_table,
-            interface_name,
-            method_name,
+    // Rename call sites on interface-typed variables
+    for site in state.method_call_sites(CallSiteKind::Interface, interface_name, method_name) {
+        insert_edit(
+            &mut changes,
+            site.uri,
+            make_rename_edit(site.start, site.end, new_name),
         );
-        for (start, end) in calls {
-            insert_edit(
-                &mut changes,
-                entry.key().clone(),
-                make_rename_edit(start, end, new_name),
-            );
-        }
     }
 
     WorkspaceEdit {
@@ -295,7 +342,7 @@ fn rename_implementation_procedure(
 
                     // Find all local references to this procedure within the same document
                     let refs = find_all_references(
-                        &doc.tree,
+                        doc.tree(),
                         &source,
                         &doc.symbol_table,
                         child.start_byte,
@@ -369,7 +416,7 @@ fn rename_codeunit_procedure(
 
             // Rename unqualified calls within the same codeunit
             let refs = find_all_references(
-                &doc.tree,
+                &doc.scan_tree(),
                 &source,
                 &doc.symbol_table,
                 proc_sym.start_byte,
@@ -383,20 +430,130 @@ fn rename_codeunit_procedure(
                 );
             }
         }
+    }
 
-        // Rename qualified call sites (codeunit-typed variable calls)
-        let calls = find_codeunit_method_calls(
-            &doc.tree,
-            &source,
-            &doc.symbol_table,
-            codeunit_name,
-            method_name,
+    // Rename qualified call sites (codeunit-typed variable calls)
+    for site in state.method_call_sites(CallSiteKind::Codeunit, codeunit_name, method_name) {
+        insert_edit(
+            &mut changes,
+            site.uri,
+            make_rename_edit(site.start, site.end, new_name),
         );
-        for (start, end) in calls {
+    }
+
+    WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    }
+}
+
+/// Rename an event publisher across all documents:
+/// - The publisher procedure name
+/// - All invocations of the event
+/// - The event-name string argument of all subscriber attributes
+fn rename_event_publisher(
+    state: &WorldState,
+    target: &EventTarget,
+    new_name: &str,
+) -> WorkspaceEdit {
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+    for publisher in find_event_publishers(state, target) {
+        insert_edit(
+            &mut changes,
+            publisher.uri,
+            make_rename_edit(publisher.name_start, publisher.name_end, new_name),
+        );
+    }
+    for usage in find_event_invocation_usages(state, target) {
+        insert_edit(
+            &mut changes,
+            usage.uri,
+            make_rename_edit(usage.start, usage.end, new_name),
+        );
+    }
+    for usage in find_event_subscriber_usages(state, target) {
+        insert_edit(
+            &mut changes,
+            usage.uri,
+            TextEdit {
+                range: ts_range_to_lsp_range(usage.start, usage.end),
+                new_text: format!("'{}'", new_name.replace('\'', "''")),
+            },
+        );
+    }
+
+    WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    }
+}
+
+/// Rename an enum value across all documents:
+/// - The value declaration in the enum or any enum extension extending it
+/// - All `Enum::Value` usages that resolve to the enum
+fn rename_enum_value(
+    state: &WorldState,
+    enum_name: &str,
+    value_name: &str,
+    new_name: &str,
+) -> WorkspaceEdit {
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        let source = doc.source();
+
+        // Rename value declarations in `enum` and `enumextension` objects
+        let tree = doc.scan_tree();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for object in root.named_children(&mut cursor) {
+            let mut value_cursor = object.walk();
+            for value_decl in object.named_children(&mut value_cursor) {
+                if value_decl.kind() != "enum_value_declaration" {
+                    continue;
+                }
+                let Some(name_node) = value_decl.child_by_field_name("name") else {
+                    continue;
+                };
+                let name = extract_name(name_node, source);
+                if !name.eq_ignore_ascii_case(value_name) {
+                    continue;
+                }
+                if enum_name_from_enum_value_declaration(value_decl, source)
+                    .is_some_and(|owner| owner.eq_ignore_ascii_case(enum_name))
+                {
+                    insert_edit(
+                        &mut changes,
+                        entry.key().clone(),
+                        make_rename_edit(
+                            name_node.start_position(),
+                            name_node.end_position(),
+                            new_name,
+                        ),
+                    );
+                }
+            }
+        }
+
+        // Rename qualified usages (`Enum::Value`, `Rec."Enum Field"::Value`, ...)
+        for usage in enum_value_usages_in_tree(&tree, source) {
+            if !usage.value_name.eq_ignore_ascii_case(value_name) {
+                continue;
+            }
+            let Some(usage_enum_name) =
+                resolve_enum_name_from_context(state, entry.key(), &usage.context)
+            else {
+                continue;
+            };
+            if !usage_enum_name.eq_ignore_ascii_case(enum_name) {
+                continue;
+            }
             insert_edit(
                 &mut changes,
                 entry.key().clone(),
-                make_rename_edit(start, end, new_name),
+                make_rename_edit(usage.start, usage.end, new_name),
             );
         }
     }
@@ -407,10 +564,285 @@ fn rename_codeunit_procedure(
     }
 }
 
+/// Object `(kind, name)` to rename when the cursor is on an object's declared
+/// name or on a reference to it (`Codeunit Foo`, `implements IFoo`, `Codeunit::Foo`).
+fn object_target_at_offset(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    byte_offset: usize,
+) -> Option<(&'static str, String)> {
+    let node = node_at_offset(tree, byte_offset)?;
+    if let Some(target) = type_target_from_type_identifier(node, source)
+        .or_else(|| interface_target_from_implements_identifier(node, source))
+    {
+        return Some(target);
+    }
+
+    let ctx = identifier_context_at_offset(tree, source, symbol_table, byte_offset)?;
+    match ctx.symbol?.kind {
+        AlSymbolKind::Object(kind) => Some((kind.label(), ctx.symbol?.name.clone())),
+        _ => None,
+    }
+}
+
+/// AL spelling of a renamed object: quoted unless it is a plain identifier.
+fn object_name_text(name: &str) -> String {
+    let mut chars = name.chars();
+    let simple = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if simple {
+        name.to_string()
+    } else {
+        format!("\"{name}\"")
+    }
+}
+
+/// Keyword qualifying an object ID reference such as `Codeunit::Foo`.
+fn object_reference_keyword(object_kind: &str) -> Option<&'static str> {
+    match object_kind {
+        "table" => Some("database"),
+        "codeunit" => Some("codeunit"),
+        "page" => Some("page"),
+        "report" => Some("report"),
+        "query" => Some("query"),
+        "xmlport" => Some("xmlport"),
+        _ => None,
+    }
+}
+
+/// Rename the object `from` refers to across the documents that can see it:
+/// - The object declaration
+/// - Type references (`Codeunit Foo`, `Record Foo`) and `implements` entries
+/// - Object ID references (`Codeunit::Foo`), enum qualifiers (`Foo::Value`) and
+///   `Foo.Method()` prefixes that do not resolve to a variable
+/// - Object-reference properties (`SourceTable`, `RunObject`, ...)
+///
+/// Files named after the object (`Foo.al`, `Foo.Codeunit.al`) are renamed too
+/// when the client supports `RenameFile` operations.
+fn rename_object(
+    state: &WorldState,
+    from: &Url,
+    object_kind: &str,
+    object_name: &str,
+    new_name: &str,
+) -> WorkspaceEdit {
+    let new_text = object_name_text(new_name);
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    let mut file_renames = Vec::new();
+    let target = find_object_declarations(state, from, object_kind, object_name)
+        .into_iter()
+        .next()
+        .map(|location| location.uri);
+
+    for entry in state.documents.iter() {
+        let doc = entry.value();
+        let source = doc.source();
+        if target.as_ref().is_some_and(|target| {
+            !state.object_visible_from(entry.key(), target, object_kind, object_name)
+        }) {
+            continue;
+        }
+
+        for object in &doc.symbol_table.symbols {
+            let AlSymbolKind::Object(kind) = object.kind else {
+                continue;
+            };
+            if kind.label() != object_kind || !object.name.eq_ignore_ascii_case(object_name) {
+                continue;
+            }
+            insert_edit(
+                &mut changes,
+                entry.key().clone(),
+                TextEdit {
+                    range: ts_range_to_lsp_range(object.name_start_point, object.name_end_point),
+                    new_text: new_text.clone(),
+                },
+            );
+            if let Some(new_uri) = renamed_object_file(entry.key(), object_name, new_name) {
+                file_renames.push((entry.key().clone(), new_uri));
+            }
+        }
+
+        let mut ranges = Vec::new();
+        collect_object_references(
+            doc.scan_tree().root_node(),
+            source,
+            &doc.symbol_table,
+            object_kind,
+            object_name,
+            &mut ranges,
+        );
+        for (start, end) in ranges {
+            insert_edit(
+                &mut changes,
+                entry.key().clone(),
+                TextEdit {
+                    range: ts_range_to_lsp_range(start, end),
+                    new_text: new_text.clone(),
+                },
+            );
+        }
+    }
+
+    if file_renames.is_empty() || !state.resource_rename.load(Ordering::Acquire) {
+        return WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        };
+    }
+
+    // Text edits address the old URIs, so they go before the file renames
+    let mut edits: Vec<(Url, Vec<TextEdit>)> = changes.into_iter().collect();
+    edits.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+    let mut operations: Vec<DocumentChangeOperation> = edits
+        .into_iter()
+        .map(|(uri, edits)| {
+            DocumentChangeOperation::Edit(TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: edits.into_iter().map(OneOf::Left).collect(),
+            })
+        })
+        .collect();
+    operations.extend(file_renames.into_iter().map(|(old_uri, new_uri)| {
+        DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+            old_uri,
+            new_uri,
+            options: None,
+            annotation_id: None,
+        }))
+    }));
+
+    WorkspaceEdit {
+        document_changes: Some(DocumentChanges::Operations(operations)),
+        ..Default::default()
+    }
+}
+
+/// New URI for a file named after `object_name` (`Foo.al` or `Foo.Codeunit.al`),
+/// or `None` when the file is named otherwise.
+fn renamed_object_file(uri: &Url, object_name: &str, new_name: &str) -> Option<Url> {
+    if new_name.contains(['/', '\\']) {
+        return None;
+    }
+    let path = uri.to_file_path().ok()?;
+    let file_name = path.file_name()?.to_str()?;
+    let stem = file_name.get(..object_name.len())?;
+    let rest = &file_name[object_name.len()..];
+    if !stem.eq_ignore_ascii_case(object_name) || !rest.starts_with('.') {
+        return None;
+    }
+    Url::from_file_path(path.with_file_name(format!("{new_name}{rest}"))).ok()
+}
+
+fn collect_object_references(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    object_kind: &str,
+    object_name: &str,
+    out: &mut Vec<(tree_sitter::Point, tree_sitter::Point)>,
+) {
+    match node.kind() {
+        "identifier" | "quoted_identifier" => {
+            if is_object_reference(node, source, symbol_table, object_kind, object_name) {
+                out.push((node.start_position(), node.end_position()));
+            }
+            return;
+        }
+        "property" => {
+            if let Some((kind, name, value_range)) = object_reference_property_value(node, source) {
+                if kind == object_kind && name.eq_ignore_ascii_case(object_name) {
+                    // The object name is the last token of the value
+                    let value = &source[value_range.clone()];
+                    let name_len = if value.ends_with('"') {
+                        name.len() + 2
+                    } else {
+                        name.len()
+                    };
+                    let start = value_range.end - name_len;
+                    out.push((
+                        point_at_byte(source, start),
+                        point_at_byte(source, value_range.end),
+                    ));
+                    return;
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_object_references(child, source, symbol_table, object_kind, object_name, out);
+    }
+}
+
+fn is_object_reference(
+    node: tree_sitter::Node<'_>,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    object_kind: &str,
+    object_name: &str,
+) -> bool {
+    let name = extract_name(node, source);
+    if !name.eq_ignore_ascii_case(object_name) {
+        return false;
+    }
+    if let Some((kind, _)) = type_target_from_type_identifier(node, source)
+        .or_else(|| interface_target_from_implements_identifier(node, source))
+    {
+        return kind == object_kind;
+    }
+
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        // `Codeunit::Foo` names the object; `Foo::Value` qualifies an enum value
+        "qualified_enum_value" => {
+            let (Some(qualifier), Some(value)) = (parent.named_child(0), parent.named_child(1))
+            else {
+                return false;
+            };
+            if value.id() == node.id() {
+                object_reference_keyword(object_kind)
+                    .is_some_and(|kw| extract_name(qualifier, source).eq_ignore_ascii_case(kw))
+            } else {
+                object_kind == "enum"
+            }
+        }
+        // `Foo.Method()` where `Foo` is not a variable or other symbol in scope
+        "primary_expression" => {
+            let Some(access) = parent.parent() else {
+                return false;
+            };
+            matches!(access.kind(), "method_call" | "member_access")
+                && access
+                    .child_by_field_name("object")
+                    .is_some_and(|object| object.id() == parent.id())
+                && symbol_table
+                    .lookup_in_scope(&name, node.start_byte())
+                    .iter()
+                    .all(|sym| matches!(sym.kind, AlSymbolKind::Object(_)))
+        }
+        _ => false,
+    }
+}
+
+fn point_at_byte(source: &str, byte: usize) -> tree_sitter::Point {
+    let before = &source[..byte];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    tree_sitter::Point::new(before.matches('\n').count(), byte - line_start)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::state::WorldState;
+    use crate::test_support::extract_cursor;
     use al_syntax::document::DocumentState;
     use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
 
@@ -440,7 +872,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Cursor on MyVar usage (line 6, col 8)
         let params = make_rename_params(uri.clone(), 6, 8, "NewVar");
@@ -484,10 +916,10 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl_uri.clone(), DocumentState::new(impl_source).unwrap());
+            .insert(impl_uri.clone(), DocumentState::new(impl_source));
 
         // Rename from interface method definition (line 2, col 14 = "GetAddress")
         let params = make_rename_params(iface_uri.clone(), 2, 14, "FetchAddress");
@@ -538,25 +970,31 @@ mod tests {
 {
     procedure GetAddress(): Text;
 }"#;
-        let impl_source = r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider
+        let (impl_source, cursor) = extract_cursor(
+            r#"codeunit 50200 CompanyAddressProvider implements IAddressProvider
 {
-    procedure GetAddress(): Text
+    procedure ⟨cursor⟩GetAddress(): Text
     begin
     end;
-}"#;
+}"#,
+        );
         let iface_uri = Url::parse("file:///test/iface.al").unwrap();
         let impl_uri = Url::parse("file:///test/impl.al").unwrap();
 
         let state = WorldState::new();
         state
             .documents
-            .insert(iface_uri.clone(), DocumentState::new(iface_source).unwrap());
+            .insert(iface_uri.clone(), DocumentState::new(iface_source));
         state
             .documents
-            .insert(impl_uri.clone(), DocumentState::new(impl_source).unwrap());
+            .insert(impl_uri.clone(), DocumentState::new(&impl_source));
 
-        // Rename from implementation procedure (line 2, col 14 = "GetAddress")
-        let params = make_rename_params(impl_uri.clone(), 2, 14, "FetchAddress");
+        let params = make_rename_params(
+            impl_uri.clone(),
+            cursor.line,
+            cursor.character,
+            "FetchAddress",
+        );
         let result = handle_rename(&state, params);
 
         assert!(result.is_some(), "expected rename result");
@@ -578,7 +1016,8 @@ mod tests {
 
     #[test]
     fn test_rename_from_method_call() {
-        let source = r#"interface IAddressProvider
+        let (source, cursor) = extract_cursor(
+            r#"interface IAddressProvider
 {
     procedure GetAddress(): Text;
 }
@@ -593,20 +1032,18 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
     var
         AddressProvider: Interface IAddressProvider;
     begin
-        AddressProvider.GetAddress();
+        AddressProvider.⟨cursor⟩GetAddress();
     end;
-}"#;
+}"#,
+        );
         let uri = Url::parse("file:///test/all.al").unwrap();
 
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(&source));
 
-        // Cursor on GetAddress in `AddressProvider.GetAddress()` (line 15)
-        // "        AddressProvider.GetAddress();"
-        // 8 spaces + "AddressProvider." = 24, so GetAddress starts at col 24
-        let params = make_rename_params(uri.clone(), 15, 24, "FetchAddress");
+        let params = make_rename_params(uri.clone(), cursor.line, cursor.character, "FetchAddress");
         let result = handle_rename(&state, params);
 
         assert!(result.is_some(), "expected rename result");
@@ -625,7 +1062,8 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
 
     #[test]
     fn test_rename_from_method_call_without_parentheses() {
-        let source = r#"interface IAddressProvider
+        let (source, cursor) = extract_cursor(
+            r#"interface IAddressProvider
 {
     procedure GetAddress(): Text;
 }
@@ -640,18 +1078,18 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
     var
         AddressProvider: Interface IAddressProvider;
     begin
-        AddressProvider.GetAddress;
+        AddressProvider.⟨cursor⟩GetAddress;
     end;
-}"#;
+}"#,
+        );
         let uri = Url::parse("file:///test/all.al").unwrap();
 
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(&source));
 
-        // Cursor on GetAddress in `AddressProvider.GetAddress;` (line 15, col 24)
-        let params = make_rename_params(uri.clone(), 15, 24, "FetchAddress");
+        let params = make_rename_params(uri.clone(), cursor.line, cursor.character, "FetchAddress");
         let result = handle_rename(&state, params);
 
         assert!(result.is_some(), "expected rename result");
@@ -684,7 +1122,7 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         // Rename ExampleAddr (line 4, col 8)
         let params = make_rename_params(uri.clone(), 4, 8, "AddressText");
@@ -697,4 +1135,315 @@ codeunit 50200 CompanyAddressProvider implements IAddressProvider
         assert_eq!(changes.len(), 1);
         assert!(changes.contains_key(&uri));
     }
+
+    #[test]
+    fn test_rename_enum_value_across_documents() {
+        let enum_source = r#"enum 50100 Color
+{
+    value(0; Red) { }
+    value(1; Green) { }
+}"#;
+        let ext_source = r#"enumextension 50101 "Color Ext" extends Color
+{
+    value(10; Purple) { }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        C: Enum Color;
+    begin
+        C := Color::Red;
+        case C of
+            Color::Red:
+                exit;
+            Color::Purple:
+                exit;
+        end;
+    end;
+}"#;
+        let enum_uri = Url::parse("file:///test/color.al").unwrap();
+        let ext_uri = Url::parse("file:///test/color_ext.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(enum_uri.clone(), DocumentState::new(enum_source));
+        state
+            .documents
+            .insert(ext_uri.clone(), DocumentState::new(ext_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        // Cursor on `Red` in the assignment `C := Color::Red;` (line 6, col 22)
+        let prepare = handle_prepare_rename(
+            &state,
+            TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: codeunit_uri.clone(),
+                },
+                position: Position::new(6, 22),
+            },
+        );
+        assert!(
+            prepare.is_some(),
+            "expected prepare-rename on enum value usage"
+        );
+
+        let params = make_rename_params(codeunit_uri.clone(), 6, 22, "Crimson");
+        let changes = handle_rename(&state, params).unwrap().changes.unwrap();
+
+        let decl_edits = changes.get(&enum_uri).expect("edit in enum declaration");
+        assert_eq!(decl_edits.len(), 1);
+        assert_eq!(decl_edits[0].range.start, Position::new(2, 13));
+
+        let usage_edits = changes.get(&codeunit_uri).expect("edits in codeunit");
+        let mut lines: Vec<u32> = usage_edits.iter().map(|e| e.range.start.line).collect();
+        lines.sort();
+        assert_eq!(lines, vec![6, 8], "assignment and case branch usages");
+        assert!(usage_edits.iter().all(|e| e.new_text == "Crimson"));
+        assert!(!changes.contains_key(&ext_uri));
+    }
+
+    #[test]
+    fn test_rename_enum_extension_value_from_declaration() {
+        let enum_source = r#"enum 50100 Color
+{
+    value(0; Red) { }
+}"#;
+        let ext_source = r#"enumextension 50101 "Color Ext" extends Color
+{
+    value(10; Purple) { }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        C: Enum Color;
+    begin
+        C := Color::Purple;
+    end;
+}"#;
+        let enum_uri = Url::parse("file:///test/color.al").unwrap();
+        let ext_uri = Url::parse("file:///test/color_ext.al").unwrap();
+        let codeunit_uri = Url::parse("file:///test/test.al").unwrap();
+
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(enum_uri, DocumentState::new(enum_source));
+        state
+            .documents
+            .insert(ext_uri.clone(), DocumentState::new(ext_source));
+        state
+            .documents
+            .insert(codeunit_uri.clone(), DocumentState::new(codeunit_source));
+
+        // Cursor on `Purple` in the enum extension (line 2, col 14)
+        let params = make_rename_params(ext_uri.clone(), 2, 14, "Dark Purple");
+        let changes = handle_rename(&state, params).unwrap().changes.unwrap();
+
+        let ext_edits = changes.get(&ext_uri).expect("edit in enum extension");
+        assert_eq!(ext_edits.len(), 1);
+        assert_eq!(ext_edits[0].new_text, "\"Dark Purple\"");
+        let usage_edits = changes.get(&codeunit_uri).expect("edit in codeunit");
+        assert_eq!(usage_edits.len(), 1);
+        assert_eq!(usage_edits[0].range.start, Position::new(6, 20));
+    }
+
+    #[test]
+    fn test_rename_event_publisher_updates_subscriber_attributes() {
+        let publisher_source = r#"codeunit 50100 "My Publisher"
+{
+    [IntegrationEvent(false, false)]
+    procedure OnAfterPost()
+    begin
+    end;
+
+    procedure Post()
+    begin
+        OnAfterPost();
+    end;
+}"#;
+        let subscriber_source = r#"codeunit 50101 "My Subscriber"
+{
+    [EventSubscriber(ObjectType::Codeunit, Codeunit::"My Publisher", 'OnAfterPost', '', false, false)]
+    local procedure HandleAfterPost()
+    begin
+    end;
+}"#;
+        let (publisher_source, position) = extract_cursor(&publisher_source.replacen(
+            "procedure OnAfterPost",
+            "procedure ⟨cursor⟩OnAfterPost",
+            1,
+        ));
+        let publisher_uri = Url::parse("file:///test/publisher.al").unwrap();
+        let subscriber_uri = Url::parse("file:///test/subscriber.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(publisher_uri.clone(), DocumentState::new(&publisher_source));
+        state.documents.insert(
+            subscriber_uri.clone(),
+            DocumentState::new(subscriber_source),
+        );
+
+        let edit = handle_rename(
+            &state,
+            make_rename_params(
+                publisher_uri.clone(),
+                position.line,
+                position.character,
+                "OnAfterPosting",
+            ),
+        )
+        .expect("expected rename edit");
+        let changes = edit.changes.unwrap();
+
+        let publisher_edits = &changes[&publisher_uri];
+        assert_eq!(publisher_edits.len(), 2, "declaration and raiser call");
+        assert!(publisher_edits
+            .iter()
+            .all(|e| e.new_text == "OnAfterPosting"));
+
+        let subscriber_edits = &changes[&subscriber_uri];
+        assert_eq!(subscriber_edits.len(), 1);
+        assert_eq!(subscriber_edits[0].new_text, "'OnAfterPosting'");
+        assert_eq!(subscriber_edits[0].range.start, Position::new(2, 69));
+        assert_eq!(subscriber_edits[0].range.end, Position::new(2, 82));
+    }
+
+    #[test]
+    fn test_rename_codeunit_updates_typed_variables_across_documents() {
+        let (provider_source, position) =
+            extract_cursor("codeunit 50101 ⟨cursor⟩AddressProvider\n{\n}");
+        let user_source = r#"codeunit 50100 Test
+{
+    var
+        Provider: Codeunit AddressProvider;
+
+    procedure Run(Other: Codeunit AddressProvider)
+    begin
+        Codeunit.Run(Codeunit::AddressProvider);
+    end;
+}"#;
+        let provider_uri = Url::parse("file:///test/provider.al").unwrap();
+        let user_uri = Url::parse("file:///test/user.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(provider_uri.clone(), DocumentState::new(&provider_source));
+        state
+            .documents
+            .insert(user_uri.clone(), DocumentState::new(user_source));
+
+        let params = make_rename_params(
+            provider_uri.clone(),
+            position.line,
+            position.character,
+            "Address Lookup",
+        );
+        let changes = handle_rename(&state, params).unwrap().changes.unwrap();
+
+        let provider_edits = &changes[&provider_uri];
+        assert_eq!(provider_edits.len(), 1);
+        assert_eq!(provider_edits[0].range.start, Position::new(0, 15));
+        assert_eq!(provider_edits[0].new_text, "\"Address Lookup\"");
+
+        let mut user_lines: Vec<(u32, u32)> = changes[&user_uri]
+            .iter()
+            .map(|e| (e.range.start.line, e.range.start.character))
+            .collect();
+        user_lines.sort();
+        assert_eq!(user_lines, vec![(3, 27), (5, 35), (7, 31)]);
+    }
+
+    #[test]
+    fn test_rename_table_updates_references_and_file() {
+        let (table_source, position) =
+            extract_cursor("table 50100 ⟨cursor⟩Customer\n{\n    fields\n    {\n        field(1; \"No.\"; Code[20]) { }\n    }\n}");
+        let page_source = r#"page 50100 "Customer Card"
+{
+    SourceTable = Customer;
+
+    trigger OnOpenPage()
+    var
+        Cust: Record Customer;
+        Customer: Integer;
+    begin
+        Page.Run(0, Cust);
+        Customer := Database::Customer;
+    end;
+}"#;
+        let table_uri = Url::parse("file:///test/Customer.Table.al").unwrap();
+        let page_uri = Url::parse("file:///test/CustomerCard.Page.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(table_uri.clone(), DocumentState::new(&table_source));
+        state
+            .documents
+            .insert(page_uri.clone(), DocumentState::new(page_source));
+
+        // Without client support for file renames only text edits are returned
+        let params = make_rename_params(
+            table_uri.clone(),
+            position.line,
+            position.character,
+            "Client",
+        );
+        let changes = handle_rename(&state, params).unwrap().changes.unwrap();
+        let mut page_edits: Vec<(u32, u32)> = changes[&page_uri]
+            .iter()
+            .map(|e| (e.range.start.line, e.range.start.character))
+            .collect();
+        page_edits.sort();
+        assert_eq!(page_edits, vec![(2, 18), (6, 21), (10, 30)]);
+        assert!(changes[&page_uri].iter().all(|e| e.new_text == "Client"));
+
+        state.resource_rename.store(true, Ordering::Release);
+        let params = make_rename_params(
+            table_uri.clone(),
+            position.line,
+            position.character,
+            "Client",
+        );
+        let edit = handle_rename(&state, params).unwrap();
+        assert!(edit.changes.is_none());
+        let Some(DocumentChanges::Operations(operations)) = edit.document_changes else {
+            panic!("expected document change operations");
+        };
+        assert_eq!(operations.len(), 3, "two document edits and a file rename");
+        assert_eq!(
+            operations.last(),
+            Some(&DocumentChangeOperation::Op(ResourceOp::Rename(
+                RenameFile {
+                    old_uri: table_uri,
+                    new_uri: Url::parse("file:///test/Client.Table.al").unwrap(),
+                    options: None,
+                    annotation_id: None,
+                }
+            )))
+        );
+    }
+
+    #[test]
+    fn test_prepare_rename_on_object_name() {
+        let (source, position) = extract_cursor("codeunit 50100 ⟨cursor⟩Greeter\n{\n}");
+        let uri = Url::parse("file:///test/greeter.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(&source));
+        let prepare = handle_prepare_rename(
+            &state,
+            TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position,
+            },
+        );
+        assert!(prepare.is_some(), "expected object names to be renameable");
+    }
 }
@@ -0,0 +1,90 @@
+use lsp_types::{Range, SelectionRange, SelectionRangeParams};
+
+use al_syntax::navigation::collect_selection_ranges;
+
+use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range_utf16};
+use crate::state::WorldState;
+
+pub fn handle_selection_range(
+    state: &WorldState,
+    params: SelectionRangeParams,
+) -> Option<Vec<SelectionRange>> {
+    let uri = params.text_document.uri;
+    let doc = state.documents.get(&uri)?;
+
+    let ranges = params
+        .positions
+        .into_iter()
+        .map(|position| {
+            let chain = lsp_position_to_byte_offset(doc.rope(), position)
+                .map(|byte_offset| collect_selection_ranges(doc.tree(), byte_offset))
+                .unwrap_or_default();
+
+            // Build from the outermost range inwards so each range links to its parent.
+            let mut selection: Option<SelectionRange> = None;
+            for (start, end) in chain.into_iter().rev() {
+                selection = Some(SelectionRange {
+                    range: ts_range_to_lsp_range_utf16(doc.rope(), start, end),
+                    parent: selection.map(Box::new),
+                });
+            }
+            selection.unwrap_or(SelectionRange {
+                range: Range {
+                    start: position,
+                    end: position,
+                },
+                parent: None,
+            })
+        })
+        .collect();
+
+    Some(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{Position, TextDocumentIdentifier, Url};
+
+    #[test]
+    fn test_selection_range_expands_variable_to_statement_then_block() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Counter: Integer;
+    begin
+        Counter := 1;
+    end;
+}"#;
+        let uri = Url::parse("file:///test/test.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let params = SelectionRangeParams {
+            text_document: TextDocumentIdentifier { uri },
+            positions: vec![Position::new(6, 10)],
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let ranges = handle_selection_range(&state, params).expect("expected selection ranges");
+        assert_eq!(ranges.len(), 1);
+
+        let identifier = &ranges[0];
+        assert_eq!(
+            identifier.range,
+            Range::new(Position::new(6, 8), Position::new(6, 15))
+        );
+        let statement = identifier.parent.as_deref().expect("statement range");
+        assert_eq!(
+            statement.range,
+            Range::new(Position::new(6, 8), Position::new(6, 20))
+        );
+        let block = statement.parent.as_deref().expect("block range");
+        assert_eq!(block.range.start, Position::new(5, 4));
+        assert_eq!(block.range.end.line, 7);
+    }
+}
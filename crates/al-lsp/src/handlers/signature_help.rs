@@ -3,10 +3,10 @@ use lsp_types::{
 };
 
 use al_syntax::ast::extract_name;
-use al_syntax::ast::AlSymbolKind;
-use al_syntax::navigation::{find_call_context, node_at_offset};
+use al_syntax::ast::AlSymbol;
+use al_syntax::builtins::find_builtin_method;
+use al_syntax::navigation::{enclosing_call_at_offset, find_call_context};
 
-use crate::builtins::find_builtin_method;
 use crate::convert::lsp_position_to_byte_offset;
 use crate::handlers::completion::resolve_object_type_from_expression;
 use crate::state::WorldState;
@@ -19,61 +19,29 @@ pub fn handle_signature_help(
     let position = params.text_document_position_params.position;
 
     let doc = state.documents.get(&uri)?;
-    let byte_offset = lsp_position_to_byte_offset(&doc.rope, position)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
     let source = doc.source();
 
-    let ctx = find_call_context(&doc.tree, &source, &doc.symbol_table, byte_offset)?;
-    if let Some(sym) = ctx.symbol {
-        // Build parameter list from the procedure's Parameter children
-        let parameters: Vec<ParameterInformation> = sym
-            .children
+    let ctx = find_call_context(doc.tree(), &source, &doc.symbol_table, byte_offset)?;
+    if !ctx.candidates.is_empty() {
+        let signatures: Vec<SignatureInformation> = ctx
+            .candidates
             .iter()
-            .filter(|c| matches!(c.kind, AlSymbolKind::Parameter))
-            .map(|p| {
-                let label = if let Some(ref t) = p.type_info {
-                    format!("{}: {}", p.name, t)
-                } else {
-                    p.name.clone()
-                };
-                ParameterInformation {
-                    label: ParameterLabel::Simple(label),
-                    documentation: None,
-                }
-            })
+            .map(|sym| procedure_signature(&ctx.function_name, sym, ctx.active_parameter))
             .collect();
-
-        // Build the signature label: "ProcName(param1: Type, param2: Type): ReturnType"
-        let params_str = parameters
+        let arities: Vec<usize> = signatures
             .iter()
-            .map(|p| match &p.label {
-                ParameterLabel::Simple(s) => s.clone(),
-                _ => String::new(),
-            })
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        let return_str = sym
-            .type_info
-            .as_ref()
-            .map(|t| format!(": {}", t))
-            .unwrap_or_default();
-
-        let label = format!("{}({}){}", ctx.function_name, params_str, return_str);
-
-        let signature = SignatureInformation {
-            label,
-            documentation: None,
-            parameters: if parameters.is_empty() {
-                None
-            } else {
-                Some(parameters)
-            },
-            active_parameter: Some(ctx.active_parameter as u32),
+            .map(|sig| sig.parameters.as_ref().map_or(0, Vec::len))
+            .collect();
+        let needed = if ctx.argument_count == 0 {
+            0
+        } else {
+            ctx.argument_count.max(ctx.active_parameter + 1)
         };
 
         return Some(SignatureHelp {
-            signatures: vec![signature],
-            active_signature: Some(0),
+            signatures,
+            active_signature: Some(best_arity_match(&arities, needed) as u32),
             active_parameter: Some(ctx.active_parameter as u32),
         });
     }
@@ -81,6 +49,67 @@ pub fn handle_signature_help(
     builtin_method_signature_help(state, &doc, &source, byte_offset, ctx.active_parameter)
 }
 
+fn procedure_signature(
+    function_name: &str,
+    sym: &AlSymbol,
+    active_parameter: usize,
+) -> SignatureInformation {
+    // Build parameter list from the procedure's Parameter children
+    let parameters: Vec<ParameterInformation> = sym
+        .parameters()
+        .map(|p| {
+            let label = if let Some(ref t) = p.type_info {
+                format!("{}: {}", p.name, t)
+            } else {
+                p.name.clone()
+            };
+            ParameterInformation {
+                label: ParameterLabel::Simple(label),
+                documentation: None,
+            }
+        })
+        .collect();
+
+    // Build the signature label: "ProcName(param1: Type, param2: Type): ReturnType"
+    let params_str = parameters
+        .iter()
+        .map(|p| match &p.label {
+            ParameterLabel::Simple(s) => s.clone(),
+            _ => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let return_str = sym
+        .type_info
+        .as_ref()
+        .map(|t| format!(": {}", t))
+        .unwrap_or_default();
+
+    SignatureInformation {
+        label: format!("{}({}){}", function_name, params_str, return_str),
+        documentation: None,
+        parameters: if parameters.is_empty() {
+            None
+        } else {
+            Some(parameters)
+        },
+        active_parameter: Some(active_parameter as u32),
+    }
+}
+
+/// Index of the overload that best fits `needed` arguments: the smallest
+/// arity that can still take them all, else the largest arity available.
+fn best_arity_match(arities: &[usize], needed: usize) -> usize {
+    arities
+        .iter()
+        .enumerate()
+        .filter(|(_, &arity)| arity >= needed)
+        .min_by_key(|(_, &arity)| arity)
+        .or_else(|| arities.iter().enumerate().max_by_key(|(_, &arity)| arity))
+        .map_or(0, |(idx, _)| idx)
+}
+
 fn builtin_method_signature_help(
     state: &WorldState,
     doc: &al_syntax::document::DocumentState,
@@ -88,15 +117,7 @@ fn builtin_method_signature_help(
     byte_offset: usize,
     active_parameter: usize,
 ) -> Option<SignatureHelp> {
-    let mut node = node_at_offset(&doc.tree, byte_offset)?;
-    loop {
-        let kind = node.kind();
-        if kind == "method_call" || kind == "function_call" {
-            break;
-        }
-        node = node.parent()?;
-    }
-
+    let node = enclosing_call_at_offset(doc.tree(), byte_offset)?;
     if node.kind() != "method_call" {
         return None;
     }
@@ -185,7 +206,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "SetRange(");
         let params = make_signature_help_params(uri, line, character);
@@ -217,7 +238,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "Contains(");
         let params = make_signature_help_params(uri, line, character);
@@ -248,7 +269,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "ReadText(");
         let params = make_signature_help_params(uri, line, character);
@@ -278,7 +299,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "RequestSessionUpdate(");
         let params = make_signature_help_params(uri, line, character);
@@ -308,7 +329,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "AddAction(");
         let params = make_signature_help_params(uri, line, character);
@@ -336,7 +357,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "CreateTask(");
         let params = make_signature_help_params(uri, line, character);
@@ -366,7 +387,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "AddNavigationAction(");
         let params = make_signature_help_params(uri, line, character);
@@ -396,7 +417,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "SetValue(");
         let params = make_signature_help_params(uri, line, character);
@@ -426,7 +447,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "SaveAsXml(");
         let params = make_signature_help_params(uri, line, character);
@@ -456,7 +477,7 @@ mod tests {
         let state = WorldState::new();
         state
             .documents
-            .insert(uri.clone(), DocumentState::new(source).unwrap());
+            .insert(uri.clone(), DocumentState::new(source));
 
         let (line, character) = cursor_after(source, "AddEntityKey(");
         let params = make_signature_help_params(uri, line, character);
@@ -470,4 +491,103 @@ mod tests {
             "expected AddEntityKey signature, got: {help:?}"
         );
     }
+
+    #[test]
+    fn test_signature_help_nested_calls() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Outer(X: Integer; Y: Integer)
+    begin
+    end;
+
+    procedure Inner(A: Integer; B: Integer): Integer
+    begin
+    end;
+
+    procedure DoWork()
+    begin
+        Outer(Inner(1, 2), 3);
+    end;
+}"#;
+        let uri = Url::parse("file:///test/nested.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        for (marker, expected_label, expected_parameter) in [
+            ("        Outer(", "Outer(X: Integer, Y: Integer)", 0),
+            ("Outer(Inn", "Outer(X: Integer, Y: Integer)", 0),
+            ("Outer(Inner(", "Inner(A: Integer, B: Integer): Integer", 0),
+            (
+                "Outer(Inner(1, ",
+                "Inner(A: Integer, B: Integer): Integer",
+                1,
+            ),
+            ("Outer(Inner(1, 2)", "Outer(X: Integer, Y: Integer)", 0),
+            ("Outer(Inner(1, 2), ", "Outer(X: Integer, Y: Integer)", 1),
+        ] {
+            let (line, character) = cursor_after(source, marker);
+            let help = handle_signature_help(
+                &state,
+                make_signature_help_params(uri.clone(), line, character),
+            )
+            .unwrap_or_else(|| panic!("expected signature help after {marker:?}"));
+            assert_eq!(help.signatures[0].label, expected_label, "after {marker:?}");
+            assert_eq!(
+                help.active_parameter,
+                Some(expected_parameter),
+                "after {marker:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_signature_help_lists_overloads_and_picks_by_arity() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Add(A: Integer): Integer
+    begin
+    end;
+
+    procedure Add(A: Integer; B: Integer): Integer
+    begin
+    end;
+
+    procedure DoWork()
+    begin
+        Add(1);
+        Add(1, 2);
+    end;
+}"#;
+        let uri = Url::parse("file:///test/all.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let (line, character) = cursor_after(source, "Add(1, ");
+        let help = handle_signature_help(
+            &state,
+            make_signature_help_params(uri.clone(), line, character),
+        )
+        .expect("expected signature help");
+        let labels: Vec<&str> = help.signatures.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Add(A: Integer): Integer",
+                "Add(A: Integer, B: Integer): Integer"
+            ]
+        );
+        assert_eq!(help.active_signature, Some(1));
+        assert_eq!(help.active_parameter, Some(1));
+
+        let (line, character) = cursor_after(source, "Add(1");
+        let help = handle_signature_help(&state, make_signature_help_params(uri, line, character))
+            .expect("expected signature help");
+        assert_eq!(help.signatures.len(), 2);
+        assert_eq!(help.active_signature, Some(0));
+        assert_eq!(help.active_parameter, Some(0));
+    }
 }
@@ -0,0 +1,259 @@
+use lsp_types::{
+    TypeHierarchyItem, TypeHierarchyPrepareParams, TypeHierarchySubtypesParams,
+    TypeHierarchySupertypesParams, Url,
+};
+
+use al_syntax::ast::{AlObjectKind, AlSymbol, AlSymbolKind};
+use al_syntax::navigation::{identifier_context_at_offset, node_at_offset};
+
+use crate::convert::{lsp_position_to_byte_offset, ts_range_to_lsp_range};
+use crate::handlers::goto_definition::{
+    interface_target_from_implements_identifier, type_target_from_type_identifier,
+};
+use crate::state::WorldState;
+
+/// Type hierarchy item for the object at the cursor: its declaration, or the
+/// declarations a type reference (`Interface IFoo`, `implements IFoo`) names.
+pub fn handle_prepare_type_hierarchy(
+    state: &WorldState,
+    params: TypeHierarchyPrepareParams,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let doc = state.documents.get(&uri)?;
+    let byte_offset = lsp_position_to_byte_offset(doc.rope(), position)?;
+    let source = doc.source();
+
+    let node = node_at_offset(doc.tree(), byte_offset)?;
+    let (object_kind, object_name) = match type_target_from_type_identifier(node, source)
+        .or_else(|| interface_target_from_implements_identifier(node, source))
+    {
+        Some((kind, name)) => (kind, name),
+        None => {
+            let ctx =
+                identifier_context_at_offset(doc.tree(), source, &doc.symbol_table, byte_offset)?;
+            let symbol = ctx.symbol?;
+            let AlSymbolKind::Object(kind) = symbol.kind else {
+                return None;
+            };
+            (kind.label(), symbol.name.clone())
+        }
+    };
+    drop(doc);
+
+    non_empty(find_object_items(state, |kind, symbol| {
+        kind.label() == object_kind && symbol.name.eq_ignore_ascii_case(&object_name)
+    }))
+}
+
+/// Interfaces implemented by the item's object.
+pub fn handle_supertypes(
+    state: &WorldState,
+    params: TypeHierarchySupertypesParams,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let interfaces = {
+        let doc = state.documents.get(&params.item.uri)?;
+        item_object(&doc.symbol_table.symbols, &params.item)?
+            .implements
+            .clone()
+    };
+
+    let mut items: Vec<TypeHierarchyItem> = state
+        .objects_of_kind(AlObjectKind::Interface)
+        .iter()
+        .filter(|(_, symbol)| {
+            interfaces
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&symbol.name))
+        })
+        .map(|(uri, symbol)| object_item(uri, AlObjectKind::Interface, symbol))
+        .collect();
+    items.sort_by(|a, b| (a.uri.as_str(), &a.name).cmp(&(b.uri.as_str(), &b.name)));
+    non_empty(items)
+}
+
+/// Objects implementing the item's interface.
+pub fn handle_subtypes(
+    state: &WorldState,
+    params: TypeHierarchySubtypesParams,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let interface_name = {
+        let doc = state.documents.get(&params.item.uri)?;
+        let object = item_object(&doc.symbol_table.symbols, &params.item)?;
+        if object.kind != AlSymbolKind::Object(AlObjectKind::Interface) {
+            return None;
+        }
+        object.name.clone()
+    };
+
+    non_empty(find_object_items(state, |_, symbol| {
+        symbol
+            .implements
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&interface_name))
+    }))
+}
+
+/// The object declaration an item was created from.
+fn item_object<'a>(symbols: &'a [AlSymbol], item: &TypeHierarchyItem) -> Option<&'a AlSymbol> {
+    symbols.iter().find(|symbol| {
+        matches!(symbol.kind, AlSymbolKind::Object(_))
+            && ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point)
+                == item.selection_range
+    })
+}
+
+fn find_object_items(
+    state: &WorldState,
+    matches: impl Fn(AlObjectKind, &AlSymbol) -> bool,
+) -> Vec<TypeHierarchyItem> {
+    let mut items = Vec::new();
+    for entry in state.documents.iter() {
+        for symbol in &entry.value().symbol_table.symbols {
+            let AlSymbolKind::Object(kind) = symbol.kind else {
+                continue;
+            };
+            if matches(kind, symbol) {
+                items.push(object_item(entry.key(), kind, symbol));
+            }
+        }
+    }
+    items.sort_by(|a, b| (a.uri.as_str(), &a.name).cmp(&(b.uri.as_str(), &b.name)));
+    items
+}
+
+fn object_item(uri: &Url, kind: AlObjectKind, symbol: &AlSymbol) -> TypeHierarchyItem {
+    let detail = match symbol.object_id {
+        Some(id) => format!("{} {id}", kind.label()),
+        None => kind.label().to_string(),
+    };
+    TypeHierarchyItem {
+        name: symbol.name.clone(),
+        kind: symbol.kind.lsp_kind(),
+        tags: None,
+        detail: Some(detail),
+        uri: uri.clone(),
+        range: ts_range_to_lsp_range(symbol.start_point, symbol.end_point),
+        selection_range: ts_range_to_lsp_range(symbol.name_start_point, symbol.name_end_point),
+        data: None,
+    }
+}
+
+fn non_empty(items: Vec<TypeHierarchyItem>) -> Option<Vec<TypeHierarchyItem>> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::extract_cursor;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{TextDocumentIdentifier, TextDocumentPositionParams};
+
+    const INTERFACE: &str = r#"interface IAddressProvider
+{
+    procedure GetAddress(): Text;
+}"#;
+
+    const COMPANY: &str = r#"codeunit 50100 CompanyAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+}"#;
+
+    const PRIVATE: &str = r#"codeunit 50101 PrivateAddressProvider implements IAddressProvider
+{
+    procedure GetAddress(): Text
+    begin
+    end;
+}"#;
+
+    fn state_with(marked: (&str, &str)) -> (WorldState, Url, lsp_types::Position) {
+        let state = WorldState::new();
+        let mut cursor = None;
+        for (path, source) in [
+            ("interface.al", INTERFACE),
+            ("company.al", COMPANY),
+            ("private.al", PRIVATE),
+        ] {
+            let uri = Url::parse(&format!("file:///test/{path}")).unwrap();
+            let source = if path == marked.0 {
+                let (source, position) =
+                    extract_cursor(&source.replacen(marked.1, &format!("⟨cursor⟩{}", marked.1), 1));
+                cursor = Some((uri.clone(), position));
+                source
+            } else {
+                source.to_string()
+            };
+            state.documents.insert(uri, DocumentState::new(&source));
+        }
+        let (uri, position) = cursor.expect("marker file not found");
+        (state, uri, position)
+    }
+
+    fn prepare(state: &WorldState, uri: Url, position: lsp_types::Position) -> TypeHierarchyItem {
+        let items = handle_prepare_type_hierarchy(
+            state,
+            TypeHierarchyPrepareParams {
+                text_document_position_params: TextDocumentPositionParams {
+                    text_document: TextDocumentIdentifier { uri },
+                    position,
+                },
+                work_done_progress_params: Default::default(),
+            },
+        )
+        .expect("expected a type hierarchy item");
+        assert_eq!(items.len(), 1);
+        items.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_interface_subtypes_are_implementers() {
+        let (state, uri, position) = state_with(("interface.al", "IAddressProvider"));
+        let item = prepare(&state, uri, position);
+        assert_eq!(item.name, "IAddressProvider");
+
+        let subtypes = handle_subtypes(
+            &state,
+            TypeHierarchySubtypesParams {
+                item,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .expect("expected subtypes");
+        let names: Vec<&str> = subtypes.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["CompanyAddressProvider", "PrivateAddressProvider"]
+        );
+        assert_eq!(subtypes[0].detail.as_deref(), Some("codeunit 50100"));
+        assert_eq!(subtypes[0].uri.path(), "/test/company.al");
+    }
+
+    #[test]
+    fn test_codeunit_supertypes_are_interfaces() {
+        let (state, uri, position) = state_with(("company.al", "CompanyAddressProvider"));
+        let item = prepare(&state, uri, position);
+
+        let supertypes = handle_supertypes(
+            &state,
+            TypeHierarchySupertypesParams {
+                item,
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .expect("expected supertypes");
+        assert_eq!(supertypes.len(), 1);
+        assert_eq!(supertypes[0].name, "IAddressProvider");
+        assert_eq!(supertypes[0].uri.path(), "/test/interface.al");
+        assert_eq!(supertypes[0].selection_range.start.line, 0);
+    }
+}
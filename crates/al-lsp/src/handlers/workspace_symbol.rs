@@ -1,15 +1,20 @@
-use lsp_types::{Location, SymbolInformation, WorkspaceSymbolParams};
+use lsp_types::{
+    Location, OneOf, Position, SymbolInformation, WorkspaceLocation, WorkspaceSymbol,
+    WorkspaceSymbolParams, WorkspaceSymbolResponse,
+};
 
 use al_syntax::ast::AlSymbol;
 
 use crate::convert::ts_range_to_lsp_range;
 use crate::state::WorldState;
 
-#[allow(deprecated)] // SymbolInformation::deprecated field is deprecated in the type itself
+/// Workspace symbols matching the query. Locations carry only the URI; the
+/// symbol's start position travels in `data` and `workspaceSymbol/resolve`
+/// fills in the range.
 pub fn handle_workspace_symbol(
     state: &WorldState,
     params: WorkspaceSymbolParams,
-) -> Option<Vec<SymbolInformation>> {
+) -> Option<WorkspaceSymbolResponse> {
     let query = params.query.to_lowercase();
 
     let mut symbols = Vec::new();
@@ -19,7 +24,7 @@ pub fn handle_workspace_symbol(
         let doc = entry.value();
 
         for obj in doc.symbols() {
-            collect_symbols_flat(obj, &uri, &query, &mut symbols);
+            collect_symbols_flat(obj, None, &uri, &query, &mut symbols);
         }
     }
 
@@ -27,39 +32,349 @@ pub fn handle_workspace_symbol(
         return None;
     }
 
-    Some(symbols)
+    symbols.sort_by_key(|(rank, _)| *rank);
+    Some(WorkspaceSymbolResponse::Nested(
+        symbols.into_iter().map(|(_, symbol)| symbol).collect(),
+    ))
+}
+
+/// Fill in the full `Location` of a symbol returned by
+/// [`handle_workspace_symbol`]. Symbols that are already resolved, or whose
+/// declaration no longer exists, are returned unchanged.
+pub fn handle_workspace_symbol_resolve(
+    state: &WorldState,
+    mut symbol: WorkspaceSymbol,
+) -> WorkspaceSymbol {
+    let OneOf::Right(WorkspaceLocation { uri }) = &symbol.location else {
+        return symbol;
+    };
+    let Some(start) = symbol
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value::<Position>(data).ok())
+    else {
+        return symbol;
+    };
+    let Some(doc) = state.documents.get(uri) else {
+        return symbol;
+    };
+    let range = doc
+        .symbols()
+        .iter()
+        .find_map(|obj| find_symbol_range(obj, &symbol.name, start));
+    drop(doc);
+
+    if let Some(range) = range {
+        symbol.location = OneOf::Left(Location {
+            uri: uri.clone(),
+            range,
+        });
+    }
+    symbol
 }
 
-#[allow(deprecated)]
+/// Flatten a workspace symbol response into `SymbolInformation`, resolving
+/// every location, for transports that only accept the flat form.
+#[allow(deprecated)] // SymbolInformation::deprecated field is deprecated in the type itself
+pub fn into_symbol_information(
+    state: &WorldState,
+    response: WorkspaceSymbolResponse,
+) -> Vec<SymbolInformation> {
+    let symbols = match response {
+        WorkspaceSymbolResponse::Flat(symbols) => return symbols,
+        WorkspaceSymbolResponse::Nested(symbols) => symbols,
+    };
+    symbols
+        .into_iter()
+        .filter_map(|symbol| {
+            let symbol = handle_workspace_symbol_resolve(state, symbol);
+            let OneOf::Left(location) = symbol.location else {
+                return None;
+            };
+            Some(SymbolInformation {
+                name: symbol.name,
+                kind: symbol.kind,
+                tags: symbol.tags,
+                deprecated: None,
+                location,
+                container_name: symbol.container_name,
+            })
+        })
+        .collect()
+}
+
+fn find_symbol_range(sym: &AlSymbol, name: &str, start: Position) -> Option<lsp_types::Range> {
+    let range = ts_range_to_lsp_range(sym.start_point, sym.end_point);
+    if range.start == start && sym.name == name {
+        return Some(range);
+    }
+    sym.children
+        .iter()
+        .find_map(|child| find_symbol_range(child, name, start))
+}
+
+/// Rank how well `candidate` matches a lowercase `query`. Every query character
+/// must appear in order (case-insensitively); lower ranks are better: an exact
+/// name, then a prefix, then a contiguous substring, then a scattered
+/// subsequence, tighter spans first.
+pub(crate) fn fuzzy_match_rank(query: &str, candidate: &str) -> Option<(u8, usize)> {
+    let candidate = candidate.to_lowercase();
+    if candidate == query {
+        return Some((0, 0));
+    }
+    if candidate.starts_with(query) {
+        return Some((1, candidate.len() - query.len()));
+    }
+    if let Some(pos) = candidate.find(query) {
+        return Some((2, pos));
+    }
+
+    let mut query_chars = query.chars().peekable();
+    let mut first = None;
+    let mut last = 0;
+    for (idx, ch) in candidate.char_indices() {
+        if query_chars.peek() == Some(&ch) {
+            query_chars.next();
+            first.get_or_insert(idx);
+            last = idx;
+        }
+    }
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    Some((3, last - first.unwrap_or(0)))
+}
+
+/// Flatten `sym` and its descendants into `result`, naming each entry's
+/// closest named ancestor (object or procedure) as its container.
 fn collect_symbols_flat(
     sym: &AlSymbol,
+    container: Option<&str>,
     uri: &lsp_types::Url,
     query: &str,
-    result: &mut Vec<SymbolInformation>,
+    result: &mut Vec<((u8, usize), WorkspaceSymbol)>,
 ) {
     if sym.name.trim().is_empty() {
         for child in &sym.children {
-            collect_symbols_flat(child, uri, query, result);
+            collect_symbols_flat(child, container, uri, query, result);
         }
         return;
     }
 
-    if query.is_empty() || sym.name.to_lowercase().contains(query) {
+    let rank = if query.is_empty() {
+        Some((0, 0))
+    } else {
+        fuzzy_match_rank(query, &sym.name)
+    };
+    if let Some(rank) = rank {
         let range = ts_range_to_lsp_range(sym.start_point, sym.end_point);
-        result.push(SymbolInformation {
+        let symbol = WorkspaceSymbol {
             name: sym.name.clone(),
             kind: sym.kind.lsp_kind(),
             tags: None,
-            deprecated: None,
-            location: Location {
-                uri: uri.clone(),
-                range,
-            },
-            container_name: None,
-        });
+            container_name: container.map(str::to_string),
+            location: OneOf::Right(WorkspaceLocation { uri: uri.clone() }),
+            data: Some(serde_json::json!(range.start)),
+        };
+        result.push((rank, symbol));
     }
 
     for child in &sym.children {
-        collect_symbols_flat(child, uri, query, result);
+        collect_symbols_flat(child, Some(&sym.name), uri, query, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use al_syntax::document::DocumentState;
+    use lsp_types::{Range, SymbolKind, Url};
+
+    fn nested(response: WorkspaceSymbolResponse) -> Vec<WorkspaceSymbol> {
+        match response {
+            WorkspaceSymbolResponse::Nested(symbols) => symbols,
+            WorkspaceSymbolResponse::Flat(_) => panic!("expected nested workspace symbols"),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_match_rank() {
+        assert!(fuzzy_match_rank("gaddr", "GetAddress").is_some());
+        assert!(fuzzy_match_rank("xyz", "GetAddress").is_none());
+        assert!(fuzzy_match_rank("getad", "GetAddress") < fuzzy_match_rank("gaddr", "GetAddress"));
+        assert!(
+            fuzzy_match_rank("address", "GetAddress") < fuzzy_match_rank("gaddr", "GetAddress")
+        );
+        assert!(
+            fuzzy_match_rank("getaddress", "GetAddress") < fuzzy_match_rank("get", "GetAddress")
+        );
+    }
+
+    #[test]
+    fn test_workspace_symbol_ranks_prefix_matches_first() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure GetAddress()
+    begin
+    end;
+
+    procedure GadgetCount()
+    begin
+    end;
+}"#;
+        let state = WorldState::new();
+        state.documents.insert(
+            Url::parse("file:///test/test.al").unwrap(),
+            DocumentState::new(source),
+        );
+
+        let names: Vec<String> = handle_workspace_symbol(
+            &state,
+            WorkspaceSymbolParams {
+                query: "gad".to_string(),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .map(nested)
+        .expect("expected symbols")
+        .into_iter()
+        .map(|symbol| symbol.name)
+        .collect();
+        assert_eq!(names, vec!["GadgetCount", "GetAddress"]);
+    }
+
+    #[test]
+    fn test_workspace_symbol_container_names() {
+        let table_source = r#"table 18 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+        field(2; Name; Text[100]) { }
+    }
+    keys
+    {
+        key(PK; "No.") { }
+    }
+}"#;
+        let codeunit_source = r#"codeunit 50100 Greeter
+{
+    procedure Greet()
+    var
+        Name: Text;
+    begin
+    end;
+}"#;
+        let state = WorldState::new();
+        state.documents.insert(
+            Url::parse("file:///test/customer.al").unwrap(),
+            DocumentState::new(table_source),
+        );
+        state.documents.insert(
+            Url::parse("file:///test/greeter.al").unwrap(),
+            DocumentState::new(codeunit_source),
+        );
+
+        let symbols = handle_workspace_symbol(
+            &state,
+            WorkspaceSymbolParams {
+                query: "Name".to_string(),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            },
+        )
+        .map(nested)
+        .expect("expected symbols");
+        let mut containers: Vec<(String, Option<String>)> = symbols
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.container_name))
+            .collect();
+        containers.sort();
+        assert_eq!(
+            containers,
+            vec![
+                ("Name".to_string(), Some("Customer".to_string())),
+                ("Name".to_string(), Some("Greet".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workspace_symbol_resolve_fills_in_location() {
+        let source = r#"codeunit 50100 Greeter
+{
+    procedure Greet()
+    begin
+    end;
+
+    procedure Greet(Name: Text)
+    begin
+    end;
+}"#;
+        let uri = Url::parse("file:///test/greeter.al").unwrap();
+        let state = WorldState::new();
+        state
+            .documents
+            .insert(uri.clone(), DocumentState::new(source));
+
+        let symbols = nested(
+            handle_workspace_symbol(
+                &state,
+                WorkspaceSymbolParams {
+                    query: "Greet".to_string(),
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                },
+            )
+            .expect("expected symbols"),
+        );
+        let greets: Vec<_> = symbols
+            .into_iter()
+            .filter(|symbol| symbol.kind == SymbolKind::FUNCTION)
+            .collect();
+        assert_eq!(greets.len(), 2);
+        assert!(greets
+            .iter()
+            .all(|symbol| symbol.location == OneOf::Right(WorkspaceLocation { uri: uri.clone() })));
+
+        let mut resolved: Vec<Range> = greets
+            .into_iter()
+            .map(|symbol| {
+                let resolved = handle_workspace_symbol_resolve(&state, symbol);
+                assert_eq!(resolved.container_name.as_deref(), Some("Greeter"));
+                match resolved.location {
+                    OneOf::Left(location) => {
+                        assert_eq!(location.uri, uri);
+                        location.range
+                    }
+                    OneOf::Right(_) => panic!("expected a resolved location"),
+                }
+            })
+            .collect();
+        resolved.sort_by_key(|range| range.start.line);
+        assert_eq!(
+            resolved,
+            vec![
+                Range::new(Position::new(2, 4), Position::new(4, 8)),
+                Range::new(Position::new(6, 4), Position::new(8, 8)),
+            ]
+        );
+
+        let flat = into_symbol_information(
+            &state,
+            handle_workspace_symbol(
+                &state,
+                WorkspaceSymbolParams {
+                    query: "Greeter".to_string(),
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                },
+            )
+            .expect("expected symbols"),
+        );
+        assert_eq!(flat[0].name, "Greeter");
+        assert_eq!(flat[0].location.uri, uri);
+        assert_eq!(flat[0].location.range.start, Position::new(0, 0));
     }
 }
@@ -1,8 +1,11 @@
 mod backend;
-mod builtins;
+mod config;
 mod convert;
 mod handlers;
+mod manifest;
 mod state;
+#[cfg(test)]
+mod test_support;
 
 use std::sync::Arc;
 
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+/// An inclusive object ID range declared in `app.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdRange {
+    pub from: i64,
+    pub to: i64,
+}
+
+/// The parts of an AL project's `app.json` the server uses.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppManifest {
+    /// Allowed object ID ranges (`idRanges`, or the older single `idRange`).
+    pub id_ranges: Vec<IdRange>,
+}
+
+impl AppManifest {
+    /// Read `app.json` from `root`, if present and valid JSON.
+    pub fn load(root: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(root.join("app.json")).ok()?;
+        match serde_json::from_str(text.trim_start_matches('\u{feff}')) {
+            Ok(value) => Some(Self::from_json(&value)),
+            Err(e) => {
+                tracing::warn!("failed to parse {}/app.json: {}", root.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Read the manifest from a parsed `app.json`; malformed ranges are skipped.
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let parse_range = |range: &serde_json::Value| {
+            Some(IdRange {
+                from: range.get("from")?.as_i64()?,
+                to: range.get("to")?.as_i64()?,
+            })
+        };
+
+        let mut id_ranges: Vec<IdRange> = value
+            .get("idRanges")
+            .and_then(serde_json::Value::as_array)
+            .map(|ranges| ranges.iter().filter_map(parse_range).collect())
+            .unwrap_or_default();
+        if let Some(range) = value.get("idRange").and_then(parse_range) {
+            id_ranges.push(range);
+        }
+        AppManifest { id_ranges }
+    }
+
+    /// Whether `id` falls in a declared range. Manifests without ranges allow any ID.
+    pub fn allows_object_id(&self, id: i64) -> bool {
+        self.id_ranges.is_empty()
+            || self
+                .id_ranges
+                .iter()
+                .any(|range| range.from <= id && id <= range.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_reads_id_ranges() {
+        let value = serde_json::json!({
+            "idRanges": [{ "from": 50100, "to": 50149 }, { "from": 60000 }],
+            "idRange": { "from": 70000, "to": 70009 }
+        });
+        let manifest = AppManifest::from_json(&value);
+        assert_eq!(
+            manifest.id_ranges,
+            vec![
+                IdRange {
+                    from: 50100,
+                    to: 50149
+                },
+                IdRange {
+                    from: 70000,
+                    to: 70009
+                },
+            ]
+        );
+        assert!(manifest.allows_object_id(50149));
+        assert!(manifest.allows_object_id(70000));
+        assert!(!manifest.allows_object_id(50150));
+        assert!(AppManifest::default().allows_object_id(1));
+    }
+}
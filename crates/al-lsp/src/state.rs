@@ -1,12 +1,19 @@
+use std::collections::VecDeque;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
-use dashmap::DashMap;
-use lsp_types::Url;
+use dashmap::{DashMap, DashSet};
+use lsp_types::{Hover, Url};
 
-use al_syntax::ast::AlSymbolKind;
+use al_syntax::ast::{AlObjectKind, AlSymbol, AlSymbolKind};
 use al_syntax::document::DocumentState;
+use al_syntax::formatting::FormatOptions;
+use al_syntax::navigation::{collect_method_call_sites, CallSiteKind};
+
+use crate::config::ServerConfig;
+use crate::manifest::AppManifest;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IndexedObjectEntry {
@@ -21,6 +28,85 @@ struct UriIndexedObjectEntry {
     object_start_byte: usize,
 }
 
+/// Number of hover results remembered per document.
+const HOVER_CACHE_CAPACITY: usize = 8;
+
+/// Most recently used hover results for one document, keyed by byte offset.
+///
+/// Entries are only valid for the parse they were computed against; a new
+/// source text (every reparse produces one) empties the cache, and a change to
+/// any other document drops it through [`WorldState::invalidate_document_caches`].
+pub struct HoverCache {
+    source: Arc<str>,
+    entries: VecDeque<(usize, Option<Hover>)>,
+}
+
+impl HoverCache {
+    pub fn new(source: Arc<str>) -> Self {
+        HoverCache {
+            source,
+            entries: VecDeque::with_capacity(HOVER_CACHE_CAPACITY),
+        }
+    }
+
+    /// Cached result at `byte_offset`, if computed against `source`.
+    pub fn get(&mut self, source: &Arc<str>, byte_offset: usize) -> Option<Option<Hover>> {
+        if !Arc::ptr_eq(&self.source, source) {
+            return None;
+        }
+        let idx = self
+            .entries
+            .iter()
+            .position(|(offset, _)| *offset == byte_offset)?;
+        let entry = self.entries.remove(idx)?;
+        let hover = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(hover)
+    }
+
+    /// Remember `hover` at `byte_offset`, evicting the least recently used entry.
+    pub fn insert(&mut self, source: Arc<str>, byte_offset: usize, hover: Option<Hover>) {
+        if !Arc::ptr_eq(&self.source, &source) {
+            self.source = source;
+            self.entries.clear();
+        }
+        self.entries.retain(|(offset, _)| *offset != byte_offset);
+        if self.entries.len() == HOVER_CACHE_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((byte_offset, hover));
+    }
+}
+
+type CallSiteKey = (CallSiteKind, String, String);
+type MethodKey = (String, String);
+type PointRange = (tree_sitter::Point, tree_sitter::Point);
+
+/// A method call site recorded in the workspace call index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedCallSite {
+    pub uri: Url,
+    pub start: tree_sitter::Point,
+    pub end: tree_sitter::Point,
+}
+
+/// A procedure implementing an interface method, recorded in the workspace
+/// implementer index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedImplementation {
+    pub uri: Url,
+    pub range: PointRange,
+    pub name_range: PointRange,
+}
+
+/// Call and implementer index keys contributed by one document, so
+/// reindexing can update only touched keys.
+#[derive(Debug, Default)]
+struct UriCallIndexEntry {
+    call_keys: Vec<CallSiteKey>,
+    implementer_keys: Vec<MethodKey>,
+}
+
 /// Global server state holding all open documents.
 pub struct WorldState {
     pub documents: DashMap<Url, DocumentState>,
@@ -37,6 +123,38 @@ pub struct WorldState {
     pub workspace_index_pending: AtomicBool,
     /// Workspace root directories (resolved from InitializeParams).
     pub workspace_roots: std::sync::Mutex<Vec<PathBuf>>,
+    /// Client-supplied server settings, resolved from InitializeParams and
+    /// then from the client's `workspace/configuration` response.
+    pub config: std::sync::Mutex<ServerConfig>,
+    /// Whether the client answers `workspace/configuration` requests.
+    pub configuration_pull: AtomicBool,
+    /// Whether the client pulls diagnostics (`textDocument/diagnostic`); they
+    /// are not pushed to such clients.
+    pub pull_diagnostics: AtomicBool,
+    /// Whether the client accepts dynamic registration of type hierarchy
+    /// requests, which lsp-types cannot advertise in the server capabilities.
+    pub type_hierarchy_registration: AtomicBool,
+    /// Whether the client applies `RenameFile` operations in workspace edits.
+    pub resource_rename: AtomicBool,
+    /// Documents currently open in the client; diagnostics are published for these.
+    pub open_documents: DashSet<Url>,
+    /// `app.json` manifests of the workspace roots that have one.
+    pub app_manifest: std::sync::Mutex<Vec<(PathBuf, AppManifest)>>,
+    /// Recent hover results per document.
+    pub hover_cache: DashMap<Url, HoverCache>,
+    /// Workspace call index:
+    /// `(kind, object_name_lower, method_name_lower) -> call sites`.
+    pub call_index: DashMap<CallSiteKey, Vec<IndexedCallSite>>,
+    /// Workspace implementer index:
+    /// `(interface_name_lower, method_name_lower) -> implementing procedures`.
+    pub implementer_index: DashMap<MethodKey, Vec<IndexedImplementation>>,
+    /// Documents covered by the call and implementer indexes.
+    /// [`WorldState::reindex_document`] indexes a document right away; an
+    /// incremental edit only drops its entry, and documents missing here are
+    /// indexed on the next call index lookup.
+    uri_call_index: DashMap<Url, UriCallIndexEntry>,
+    /// Indentation style last requested by the client when formatting each document.
+    pub format_options: DashMap<Url, FormatOptions>,
 }
 
 impl WorldState {
@@ -49,23 +167,268 @@ impl WorldState {
             workspace_index_running: AtomicBool::new(false),
             workspace_index_pending: AtomicBool::new(false),
             workspace_roots: std::sync::Mutex::new(Vec::new()),
+            config: std::sync::Mutex::new(ServerConfig::default()),
+            configuration_pull: AtomicBool::new(false),
+            pull_diagnostics: AtomicBool::new(false),
+            type_hierarchy_registration: AtomicBool::new(false),
+            resource_rename: AtomicBool::new(false),
+            open_documents: DashSet::new(),
+            app_manifest: std::sync::Mutex::new(Vec::new()),
+            hover_cache: DashMap::new(),
+            call_index: DashMap::new(),
+            implementer_index: DashMap::new(),
+            uri_call_index: DashMap::new(),
+            format_options: DashMap::new(),
         }
     }
 
     /// Insert or replace a document and refresh object index entries for its URI.
     pub fn upsert_document(&self, uri: Url, doc: DocumentState) {
         self.documents.insert(uri.clone(), doc);
+        self.invalidate_document_caches(&uri);
         self.reindex_document(&uri);
     }
 
-    /// Remove a document and all object index entries pointing to it.
-    pub fn remove_document(&self, uri: &Url) {
+    /// Insert or replace a document that is not open in the editor: index it
+    /// from the tree it was just parsed into, then drop that tree.
+    pub fn upsert_unloaded_document(&self, uri: Url, doc: DocumentState) {
+        self.upsert_document(uri.clone(), doc);
+        if let Some(mut doc) = self.documents.get_mut(&uri) {
+            doc.unload();
+        }
+    }
+
+    /// Remove a document and purge its symbols from the workspace symbol and object indexes.
+    pub fn remove_document_symbols(&self, uri: &Url) {
         self.documents.remove(uri);
+        self.format_options.remove(uri);
+        self.invalidate_document_caches(uri);
         self.remove_uri_from_object_index(uri);
     }
 
+    /// The innermost workspace root containing the document at `uri`, i.e. the
+    /// AL project it belongs to. `None` for documents outside every root.
+    pub fn root_for_uri(&self, uri: &Url) -> Option<PathBuf> {
+        let path = uri.to_file_path().ok()?;
+        self.workspace_roots
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+    }
+
+    /// Whether a reference in `from` to the `object_kind` object named
+    /// `object_name` can mean the one declared in `declared_in`: both share a
+    /// workspace root, or the root of `from` declares no such object itself.
+    /// Apps in a multi-root workspace may reuse object names, and each
+    /// resolves to its own before reaching into another root.
+    pub fn object_visible_from(
+        &self,
+        from: &Url,
+        declared_in: &Url,
+        object_kind: &str,
+        object_name: &str,
+    ) -> bool {
+        let from_root = self.root_for_uri(from);
+        if from_root == self.root_for_uri(declared_in) {
+            return true;
+        }
+        let key = (
+            object_kind.to_ascii_lowercase(),
+            object_name.to_ascii_lowercase(),
+        );
+        let Some(entries) = self.object_index.get(&key) else {
+            return true;
+        };
+        !entries
+            .iter()
+            .any(|entry| self.root_for_uri(&entry.uri) == from_root)
+    }
+
+    /// Whether the document at `uri` declares one of `namespaces`
+    /// (case-insensitive).
+    pub fn document_in_namespaces(&self, uri: &Url, namespaces: &[String]) -> bool {
+        let Some(doc) = self.documents.get(uri) else {
+            return false;
+        };
+        doc.symbol_table
+            .namespaces
+            .namespace
+            .as_deref()
+            .is_some_and(|ns| namespaces.iter().any(|c| c.eq_ignore_ascii_case(ns)))
+    }
+
+    /// Apply the client's `workspace/configuration` response for the
+    /// `alLsp` section over the current settings. Returns whether they changed.
+    pub fn apply_configuration_response(&self, values: &[serde_json::Value]) -> bool {
+        let Some(value) = values.first().filter(|value| value.is_object()) else {
+            return false;
+        };
+        let mut config = self.config.lock().unwrap();
+        let updated = config.merge_json(value);
+        let changed = updated != *config;
+        *config = updated;
+        changed
+    }
+
+    /// Indentation style for generated code in the document at `uri`: the
+    /// options of its last formatting request, or the formatter defaults.
+    pub fn format_options(&self, uri: &Url) -> FormatOptions {
+        self.format_options
+            .get(uri)
+            .map(|options| options.clone())
+            .unwrap_or_default()
+    }
+
+    /// Drop cached results a change to the document at `uri` can affect. Call
+    /// after every edit or reparse. Hovers show declarations and doc comments
+    /// from other documents, so every document's hover cache goes; only the
+    /// call index keeps the entries of other documents.
+    pub fn invalidate_document_caches(&self, uri: &Url) {
+        self.hover_cache.clear();
+        self.remove_uri_from_call_index(uri);
+    }
+
+    /// Call sites of `object_name.method_name` on variables typed as that
+    /// interface or codeunit, across all documents.
+    pub fn method_call_sites(
+        &self,
+        kind: CallSiteKind,
+        object_name: &str,
+        method_name: &str,
+    ) -> Vec<IndexedCallSite> {
+        self.index_cold_documents();
+        let key = (kind, object_name.to_lowercase(), method_name.to_lowercase());
+        self.call_index
+            .get(&key)
+            .map(|sites| sites.clone())
+            .unwrap_or_default()
+    }
+
+    /// Procedures implementing `interface_name.method_name`, across all documents.
+    pub fn interface_implementations(
+        &self,
+        interface_name: &str,
+        method_name: &str,
+    ) -> Vec<IndexedImplementation> {
+        self.index_cold_documents();
+        let key = (interface_name.to_lowercase(), method_name.to_lowercase());
+        self.implementer_index
+            .get(&key)
+            .map(|implementations| implementations.clone())
+            .unwrap_or_default()
+    }
+
+    /// Index the call sites and interface implementations of every document
+    /// not covered by the call index yet.
+    fn index_cold_documents(&self) {
+        let cold: Vec<Url> = self
+            .documents
+            .iter()
+            .filter(|entry| !self.uri_call_index.contains_key(entry.key()))
+            .map(|entry| entry.key().clone())
+            .collect();
+        for uri in cold {
+            self.index_document_calls(&uri);
+        }
+    }
+
+    /// Rebuild the call and implementer index entries of the document at `uri`.
+    fn index_document_calls(&self, uri: &Url) {
+        self.remove_uri_from_call_index(uri);
+        let Some(doc) = self.documents.get(uri) else {
+            return;
+        };
+
+        let mut uri_entry = UriCallIndexEntry::default();
+        for site in collect_method_call_sites(&doc.scan_tree(), doc.source(), &doc.symbol_table) {
+            let key = (site.kind, site.object_name, site.method_name);
+            self.call_index
+                .entry(key.clone())
+                .or_default()
+                .push(IndexedCallSite {
+                    uri: uri.clone(),
+                    start: site.start,
+                    end: site.end,
+                });
+            if !uri_entry.call_keys.contains(&key) {
+                uri_entry.call_keys.push(key);
+            }
+        }
+        for object in &doc.symbol_table.symbols {
+            for interface_name in &object.implements {
+                for child in &object.children {
+                    if !matches!(child.kind, AlSymbolKind::Procedure) {
+                        continue;
+                    }
+                    let key = (interface_name.to_lowercase(), child.name.to_lowercase());
+                    self.implementer_index.entry(key.clone()).or_default().push(
+                        IndexedImplementation {
+                            uri: uri.clone(),
+                            range: (child.start_point, child.end_point),
+                            name_range: (child.name_start_point, child.name_end_point),
+                        },
+                    );
+                    if !uri_entry.implementer_keys.contains(&key) {
+                        uri_entry.implementer_keys.push(key);
+                    }
+                }
+            }
+        }
+        self.uri_call_index.insert(uri.clone(), uri_entry);
+    }
+
+    fn remove_uri_from_call_index(&self, uri: &Url) {
+        let Some((_, uri_entry)) = self.uri_call_index.remove(uri) else {
+            return;
+        };
+
+        for key in uri_entry.call_keys {
+            let mut remove_key = false;
+            if let Some(mut sites) = self.call_index.get_mut(&key) {
+                sites.retain(|site| site.uri != *uri);
+                remove_key = sites.is_empty();
+            }
+            if remove_key {
+                self.call_index.remove(&key);
+            }
+        }
+        for key in uri_entry.implementer_keys {
+            let mut remove_key = false;
+            if let Some(mut implementations) = self.implementer_index.get_mut(&key) {
+                implementations.retain(|implementation| implementation.uri != *uri);
+                remove_key = implementations.is_empty();
+            }
+            if remove_key {
+                self.implementer_index.remove(&key);
+            }
+        }
+    }
+
+    /// Remove every file-backed document under `dir` (e.g. a removed workspace folder).
+    /// Documents still covered by another workspace root are kept.
+    pub fn remove_documents_under(&self, dir: &Path) -> usize {
+        let roots = self.workspace_roots.lock().unwrap().clone();
+        let uris: Vec<Url> = self
+            .documents
+            .iter()
+            .filter_map(|entry| {
+                let path = entry.key().to_file_path().ok()?;
+                let covered = roots.iter().any(|root| path.starts_with(root));
+                (path.starts_with(dir) && !covered).then(|| entry.key().clone())
+            })
+            .collect();
+        for uri in &uris {
+            self.remove_document_symbols(uri);
+        }
+        uris.len()
+    }
+
     /// Rebuild object index entries for a loaded document.
     pub fn reindex_document(&self, uri: &Url) {
+        self.index_document_calls(uri);
         self.remove_uri_from_object_index(uri);
         let Some(doc) = self.documents.get(uri) else {
             return;
@@ -134,6 +497,28 @@ impl WorldState {
         }
     }
 
+    /// Objects of `kind` across all documents with the URI declaring each,
+    /// ordered by URI and then by position in the document.
+    pub fn objects_of_kind(&self, kind: AlObjectKind) -> Vec<(Url, AlSymbol)> {
+        let mut objects: Vec<(Url, AlSymbol)> = self
+            .documents
+            .iter()
+            .flat_map(|entry| {
+                entry
+                    .value()
+                    .symbol_table
+                    .objects_of_kind(kind)
+                    .into_iter()
+                    .map(|symbol| (entry.key().clone(), symbol.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        objects.sort_by(|(a, a_symbol), (b, b_symbol)| {
+            (a.as_str(), a_symbol.start_byte).cmp(&(b.as_str(), b_symbol.start_byte))
+        });
+        objects
+    }
+
     pub fn visit_object_names_for_kind<F>(&self, object_kind: &str, mut visit: F) -> bool
     where
         F: FnMut(&str) -> bool,
@@ -158,17 +543,73 @@ impl WorldState {
 
     /// Scan workspace roots for source `.al` files only.
     pub fn load_workspace_source_files(&self) -> usize {
+        self.load_app_manifests();
         let roots = self.workspace_roots.lock().unwrap().clone();
         if roots.is_empty() {
             tracing::warn!("no workspace roots configured — cannot scan for .al files");
             return 0;
         }
-        let mut count = 0;
+        let mut paths = Vec::new();
         for root in &roots {
             tracing::info!("scanning workspace root: {}", root.display());
-            count += self.scan_directory(root);
+            collect_al_files(root, &mut paths);
         }
-        count
+        self.load_files_parallel(&paths)
+    }
+
+    /// Re-read `app.json` from every workspace root.
+    pub fn load_app_manifests(&self) {
+        let roots = self.workspace_roots.lock().unwrap().clone();
+        let manifests = roots
+            .into_iter()
+            .filter_map(|root| AppManifest::load(&root).map(|manifest| (root, manifest)))
+            .collect();
+        *self.app_manifest.lock().unwrap() = manifests;
+    }
+
+    /// Manifest of the innermost workspace root containing the document at `uri`.
+    pub fn app_manifest_for(&self, uri: &Url) -> Option<AppManifest> {
+        let path = uri.to_file_path().ok()?;
+        self.app_manifest
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(root, _)| path.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+            .map(|(_, manifest)| manifest.clone())
+    }
+
+    /// Parse and insert `paths` on a pool of worker threads. Returns how many
+    /// were loaded (already-loaded files count, as in `load_file`).
+    fn load_files_parallel(&self, paths: &[PathBuf]) -> usize {
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(paths.len());
+        if workers <= 1 {
+            return paths.iter().filter(|path| self.load_file(path)).count();
+        }
+
+        let next = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut count = 0;
+                        while let Some(path) = paths.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            if self.load_file(path) {
+                                count += 1;
+                            }
+                        }
+                        count
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or(0))
+                .sum()
+        })
     }
 
     /// Load `.al` files from `.app` archives (ZIP) in `.alpackages/` directories.
@@ -271,43 +712,8 @@ impl WorldState {
                 continue;
             }
 
-            if let Some(doc) = DocumentState::new(&source) {
-                self.upsert_document(uri, doc);
-                count += 1;
-            }
-        }
-        count
-    }
-
-    fn scan_directory(&self, dir: &Path) -> usize {
-        let entries = match std::fs::read_dir(dir) {
-            Ok(e) => e,
-            Err(e) => {
-                tracing::warn!("failed to read directory {}: {}", dir.display(), e);
-                return 0;
-            }
-        };
-
-        let mut count = 0;
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                // Skip hidden dirs and common non-source dirs
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.starts_with('.') || name == "node_modules" || name == "target" {
-                        continue;
-                    }
-                }
-                count += self.scan_directory(&path);
-            } else if path
-                .extension()
-                .and_then(|e| e.to_str())
-                .map(|e| e.eq_ignore_ascii_case("al"))
-                == Some(true)
-                && self.load_file(&path)
-            {
-                count += 1;
-            }
+            self.upsert_unloaded_document(uri, DocumentState::new(&source));
+            count += 1;
         }
         count
     }
@@ -340,16 +746,8 @@ impl WorldState {
             }
         };
 
-        match DocumentState::new(&source) {
-            Some(doc) => {
-                self.upsert_document(uri.clone(), doc);
-                true
-            }
-            None => {
-                tracing::warn!("failed to parse {}", path.display());
-                false
-            }
-        }
+        self.upsert_unloaded_document(uri.clone(), DocumentState::new(&source));
+        true
     }
 
     /// Reload a file from disk (e.g. after an external change notification).
@@ -361,7 +759,7 @@ impl WorldState {
 
         if !path.exists() {
             // File was deleted
-            self.remove_document(uri);
+            self.remove_document_symbols(uri);
             return;
         }
 
@@ -370,8 +768,38 @@ impl WorldState {
             Err(_) => return,
         };
 
-        if let Some(doc) = DocumentState::new(&source) {
-            self.upsert_document(uri.clone(), doc);
+        self.upsert_unloaded_document(uri.clone(), DocumentState::new(&source));
+    }
+}
+
+/// Recursively collect `.al` files under `dir`, skipping hidden directories,
+/// `node_modules` and `target`.
+fn collect_al_files(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(e) => {
+            tracing::warn!("failed to read directory {}: {}", dir.display(), e);
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            // Skip hidden dirs and common non-source dirs
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if name.starts_with('.') || name == "node_modules" || name == "target" {
+                    continue;
+                }
+            }
+            collect_al_files(&path, paths);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("al"))
+            == Some(true)
+        {
+            paths.push(path);
         }
     }
 }
@@ -429,6 +857,350 @@ mod tests {
         assert_eq!(state.documents.len(), 2);
     }
 
+    #[test]
+    fn test_scanned_files_stay_unloaded_through_call_indexing() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("consumer.al"),
+            r#"codeunit 50100 Consumer
+{
+    procedure Run(Runner: Interface IRunner)
+    begin
+        Runner.Start();
+    end;
+}"#,
+        )
+        .unwrap();
+
+        let state = WorldState::new();
+        *state.workspace_roots.lock().unwrap() = vec![dir.path().to_path_buf()];
+        assert_eq!(state.load_workspace_files(), 1);
+        // Call sites are indexed while loading, from the tree parsed there
+        assert_eq!(state.uri_call_index.len(), 1);
+        assert!(state
+            .documents
+            .iter()
+            .all(|entry| entry.value().is_unloaded()));
+
+        let sites = state.method_call_sites(CallSiteKind::Interface, "IRunner", "Start");
+        assert_eq!(sites.len(), 1);
+        assert!(state
+            .documents
+            .iter()
+            .all(|entry| entry.value().is_unloaded()));
+    }
+
+    #[test]
+    fn test_remove_document_symbols_purges_workspace_symbols_and_object_index() {
+        use crate::handlers::workspace_symbol::handle_workspace_symbol;
+        use lsp_types::{WorkspaceSymbolParams, WorkspaceSymbolResponse};
+
+        let query = |state: &WorldState| {
+            handle_workspace_symbol(
+                state,
+                WorkspaceSymbolParams {
+                    query: "Greet".to_string(),
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                },
+            )
+            .map_or(0, |response| match response {
+                WorkspaceSymbolResponse::Flat(symbols) => symbols.len(),
+                WorkspaceSymbolResponse::Nested(symbols) => symbols.len(),
+            })
+        };
+
+        let state = WorldState::new();
+        let uri = Url::parse("file:///test/hello.al").unwrap();
+        let source = r#"codeunit 50100 Hello
+{
+    procedure Greet()
+    begin
+    end;
+}"#;
+        state.upsert_document(uri.clone(), DocumentState::new(source));
+        assert_eq!(query(&state), 1);
+        assert!(state
+            .object_index
+            .contains_key(&("codeunit".to_string(), "hello".to_string())));
+
+        state.remove_document_symbols(&uri);
+        assert_eq!(query(&state), 0);
+        assert!(state.object_index.is_empty());
+        assert!(!state.visit_object_names_for_kind("codeunit", |_| true));
+    }
+
+    #[test]
+    fn test_objects_of_kind_aggregates_across_documents() {
+        let state = WorldState::new();
+        let second = Url::parse("file:///test/second.al").unwrap();
+        let first = Url::parse("file:///test/first.al").unwrap();
+        state.upsert_document(
+            second.clone(),
+            DocumentState::new(
+                r#"interface Shipping
+{
+    procedure Ship();
+}
+
+codeunit 50101 Courier
+{
+}"#,
+            ),
+        );
+        state.upsert_document(
+            first.clone(),
+            DocumentState::new(
+                r#"codeunit 50100 Cashier
+{
+}
+
+interface "Payment Method"
+{
+    procedure Pay();
+}
+
+interface Refunds
+{
+    procedure Refund();
+}"#,
+            ),
+        );
+
+        let names = |kind| {
+            state
+                .objects_of_kind(kind)
+                .into_iter()
+                .map(|(uri, symbol)| (uri, symbol.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(AlObjectKind::Interface),
+            vec![
+                (first.clone(), "Payment Method".to_string()),
+                (first.clone(), "Refunds".to_string()),
+                (second.clone(), "Shipping".to_string()),
+            ]
+        );
+        assert_eq!(
+            names(AlObjectKind::Codeunit),
+            vec![
+                (first, "Cashier".to_string()),
+                (second, "Courier".to_string()),
+            ]
+        );
+        assert!(names(AlObjectKind::Table).is_empty());
+    }
+
+    #[test]
+    fn test_invalidate_document_caches_keeps_other_documents_call_sites() {
+        let caller = |name: &str| {
+            format!(
+                r#"codeunit 50100 {name}
+{{
+    procedure Run()
+    var
+        Provider: Interface IAddressProvider;
+    begin
+        Provider.GetAddress();
+    end;
+}}"#
+            )
+        };
+        let state = WorldState::new();
+        let first = Url::parse("file:///test/first.al").unwrap();
+        let second = Url::parse("file:///test/second.al").unwrap();
+        state.upsert_document(first.clone(), DocumentState::new(&caller("First")));
+        state.upsert_document(second.clone(), DocumentState::new(&caller("Second")));
+
+        let calls =
+            state.method_call_sites(CallSiteKind::Interface, "IAddressProvider", "GetAddress");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(state.uri_call_index.len(), 2);
+
+        state.invalidate_document_caches(&first);
+        assert!(!state.uri_call_index.contains_key(&first));
+        let kept = state
+            .call_index
+            .get(&(
+                CallSiteKind::Interface,
+                "iaddressprovider".to_string(),
+                "getaddress".to_string(),
+            ))
+            .expect("second document's call sites kept");
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].uri, second);
+    }
+
+    #[test]
+    fn test_call_index_matches_document_scan() {
+        use al_syntax::navigation::{find_codeunit_method_calls, find_interface_method_calls};
+        use std::collections::BTreeSet;
+
+        let state = WorldState::new();
+        state.upsert_document(
+            Url::parse("file:///test/iface.al").unwrap(),
+            DocumentState::new(
+                "interface IAddressProvider\n{\n    procedure GetAddress(): Text;\n}",
+            ),
+        );
+        for i in 0..60 {
+            let source = format!(
+                r#"codeunit {id} Worker{i} implements IAddressProvider
+{{
+    procedure GetAddress(): Text
+    begin
+        Helper();
+    end;
+
+    procedure Helper()
+    var
+        Provider: Interface IAddressProvider;
+        Next: Codeunit Worker{next};
+    begin
+        Provider.GetAddress();
+        Next.Helper();
+        Next.Helper;
+    end;
+}}"#,
+                id = 50000 + i,
+                next = (i + 1) % 60,
+            );
+            let uri = Url::parse(&format!("file:///test/worker{i}.al")).unwrap();
+            state.upsert_document(uri, DocumentState::new(&source));
+        }
+        // Edited documents drop out of the index and are re-indexed on demand.
+        let edited = Url::parse("file:///test/worker7.al").unwrap();
+        state.invalidate_document_caches(&edited);
+
+        let queries = [
+            (
+                CallSiteKind::Interface,
+                "IAddressProvider".to_string(),
+                "GetAddress",
+            ),
+            (CallSiteKind::Codeunit, "Worker3".to_string(), "Helper"),
+            (CallSiteKind::Codeunit, "worker8".to_string(), "HELPER"),
+            (CallSiteKind::Codeunit, "Worker9".to_string(), "GetAddress"),
+        ];
+        let scanned: Vec<BTreeSet<(String, PointRange)>> = queries
+            .iter()
+            .map(|(kind, object, method)| {
+                let find = match kind {
+                    CallSiteKind::Interface => find_interface_method_calls,
+                    CallSiteKind::Codeunit => find_codeunit_method_calls,
+                };
+                state
+                    .documents
+                    .iter()
+                    .flat_map(|entry| {
+                        let doc = entry.value();
+                        find(doc.tree(), doc.source(), &doc.symbol_table, object, method)
+                            .into_iter()
+                            .map(|range| (entry.key().to_string(), range))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let indexed: Vec<BTreeSet<(String, PointRange)>> = queries
+            .iter()
+            .map(|(kind, object, method)| {
+                state
+                    .method_call_sites(*kind, object, method)
+                    .into_iter()
+                    .map(|site| (site.uri.to_string(), (site.start, site.end)))
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(indexed, scanned);
+        assert_eq!(indexed[0].len(), 60);
+        assert!(indexed[3].is_empty());
+        assert_eq!(
+            state
+                .interface_implementations("iaddressprovider", "getaddress")
+                .len(),
+            60
+        );
+    }
+
+    #[test]
+    fn test_load_workspace_source_files_in_parallel() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..120 {
+            let sub = dir.path().join(format!("module{}", i % 7));
+            fs::create_dir_all(&sub).unwrap();
+            fs::write(
+                sub.join(format!("cu{i}.al")),
+                format!("codeunit {} Worker{i}\n{{\n}}", 50000 + i),
+            )
+            .unwrap();
+        }
+        for skipped in ["node_modules", "target", ".git"] {
+            let sub = dir.path().join(skipped);
+            fs::create_dir(&sub).unwrap();
+            fs::write(sub.join("skip.al"), "codeunit 50999 Skipped\n{\n}").unwrap();
+        }
+
+        let state = WorldState::new();
+        *state.workspace_roots.lock().unwrap() = vec![dir.path().to_path_buf()];
+        // A file that is already open still counts but is not replaced.
+        let open_uri = Url::from_file_path(dir.path().join("module0").join("cu0.al")).unwrap();
+        state.upsert_document(
+            open_uri.clone(),
+            DocumentState::new("codeunit 50000 Edited\n{\n}"),
+        );
+
+        assert_eq!(state.load_workspace_source_files(), 120);
+        assert_eq!(state.documents.len(), 120);
+        assert_eq!(
+            state.documents.get(&open_uri).unwrap().symbols()[0].name,
+            "Edited"
+        );
+        for i in 1..120 {
+            assert!(
+                state
+                    .object_index
+                    .contains_key(&("codeunit".to_string(), format!("worker{i}"))),
+                "Worker{i} missing from the object index"
+            );
+        }
+        assert!(!state
+            .object_index
+            .contains_key(&("codeunit".to_string(), "skipped".to_string())));
+    }
+
+    #[test]
+    fn test_remove_documents_under_removed_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept");
+        let removed = dir.path().join("removed");
+        fs::create_dir(&kept).unwrap();
+        fs::create_dir(&removed).unwrap();
+        fs::write(kept.join("a.al"), "codeunit 50100 A\n{\n}").unwrap();
+        fs::write(removed.join("b.al"), "codeunit 50101 B\n{\n}").unwrap();
+
+        let state = WorldState::new();
+        *state.workspace_roots.lock().unwrap() = vec![kept.clone(), removed.clone()];
+        assert_eq!(state.load_workspace_source_files(), 2);
+
+        state
+            .workspace_roots
+            .lock()
+            .unwrap()
+            .retain(|r| r != &removed);
+        assert_eq!(state.remove_documents_under(&removed), 1);
+        assert_eq!(state.documents.len(), 1);
+        assert!(state
+            .object_index
+            .contains_key(&("codeunit".to_string(), "a".to_string())));
+        assert!(!state
+            .object_index
+            .contains_key(&("codeunit".to_string(), "b".to_string())));
+    }
+
     #[test]
     fn test_load_workspace_no_roots() {
         let state = WorldState::new();
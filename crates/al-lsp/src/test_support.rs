@@ -0,0 +1,48 @@
+//! Helpers shared by handler tests.
+
+use lsp_types::Position;
+
+/// Marks the cursor inside test sources passed to [`extract_cursor`].
+pub const CURSOR: &str = "⟨cursor⟩";
+
+/// Remove the single [`CURSOR`] marker from `marked` and return the clean
+/// source together with the marker's LSP position (UTF-16 columns).
+///
+/// Panics unless `marked` contains exactly one marker.
+pub fn extract_cursor(marked: &str) -> (String, Position) {
+    let offset = marked
+        .find(CURSOR)
+        .unwrap_or_else(|| panic!("no {CURSOR} marker in test source"));
+    let source = marked.replacen(CURSOR, "", 1);
+    assert!(
+        !source.contains(CURSOR),
+        "more than one {CURSOR} marker in test source"
+    );
+
+    let before = &source[..offset];
+    let line = before.matches('\n').count() as u32;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let character = before[line_start..].encode_utf16().count() as u32;
+    (source, Position::new(line, character))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_cursor_position() {
+        let (source, position) = extract_cursor("codeunit 1 \"Ünï\"\n{\n    \"Ä\".⟨cursor⟩Run;\n}");
+        assert_eq!(source, "codeunit 1 \"Ünï\"\n{\n    \"Ä\".Run;\n}");
+        assert_eq!(position, Position::new(2, 8));
+
+        let (_, position) = extract_cursor("⟨cursor⟩codeunit");
+        assert_eq!(position, Position::new(0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than one")]
+    fn test_extract_cursor_rejects_multiple_markers() {
+        extract_cursor("a⟨cursor⟩b⟨cursor⟩");
+    }
+}
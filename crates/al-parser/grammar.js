@@ -32,7 +32,7 @@ function semiSep1(rule) {
 module.exports = grammar({
   name: "al",
 
-  extras: ($) => [/\s/, $.line_comment, $.block_comment, $.region_directive, $.pragma_directive],
+  extras: ($) => [/\s/, $.line_comment, $.block_comment, $.region_directive, $.pragma_directive, $.preprocessor_directive],
 
   // NOTE: We do NOT use the `word` property because our keywords are
   // case-insensitive regexes (kw()), and tree-sitter's keyword extraction
@@ -45,7 +45,26 @@ module.exports = grammar({
   rules: {
     // ─── Top-level ───────────────────────────────────────────
 
-    source_file: ($) => repeat($._object_declaration),
+    source_file: ($) =>
+      repeat(choice($.namespace_declaration, $.using_directive, $._object_declaration)),
+
+    // `namespace MyCompany.Sales;` and `using MyCompany.Common;`
+    namespace_declaration: ($) =>
+      seq(kw("namespace"), field("name", $._qualifiable_name), ";"),
+
+    using_directive: ($) => seq(kw("using"), field("name", $._qualifiable_name), ";"),
+
+    _qualifiable_name: ($) => choice($.identifier, $.quoted_identifier, $.qualified_name),
+
+    // Dotted name such as `MyCompany.Sales.Customer`; the last segment names
+    // the object, the preceding ones its namespace.
+    qualified_name: ($) =>
+      prec.left(
+        seq(
+          choice($.identifier, $.quoted_identifier),
+          repeat1(seq(".", choice($.identifier, $.quoted_identifier)))
+        )
+      ),
 
     _object_declaration: ($) =>
       choice(
@@ -1195,36 +1214,36 @@ module.exports = grammar({
     record_type: ($) =>
       seq(
         kw("record"),
-        field("table", choice($.identifier, $.quoted_identifier)),
+        field("table", $._qualifiable_name),
         optional(kw("temporary"))
       ),
 
     codeunit_type: ($) =>
-      seq(kw("codeunit"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("codeunit"), field("name", $._qualifiable_name)),
 
     page_type: ($) =>
-      seq(kw("page"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("page"), field("name", $._qualifiable_name)),
 
     testpage_type: ($) =>
-      seq(kw("testpage"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("testpage"), field("name", $._qualifiable_name)),
 
     report_type: ($) =>
-      seq(kw("report"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("report"), field("name", $._qualifiable_name)),
 
     testrequestpage_type: ($) =>
-      seq(kw("testrequestpage"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("testrequestpage"), field("name", $._qualifiable_name)),
 
     query_type: ($) =>
-      seq(kw("query"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("query"), field("name", $._qualifiable_name)),
 
     xmlport_type: ($) =>
-      seq(kw("xmlport"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("xmlport"), field("name", $._qualifiable_name)),
 
     enum_type: ($) =>
-      seq(kw("enum"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("enum"), field("name", $._qualifiable_name)),
 
     interface_type: ($) =>
-      seq(kw("interface"), field("name", choice($.identifier, $.quoted_identifier))),
+      seq(kw("interface"), field("name", $._qualifiable_name)),
 
     dictionary_type: ($) =>
       seq(
@@ -1324,5 +1343,17 @@ module.exports = grammar({
 
     pragma_directive: ($) =>
       token(seq(/#[pP][rR][aA][gG][mM][aA]/, /.*/)),
+
+    preprocessor_directive: ($) =>
+      token(
+        choice(
+          seq(/#[iI][fF]/, /.*/),
+          seq(/#[eE][lL][iI][fF]/, /.*/),
+          seq(/#[eE][lL][sS][eE]/, /.*/),
+          seq(/#[eE][nN][dD][iI][fF]/, /.*/),
+          seq(/#[dD][eE][fF][iI][nN][eE]/, /.*/),
+          seq(/#[uU][nN][dD][eE][fF]/, /.*/)
+        )
+      ),
   },
 });
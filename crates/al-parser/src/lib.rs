@@ -423,6 +423,49 @@ codeunit 50100 Test
         assert!(!root.has_error(), "tree has errors: {}", root.to_sexp());
     }
 
+    #[test]
+    fn test_parse_preprocessor_directives() {
+        let source = r#"codeunit 50100 Test
+{
+#if not CLEAN22
+    procedure Legacy()
+    begin
+    end;
+#else
+    procedure Current()
+    begin
+    end;
+#endif
+}"#;
+        let tree = parse(source).expect("parse failed");
+        let root = tree.root_node();
+        assert!(!root.has_error(), "tree has errors: {}", root.to_sexp());
+    }
+
+    #[test]
+    fn test_parse_namespace_and_qualified_type() {
+        let source = r#"namespace MyCompany.App;
+
+using MyCompany.Sales;
+
+codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Cust: Record MyCompany.Sales.Customer;
+        Mgt: Codeunit MyCompany."Sales Mgt.";
+    begin
+    end;
+}"#;
+        let tree = parse(source).expect("parse failed");
+        let root = tree.root_node();
+        assert!(!root.has_error(), "tree has errors: {}", root.to_sexp());
+        let sexp = root.to_sexp();
+        assert!(sexp.contains("(namespace_declaration name: (qualified_name"));
+        assert!(sexp.contains("(using_directive name: (qualified_name"));
+        assert!(sexp.contains("(record_type table: (qualified_name"));
+    }
+
     #[test]
     fn test_parse_if_then_empty_statement() {
         let source = r#"codeunit 50100 Test
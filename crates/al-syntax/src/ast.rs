@@ -1,7 +1,7 @@
 use tree_sitter::{Node, Tree};
 
 /// The kind of AL object declaration.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AlObjectKind {
     Table,
     TableExtension,
@@ -38,6 +38,23 @@ impl AlObjectKind {
         }
     }
 
+    /// Object kind named by a type keyword in a declaration, e.g. `Record`
+    /// (tables) or `Codeunit` (case-insensitive).
+    pub fn from_type_keyword(keyword: &str) -> Option<Self> {
+        match keyword.to_ascii_lowercase().as_str() {
+            "record" => Some(Self::Table),
+            "page" => Some(Self::Page),
+            "codeunit" => Some(Self::Codeunit),
+            "report" => Some(Self::Report),
+            "enum" => Some(Self::Enum),
+            "xmlport" => Some(Self::Xmlport),
+            "query" => Some(Self::Query),
+            "interface" => Some(Self::Interface),
+            "controladdin" => Some(Self::ControlAddin),
+            _ => None,
+        }
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             Self::Table => "table",
@@ -55,10 +72,21 @@ impl AlObjectKind {
             Self::ControlAddin => "controladdin",
         }
     }
+
+    /// Kind of the object an extension kind extends: `Table` for
+    /// `TableExtension`, and so on. `None` for kinds that extend nothing.
+    pub fn extended_kind(&self) -> Option<Self> {
+        match self {
+            Self::TableExtension => Some(Self::Table),
+            Self::PageExtension => Some(Self::Page),
+            Self::EnumExtension => Some(Self::Enum),
+            _ => None,
+        }
+    }
 }
 
 /// The kind of symbol extracted from the tree.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AlSymbolKind {
     Object(AlObjectKind),
     Procedure,
@@ -93,17 +121,58 @@ pub struct AlSymbol {
     pub type_info: Option<String>,
     /// For codeunits: the list of interface names from the `implements` clause.
     pub implements: Vec<String>,
+    /// For objects: the numeric ID from the declaration (e.g. `50100`).
+    pub object_id: Option<i64>,
+    /// For table, page and enum extensions: the name of the extended object.
+    pub extends: Option<String>,
     pub start_byte: usize,
     pub end_byte: usize,
     pub start_point: tree_sitter::Point,
     pub end_point: tree_sitter::Point,
-    /// Start position of the name identifier node (for rename edits).
+    /// Start of the name identifier node, quotes included. Set for every
+    /// symbol kind; rename, references and goto-definition target this span
+    /// rather than the whole declaration (`start_point`..`end_point`).
     pub name_start_point: tree_sitter::Point,
-    /// End position of the name identifier node (for rename edits).
+    /// End of the name identifier node.
     pub name_end_point: tree_sitter::Point,
     pub children: Vec<AlSymbol>,
 }
 
+impl AlSymbol {
+    /// Base type and length of a sized `type_info` such as `Code[20]`.
+    pub fn sized_type(&self) -> Option<(&str, usize)> {
+        self.type_info.as_deref().and_then(parse_sized_type)
+    }
+
+    /// Parameters of a procedure or trigger, in declaration order.
+    pub fn parameters(&self) -> impl Iterator<Item = &AlSymbol> {
+        self.children
+            .iter()
+            .filter(|child| matches!(child.kind, AlSymbolKind::Parameter))
+    }
+}
+
+/// `procedure Name(<params>)[: ReturnType]` of a procedure declaration or
+/// interface method, on a single line with runs of whitespace collapsed.
+pub fn procedure_signature_text(declaration: Node, source: &str) -> Option<String> {
+    let name = declaration.child_by_field_name("name")?;
+    let end = match declaration.child_by_field_name("return_type") {
+        Some(return_type) => return_type.end_byte(),
+        None => {
+            let mut cursor = declaration.walk();
+            let close_paren = declaration
+                .children(&mut cursor)
+                .find(|child| child.kind() == ")")?;
+            close_paren.end_byte()
+        }
+    };
+    let text = source[name.start_byte()..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(format!("procedure {text}"))
+}
+
 /// Extract the text of a node from the source.
 pub fn node_text<'a>(node: Node<'a>, source: &'a str) -> &'a str {
     node.utf8_text(source.as_bytes()).unwrap_or("")
@@ -145,11 +214,107 @@ fn find_object_name(
     None
 }
 
+/// Name of the object an extension declaration extends: the name after
+/// `extends`, i.e. the second identifier of the declaration.
+fn find_extended_object_name(node: Node, source: &str, kind: AlObjectKind) -> Option<String> {
+    kind.extended_kind()?;
+    let mut cursor = node.walk();
+    let base = node
+        .named_children(&mut cursor)
+        .filter(|child| matches!(child.kind(), "identifier" | "quoted_identifier"))
+        .nth(1)?;
+    Some(extract_name(base, source))
+}
+
+/// Find the object ID: the `integer_literal` preceding the object's name.
+fn find_object_id(node: Node, source: &str) -> Option<i64> {
+    let mut cursor = node.walk();
+    let id = node
+        .named_children(&mut cursor)
+        .take_while(|child| !matches!(child.kind(), "identifier" | "quoted_identifier"))
+        .find(|child| child.kind() == "integer_literal")?;
+    node_text(id, source).parse().ok()
+}
+
 /// Extract type information from a type reference node.
 fn extract_type_info(node: Node, source: &str) -> String {
     node_text(node, source).to_string()
 }
 
+/// Split a sized type such as `Code[20]` or `Text [100]` into its base type
+/// and length. Returns `None` for unsized types, arrays and generics.
+pub fn parse_sized_type(type_info: &str) -> Option<(&str, usize)> {
+    let (base, rest) = type_info.split_once('[')?;
+    let base = base.trim();
+    if base.is_empty() || base.contains(char::is_whitespace) {
+        return None;
+    }
+    let length = rest.trim_end().strip_suffix(']')?.trim().parse().ok()?;
+    Some((base, length))
+}
+
+/// The namespace a document declares and the namespaces it imports with
+/// `using`, as written (dotted, without surrounding whitespace).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceInfo {
+    pub namespace: Option<String>,
+    pub usings: Vec<String>,
+}
+
+impl NamespaceInfo {
+    /// Namespaces an object reference may resolve into: the explicit
+    /// `qualifier` when given, otherwise the document's own namespace and its
+    /// `using` imports.
+    pub fn candidates(&self, qualifier: Option<&str>) -> Vec<String> {
+        match qualifier {
+            Some(qualifier) => vec![qualifier.to_string()],
+            None => self.namespace.iter().chain(&self.usings).cloned().collect(),
+        }
+    }
+}
+
+/// Collect the `namespace` declaration and `using` directives of a document.
+pub fn extract_namespace_info(tree: &Tree, source: &str) -> NamespaceInfo {
+    let root = tree.root_node();
+    let mut info = NamespaceInfo::default();
+    let mut cursor = root.walk();
+    for child in root.named_children(&mut cursor) {
+        let Some(name) = child.child_by_field_name("name") else {
+            continue;
+        };
+        let name = node_text(name, source)
+            .split_whitespace()
+            .collect::<String>();
+        match child.kind() {
+            "namespace_declaration" => info.namespace = Some(name),
+            "using_directive" => info.usings.push(name),
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Split a possibly namespace-qualified object name into its namespace and
+/// final segment: `MyCompany.Sales."Sales Header"` ->
+/// `(Some("MyCompany.Sales"), "\"Sales Header\"")`. Dots inside quoted
+/// segments are not separators.
+pub fn split_namespace_qualifier(name: &str) -> (Option<&str>, &str) {
+    let name = name.trim();
+    let mut in_quotes = false;
+    let mut last_dot = None;
+    for (idx, ch) in name.char_indices() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '.' if !in_quotes => last_dot = Some(idx),
+            _ => {}
+        }
+    }
+    match last_dot {
+        Some(idx) => (Some(name[..idx].trim()), name[idx + 1..].trim()),
+        None => (None, name),
+    }
+}
+
 /// Walk the tree and extract all symbols, building a nested structure.
 pub fn extract_symbols(tree: &Tree, source: &str) -> Vec<AlSymbol> {
     let root = tree.root_node();
@@ -168,6 +333,13 @@ pub fn extract_symbols(tree: &Tree, source: &str) -> Vec<AlSymbol> {
     symbols
 }
 
+/// The symbol of a single top-level object node, as `extract_symbols` would
+/// produce it. `None` for nodes that are not named object declarations.
+pub fn extract_object_symbol_at(node: Node, source: &str) -> Option<AlSymbol> {
+    let kind = AlObjectKind::from_node_kind(node.kind())?;
+    extract_object_symbol(node, source, kind)
+}
+
 fn extract_object_symbol(node: Node, source: &str, kind: AlObjectKind) -> Option<AlSymbol> {
     let (name, name_start, name_end) = find_object_name(node, source)?;
     let mut children = Vec::new();
@@ -182,6 +354,8 @@ fn extract_object_symbol(node: Node, source: &str, kind: AlObjectKind) -> Option
         kind: AlSymbolKind::Object(kind),
         type_info: Some(kind.label().to_string()),
         implements,
+        object_id: find_object_id(node, source),
+        extends: find_extended_object_name(node, source, kind),
         start_byte: node.start_byte(),
         end_byte: node.end_byte(),
         start_point: node.start_position(),
@@ -249,6 +423,46 @@ fn extract_children_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbol
             | "usercontrol_section" => {
                 extract_nested_page_symbols(child, source, symbols);
             }
+            "requestpage_section" => {
+                extract_children_symbols(child, source, symbols);
+                extract_request_page_field_symbols(child, source, symbols);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Fields in a report request page layout, as `Field` symbols whose type is
+/// the source expression and whose children are the field's triggers.
+fn extract_request_page_field_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbol>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "layout_section" | "area_section" | "group_section" | "repeater_section" => {
+                extract_request_page_field_symbols(child, source, symbols);
+            }
+            "page_field" => {
+                let Some(name_node) = child.child_by_field_name("name") else {
+                    continue;
+                };
+                symbols.push(AlSymbol {
+                    name: extract_name(name_node, source),
+                    kind: AlSymbolKind::Field,
+                    type_info: child
+                        .child_by_field_name("expression")
+                        .map(|expr| node_text(expr, source).to_string()),
+                    implements: Vec::new(),
+                    object_id: None,
+                    extends: None,
+                    start_byte: child.start_byte(),
+                    end_byte: child.end_byte(),
+                    start_point: child.start_position(),
+                    end_point: child.end_position(),
+                    name_start_point: name_node.start_position(),
+                    name_end_point: name_node.end_position(),
+                    children: extract_control_member_symbols(child, source),
+                });
+            }
             _ => {}
         }
     }
@@ -302,6 +516,8 @@ fn extract_procedure_symbol(node: Node, source: &str) -> Option<AlSymbol> {
                     kind: AlSymbolKind::Variable,
                     type_info: rt_type,
                     implements: Vec::new(),
+                    object_id: None,
+                    extends: None,
                     start_byte: rt.start_byte(),
                     end_byte: rt.end_byte(),
                     start_point: rt.start_position(),
@@ -335,6 +551,8 @@ fn extract_procedure_symbol(node: Node, source: &str) -> Option<AlSymbol> {
         kind: AlSymbolKind::Procedure,
         type_info,
         implements: Vec::new(),
+        object_id: None,
+        extends: None,
         start_byte: node.start_byte(),
         end_byte: node.end_byte(),
         start_point: node.start_position(),
@@ -372,6 +590,8 @@ fn extract_trigger_symbol(node: Node, source: &str) -> Option<AlSymbol> {
                 kind: AlSymbolKind::Variable,
                 type_info: rt_type,
                 implements: Vec::new(),
+                object_id: None,
+                extends: None,
                 start_byte: rt.start_byte(),
                 end_byte: rt.end_byte(),
                 start_point: rt.start_position(),
@@ -400,6 +620,8 @@ fn extract_trigger_symbol(node: Node, source: &str) -> Option<AlSymbol> {
         kind: AlSymbolKind::Trigger,
         type_info,
         implements: Vec::new(),
+        object_id: None,
+        extends: None,
         start_byte: node.start_byte(),
         end_byte: node.end_byte(),
         start_point: node.start_position(),
@@ -424,6 +646,8 @@ fn extract_event_symbol(node: Node, source: &str) -> Option<AlSymbol> {
         kind: AlSymbolKind::Trigger,
         type_info: None,
         implements: Vec::new(),
+        object_id: None,
+        extends: None,
         start_byte: node.start_byte(),
         end_byte: node.end_byte(),
         start_point: node.start_position(),
@@ -440,6 +664,25 @@ fn extract_usercontrol_symbol(node: Node, source: &str) -> Option<AlSymbol> {
     let name = extract_name(name_node, source);
     let addin_name = extract_name(addin_node, source);
 
+    Some(AlSymbol {
+        name,
+        kind: AlSymbolKind::Field,
+        type_info: Some(format!("ControlAddIn {}", addin_name)),
+        implements: Vec::new(),
+        object_id: None,
+        extends: None,
+        start_byte: node.start_byte(),
+        end_byte: node.end_byte(),
+        start_point: node.start_position(),
+        end_point: node.end_position(),
+        name_start_point: name_node.start_position(),
+        name_end_point: name_node.end_position(),
+        children: extract_control_member_symbols(node, source),
+    })
+}
+
+/// Triggers, procedures and variables declared inside a page control.
+fn extract_control_member_symbols(node: Node, source: &str) -> Vec<AlSymbol> {
     let mut children = Vec::new();
     let mut cursor = node.walk();
     for child in node.named_children(&mut cursor) {
@@ -458,20 +701,7 @@ fn extract_usercontrol_symbol(node: Node, source: &str) -> Option<AlSymbol> {
             _ => {}
         }
     }
-
-    Some(AlSymbol {
-        name,
-        kind: AlSymbolKind::Field,
-        type_info: Some(format!("ControlAddIn {}", addin_name)),
-        implements: Vec::new(),
-        start_byte: node.start_byte(),
-        end_byte: node.end_byte(),
-        start_point: node.start_position(),
-        end_point: node.end_position(),
-        name_start_point: name_node.start_position(),
-        name_end_point: name_node.end_position(),
-        children,
-    })
+    children
 }
 
 fn extract_var_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbol>) {
@@ -492,6 +722,8 @@ fn extract_var_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbol>) {
                     kind: AlSymbolKind::Variable,
                     type_info: type_info.clone(),
                     implements: Vec::new(),
+                    object_id: None,
+                    extends: None,
                     start_byte: child.start_byte(),
                     end_byte: child.end_byte(),
                     start_point: child.start_position(),
@@ -520,6 +752,8 @@ fn extract_parameter_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbo
                     kind: AlSymbolKind::Parameter,
                     type_info,
                     implements: Vec::new(),
+                    object_id: None,
+                    extends: None,
                     start_byte: child.start_byte(),
                     end_byte: child.end_byte(),
                     start_point: child.start_position(),
@@ -548,6 +782,8 @@ fn extract_field_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbol>)
                     kind: AlSymbolKind::Field,
                     type_info,
                     implements: Vec::new(),
+                    object_id: None,
+                    extends: None,
                     start_byte: child.start_byte(),
                     end_byte: child.end_byte(),
                     start_point: child.start_position(),
@@ -573,6 +809,8 @@ fn extract_key_symbols(node: Node, source: &str, symbols: &mut Vec<AlSymbol>) {
                     kind: AlSymbolKind::Key,
                     type_info: None,
                     implements: Vec::new(),
+                    object_id: None,
+                    extends: None,
                     start_byte: child.start_byte(),
                     end_byte: child.end_byte(),
                     start_point: child.start_position(),
@@ -595,6 +833,8 @@ fn extract_enum_value_symbol(node: Node, source: &str) -> Option<AlSymbol> {
         kind: AlSymbolKind::EnumValue,
         type_info: None,
         implements: Vec::new(),
+        object_id: None,
+        extends: None,
         start_byte: node.start_byte(),
         end_byte: node.end_byte(),
         start_point: node.start_position(),
@@ -609,6 +849,54 @@ fn extract_enum_value_symbol(node: Node, source: &str) -> Option<AlSymbol> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_sized_type() {
+        assert_eq!(parse_sized_type("Code[20]"), Some(("Code", 20)));
+        assert_eq!(parse_sized_type("Text [100]"), Some(("Text", 100)));
+        assert_eq!(parse_sized_type("Integer"), None);
+        assert_eq!(parse_sized_type("List of [Text]"), None);
+        assert_eq!(parse_sized_type("array [10] of Integer"), None);
+    }
+
+    #[test]
+    fn test_split_namespace_qualifier() {
+        assert_eq!(
+            split_namespace_qualifier("MyCompany.Sales.Customer"),
+            (Some("MyCompany.Sales"), "Customer")
+        );
+        assert_eq!(
+            split_namespace_qualifier("MyCompany.\"Sales Header\""),
+            (Some("MyCompany"), "\"Sales Header\"")
+        );
+        assert_eq!(
+            split_namespace_qualifier("\"Sales Mgt.\""),
+            (None, "\"Sales Mgt.\"")
+        );
+        assert_eq!(split_namespace_qualifier("Customer"), (None, "Customer"));
+    }
+
+    #[test]
+    fn test_extract_namespace_info() {
+        let source = r#"namespace MyCompany.Sales;
+
+using MyCompany.Common;
+using System.Utilities;
+
+codeunit 50100 Test
+{
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let info = extract_namespace_info(&tree, source);
+        assert_eq!(info.namespace.as_deref(), Some("MyCompany.Sales"));
+        assert_eq!(info.usings, vec!["MyCompany.Common", "System.Utilities"]);
+        assert_eq!(extract_symbols(&tree, source).len(), 1);
+        assert_eq!(
+            info.candidates(None),
+            vec!["MyCompany.Sales", "MyCompany.Common", "System.Utilities"]
+        );
+        assert_eq!(info.candidates(Some("Other")), vec!["Other"]);
+    }
+
     #[test]
     fn test_extract_codeunit_symbols() {
         let source = r#"codeunit 50100 "My Codeunit"
@@ -699,6 +987,117 @@ mod tests {
         assert!(matches!(table.children[2].kind, AlSymbolKind::Key));
     }
 
+    #[test]
+    fn test_symbol_name_points_cover_the_identifier() {
+        let source = r#"table 50100 "Customer Ledger"
+{
+    fields
+    {
+        field(1; "Entry No."; Integer)
+        {
+        }
+    }
+
+    keys
+    {
+        key(PK; "Entry No.")
+        {
+        }
+    }
+
+    trigger OnInsert()
+    begin
+    end;
+
+    procedure Post(Amount: Decimal)
+    var
+        Total: Decimal;
+    begin
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+        let lines: Vec<&str> = source.lines().collect();
+        let name_text = |symbol: &AlSymbol| {
+            assert_eq!(
+                symbol.name_start_point.row, symbol.name_end_point.row,
+                "{}",
+                symbol.name
+            );
+            &lines[symbol.name_start_point.row]
+                [symbol.name_start_point.column..symbol.name_end_point.column]
+        };
+
+        let table = &symbols[0];
+        assert_eq!(name_text(table), "\"Customer Ledger\"");
+        assert_eq!(
+            (table.name_start_point.row, table.name_start_point.column),
+            (0, 12)
+        );
+
+        let mut checked = 0;
+        let mut pending: Vec<&AlSymbol> = table.children.iter().collect();
+        while let Some(symbol) = pending.pop() {
+            assert_eq!(
+                name_text(symbol).trim_matches('"'),
+                symbol.name,
+                "{:?}",
+                symbol.kind
+            );
+            checked += 1;
+            pending.extend(&symbol.children);
+        }
+        // field, key, trigger, procedure, parameter, local variable
+        assert_eq!(checked, 6);
+
+        let procedure = table
+            .children
+            .iter()
+            .find(|child| child.name == "Post")
+            .unwrap();
+        assert_eq!(
+            (
+                procedure.name_start_point.row,
+                procedure.name_start_point.column
+            ),
+            (20, 14)
+        );
+        let field = &table.children[0];
+        assert_eq!(
+            (field.name_start_point.row, field.name_start_point.column),
+            (4, 17)
+        );
+    }
+
+    #[test]
+    fn test_extract_extension_base_object() {
+        let source = r#"tableextension 50100 "Customer Ext" extends Customer
+{
+}
+
+pageextension 50101 CustomerCardExt extends "Customer Card"
+{
+}
+
+codeunit 50102 Helper
+{
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+        let extends: Vec<_> = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.extends.as_deref()))
+            .collect();
+        assert_eq!(
+            extends,
+            vec![
+                ("Customer Ext", Some("Customer")),
+                ("CustomerCardExt", Some("Customer Card")),
+                ("Helper", None),
+            ]
+        );
+    }
+
     #[test]
     fn test_extract_named_return_value_as_local_variable() {
         let source = r#"codeunit 50100 Test
@@ -989,6 +1388,27 @@ page 50100 "Dummy Card"
         assert_eq!(global.type_info.as_deref(), Some("Option ,Start,Stop"));
     }
 
+    #[test]
+    fn test_extract_global_temporary_record_keeps_full_type() {
+        let source = r#"codeunit 50100 Dummy
+{
+    var
+        MyTempCust: Record Customer temporary;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+
+        let global = symbols[0]
+            .children
+            .iter()
+            .find(|c| matches!(c.kind, AlSymbolKind::Variable) && c.name == "MyTempCust")
+            .expect("global temporary record variable");
+        assert_eq!(
+            global.type_info.as_deref(),
+            Some("Record Customer temporary")
+        );
+    }
+
     #[test]
     fn test_extract_controladdin_and_usercontrol_symbols() {
         let source = r#"controladdin "Dummy AddIn"
@@ -1054,4 +1474,57 @@ page 50100 "Dummy Host"
             "expected usercontrol trigger symbol"
         );
     }
+
+    #[test]
+    fn test_extract_report_request_page_symbols() {
+        let source = r#"report 50100 "Customer List"
+{
+    requestpage
+    {
+        layout
+        {
+            area(Content)
+            {
+                field(StartDate; StartDate)
+                {
+                    ApplicationArea = All;
+                }
+            }
+        }
+
+        trigger OnOpenPage()
+        begin
+            StartDate := Today;
+        end;
+    }
+
+    var
+        StartDate: Date;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+
+        assert_eq!(symbols.len(), 1);
+        let report = &symbols[0];
+        let field = report
+            .children
+            .iter()
+            .find(|c| matches!(c.kind, AlSymbolKind::Field) && c.name == "StartDate")
+            .expect("request page field symbol");
+        assert_eq!(field.type_info.as_deref(), Some("StartDate"));
+        assert!(
+            report
+                .children
+                .iter()
+                .any(|c| matches!(c.kind, AlSymbolKind::Trigger) && c.name == "OnOpenPage"),
+            "expected request page trigger symbol"
+        );
+        assert!(
+            report
+                .children
+                .iter()
+                .any(|c| matches!(c.kind, AlSymbolKind::Variable) && c.name == "StartDate"),
+            "expected report global variable"
+        );
+    }
 }
@@ -20,6 +20,23 @@ pub struct BuiltinPropertyDoc {
     pub literal_values: &'static [&'static str],
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinTriggerDoc {
+    pub name: &'static str,
+    /// Declaration after the `trigger` keyword, e.g. `OnNewRecord(BelowxRec: Boolean)`.
+    pub signature: &'static str,
+    pub summary: &'static str,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinAttributeDoc {
+    pub name: &'static str,
+    pub signature: &'static str,
+    pub summary: &'static str,
+    pub docs_url: &'static str,
+    pub params: &'static [BuiltinMethodParam],
+}
+
 const RECORD_DOCS: &str =
     "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/methods-auto/record/record-data-type";
 const CODEUNIT_DOCS: &str =
@@ -1503,6 +1520,116 @@ const TEXT_METHODS: &[BuiltinMethodDoc] = &[
             },
         ],
     },
+    BuiltinMethodDoc {
+        name: "CopyStr",
+        signature: "CopyStr(String: Text, Position: Integer [, Length: Integer]): Text",
+        summary: "Copies a substring of any length from a specific position.",
+        docs_url: TEXT_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Source text.",
+            },
+            BuiltinMethodParam {
+                label: "Position: Integer",
+                documentation: "1-based start position.",
+            },
+            BuiltinMethodParam {
+                label: "Length: Integer",
+                documentation: "Optional number of characters.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "DelChr",
+        signature: "DelChr(String: Text [, Where: Text] [, Which: Text]): Text",
+        summary: "Deletes characters from a string.",
+        docs_url: TEXT_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Source text.",
+            },
+            BuiltinMethodParam {
+                label: "Where: Text",
+                documentation: "Optional '<', '>' or '=' positions.",
+            },
+            BuiltinMethodParam {
+                label: "Which: Text",
+                documentation: "Optional characters to delete.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "DelStr",
+        signature: "DelStr(String: Text, Position: Integer [, Length: Integer]): Text",
+        summary: "Deletes a substring from a string.",
+        docs_url: TEXT_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Source text.",
+            },
+            BuiltinMethodParam {
+                label: "Position: Integer",
+                documentation: "1-based start position.",
+            },
+            BuiltinMethodParam {
+                label: "Length: Integer",
+                documentation: "Optional number of characters.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "PadStr",
+        signature: "PadStr(String: Text, Length: Integer [, FillCharacter: Text]): Text",
+        summary: "Pads or truncates a string to a length.",
+        docs_url: TEXT_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Source text.",
+            },
+            BuiltinMethodParam {
+                label: "Length: Integer",
+                documentation: "Resulting length.",
+            },
+            BuiltinMethodParam {
+                label: "FillCharacter: Text",
+                documentation: "Optional fill character.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "StrCheckSum",
+        signature: "StrCheckSum(String: Text [, WeightString: Text] [, Modulus: Integer]): Integer",
+        summary: "Calculates a checksum for a string of digits.",
+        docs_url: TEXT_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Digits to check.",
+            },
+            BuiltinMethodParam {
+                label: "WeightString: Text",
+                documentation: "Optional digit weights.",
+            },
+            BuiltinMethodParam {
+                label: "Modulus: Integer",
+                documentation: "Optional modulus.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "StrSubstNo",
+        signature: "StrSubstNo(String: Text [, Value1: Any, ...]): Text",
+        summary: "Replaces %1, %2, ... placeholders with values.",
+        docs_url: TEXT_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "String: Text",
+            documentation: "Text with placeholders.",
+        }],
+    },
 ];
 
 const BIGTEXT_METHODS: &[BuiltinMethodDoc] = &[
@@ -3360,6 +3487,53 @@ const DIALOG_METHODS: &[BuiltinMethodDoc] = &[
         docs_url: DIALOG_DOCS,
         params: PARAM_NONE,
     },
+    BuiltinMethodDoc {
+        name: "Message",
+        signature: "Message(String: Text [, Value1: Any, ...])",
+        summary: "Displays a message in a dialog box.",
+        docs_url: DIALOG_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "String: Text",
+            documentation: "Message text with placeholders.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "Confirm",
+        signature: "Confirm(String: Text [, Default: Boolean] [, Value1: Any, ...]): Boolean",
+        summary: "Asks the user a yes/no question.",
+        docs_url: DIALOG_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Question text with placeholders.",
+            },
+            BuiltinMethodParam {
+                label: "Default: Boolean",
+                documentation: "Optional default answer.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "StrMenu",
+        signature:
+            "StrMenu(OptionMembers: Text [, DefaultNumber: Integer] [, Instruction: Text]): Integer",
+        summary: "Lets the user pick one of a comma-separated list of options.",
+        docs_url: DIALOG_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "OptionMembers: Text",
+                documentation: "Comma-separated options.",
+            },
+            BuiltinMethodParam {
+                label: "DefaultNumber: Integer",
+                documentation: "Optional preselected option.",
+            },
+            BuiltinMethodParam {
+                label: "Instruction: Text",
+                documentation: "Optional instruction text.",
+            },
+        ],
+    },
 ];
 
 const SESSION_METHODS: &[BuiltinMethodDoc] = &[
@@ -3410,6 +3584,36 @@ const SESSION_METHODS: &[BuiltinMethodDoc] = &[
         docs_url: SESSION_DOCS,
         params: PARAM_NONE,
     },
+    BuiltinMethodDoc {
+        name: "GetModuleExecutionContext",
+        signature: "GetModuleExecutionContext(AppId: Guid): ExecutionContext",
+        summary: "Returns the execution context of an app.",
+        docs_url: SESSION_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "AppId: Guid",
+            documentation: "App identifier.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "LogMessage",
+        signature: "LogMessage(EventId: Text, Message: Text, Verbosity: Verbosity, DataClassification: DataClassification, TelemetryScope: TelemetryScope, Dimension1: Text, Value1: Text [, ...])",
+        summary: "Emits a telemetry trace message.",
+        docs_url: SESSION_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "EventId: Text",
+                documentation: "Event identifier.",
+            },
+            BuiltinMethodParam {
+                label: "Message: Text",
+                documentation: "Message text.",
+            },
+            BuiltinMethodParam {
+                label: "Verbosity: Verbosity",
+                documentation: "Severity level.",
+            },
+        ],
+    },
 ];
 
 const MODULE_INFO_METHODS: &[BuiltinMethodDoc] = &[
@@ -3524,6 +3728,26 @@ const DATABASE_METHODS: &[BuiltinMethodDoc] = &[
         docs_url: DATABASE_DOCS,
         params: PARAM_NONE,
     },
+    BuiltinMethodDoc {
+        name: "CurrentTransactionType",
+        signature: "CurrentTransactionType([TransactionType: TransactionType]): TransactionType",
+        summary: "Gets or sets the current transaction type.",
+        docs_url: DATABASE_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "TransactionType: TransactionType",
+            documentation: "Optional new transaction type.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "LockTimeout",
+        signature: "LockTimeout([Wait: Boolean]): Boolean",
+        summary: "Gets or sets whether to time out waiting for locks.",
+        docs_url: DATABASE_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "Wait: Boolean",
+            documentation: "Optional; whether to time out.",
+        }],
+    },
 ];
 
 const SYSTEM_METHODS: &[BuiltinMethodDoc] = &[
@@ -3590,6 +3814,220 @@ const SYSTEM_METHODS: &[BuiltinMethodDoc] = &[
         docs_url: SYSTEM_DOCS,
         params: PARAM_NONE,
     },
+    BuiltinMethodDoc {
+        name: "ArrayLen",
+        signature: "ArrayLen(Array: Array [, Dimension: Integer]): Integer",
+        summary: "Returns the number of elements in an array dimension.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "Array: Array",
+                documentation: "Input array.",
+            },
+            BuiltinMethodParam {
+                label: "Dimension: Integer",
+                documentation: "Optional dimension.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "CompressArray",
+        signature: "CompressArray(Array: Array of [Text]): Integer",
+        summary: "Moves non-empty strings to the front of an array.",
+        docs_url: SYSTEM_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "Array: Array of [Text]",
+            documentation: "Array to compress.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "CopyArray",
+        signature: "CopyArray(var NewArray: Array, Array: Array, Position: Integer [, Length: Integer])",
+        summary: "Copies elements of one array into another.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "var NewArray: Array",
+                documentation: "Destination array.",
+            },
+            BuiltinMethodParam {
+                label: "Array: Array",
+                documentation: "Source array.",
+            },
+            BuiltinMethodParam {
+                label: "Position: Integer",
+                documentation: "1-based start position.",
+            },
+            BuiltinMethodParam {
+                label: "Length: Integer",
+                documentation: "Optional number of elements.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "CopyStream",
+        signature: "CopyStream(OutStream: OutStream, InStream: InStream [, BytesToRead: Integer]): Boolean",
+        summary: "Copies an InStream into an OutStream.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "OutStream: OutStream",
+                documentation: "Destination stream.",
+            },
+            BuiltinMethodParam {
+                label: "InStream: InStream",
+                documentation: "Source stream.",
+            },
+            BuiltinMethodParam {
+                label: "BytesToRead: Integer",
+                documentation: "Optional number of bytes.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "DMY2Date",
+        signature: "DMY2Date(Day: Integer [, Month: Integer] [, Year: Integer]): Date",
+        summary: "Builds a date from day, month and year.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "Day: Integer",
+                documentation: "Day of the month.",
+            },
+            BuiltinMethodParam {
+                label: "Month: Integer",
+                documentation: "Optional month.",
+            },
+            BuiltinMethodParam {
+                label: "Year: Integer",
+                documentation: "Optional year.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "DWY2Date",
+        signature: "DWY2Date(WeekDay: Integer [, Week: Integer] [, Year: Integer]): Date",
+        summary: "Builds a date from weekday, week and year.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "WeekDay: Integer",
+                documentation: "Day of the week.",
+            },
+            BuiltinMethodParam {
+                label: "Week: Integer",
+                documentation: "Optional week number.",
+            },
+            BuiltinMethodParam {
+                label: "Year: Integer",
+                documentation: "Optional year.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "Evaluate",
+        signature: "Evaluate(var Variable: Any, String: Text [, Number: Integer]): Boolean",
+        summary: "Parses text into a variable of any type.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "var Variable: Any",
+                documentation: "Target variable.",
+            },
+            BuiltinMethodParam {
+                label: "String: Text",
+                documentation: "Text to parse.",
+            },
+            BuiltinMethodParam {
+                label: "Number: Integer",
+                documentation: "Optional format number.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "GetCollectedErrors",
+        signature: "GetCollectedErrors([Clear: Boolean]): List of [ErrorInfo]",
+        summary: "Returns the errors collected in the current transaction.",
+        docs_url: SYSTEM_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "Clear: Boolean",
+            documentation: "Optional; clears the collected errors.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "GetUrl",
+        signature: "GetUrl(ClientType: ClientType [, Company: Text] [, ObjectType: ObjectType] [, ObjectId: Integer] [, Record: Record] [, UseFilters: Boolean]): Text",
+        summary: "Builds a URL for a client type, company and object.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "ClientType: ClientType",
+                documentation: "Client to target.",
+            },
+            BuiltinMethodParam {
+                label: "Company: Text",
+                documentation: "Optional company.",
+            },
+            BuiltinMethodParam {
+                label: "ObjectType: ObjectType",
+                documentation: "Optional object type.",
+            },
+            BuiltinMethodParam {
+                label: "ObjectId: Integer",
+                documentation: "Optional object ID.",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "GlobalLanguage",
+        signature: "GlobalLanguage([LanguageId: Integer]): Integer",
+        summary: "Gets or sets the current global language.",
+        docs_url: SYSTEM_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "LanguageId: Integer",
+            documentation: "Optional language to switch to.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "Randomize",
+        signature: "Randomize([Seed: Integer])",
+        summary: "Seeds the random number generator.",
+        docs_url: SYSTEM_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "Seed: Integer",
+            documentation: "Optional seed.",
+        }],
+    },
+    BuiltinMethodDoc {
+        name: "RoundDateTime",
+        signature: "RoundDateTime(DateTime: DateTime [, Precision: BigInteger] [, Direction: Text]): DateTime",
+        summary: "Rounds a DateTime to a precision.",
+        docs_url: SYSTEM_DOCS,
+        params: &[
+            BuiltinMethodParam {
+                label: "DateTime: DateTime",
+                documentation: "Input value.",
+            },
+            BuiltinMethodParam {
+                label: "Precision: BigInteger",
+                documentation: "Optional precision in milliseconds.",
+            },
+            BuiltinMethodParam {
+                label: "Direction: Text",
+                documentation: "Optional direction ('='/'>'/'<').",
+            },
+        ],
+    },
+    BuiltinMethodDoc {
+        name: "WorkDate",
+        signature: "WorkDate([Date: Date]): Date",
+        summary: "Gets or sets the work date.",
+        docs_url: SYSTEM_DOCS,
+        params: &[BuiltinMethodParam {
+            label: "Date: Date",
+            documentation: "Optional new work date.",
+        }],
+    },
 ];
 
 const SESSION_INFORMATION_METHODS: &[BuiltinMethodDoc] = &[
@@ -7339,6 +7777,343 @@ const INTERFACE_PROPERTIES: &[BuiltinPropertyDoc] = &[
 
 const EMPTY_PROPERTIES: &[BuiltinPropertyDoc] = &[];
 
+const PROCEDURE_ATTRIBUTES: &[BuiltinAttributeDoc] = &[
+    BuiltinAttributeDoc {
+        name: "BusinessEvent",
+        signature: "[BusinessEvent(IncludeSender: Boolean, Isolated: Boolean)]",
+        summary: "Publishes a business event with a stable, documented contract for partners to subscribe to.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-businessevent-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "IncludeSender: Boolean",
+                documentation: "Exposes the publishing object as a `sender` parameter to subscribers.",
+            },
+            BuiltinMethodParam {
+                label: "Isolated: Boolean",
+                documentation: "Runs each subscriber in its own transaction (optional).",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "ConfirmHandler",
+        signature: "[ConfirmHandler]",
+        summary: "Marks a test handler method that handles `Confirm` dialogs raised by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "EventSubscriber",
+        signature: "[EventSubscriber(ObjectType: ObjectType, ObjectId: Integer, EventName: Text, ElementName: Text, SkipOnMissingLicense: Boolean, SkipOnMissingPermission: Boolean)]",
+        summary: "Subscribes the method to an event published by another object.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-eventsubscriber-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "ObjectType: ObjectType",
+                documentation: "Type of the object that publishes the event.",
+            },
+            BuiltinMethodParam {
+                label: "ObjectId: Integer",
+                documentation: "The publishing object, usually written as `ObjectType::Name`.",
+            },
+            BuiltinMethodParam {
+                label: "EventName: Text",
+                documentation: "Name of the event to subscribe to.",
+            },
+            BuiltinMethodParam {
+                label: "ElementName: Text",
+                documentation: "Table field or page control for trigger events; empty otherwise.",
+            },
+            BuiltinMethodParam {
+                label: "SkipOnMissingLicense: Boolean",
+                documentation: "Skips the subscriber when the user lacks a license for the subscriber's object.",
+            },
+            BuiltinMethodParam {
+                label: "SkipOnMissingPermission: Boolean",
+                documentation: "Skips the subscriber when the user lacks permission for the subscriber's object.",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "HandlerFunctions",
+        signature: "[HandlerFunctions(Handlers: Text)]",
+        summary: "Lists the handler methods a test method uses to handle UI raised by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-handlerfunctions-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "Handlers: Text",
+                documentation: "Comma-separated names of the handler methods.",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "InherentPermissions",
+        signature: "[InherentPermissions(PermissionObjectType: PermissionObjectType, ObjectId: Integer, Permissions: Text, InherentPermissionsScope: InherentPermissionsScope)]",
+        summary: "Grants permissions to the method for the duration of its execution.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-inherentpermissions-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "PermissionObjectType: PermissionObjectType",
+                documentation: "Type of the object the permissions apply to.",
+            },
+            BuiltinMethodParam {
+                label: "ObjectId: Integer",
+                documentation: "The object the permissions apply to.",
+            },
+            BuiltinMethodParam {
+                label: "Permissions: Text",
+                documentation: "Permission letters such as `RIMD`.",
+            },
+            BuiltinMethodParam {
+                label: "InherentPermissionsScope: InherentPermissionsScope",
+                documentation: "Whether the permissions apply to permissions, entitlements or both (optional).",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "IntegrationEvent",
+        signature: "[IntegrationEvent(IncludeSender: Boolean, GlobalVarAccess: Boolean, Isolated: Boolean)]",
+        summary: "Publishes an integration event that other extensions can subscribe to.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-integrationevent-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "IncludeSender: Boolean",
+                documentation: "Exposes the publishing object as a `sender` parameter to subscribers.",
+            },
+            BuiltinMethodParam {
+                label: "GlobalVarAccess: Boolean",
+                documentation: "Gives subscribers access to the publisher's global variables.",
+            },
+            BuiltinMethodParam {
+                label: "Isolated: Boolean",
+                documentation: "Runs each subscriber in its own transaction (optional).",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "InternalEvent",
+        signature: "[InternalEvent(IncludeSender: Boolean, Isolated: Boolean)]",
+        summary: "Publishes an event that can only be subscribed to from within the same module.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-internalevent-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "IncludeSender: Boolean",
+                documentation: "Exposes the publishing object as a `sender` parameter to subscribers.",
+            },
+            BuiltinMethodParam {
+                label: "Isolated: Boolean",
+                documentation: "Runs each subscriber in its own transaction (optional).",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "MessageHandler",
+        signature: "[MessageHandler]",
+        summary: "Marks a test handler method that handles `Message` calls raised by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "ModalPageHandler",
+        signature: "[ModalPageHandler]",
+        summary: "Marks a test handler method that handles pages run modally by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "NonDebuggable",
+        signature: "[NonDebuggable]",
+        summary: "Prevents the method and its variables from being debugged.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-nondebuggable-attribute",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "Obsolete",
+        signature: "[Obsolete(Reason: Text, Tag: Text)]",
+        summary: "Marks the method as obsolete; callers get a compiler warning.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-obsolete-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "Reason: Text",
+                documentation: "Explanation shown to callers of the method.",
+            },
+            BuiltinMethodParam {
+                label: "Tag: Text",
+                documentation: "Version tag identifying when the method became obsolete (optional).",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "PageHandler",
+        signature: "[PageHandler]",
+        summary: "Marks a test handler method that handles pages run non-modally by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "ReportHandler",
+        signature: "[ReportHandler]",
+        summary: "Marks a test handler method that handles reports run by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "RequestPageHandler",
+        signature: "[RequestPageHandler]",
+        summary: "Marks a test handler method that handles request pages opened by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "Scope",
+        signature: "[Scope(Scope: Text)]",
+        summary: "Sets the scope of the method, for example `'OnPrem'` or `'Cloud'`.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-scope-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "Scope: Text",
+                documentation: "Either `'OnPrem'` or `'Cloud'`.",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "SendNotificationHandler",
+        signature: "[SendNotificationHandler]",
+        summary: "Marks a test handler method that handles notifications sent by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "ServiceEnabled",
+        signature: "[ServiceEnabled]",
+        summary: "Exposes the page method as an action on the page's OData web service.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-serviceenabled-attribute",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "StrMenuHandler",
+        signature: "[StrMenuHandler]",
+        summary: "Marks a test handler method that handles `StrMenu` calls raised by the code under test.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/devenv-creating-handler-methods",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "Test",
+        signature: "[Test]",
+        summary: "Marks the method as a test method in a test codeunit.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-test-attribute",
+        params: &[],
+    },
+    BuiltinAttributeDoc {
+        name: "TransactionModel",
+        signature: "[TransactionModel(TransactionModel: TransactionModel)]",
+        summary: "Controls whether a test method commits or rolls back its database transaction.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-transactionmodel-attribute",
+        params: &[
+            BuiltinMethodParam {
+                label: "TransactionModel: TransactionModel",
+                documentation: "`AutoCommit`, `AutoRollback` or `None`.",
+            },
+        ],
+    },
+    BuiltinAttributeDoc {
+        name: "TryFunction",
+        signature: "[TryFunction]",
+        summary: "Catches errors raised by the method and reports them as a `false` return value.",
+        docs_url: "https://learn.microsoft.com/en-us/dynamics365/business-central/dev-itpro/developer/attributes/devenv-tryfunction-attribute",
+        params: &[],
+    },
+];
+
+const CODEUNIT_TRIGGERS: &[BuiltinTriggerDoc] = &[BuiltinTriggerDoc {
+    name: "OnRun",
+    signature: "OnRun()",
+    summary: "Runs when the codeunit is executed with `Codeunit.Run`.",
+}];
+
+const TABLE_TRIGGERS: &[BuiltinTriggerDoc] = &[
+    BuiltinTriggerDoc {
+        name: "OnInsert",
+        signature: "OnInsert()",
+        summary: "Runs when a record is inserted into the table.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnModify",
+        signature: "OnModify()",
+        summary: "Runs when a record in the table is modified.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnDelete",
+        signature: "OnDelete()",
+        summary: "Runs when a record is deleted from the table.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnRename",
+        signature: "OnRename()",
+        summary: "Runs when a primary key field of a record is changed.",
+    },
+];
+
+const PAGE_TRIGGERS: &[BuiltinTriggerDoc] = &[
+    BuiltinTriggerDoc {
+        name: "OnOpenPage",
+        signature: "OnOpenPage()",
+        summary: "Runs when the page is opened, before it is displayed.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnClosePage",
+        signature: "OnClosePage()",
+        summary: "Runs when the page is closed.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnAfterGetRecord",
+        signature: "OnAfterGetRecord()",
+        summary: "Runs after a record is retrieved from the table, before it is displayed.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnAfterGetCurrRecord",
+        signature: "OnAfterGetCurrRecord()",
+        summary: "Runs after the current record is retrieved from the table.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnNewRecord",
+        signature: "OnNewRecord(BelowxRec: Boolean)",
+        summary: "Runs when a new record is initialized on the page.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnInsertRecord",
+        signature: "OnInsertRecord(BelowxRec: Boolean): Boolean",
+        summary: "Runs before a new record is inserted; return `false` to cancel.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnModifyRecord",
+        signature: "OnModifyRecord(): Boolean",
+        summary: "Runs before a record is modified; return `false` to cancel.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnDeleteRecord",
+        signature: "OnDeleteRecord(): Boolean",
+        summary: "Runs before a record is deleted; return `false` to cancel.",
+    },
+];
+
+const REPORT_TRIGGERS: &[BuiltinTriggerDoc] = &[
+    BuiltinTriggerDoc {
+        name: "OnInitReport",
+        signature: "OnInitReport()",
+        summary: "Runs when the report is loaded, before the request page opens.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnPreReport",
+        signature: "OnPreReport()",
+        summary: "Runs before the report's data items are processed.",
+    },
+    BuiltinTriggerDoc {
+        name: "OnPostReport",
+        signature: "OnPostReport()",
+        summary: "Runs after all data items have been processed.",
+    },
+];
+
 fn normalize_object_kind(object_kind: &str) -> &str {
     if object_kind.eq_ignore_ascii_case("page_builtin") {
         "page"
@@ -7490,6 +8265,36 @@ pub fn builtin_method_return_type(object_kind: &str, method_name: &str) -> Optio
     }
 }
 
+/// Implicit variables that AL objects declare without a `var` section.
+const IMPLICIT_VARIABLES: &[&str] = &[
+    "Rec",
+    "xRec",
+    "CurrPage",
+    "CurrReport",
+    "CurrXMLport",
+    "CurrQuery",
+    "CurrFieldNo",
+    "RequestOptionsPage",
+    "this",
+];
+
+/// Object kinds whose methods can be called without a receiver, such as
+/// `Message(...)`, `CopyStr(...)` or `Commit()`.
+const GLOBAL_METHOD_KINDS: &[&str] = &["system", "dialog", "text", "database", "session"];
+
+/// Whether `name` resolves without a declaration: an implicit variable, a
+/// builtin type used as a receiver (`ErrorInfo.Create()`) or a method that
+/// needs no receiver (`EncryptionEnabled()`).
+pub fn is_builtin_identifier(name: &str) -> bool {
+    IMPLICIT_VARIABLES
+        .iter()
+        .any(|variable| variable.eq_ignore_ascii_case(name))
+        || builtin_object_kind_from_name(name).is_some()
+        || GLOBAL_METHOD_KINDS
+            .iter()
+            .any(|kind| is_known_builtin_method(kind, name))
+}
+
 pub fn builtin_object_kind_from_name(object_name: &str) -> Option<&'static str> {
     match object_name.to_ascii_lowercase().as_str() {
         "any" => Some("any"),
@@ -7630,3 +8435,24 @@ pub fn literal_values_for_property(property_name: &str) -> Option<&'static [&'st
     .find(|prop| prop.name.eq_ignore_ascii_case(property_name))
     .map(|prop| prop.literal_values)
 }
+
+/// Object-level triggers that can be declared in an object of `object_kind`.
+pub fn object_triggers(object_kind: &str) -> &'static [BuiltinTriggerDoc] {
+    match object_kind.to_ascii_lowercase().as_str() {
+        "codeunit" => CODEUNIT_TRIGGERS,
+        "table" => TABLE_TRIGGERS,
+        "page" => PAGE_TRIGGERS,
+        "report" => REPORT_TRIGGERS,
+        _ => &[],
+    }
+}
+
+pub fn procedure_attributes() -> &'static [BuiltinAttributeDoc] {
+    PROCEDURE_ATTRIBUTES
+}
+
+pub fn find_procedure_attribute(name: &str) -> Option<&'static BuiltinAttributeDoc> {
+    PROCEDURE_ATTRIBUTES
+        .iter()
+        .find(|attr| attr.name.eq_ignore_ascii_case(name))
+}
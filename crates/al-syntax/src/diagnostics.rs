@@ -1,10 +1,18 @@
-use lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use std::collections::HashSet;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Position, Range};
 use tree_sitter::Tree;
 
+use crate::ast::{extract_name, AlSymbol, AlSymbolKind};
+use crate::builtins::is_builtin_identifier;
+use crate::navigation::find_all_references;
+use crate::symbols::{al_keywords, DocumentSymbolTable};
+
 /// Walk the tree and extract diagnostics from ERROR and MISSING nodes.
 pub fn extract_diagnostics(tree: &Tree, source: &str) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
     walk_for_errors(tree.root_node(), source, &mut diagnostics);
+    check_block_balance(source, &mut diagnostics);
     diagnostics
 }
 
@@ -35,6 +43,12 @@ fn walk_for_errors(node: tree_sitter::Node, source: &str, diagnostics: &mut Vec<
             message: format!("Syntax error: unexpected `{text}`"),
             ..Default::default()
         });
+    } else if node.is_missing() && node.kind() == ";" {
+        // Report at the end of whatever precedes the gap, not the next token
+        let end = node
+            .prev_sibling()
+            .map_or(node.start_position(), |prev| prev.end_position());
+        diagnostics.push(missing_semicolon_diagnostic(end));
     } else if node.is_missing() {
         let start = node.start_position();
         let kind = node.kind();
@@ -57,6 +71,10 @@ fn walk_for_errors(node: tree_sitter::Node, source: &str, diagnostics: &mut Vec<
         });
     }
 
+    if matches!(node.kind(), "block" | "repeat_statement" | "case_statement") {
+        check_statement_separators(node, diagnostics);
+    }
+
     // Recurse into children
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
@@ -64,6 +82,815 @@ fn walk_for_errors(node: tree_sitter::Node, source: &str, diagnostics: &mut Vec<
     }
 }
 
+/// Diagnostic `code` of missing statement separators.
+pub const MISSING_SEMICOLON_CODE: &str = "missing-semicolon";
+
+/// The grammar treats `;` between statements as optional, so `A := 1 B := 2`
+/// parses cleanly. Flag each statement directly followed by another one.
+fn check_statement_separators(node: tree_sitter::Node, diagnostics: &mut Vec<Diagnostic>) {
+    let mut unterminated: Option<tree_sitter::Node> = None;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.is_extra() {
+            continue;
+        }
+        let kind = child.kind();
+        if !(kind.ends_with("_statement") || kind == "block" || kind == "case_branch") {
+            unterminated = None;
+            continue;
+        }
+        if let Some(previous) = unterminated {
+            diagnostics.push(missing_semicolon_diagnostic(previous.end_position()));
+        }
+        // A block swallows its own trailing `;`, also when it is the body of
+        // an `if`, `else`, case branch or loop
+        unterminated = (!ends_with_semicolon(child)).then_some(child);
+    }
+}
+
+/// Whether the last token under `node` is a `;`.
+fn ends_with_semicolon(node: tree_sitter::Node) -> bool {
+    let mut last = node;
+    loop {
+        let mut cursor = last.walk();
+        let Some(child) = last
+            .children(&mut cursor)
+            .filter(|child| !child.is_extra())
+            .last()
+        else {
+            return last.kind() == ";";
+        };
+        last = child;
+    }
+}
+
+/// Diagnostic `code` of unbalanced `begin`/`end` and `repeat`/`until` pairs.
+pub const UNBALANCED_BLOCK_CODE: &str = "unbalanced-block";
+
+/// A keyword that opens a block in code: `begin` and `case` close with `end`,
+/// `repeat` with `until`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockOpener {
+    Begin,
+    Case,
+    Repeat,
+}
+
+impl BlockOpener {
+    fn label(self) -> &'static str {
+        match self {
+            BlockOpener::Begin => "begin",
+            BlockOpener::Case => "case",
+            BlockOpener::Repeat => "repeat",
+        }
+    }
+
+    fn closer(self) -> &'static str {
+        match self {
+            BlockOpener::Begin | BlockOpener::Case => "end",
+            BlockOpener::Repeat => "until",
+        }
+    }
+}
+
+/// Tree-sitter recovers from a stray `end;` or a missing `until` by silently
+/// reshaping the rest of the procedure. Track block nesting over the words of
+/// each procedure and trigger body instead, skipping strings, quoted
+/// identifiers and comments, and flag the keyword where the balance goes
+/// negative or the opener of a block still open when the body ends.
+fn check_block_balance(source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let mut open: Vec<(BlockOpener, usize, usize)> = Vec::new();
+    let mut in_code = false;
+
+    for (start, end) in code_words(source) {
+        let word = &source[start..end];
+        let opener = if word.eq_ignore_ascii_case("begin") {
+            Some(BlockOpener::Begin)
+        } else if word.eq_ignore_ascii_case("case") {
+            Some(BlockOpener::Case)
+        } else if word.eq_ignore_ascii_case("repeat") {
+            Some(BlockOpener::Repeat)
+        } else {
+            None
+        };
+
+        if word.eq_ignore_ascii_case("procedure")
+            || word.eq_ignore_ascii_case("trigger")
+            || word == "}"
+        {
+            report_unclosed_blocks(source, open.drain(..), diagnostics);
+            in_code = word != "}";
+        } else if !in_code {
+            continue;
+        } else if let Some(opener) = opener {
+            open.push((opener, start, end));
+        } else if word.eq_ignore_ascii_case("end") {
+            while open
+                .last()
+                .is_some_and(|(opener, ..)| *opener == BlockOpener::Repeat)
+            {
+                let repeat = open.pop();
+                report_unclosed_blocks(source, repeat.into_iter(), diagnostics);
+            }
+            if open.pop().is_none() {
+                diagnostics.push(unbalanced_block_diagnostic(
+                    source,
+                    start,
+                    end,
+                    "`end` without a matching `begin` or `case`".to_string(),
+                ));
+            }
+        } else if word.eq_ignore_ascii_case("until") {
+            match open
+                .iter()
+                .rposition(|(opener, ..)| *opener == BlockOpener::Repeat)
+            {
+                Some(index) => {
+                    let inner = open.split_off(index + 1);
+                    report_unclosed_blocks(source, inner.into_iter(), diagnostics);
+                    open.pop();
+                }
+                None => diagnostics.push(unbalanced_block_diagnostic(
+                    source,
+                    start,
+                    end,
+                    "`until` without a matching `repeat`".to_string(),
+                )),
+            }
+        }
+    }
+    report_unclosed_blocks(source, open.drain(..), diagnostics);
+}
+
+fn report_unclosed_blocks(
+    source: &str,
+    blocks: impl Iterator<Item = (BlockOpener, usize, usize)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (opener, start, end) in blocks {
+        diagnostics.push(unbalanced_block_diagnostic(
+            source,
+            start,
+            end,
+            format!(
+                "`{}` is never closed with `{}`",
+                opener.label(),
+                opener.closer()
+            ),
+        ));
+    }
+}
+
+/// Byte ranges of the words and `}` braces of `source` outside strings,
+/// quoted identifiers and comments.
+fn code_words(source: &str) -> Vec<(usize, usize)> {
+    let bytes = source.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &source[i..];
+        if rest.starts_with("//") {
+            i += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            i += comment.find("*/").map_or(rest.len(), |close| close + 4);
+        } else if bytes[i] == b'\'' || bytes[i] == b'"' {
+            // `''` inside a string is an escaped quote: the scan simply
+            // closes and reopens the literal.
+            let quote = bytes[i] as char;
+            i += rest[1..].find(quote).map_or(rest.len(), |close| close + 2);
+        } else if bytes[i] == b'}' {
+            words.push((i, i + 1));
+            i += 1;
+        } else if bytes[i].is_ascii_alphabetic() || bytes[i] == b'_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            words.push((start, i));
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    words
+}
+
+fn unbalanced_block_diagnostic(
+    source: &str,
+    start: usize,
+    end: usize,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: Range {
+            start: byte_offset_position(source, start),
+            end: byte_offset_position(source, end),
+        },
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(UNBALANCED_BLOCK_CODE.to_string())),
+        source: Some("al-lsp".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Row and byte column of `offset`, matching tree-sitter points.
+fn byte_offset_position(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: (offset - line_start) as u32,
+    }
+}
+
+/// The diagnostic a document carries when the parser produced no tree at all.
+pub fn parse_failure_diagnostic() -> Diagnostic {
+    Diagnostic {
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("al-lsp".to_string()),
+        message: "Failed to parse document".to_string(),
+        ..Default::default()
+    }
+}
+
+fn missing_semicolon_diagnostic(at: tree_sitter::Point) -> Diagnostic {
+    let position = Position {
+        line: at.row as u32,
+        character: at.column as u32,
+    };
+    Diagnostic {
+        range: Range {
+            start: position,
+            end: position,
+        },
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(MISSING_SEMICOLON_CODE.to_string())),
+        source: Some("al-lsp".to_string()),
+        message: "Missing ';' after statement".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Warn about identifiers in procedure and trigger bodies that resolve to no
+/// declaration in the document and are not AL keywords or builtins.
+///
+/// Objects whose code can reach members we cannot see (extensions of another
+/// object, reports, xmlports, queries) and `with` bodies are skipped entirely.
+/// Bare calls inside tables and pages are skipped as well, since they may be
+/// implicit record or page methods such as `TestField` or `Update`, and so are
+/// bare names inside pages and `TableNo` codeunits, which may be fields of a
+/// source table declared in another document.
+pub fn extract_semantic_diagnostics(
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    walk_for_unknown_identifiers(
+        tree.root_node(),
+        source,
+        symbol_table,
+        UnknownIdentifierScope::default(),
+        &mut diagnostics,
+    );
+    diagnostics
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct UnknownIdentifierScope {
+    in_body: bool,
+    implicit_methods: bool,
+    implicit_fields: bool,
+}
+
+fn walk_for_unknown_identifiers(
+    node: tree_sitter::Node,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    mut scope: UnknownIdentifierScope,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match node.kind() {
+        "table_extension_declaration"
+        | "page_extension_declaration"
+        | "report_declaration"
+        | "report_extension_declaration"
+        | "xmlport_declaration"
+        | "query_declaration"
+        | "with_statement" => return,
+        "table_declaration" => scope.implicit_methods = true,
+        "page_declaration" => {
+            scope.implicit_methods = true;
+            scope.implicit_fields = true;
+        }
+        "codeunit_declaration" => scope.implicit_fields = has_property(node, source, "TableNo"),
+        "block" => scope.in_body = true,
+        "identifier" | "quoted_identifier" => {
+            if scope.in_body {
+                if let Some(diagnostic) =
+                    unknown_identifier_diagnostic(node, source, symbol_table, scope)
+                {
+                    diagnostics.push(diagnostic);
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        walk_for_unknown_identifiers(child, source, symbol_table, scope, diagnostics);
+    }
+}
+
+/// Whether an object declaration sets the property `name` (e.g. `TableNo`).
+fn has_property(object: tree_sitter::Node, source: &str, name: &str) -> bool {
+    let mut cursor = object.walk();
+    let has = object.named_children(&mut cursor).any(|child| {
+        child.kind() == "property"
+            && child
+                .child_by_field_name("name")
+                .is_some_and(|property| extract_name(property, source).eq_ignore_ascii_case(name))
+    });
+    has
+}
+
+fn unknown_identifier_diagnostic(
+    node: tree_sitter::Node,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    scope: UnknownIdentifierScope,
+) -> Option<Diagnostic> {
+    // Only bare references: `X`, `X := ...`, `X.Member`, `X(...)`. Member names,
+    // qualified enum/option parts and type names never reach this point.
+    let parent = node.parent()?;
+    match parent.kind() {
+        "primary_expression" if !scope.implicit_fields => {}
+        "function_call" if !scope.implicit_methods => {
+            let function = parent.child_by_field_name("function")?;
+            if function.id() != node.id() {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    let name = extract_name(node, source);
+    if name.is_empty()
+        || is_builtin_identifier(&name)
+        || al_keywords()
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(&name))
+    {
+        return None;
+    }
+    if !symbol_table
+        .lookup_in_scope(&name, node.start_byte())
+        .is_empty()
+    {
+        return None;
+    }
+
+    let start = node.start_position();
+    let end = node.end_position();
+    Some(Diagnostic {
+        range: Range {
+            start: Position {
+                line: start.row as u32,
+                character: start.column as u32,
+            },
+            end: Position {
+                line: end.row as u32,
+                character: end.column as u32,
+            },
+        },
+        severity: Some(DiagnosticSeverity::WARNING),
+        source: Some("al-lsp".to_string()),
+        message: format!("unknown identifier `{name}`"),
+        ..Default::default()
+    })
+}
+
+/// Diagnostic `code` of unused local variable hints.
+pub const UNUSED_VARIABLE_CODE: &str = "unused-variable";
+
+/// Hint at local variables of procedures and triggers that are never referenced.
+/// Parameters and object-level (global) variables are not checked.
+pub fn extract_unused_variable_diagnostics(
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for symbol in &symbol_table.symbols {
+        collect_unused_variables(symbol, tree, source, symbol_table, &mut diagnostics);
+    }
+    diagnostics
+}
+
+fn collect_unused_variables(
+    symbol: &AlSymbol,
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let is_routine = matches!(symbol.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger);
+    for child in &symbol.children {
+        if is_routine && matches!(child.kind, AlSymbolKind::Variable) {
+            if let Some(diagnostic) = unused_variable_diagnostic(child, tree, source, symbol_table)
+            {
+                diagnostics.push(diagnostic);
+            }
+        }
+        collect_unused_variables(child, tree, source, symbol_table, diagnostics);
+    }
+}
+
+fn unused_variable_diagnostic(
+    variable: &AlSymbol,
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Option<Diagnostic> {
+    let name_node = tree
+        .root_node()
+        .descendant_for_point_range(variable.name_start_point, variable.name_end_point)?;
+    // Named return values (`procedure Foo() Result: Integer`) are returned implicitly.
+    if name_node.parent()?.kind() == "return_type" {
+        return None;
+    }
+    let references = find_all_references(tree, source, symbol_table, name_node.start_byte(), false);
+    if !references.is_empty() {
+        return None;
+    }
+
+    Some(Diagnostic {
+        range: Range {
+            start: Position {
+                line: variable.name_start_point.row as u32,
+                character: variable.name_start_point.column as u32,
+            },
+            end: Position {
+                line: variable.name_end_point.row as u32,
+                character: variable.name_end_point.column as u32,
+            },
+        },
+        severity: Some(DiagnosticSeverity::HINT),
+        code: Some(NumberOrString::String(UNUSED_VARIABLE_CODE.to_string())),
+        source: Some("al-lsp".to_string()),
+        message: format!("`{}` is declared but never used", variable.name),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    })
+}
+
+/// Diagnostic `code` of local-shadows-global hints.
+pub const SHADOWED_VARIABLE_CODE: &str = "shadowed-variable";
+
+/// Hint at local variables of procedures and triggers named like a global
+/// variable or field of their object. Parameters are not checked: naming a
+/// parameter after the global it initializes is a common, deliberate pattern.
+pub fn extract_shadowed_variable_diagnostics(
+    symbol_table: &DocumentSymbolTable,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for object in &symbol_table.symbols {
+        let globals: Vec<&AlSymbol> = object
+            .children
+            .iter()
+            .filter(|child| matches!(child.kind, AlSymbolKind::Variable | AlSymbolKind::Field))
+            .collect();
+        if !globals.is_empty() {
+            collect_shadowed_variables(object, &globals, &mut diagnostics);
+        }
+    }
+    diagnostics
+}
+
+fn collect_shadowed_variables(
+    symbol: &AlSymbol,
+    globals: &[&AlSymbol],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let is_routine = matches!(symbol.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger);
+    for child in &symbol.children {
+        if is_routine
+            && matches!(child.kind, AlSymbolKind::Variable)
+            && globals
+                .iter()
+                .any(|global| global.name.eq_ignore_ascii_case(&child.name))
+        {
+            diagnostics.push(Diagnostic {
+                range: Range {
+                    start: Position {
+                        line: child.name_start_point.row as u32,
+                        character: child.name_start_point.column as u32,
+                    },
+                    end: Position {
+                        line: child.name_end_point.row as u32,
+                        character: child.name_end_point.column as u32,
+                    },
+                },
+                severity: Some(DiagnosticSeverity::HINT),
+                code: Some(NumberOrString::String(SHADOWED_VARIABLE_CODE.to_string())),
+                source: Some("al-lsp".to_string()),
+                message: format!("local `{}` shadows a global", child.name),
+                ..Default::default()
+            });
+        }
+        collect_shadowed_variables(child, globals, diagnostics);
+    }
+}
+
+/// Warn about `exit(<literal>)` statements whose literal obviously cannot be
+/// converted to the enclosing procedure's return type, e.g. `exit('x')` in a
+/// procedure returning `Integer`. Non-literal expressions are never flagged.
+pub fn extract_exit_type_diagnostics(tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    walk_for_exit_mismatches(tree.root_node(), source, &mut diagnostics);
+    diagnostics
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiteralClass {
+    Text,
+    Number,
+    Boolean,
+}
+
+impl LiteralClass {
+    fn label(self) -> &'static str {
+        match self {
+            LiteralClass::Text => "Text",
+            LiteralClass::Number => "Number",
+            LiteralClass::Boolean => "Boolean",
+        }
+    }
+}
+
+fn walk_for_exit_mismatches(
+    node: tree_sitter::Node,
+    source: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if node.kind() == "exit_statement" {
+        if let Some(diagnostic) = exit_mismatch_diagnostic(node, source) {
+            diagnostics.push(diagnostic);
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        walk_for_exit_mismatches(child, source, diagnostics);
+    }
+}
+
+fn exit_mismatch_diagnostic(exit_node: tree_sitter::Node, source: &str) -> Option<Diagnostic> {
+    let mut cursor = exit_node.walk();
+    let value = exit_node.named_children(&mut cursor).next()?;
+    let literal_class = classify_literal(value)?;
+
+    let procedure = enclosing_procedure(exit_node)?;
+    let return_type = procedure.child_by_field_name("return_type")?;
+    let mut cursor = return_type.walk();
+    let type_node = return_type.named_children(&mut cursor).last()?;
+    let type_text = type_node.utf8_text(source.as_bytes()).ok()?;
+    let return_class = classify_return_type(type_text)?;
+
+    if literal_class == return_class {
+        return None;
+    }
+
+    let start = value.start_position();
+    let end = value.end_position();
+    Some(Diagnostic {
+        range: Range {
+            start: Position {
+                line: start.row as u32,
+                character: start.column as u32,
+            },
+            end: Position {
+                line: end.row as u32,
+                character: end.column as u32,
+            },
+        },
+        severity: Some(DiagnosticSeverity::WARNING),
+        source: Some("al-lsp".to_string()),
+        message: format!(
+            "`exit` value of type {} does not match return type `{}`",
+            literal_class.label(),
+            type_text.trim()
+        ),
+        ..Default::default()
+    })
+}
+
+/// Warn about string literals assigned to a `Code[N]`/`Text[N]` variable,
+/// parameter or field that are longer than `N` characters, e.g. a 21-character
+/// literal assigned to a `Code[20]` field. Only unqualified targets declared
+/// in the document are checked.
+pub fn extract_length_diagnostics(
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    walk_for_length_overflows(tree.root_node(), source, symbol_table, &mut diagnostics);
+    diagnostics
+}
+
+fn walk_for_length_overflows(
+    node: tree_sitter::Node,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if node.kind() == "assignment_statement" {
+        if let Some(diagnostic) = length_overflow_diagnostic(node, source, symbol_table) {
+            diagnostics.push(diagnostic);
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        walk_for_length_overflows(child, source, symbol_table, diagnostics);
+    }
+}
+
+fn length_overflow_diagnostic(
+    assignment: tree_sitter::Node,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Option<Diagnostic> {
+    let target = unwrap_primary(assignment.child_by_field_name("target")?)?;
+    if !matches!(target.kind(), "identifier" | "quoted_identifier") {
+        return None;
+    }
+    let value = unwrap_primary(assignment.child_by_field_name("value")?)?;
+    if value.kind() != "string_literal" {
+        return None;
+    }
+
+    let name = extract_name(target, source);
+    let symbol = symbol_table
+        .lookup_in_scope(&name, target.start_byte())
+        .into_iter()
+        .find(|s| {
+            matches!(
+                s.kind,
+                AlSymbolKind::Variable | AlSymbolKind::Parameter | AlSymbolKind::Field
+            )
+        })?;
+    let (base, max_length) = symbol.sized_type()?;
+    if !matches!(base.to_ascii_lowercase().as_str(), "code" | "text") {
+        return None;
+    }
+
+    let literal = value.utf8_text(source.as_bytes()).ok()?;
+    let length = literal
+        .strip_prefix('\'')
+        .and_then(|l| l.strip_suffix('\''))
+        .unwrap_or(literal)
+        .replace("''", "'")
+        .chars()
+        .count();
+    if length <= max_length {
+        return None;
+    }
+
+    let start = value.start_position();
+    let end = value.end_position();
+    Some(Diagnostic {
+        range: Range {
+            start: Position {
+                line: start.row as u32,
+                character: start.column as u32,
+            },
+            end: Position {
+                line: end.row as u32,
+                character: end.column as u32,
+            },
+        },
+        severity: Some(DiagnosticSeverity::WARNING),
+        source: Some("al-lsp".to_string()),
+        message: format!(
+            "Text of length {length} exceeds the maximum length of `{}` ({base}[{max_length}])",
+            symbol.name
+        ),
+        ..Default::default()
+    })
+}
+
+/// Report every `field(N; ...)` in a table or table extension whose ID `N`
+/// was already used by an earlier field of the same object.
+pub fn extract_duplicate_field_id_diagnostics(tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    walk_for_duplicate_field_ids(tree.root_node(), source, &mut diagnostics);
+    diagnostics
+}
+
+fn walk_for_duplicate_field_ids(
+    node: tree_sitter::Node,
+    source: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if node.kind() == "fields_section" {
+        let mut seen = HashSet::new();
+        let mut cursor = node.walk();
+        for field in node.named_children(&mut cursor) {
+            if field.kind() != "field_declaration" {
+                continue;
+            }
+            let Some(id_node) = field.child_by_field_name("id") else {
+                continue;
+            };
+            let id = id_node.utf8_text(source.as_bytes()).unwrap_or("").trim();
+            if seen.insert(id.to_string()) {
+                continue;
+            }
+            let start = id_node.start_position();
+            let end = id_node.end_position();
+            diagnostics.push(Diagnostic {
+                range: Range {
+                    start: Position {
+                        line: start.row as u32,
+                        character: start.column as u32,
+                    },
+                    end: Position {
+                        line: end.row as u32,
+                        character: end.column as u32,
+                    },
+                },
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("al-lsp".to_string()),
+                message: format!("Duplicate field ID {id}"),
+                ..Default::default()
+            });
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        walk_for_duplicate_field_ids(child, source, diagnostics);
+    }
+}
+
+fn unwrap_primary(mut node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    while matches!(
+        node.kind(),
+        "primary_expression" | "parenthesized_expression"
+    ) {
+        let mut cursor = node.walk();
+        node = node.named_children(&mut cursor).next()?;
+    }
+    Some(node)
+}
+
+fn enclosing_procedure(node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        match n.kind() {
+            "procedure_declaration" => return Some(n),
+            "trigger_declaration" => return None,
+            _ => current = n.parent(),
+        }
+    }
+    None
+}
+
+fn classify_literal(mut node: tree_sitter::Node) -> Option<LiteralClass> {
+    loop {
+        match node.kind() {
+            "primary_expression" | "parenthesized_expression" | "negation_expression" => {
+                let mut cursor = node.walk();
+                node = node.named_children(&mut cursor).next()?;
+            }
+            "string_literal" => return Some(LiteralClass::Text),
+            "integer_literal" | "decimal_literal" => return Some(LiteralClass::Number),
+            "boolean_literal" => return Some(LiteralClass::Boolean),
+            _ => return None,
+        }
+    }
+}
+
+fn classify_return_type(type_text: &str) -> Option<LiteralClass> {
+    let base = type_text
+        .split(|c: char| c == '[' || c.is_whitespace())
+        .next()?
+        .to_ascii_lowercase();
+    match base.as_str() {
+        "text" | "code" | "bigtext" | "secrettext" => Some(LiteralClass::Text),
+        "integer" | "biginteger" | "decimal" | "byte" => Some(LiteralClass::Number),
+        "boolean" => Some(LiteralClass::Boolean),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,6 +908,116 @@ mod tests {
         assert!(diags.is_empty(), "expected no errors, got: {:?}", diags);
     }
 
+    fn unbalanced_block_diagnostics(source: &str) -> Vec<Diagnostic> {
+        let tree = al_parser::parse(source).unwrap();
+        extract_diagnostics(&tree, source)
+            .into_iter()
+            .filter(|d| d.code == Some(NumberOrString::String(UNBALANCED_BLOCK_CODE.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_extra_end_is_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        I: Integer;
+    begin
+        if I > 0 then begin
+            I := 1;
+        end;
+        end;
+    end;
+
+    procedure Other()
+    begin
+    end;
+}"#;
+        let diags = unbalanced_block_diagnostics(source);
+        assert_eq!(diags.len(), 1, "got: {diags:?}");
+        assert_eq!(
+            diags[0].message,
+            "`end` without a matching `begin` or `case`"
+        );
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(10, 4), Position::new(10, 7))
+        );
+    }
+
+    #[test]
+    fn test_missing_until_is_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        I: Integer;
+    begin
+        REPEAT
+            I := I + 1;
+        case I of
+            1:
+                I := 2;
+        end;
+    end;
+
+    procedure Other()
+    begin
+    end;
+}"#;
+        let diags = unbalanced_block_diagnostics(source);
+        assert_eq!(diags.len(), 1, "got: {diags:?}");
+        assert_eq!(diags[0].message, "`repeat` is never closed with `until`");
+        assert_eq!(
+            diags[0].range,
+            Range::new(Position::new(6, 8), Position::new(6, 14))
+        );
+    }
+
+    #[test]
+    fn test_block_balance_ignores_strings_comments_and_quoted_names() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        "Begin": Integer;
+        EndLbl: Label 'end; until it''s begin';
+    begin
+        // end
+        /* repeat */
+        repeat
+            "Begin" += 1;
+        until "Begin" > 10;
+        case "Begin" of
+            1:
+                begin
+                end;
+        end;
+    end;
+}"#;
+        assert!(unbalanced_block_diagnostics(source).is_empty());
+    }
+
+    #[test]
+    fn test_unclosed_begin_is_flagged_at_procedure_end() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    begin
+        if true then begin
+    end;
+
+    procedure Other()
+    begin
+    end;
+}"#;
+        let diags = unbalanced_block_diagnostics(source);
+        assert_eq!(diags.len(), 1, "got: {diags:?}");
+        assert_eq!(diags[0].message, "`begin` is never closed with `end`");
+        assert_eq!(diags[0].range.start, Position::new(3, 4));
+    }
+
     #[test]
     fn test_no_errors_for_interface() {
         let source = r#"interface ICustomer
@@ -871,4 +1808,420 @@ page 50100 "Dummy Host"
         let diags = extract_diagnostics(&tree, source);
         assert!(!diags.is_empty(), "expected errors for invalid code");
     }
+
+    #[test]
+    fn test_missing_semicolon_between_statements() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        A: Integer;
+    begin
+        A := 1
+        A := 2;
+        begin
+        end
+        A := 3
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let diags = extract_diagnostics(&tree, source);
+        let missing: Vec<_> = diags
+            .iter()
+            .filter(|d| d.message == "Missing ';' after statement")
+            .collect();
+        assert_eq!(missing.len(), 2, "got: {diags:?}");
+        assert_eq!(missing[0].range.start, Position::new(6, 14));
+        assert_eq!(missing[0].range.end, Position::new(6, 14));
+        assert_eq!(missing[1].range.start, Position::new(9, 11));
+        assert_eq!(
+            missing[0].code,
+            Some(NumberOrString::String(MISSING_SEMICOLON_CODE.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_statements_ending_in_a_terminated_block_need_no_semicolon() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        A: Integer;
+        I: Integer;
+    begin
+        if A = 1 then begin
+            A := 2;
+        end;
+        A := 3;
+        if A = 2 then
+            A := 1
+        else begin
+            A := 4;
+        end;
+        case A of
+            1:
+                begin
+                    A := 2;
+                end;
+            2:
+                A := 3;
+        end;
+        for I := 1 to 2 do begin
+            A := I;
+        end;
+        while A > 0 do begin
+            A -= 1;
+        end;
+        A := 5;
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let diags = extract_diagnostics(&tree, source);
+        assert!(
+            !diags
+                .iter()
+                .any(|d| d.message == "Missing ';' after statement"),
+            "got: {diags:?}"
+        );
+    }
+
+    #[test]
+    fn test_exit_string_in_integer_procedure_is_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure GetValue(): Integer
+    begin
+        exit('x');
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let diags = extract_exit_type_diagnostics(&tree, source);
+        assert_eq!(diags.len(), 1, "expected one mismatch, got: {:?}", diags);
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diags[0].range.start, Position::new(4, 13));
+        assert!(diags[0].message.contains("Integer"), "{}", diags[0].message);
+    }
+
+    #[test]
+    fn test_exit_call_expression_is_not_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure GetValue(): Integer
+    begin
+        exit(Foo());
+    end;
+
+    procedure Foo(): Integer
+    begin
+        exit(-1);
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let diags = extract_exit_type_diagnostics(&tree, source);
+        assert!(diags.is_empty(), "expected no mismatches, got: {:?}", diags);
+    }
+
+    #[test]
+    fn test_exit_literal_matching_return_type_is_not_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure GetName(): Text[30]
+    begin
+        exit('x');
+    end;
+
+    procedure IsReady(): Boolean
+    begin
+        exit(true);
+    end;
+
+    procedure GetStatus(): Enum "Status"
+    begin
+        exit(1);
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let diags = extract_exit_type_diagnostics(&tree, source);
+        assert!(diags.is_empty(), "expected no mismatches, got: {:?}", diags);
+    }
+
+    #[test]
+    fn test_too_long_literal_assigned_to_code_field_is_flagged() {
+        let source = r#"table 50100 Item
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+    }
+
+    trigger OnInsert()
+    var
+        Name: Text[30];
+    begin
+        "No." := 'ABCDEFGHIJKLMNOPQRSTU';
+        "No." := 'ABCDEFGHIJKLMNOPQRST';
+        Name := 'ABCDEFGHIJKLMNOPQRSTU';
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = crate::ast::extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+        let diags = extract_length_diagnostics(&tree, source, &table);
+        assert_eq!(diags.len(), 1, "expected one overflow, got: {:?}", diags);
+        assert_eq!(diags[0].range.start, Position::new(11, 17));
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diags[0].message,
+            "Text of length 21 exceeds the maximum length of `No.` (Code[20])"
+        );
+    }
+
+    fn semantic_diagnostics(source: &str) -> Vec<Diagnostic> {
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = crate::ast::extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+        extract_semantic_diagnostics(&tree, source, &table)
+    }
+
+    #[test]
+    fn test_unknown_identifier_is_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Known: Integer;
+    begin
+        Known := Missing;
+        Undeclared();
+    end;
+}"#;
+        let diags = semantic_diagnostics(source);
+        let messages: Vec<&str> = diags.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "unknown identifier `Missing`",
+                "unknown identifier `Undeclared`"
+            ]
+        );
+        assert!(diags
+            .iter()
+            .all(|d| d.severity == Some(DiagnosticSeverity::WARNING)));
+        assert_eq!(diags[0].range.start, Position::new(6, 17));
+    }
+
+    #[test]
+    fn test_declared_and_builtin_identifiers_are_not_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    var
+        Counter: Integer;
+
+    procedure DoWork(Amount: Decimal): Integer
+    var
+        Cust: Record Customer;
+    begin
+        Counter := Helper(Amount);
+        Cust.Name := Format(Amount);
+        Message('Done');
+        exit(Counter);
+    end;
+
+    local procedure Helper(Value: Decimal): Integer
+    begin
+        exit(Round(Value, 1));
+    end;
+}"#;
+        let diags = semantic_diagnostics(source);
+        assert!(
+            diags.is_empty(),
+            "expected no diagnostics, got: {:?}",
+            diags
+        );
+    }
+
+    #[test]
+    fn test_builtin_receivers_and_global_functions_are_not_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork(): Boolean
+    var
+        Text: Text;
+    begin
+        ErrorInfo.Create('Failed');
+        Text := CopyStr(StrSubstNo('%1', Today()), 1, 10);
+        if Confirm(Text) then
+            Commit();
+        exit(EncryptionEnabled());
+    end;
+}"#;
+        let diags = semantic_diagnostics(source);
+        assert!(
+            diags.is_empty(),
+            "expected no diagnostics, got: {:?}",
+            diags
+        );
+    }
+
+    #[test]
+    fn test_unknown_identifiers_skipped_in_table_extension() {
+        let source = r#"tableextension 50100 CustExt extends Customer
+{
+    procedure DoWork()
+    begin
+        Name := 'x';
+    end;
+}"#;
+        let diags = semantic_diagnostics(source);
+        assert!(
+            diags.is_empty(),
+            "expected no diagnostics, got: {:?}",
+            diags
+        );
+    }
+
+    #[test]
+    fn test_bare_source_table_fields_skipped_in_pages_and_tableno_codeunits() {
+        let source = r#"page 50100 "Customer Card"
+{
+    SourceTable = Customer;
+
+    trigger OnAfterGetRecord()
+    begin
+        if "No." <> '' then
+            Name := '';
+    end;
+}
+
+codeunit 50101 "Customer Poster"
+{
+    TableNo = Customer;
+
+    trigger OnRun()
+    begin
+        if "No." <> '' then
+            Missing();
+    end;
+}"#;
+        let diags = semantic_diagnostics(source);
+        let messages: Vec<&str> = diags.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, vec!["unknown identifier `Missing`"]);
+    }
+
+    fn unused_variable_diagnostics(source: &str) -> Vec<Diagnostic> {
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = crate::ast::extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+        extract_unused_variable_diagnostics(&tree, source, &table)
+    }
+
+    #[test]
+    fn test_used_local_variable_is_not_flagged() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Counter: Integer;
+    begin
+        Message(Format(COUNTER));
+    end;
+}"#;
+        let diags = unused_variable_diagnostics(source);
+        assert!(
+            diags.is_empty(),
+            "expected no diagnostics, got: {:?}",
+            diags
+        );
+    }
+
+    #[test]
+    fn test_unused_local_variable_is_hinted() {
+        let source = r#"codeunit 50100 Test
+{
+    var
+        GlobalCounter: Integer;
+
+    procedure DoWork(Unused: Integer)
+    var
+        Leftover: Text;
+    begin
+    end;
+}"#;
+        let diags = unused_variable_diagnostics(source);
+        assert_eq!(diags.len(), 1, "expected one diagnostic, got: {:?}", diags);
+        assert_eq!(diags[0].message, "`Leftover` is declared but never used");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(diags[0].tags, Some(vec![DiagnosticTag::UNNECESSARY]));
+        assert_eq!(diags[0].range.start, Position::new(7, 8));
+    }
+
+    #[test]
+    fn test_assigned_only_local_variable_counts_as_used() {
+        // Any reference, including a write, counts as a use.
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Total: Decimal;
+    begin
+        Total := 1;
+    end;
+}"#;
+        let diags = unused_variable_diagnostics(source);
+        assert!(
+            diags.is_empty(),
+            "expected no diagnostics, got: {:?}",
+            diags
+        );
+    }
+
+    #[test]
+    fn test_local_variable_shadowing_global_is_hinted() {
+        let source = r#"codeunit 50100 Test
+{
+    var
+        Counter: Integer;
+
+    procedure DoWork(counter2: Integer)
+    var
+        counter: Integer;
+        Other: Integer;
+    begin
+    end;
+
+    procedure Reset(Counter: Integer)
+    begin
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let table = DocumentSymbolTable::new(crate::ast::extract_symbols(&tree, source));
+        let diags = extract_shadowed_variable_diagnostics(&table);
+        assert_eq!(diags.len(), 1, "expected one diagnostic, got: {:?}", diags);
+        assert_eq!(diags[0].message, "local `counter` shadows a global");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(
+            diags[0].code,
+            Some(NumberOrString::String(SHADOWED_VARIABLE_CODE.to_string()))
+        );
+        assert_eq!(diags[0].range.start, Position::new(7, 8));
+    }
+
+    #[test]
+    fn test_duplicate_field_id() {
+        let source = r#"table 50100 Customer
+{
+    fields
+    {
+        field(1; "No."; Code[20]) { }
+        field(1; Name; Text[100]) { }
+        field(2; City; Text[30]) { }
+    }
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let diags = extract_duplicate_field_id_diagnostics(&tree, source);
+        assert_eq!(diags.len(), 1, "{diags:?}");
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diags[0].message, "Duplicate field ID 1");
+        assert_eq!(diags[0].range.start, Position::new(5, 14));
+    }
 }
@@ -1,21 +1,38 @@
 use lsp_types::Diagnostic;
 use ropey::Rope;
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
 use tree_sitter::Tree;
 
-use crate::ast::{extract_symbols, AlSymbol};
-use crate::diagnostics::extract_diagnostics;
+use crate::ast::{
+    extract_namespace_info, extract_object_symbol_at, extract_symbols, AlObjectKind, AlSymbol,
+};
+use crate::diagnostics::{
+    extract_diagnostics, extract_semantic_diagnostics, extract_shadowed_variable_diagnostics,
+    extract_unused_variable_diagnostics, parse_failure_diagnostic,
+};
 use crate::symbols::DocumentSymbolTable;
 
 /// A document's state: source text (as Rope), parse tree, symbols, and diagnostics.
+///
+/// The rope and tree can be dropped with [`DocumentState::unload`] to save
+/// memory while the document is closed; [`DocumentState::rope`] and
+/// [`DocumentState::tree`] rebuild them from the retained source on first use.
+/// Workspace-wide scans go through [`DocumentState::scan_tree`] instead, which
+/// leaves unloaded documents unloaded.
 pub struct DocumentState {
-    pub rope: Rope,
+    parsed: OnceLock<Parsed>,
     source_text: Arc<str>,
-    pub tree: Tree,
     pub symbol_table: DocumentSymbolTable,
     pub diagnostics: Vec<Diagnostic>,
 }
 
+/// The parts of a document derived from its source that are only kept while needed.
+struct Parsed {
+    rope: Rope,
+    tree: Tree,
+}
+
 pub struct IncrementalEdit<'a> {
     pub start_byte: usize,
     pub old_end_byte: usize,
@@ -27,22 +44,89 @@ pub struct IncrementalEdit<'a> {
 
 impl DocumentState {
     /// Create a new document state from full source text.
-    pub fn new(source: &str) -> Option<Self> {
-        let tree = al_parser::parse(source)?;
-        let symbols = extract_symbols(&tree, source);
-        let diagnostics = extract_diagnostics(&tree, source);
-        let symbol_table = DocumentSymbolTable::new(symbols);
+    ///
+    /// A document the parser cannot handle at all still gets a state, with an
+    /// empty tree and a single "Failed to parse document" diagnostic.
+    pub fn new(source: &str) -> Self {
+        Self::from_tree(source, al_parser::parse(source))
+    }
+
+    /// Create a document state for a file that is not open in the editor,
+    /// keeping only its source text, symbol table and diagnostics.
+    pub fn new_unloaded(source: &str) -> Self {
+        let mut doc = Self::new(source);
+        doc.unload();
+        doc
+    }
+
+    fn from_tree(source: &str, tree: Option<Tree>) -> Self {
+        let (tree, symbol_table, diagnostics) = match tree {
+            Some(tree) => {
+                let symbols = extract_symbols(&tree, source);
+                let symbol_table = DocumentSymbolTable::new(symbols)
+                    .with_namespaces(extract_namespace_info(&tree, source));
+                let diagnostics = compute_diagnostics(&tree, source, &symbol_table);
+                (tree, symbol_table, diagnostics)
+            }
+            None => (
+                empty_tree(),
+                DocumentSymbolTable::default(),
+                vec![parse_failure_diagnostic()],
+            ),
+        };
         let rope = Rope::from_str(source);
 
-        Some(DocumentState {
-            rope,
+        DocumentState {
+            parsed: OnceLock::from(Parsed { rope, tree }),
             source_text: Arc::<str>::from(source),
-            tree,
             symbol_table,
             diagnostics,
+        }
+    }
+
+    /// Source text as a rope, rebuilt if the document was unloaded.
+    pub fn rope(&self) -> &Rope {
+        &self.parsed().rope
+    }
+
+    /// Parse tree, re-parsed if the document was unloaded.
+    pub fn tree(&self) -> &Tree {
+        &self.parsed().tree
+    }
+
+    /// Parse tree for a one-off read such as a workspace-wide scan: borrowed
+    /// while the document is loaded, otherwise parsed without being kept.
+    pub fn scan_tree(&self) -> Cow<'_, Tree> {
+        match self.parsed.get() {
+            Some(parsed) => Cow::Borrowed(&parsed.tree),
+            None => Cow::Owned(al_parser::parse(&self.source_text).unwrap_or_else(empty_tree)),
+        }
+    }
+
+    /// Drop the rope and parse tree, keeping the source text, symbol table and
+    /// diagnostics. Used for closed documents, which cross-document features
+    /// mostly reach through their symbol tables.
+    pub fn unload(&mut self) {
+        self.parsed.take();
+    }
+
+    /// Whether the rope and parse tree are currently unloaded.
+    pub fn is_unloaded(&self) -> bool {
+        self.parsed.get().is_none()
+    }
+
+    fn parsed(&self) -> &Parsed {
+        self.parsed.get_or_init(|| Parsed {
+            rope: Rope::from_str(&self.source_text),
+            tree: al_parser::parse(&self.source_text).unwrap_or_else(empty_tree),
         })
     }
 
+    fn parsed_mut(&mut self) -> &mut Parsed {
+        self.parsed();
+        self.parsed.get_mut().expect("initialized above")
+    }
+
     /// Apply an incremental edit and re-parse.
     pub fn apply_edit(&mut self, edit: IncrementalEdit<'_>) {
         let IncrementalEdit {
@@ -56,13 +140,14 @@ impl DocumentState {
         let new_end_byte = start_byte + new_text.len();
 
         // Update rope
-        let start_char = self.rope.byte_to_char(start_byte);
-        let old_end_char = self
+        let parsed = self.parsed_mut();
+        let start_char = parsed.rope.byte_to_char(start_byte);
+        let old_end_char = parsed
             .rope
-            .byte_to_char(old_end_byte.min(self.rope.len_bytes()));
-        self.rope.remove(start_char..old_end_char);
+            .byte_to_char(old_end_byte.min(parsed.rope.len_bytes()));
+        parsed.rope.remove(start_char..old_end_char);
         if !new_text.is_empty() {
-            self.rope.insert(start_char, new_text);
+            parsed.rope.insert(start_char, new_text);
         }
 
         // Apply edit to tree for incremental parsing
@@ -74,7 +159,7 @@ impl DocumentState {
             old_end_position: old_end_point,
             new_end_position: new_end_point,
         };
-        self.tree.edit(&edit);
+        parsed.tree.edit(&edit);
 
         // Re-parse incrementally
         let mut full_source = self.source_text.to_string();
@@ -85,25 +170,25 @@ impl DocumentState {
         }
         full_source.replace_range(replace_start..replace_end, new_text);
         self.source_text = Arc::<str>::from(full_source.as_str());
-        if let Some(new_tree) = al_parser::parse_with(&full_source, Some(&self.tree)) {
-            let symbols = extract_symbols(&new_tree, &full_source);
-            self.diagnostics = extract_diagnostics(&new_tree, &full_source);
-            self.symbol_table = DocumentSymbolTable::new(symbols);
-            self.tree = new_tree;
+        if let Some(new_tree) = al_parser::parse_with(&full_source, Some(self.tree())) {
+            if patch_symbol_table(&mut self.symbol_table, &new_tree, &full_source, &edit) {
+                self.symbol_table.namespaces = extract_namespace_info(&new_tree, &full_source);
+            } else {
+                let symbols = extract_symbols(&new_tree, &full_source);
+                self.symbol_table = DocumentSymbolTable::new(symbols)
+                    .with_namespaces(extract_namespace_info(&new_tree, &full_source));
+            }
+            self.diagnostics = compute_diagnostics(&new_tree, &full_source, &self.symbol_table);
+            self.parsed_mut().tree = new_tree;
+        } else {
+            *self = Self::from_tree(&full_source, None);
         }
     }
 
     /// Re-parse the document from the current rope contents.
     /// Used when applying full-document changes.
     pub fn reparse_full(&mut self, source: &str) {
-        self.rope = Rope::from_str(source);
-        self.source_text = Arc::<str>::from(source);
-        if let Some(new_tree) = al_parser::parse(source) {
-            let symbols = extract_symbols(&new_tree, source);
-            self.diagnostics = extract_diagnostics(&new_tree, source);
-            self.symbol_table = DocumentSymbolTable::new(symbols);
-            self.tree = new_tree;
-        }
+        *self = Self::new(source);
     }
 
     /// Get all top-level symbols.
@@ -122,6 +207,82 @@ impl DocumentState {
     }
 }
 
+/// Update `table` for an edit strictly inside one object by re-extracting only
+/// that object. Returns `false`, leaving `table` untouched, when the edit
+/// reaches an object boundary or the other objects did not just move with it;
+/// the caller then rebuilds the table from scratch.
+fn patch_symbol_table(
+    table: &mut DocumentSymbolTable,
+    tree: &Tree,
+    source: &str,
+    edit: &tree_sitter::InputEdit,
+) -> bool {
+    let Some(object_idx) = table
+        .symbols
+        .iter()
+        .position(|sym| sym.start_byte < edit.start_byte && edit.old_end_byte < sym.end_byte)
+    else {
+        return false;
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let nodes: Vec<tree_sitter::Node> = root
+        .named_children(&mut cursor)
+        .filter(|node| AlObjectKind::from_node_kind(node.kind()).is_some())
+        .collect();
+    if nodes.len() != table.symbols.len() {
+        return false;
+    }
+
+    let byte_delta = edit.new_end_byte as isize - edit.old_end_byte as isize;
+    let row_delta = edit.new_end_position.row as isize - edit.old_end_position.row as isize;
+    for (idx, (node, sym)) in nodes.iter().zip(&table.symbols).enumerate() {
+        let unchanged = if idx < object_idx {
+            node.byte_range() == (sym.start_byte..sym.end_byte)
+        } else if idx > object_idx {
+            sym.start_point.row > edit.old_end_position.row
+                && node.start_byte() as isize == sym.start_byte as isize + byte_delta
+                && node.end_byte() as isize == sym.end_byte as isize + byte_delta
+        } else {
+            true
+        };
+        if !unchanged {
+            return false;
+        }
+    }
+
+    let Some(symbol) = extract_object_symbol_at(nodes[object_idx], source) else {
+        return false;
+    };
+    table.replace_object(object_idx, symbol, byte_delta, row_delta);
+    true
+}
+
+/// Tree of an empty document, standing in for a source the parser rejected.
+fn empty_tree() -> Tree {
+    al_parser::parse("").expect("an empty document always parses")
+}
+
+/// Syntax diagnostics, plus semantic ones once the document parses cleanly.
+fn compute_diagnostics(
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = extract_diagnostics(tree, source);
+    if diagnostics.is_empty() {
+        diagnostics.extend(extract_semantic_diagnostics(tree, source, symbol_table));
+        diagnostics.extend(extract_unused_variable_diagnostics(
+            tree,
+            source,
+            symbol_table,
+        ));
+        diagnostics.extend(extract_shadowed_variable_diagnostics(symbol_table));
+    }
+    diagnostics
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,7 +298,7 @@ mod tests {
     begin
     end;
 }"#;
-        let doc = DocumentState::new(source).unwrap();
+        let doc = DocumentState::new(source);
         assert!(doc.diagnostics.is_empty());
         assert_eq!(doc.symbols().len(), 1);
         assert_eq!(doc.symbols()[0].name, "Test");
@@ -145,12 +306,169 @@ mod tests {
         assert_eq!(doc.symbols()[0].children.len(), 2);
     }
 
+    const TWO_OBJECTS: &str = r#"codeunit 50100 First
+{
+    procedure Hello()
+    begin
+    end;
+}
+
+codeunit 50101 Second
+{
+    var
+        Counter: Integer;
+
+    procedure World()
+    begin
+    end;
+}"#;
+
+    fn point_at(source: &str, byte: usize) -> tree_sitter::Point {
+        let before = &source[..byte];
+        let row = before.matches('\n').count();
+        let column = byte - before.rfind('\n').map_or(0, |i| i + 1);
+        tree_sitter::Point { row, column }
+    }
+
+    /// `old` with `start..end` replaced by `new_text`, and the matching tree edit.
+    fn replace(
+        old: &str,
+        start: usize,
+        end: usize,
+        new_text: &str,
+    ) -> (String, tree_sitter::InputEdit) {
+        let mut new = old.to_string();
+        new.replace_range(start..end, new_text);
+        let new_end_byte = start + new_text.len();
+        let input_edit = tree_sitter::InputEdit {
+            start_byte: start,
+            old_end_byte: end,
+            new_end_byte,
+            start_position: point_at(old, start),
+            old_end_position: point_at(old, end),
+            new_end_position: point_at(&new, new_end_byte),
+        };
+        (new, input_edit)
+    }
+
+    /// Apply `new_text` over `start..end` of the document's source.
+    fn edit(doc: &mut DocumentState, start: usize, end: usize, new_text: &str) -> String {
+        let (new, input_edit) = replace(doc.source(), start, end, new_text);
+        doc.apply_edit(IncrementalEdit {
+            start_byte: start,
+            old_end_byte: end,
+            start_point: input_edit.start_position,
+            old_end_point: input_edit.old_end_position,
+            new_end_point: input_edit.new_end_position,
+            new_text,
+        });
+        new
+    }
+
+    /// Whether `patch_symbol_table` handles the edit without a full rebuild.
+    fn patches_incrementally(source: &str, start: usize, end: usize, new_text: &str) -> bool {
+        let doc = DocumentState::new(source);
+        let (new, input_edit) = replace(source, start, end, new_text);
+        let mut tree = doc.tree().clone();
+        tree.edit(&input_edit);
+        let new_tree = al_parser::parse_with(&new, Some(&tree)).unwrap();
+        let mut table = DocumentSymbolTable::new(doc.symbols().to_vec());
+        patch_symbol_table(&mut table, &new_tree, &new, &input_edit)
+    }
+
+    fn assert_matches_full_rebuild(doc: &DocumentState, source: &str) {
+        let rebuilt = DocumentState::new(source);
+        assert_eq!(doc.source(), source);
+        assert_eq!(
+            format!("{:?}", doc.symbols()),
+            format!("{:?}", rebuilt.symbols())
+        );
+        for name in [
+            "First", "Hello", "Temp", "Second", "Counter", "World", "Renamed",
+        ] {
+            assert_eq!(
+                format!("{:?}", doc.symbol_table.lookup(name)),
+                format!("{:?}", rebuilt.symbol_table.lookup(name)),
+                "lookup({name})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_in_body_edit_patches_symbol_table() {
+        let at = TWO_OBJECTS.find("    begin").unwrap();
+        let new_text = "    var\n        Temp: Integer;\n        Counter: Text;\n";
+        assert!(patches_incrementally(TWO_OBJECTS, at, at, new_text));
+
+        let mut doc = DocumentState::new(TWO_OBJECTS);
+        let source = edit(&mut doc, at, at, new_text);
+        assert_matches_full_rebuild(&doc, &source);
+        assert_eq!(doc.symbol_table.lookup("Counter").len(), 2);
+
+        // Editing the second object leaves the first one's symbols alone
+        let at = source.find("World").unwrap();
+        let source = edit(&mut doc, at, at + "World".len(), "Temp");
+        assert_matches_full_rebuild(&doc, &source);
+        assert_eq!(doc.symbol_table.lookup("Temp").len(), 2);
+
+        // Renaming an object from its header re-extracts just that object
+        let at = source.find("Second").unwrap();
+        assert!(patches_incrementally(&source, at, at + 6, "Renamed"));
+        let source = edit(&mut doc, at, at + 6, "Renamed");
+        assert_matches_full_rebuild(&doc, &source);
+        assert_eq!(doc.symbol_table.lookup("Renamed").len(), 1);
+    }
+
+    #[test]
+    fn test_structural_edit_falls_back_to_full_rebuild() {
+        // Deleting the closing brace of the first object
+        let at = TWO_OBJECTS.find("}\n\ncodeunit").unwrap();
+        assert!(!patches_incrementally(TWO_OBJECTS, at, at + 1, ""));
+        let mut doc = DocumentState::new(TWO_OBJECTS);
+        let source = edit(&mut doc, at, at + 1, "");
+        assert_matches_full_rebuild(&doc, &source);
+
+        // An edit spanning both objects
+        let start = TWO_OBJECTS.find("Hello").unwrap();
+        let end = TWO_OBJECTS.find("Counter").unwrap();
+        assert!(!patches_incrementally(TWO_OBJECTS, start, end, "Renamed"));
+        let mut doc = DocumentState::new(TWO_OBJECTS);
+        let source = edit(&mut doc, start, end, "Renamed");
+        assert_matches_full_rebuild(&doc, &source);
+    }
+
+    #[test]
+    fn test_unloaded_document_reparses_on_demand() {
+        let source = "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n    end;\n}";
+        let mut doc = DocumentState::new(source);
+        let sexp = doc.tree().root_node().to_sexp();
+
+        doc.unload();
+        assert!(doc.is_unloaded());
+        assert_eq!(doc.symbols().len(), 1);
+        assert_eq!(doc.source(), source);
+
+        assert_eq!(doc.tree().root_node().to_sexp(), sexp);
+        assert_eq!(doc.rope().to_string(), source);
+        assert!(!doc.is_unloaded());
+
+        // Scans of an unloaded document leave it unloaded
+        doc.unload();
+        assert_eq!(doc.scan_tree().root_node().to_sexp(), sexp);
+        assert!(doc.is_unloaded());
+        assert!(DocumentState::new_unloaded(source).is_unloaded());
+
+        // Edits work on a document that was unloaded
+        let edited = edit(&mut doc, 36, 39, "Start");
+        assert_matches_full_rebuild(&doc, &edited);
+    }
+
     #[test]
     fn test_full_reparse() {
         let source1 = r#"codeunit 50100 Test
 {
 }"#;
-        let mut doc = DocumentState::new(source1).unwrap();
+        let mut doc = DocumentState::new(source1);
         assert_eq!(doc.symbols()[0].children.len(), 0);
 
         let source2 = r#"codeunit 50100 Test
@@ -163,4 +481,55 @@ mod tests {
         assert_eq!(doc.symbols()[0].children.len(), 1);
         assert_eq!(doc.symbols()[0].children[0].name, "Hello");
     }
+
+    #[test]
+    fn test_unknown_identifier_diagnostics_follow_reparse() {
+        let source1 = r#"codeunit 50100 Test
+{
+    procedure Hello()
+    begin
+        Missing := 1;
+    end;
+}"#;
+        let mut doc = DocumentState::new(source1);
+        assert_eq!(doc.diagnostics.len(), 1);
+        assert_eq!(doc.diagnostics[0].message, "unknown identifier `Missing`");
+
+        let source2 = r#"codeunit 50100 Test
+{
+    procedure Hello()
+    var
+        Missing: Integer;
+    begin
+        Missing := 1;
+    end;
+}"#;
+        doc.reparse_full(source2);
+        assert!(doc.diagnostics.is_empty(), "{:?}", doc.diagnostics);
+    }
+
+    #[test]
+    fn test_parse_failure_keeps_a_degraded_document() {
+        let source = "codeunit 50100 Test\n{\n}";
+        let doc = DocumentState::from_tree(source, None);
+        assert_eq!(doc.source(), source);
+        assert_eq!(doc.rope().len_lines(), 3);
+        assert_eq!(doc.tree().root_node().kind(), "source_file");
+        assert!(doc.symbols().is_empty());
+        assert_eq!(doc.diagnostics.len(), 1);
+        assert_eq!(doc.diagnostics[0].message, "Failed to parse document");
+    }
+
+    #[test]
+    fn test_pathological_input_still_yields_a_document() {
+        let source = format!(
+            "codeunit \u{0} {{\n{}{}\n\u{feff}end;;;",
+            "begin (".repeat(500),
+            "\"unterminated"
+        );
+        let doc = DocumentState::new(&source);
+        assert_eq!(doc.source(), source);
+        assert_eq!(doc.tree().root_node().kind(), "source_file");
+        assert!(!doc.diagnostics.is_empty());
+    }
 }
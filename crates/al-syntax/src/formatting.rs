@@ -5,6 +5,25 @@ use tree_sitter::{Node, Tree};
 pub struct FormatOptions {
     pub tab_size: usize,
     pub insert_spaces: bool,
+    /// Lowercase keywords (`BEGIN`, `Procedure`) while formatting.
+    pub normalize_keyword_case: bool,
+    /// Runs of blank lines longer than this are collapsed to this many.
+    pub max_blank_lines: usize,
+    /// Where the `begin` of an `if`/`for`/`while`/`with` body goes.
+    pub begin_style: BeginStyle,
+    /// Keep one blank line between a procedure's `var` section and its
+    /// `begin` instead of removing it.
+    pub blank_line_before_begin: bool,
+}
+
+/// Placement of `begin` after `then`, `else` and `do`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BeginStyle {
+    /// `begin` stays on the line of its control keyword: `if X then begin`.
+    #[default]
+    SameLine,
+    /// `begin` goes on its own line, aligned with the control statement.
+    NextLine,
 }
 
 impl Default for FormatOptions {
@@ -12,8 +31,65 @@ impl Default for FormatOptions {
         FormatOptions {
             tab_size: 4,
             insert_spaces: true,
+            normalize_keyword_case: false,
+            max_blank_lines: 1,
+            begin_style: BeginStyle::default(),
+            blank_line_before_begin: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// One indentation level: `tab_size` spaces, or a tab.
+    pub fn indent_unit(&self) -> String {
+        if self.insert_spaces {
+            " ".repeat(self.tab_size)
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// Whitespace that reaches display column `column`.
+    fn indent_to(&self, column: usize) -> String {
+        if self.insert_spaces || self.tab_size == 0 {
+            " ".repeat(column)
+        } else {
+            format!(
+                "{}{}",
+                "\t".repeat(column / self.tab_size),
+                " ".repeat(column % self.tab_size)
+            )
+        }
+    }
+}
+
+/// Lay out a snippet body for insertion after `line_prefix` (the text
+/// before the insertion point on its line).
+///
+/// The body is written with one leading `\t` per nesting level. Each level
+/// becomes an `options` indent unit and every line after the first is
+/// shifted to the insertion column, so the snippet lines up with the code
+/// around it. Tabs in `line_prefix` advance to the next tab stop.
+pub fn indent_snippet(body: &str, line_prefix: &str, options: &FormatOptions) -> String {
+    let base = options.indent_to(display_column(line_prefix, options.tab_size));
+    let unit = options.indent_unit();
+
+    let mut out = String::with_capacity(body.len());
+    for (i, line) in body.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+            if line.is_empty() {
+                continue;
+            }
+            out.push_str(&base);
         }
+        let text = line.trim_start_matches('\t');
+        for _ in 0..line.len() - text.len() {
+            out.push_str(&unit);
+        }
+        out.push_str(text);
     }
+    out
 }
 
 /// Format an AL document using the parse tree to determine structure.
@@ -22,42 +98,65 @@ impl Default for FormatOptions {
 /// spacing rules (around `:=`, after `,`, etc.), and preserves the user's
 /// line break decisions where possible.
 pub fn format_document(tree: &Tree, source: &str, options: &FormatOptions) -> String {
-    let indent_str = if options.insert_spaces {
-        " ".repeat(options.tab_size)
+    let indent_str = options.indent_unit();
+    let normalized;
+    let source = if options.normalize_keyword_case {
+        normalized = normalize_keyword_case(tree, source);
+        normalized.as_str()
     } else {
-        "\t".to_string()
+        source
     };
 
-    // Build a map: line_number -> indent_level.
-    // We use Option to track which lines have been explicitly set.
-    let line_count = source.lines().count().max(1);
-    let mut indent_levels: Vec<Option<i32>> = vec![None; line_count + 1];
-
-    // Track lines whose content should be joined to the previous line
-    // (e.g., `begin` after `for...do`)
-    let mut join_to_prev: Vec<bool> = vec![false; line_count + 1];
-
-    // Track lines that should be removed (blank lines between var and begin)
-    let mut remove_line: Vec<bool> = vec![false; line_count + 1];
-
     // Walk the tree to assign indent levels and join markers
-    assign_indentation(
-        tree.root_node(),
-        source,
-        &mut indent_levels,
-        &mut join_to_prev,
-        &mut remove_line,
-        0,
-    );
+    let line_count = source.lines().count().max(1);
+    let mut layout = LineLayout::new(line_count, options);
+    assign_indentation(tree.root_node(), source, &mut layout, 0);
+    let LineLayout {
+        levels: indent_levels,
+        join_to_prev,
+        remove_line,
+        begin_breaks,
+        ..
+    } = layout;
+
+    // Lines after the first of each multi-line block comment
+    let mut comment_lines: Vec<Option<(usize, usize)>> = vec![None; line_count + 1];
+    collect_block_comment_lines(tree.root_node(), &mut comment_lines);
 
     // Apply indentation, line joining, and basic spacing to each line
     let mut result = String::with_capacity(source.len());
     let source_lines: Vec<&str> = source.lines().collect();
     let mut line_idx = 0;
+    let mut after_directive = false;
+    let mut blank_run = 0;
     while line_idx < source_lines.len() {
         let line = source_lines[line_idx];
         let trimmed = line.trim();
 
+        // Block comment continuation lines move with the comment's first line
+        // and otherwise keep their text and relative indentation
+        if let Some((comment_line, comment_column)) = comment_lines[line_idx] {
+            let own_indent = &line[..line.len() - line.trim_start().len()];
+            let mut column = display_column(own_indent, options.tab_size);
+            let comment_prefix = source_lines[comment_line]
+                .get(..comment_column)
+                .unwrap_or_default();
+            if comment_prefix.trim().is_empty() {
+                let level = indent_levels[comment_line].unwrap_or(0).max(0) as usize;
+                let new_column = display_column(&indent_str.repeat(level), options.tab_size);
+                column = (column + new_column)
+                    .saturating_sub(display_column(comment_prefix, options.tab_size));
+            }
+            if !trimmed.is_empty() {
+                result.push_str(&options.indent_to(column));
+                result.push_str(line.trim());
+            }
+            result.push('\n');
+            blank_run = 0;
+            line_idx += 1;
+            continue;
+        }
+
         // Skip removed lines
         if remove_line.get(line_idx).copied().unwrap_or(false) {
             line_idx += 1;
@@ -65,14 +164,29 @@ pub fn format_document(tree: &Tree, source: &str, options: &FormatOptions) -> St
         }
 
         if trimmed.is_empty() {
-            // Preserve blank lines
+            // Preserve blank lines, up to `max_blank_lines` in a row
+            blank_run += 1;
+            if blank_run <= options.max_blank_lines {
+                result.push('\n');
+            }
+            line_idx += 1;
+            continue;
+        }
+        blank_run = 0;
+
+        // Preprocessor directives keep their column and content untouched
+        if is_directive_line(trimmed) {
+            result.push_str(line.trim_end());
             result.push('\n');
+            after_directive = true;
             line_idx += 1;
             continue;
         }
 
         // Check if this line should be joined to the previous line
-        if join_to_prev.get(line_idx).copied().unwrap_or(false) && !result.is_empty() {
+        let join = join_to_prev.get(line_idx).copied().unwrap_or(false) && !after_directive;
+        after_directive = false;
+        if join && !result.is_empty() {
             // Remove the trailing newline from the previous line
             if result.ends_with('\n') {
                 result.pop();
@@ -88,6 +202,20 @@ pub fn format_document(tree: &Tree, source: &str, options: &FormatOptions) -> St
         let level = indent_levels[line_idx].unwrap_or(0).max(0) as usize;
         let indent = indent_str.repeat(level);
 
+        // `begin` moves off the line of its control keyword
+        if let Some((column, depth)) = begin_breaks[line_idx] {
+            if let Some((head, begin)) = line.split_at_checked(column) {
+                result.push_str(&indent);
+                result.push_str(&apply_spacing(head.trim()));
+                result.push('\n');
+                result.push_str(&indent_str.repeat(depth.max(0) as usize));
+                result.push_str(&apply_spacing(begin.trim()));
+                result.push('\n');
+                line_idx += 1;
+                continue;
+            }
+        }
+
         // Apply basic spacing rules to the trimmed line content
         let formatted_line = apply_spacing(trimmed);
 
@@ -105,14 +233,397 @@ pub fn format_document(tree: &Tree, source: &str, options: &FormatOptions) -> St
         result.push('\n');
     }
 
+    if line_ending(source) == "\r\n" {
+        result = result.replace('\n', "\r\n");
+    }
     result
 }
 
-/// Set the indent level for `line` only if it hasn't been set yet.
-/// The first token to claim a line wins (leftmost on that line).
-fn set_line_indent(levels: &mut [Option<i32>], line: usize, depth: i32) {
-    if line < levels.len() && levels[line].is_none() {
-        levels[line] = Some(depth);
+/// The document's dominant line terminator: `"\r\n"` when most lines end
+/// with it, `"\n"` otherwise.
+pub fn line_ending(source: &str) -> &'static str {
+    let crlf = source.matches("\r\n").count();
+    if crlf > 0 && crlf * 2 >= source.matches('\n').count() {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Display column reached after `text`, with tabs advancing to the next
+/// multiple of `tab_size`.
+fn display_column(text: &str, tab_size: usize) -> usize {
+    let tab_size = tab_size.max(1);
+    text.chars().fold(0, |column, ch| match ch {
+        '\t' => (column / tab_size + 1) * tab_size,
+        _ => column + 1,
+    })
+}
+
+/// A replacement of the whole source lines `start_line..end_line` (end
+/// exclusive, line terminators included) with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineEdit {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub new_text: String,
+}
+
+/// Format only the lines `start_line..=end_line`.
+///
+/// The whole document is formatted so indentation context outside the
+/// selection is still taken into account, then the result is aligned with
+/// the source line by line and only changed lines intersecting the range
+/// are returned as edits.
+pub fn format_range(
+    tree: &Tree,
+    source: &str,
+    options: &FormatOptions,
+    start_line: usize,
+    end_line: usize,
+) -> Vec<LineEdit> {
+    let formatted = format_document(tree, source, options);
+    let ending = line_ending(source);
+    let source_lines: Vec<&str> = source.lines().collect();
+    let formatted_lines: Vec<&str> = formatted.lines().collect();
+
+    let mut edits = Vec::new();
+    for (src, out) in align_formatted_lines(&source_lines, &formatted_lines) {
+        if source_lines[src.clone()] == formatted_lines[out.clone()] {
+            continue;
+        }
+        let intersects = if src.is_empty() {
+            start_line <= src.start && src.start <= end_line
+        } else {
+            src.start <= end_line && src.end > start_line
+        };
+        if !intersects {
+            continue;
+        }
+
+        let mut new_text: String = formatted_lines[out]
+            .iter()
+            .map(|line| format!("{line}{ending}"))
+            .collect();
+        // The last source line has no terminator to replace.
+        if src.end == source_lines.len() && !source.ends_with('\n') {
+            new_text.truncate(new_text.len() - ending.len());
+        }
+        edits.push(LineEdit {
+            start_line: src.start,
+            end_line: src.end,
+            new_text,
+        });
+    }
+    edits
+}
+
+/// The leading whitespace of one line should become `indent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndentEdit {
+    pub line: usize,
+    /// Byte length of the line's current leading whitespace.
+    pub current_len: usize,
+    pub indent: String,
+}
+
+/// Re-indent `line` and the line before it after the user typed a trigger
+/// character (newline, `;`, or the `d` of `end`/`do`).
+///
+/// Only the innermost `block` enclosing `line` is walked; its own depth is
+/// taken from the indentation already present on its `begin` line. Blank
+/// lines inside the block get the statement depth, and a blank line after
+/// an unterminated `begin` is indented one level past it.
+pub fn format_on_type(
+    tree: &Tree,
+    source: &str,
+    options: &FormatOptions,
+    line: usize,
+) -> Vec<IndentEdit> {
+    let lines: Vec<&str> = source
+        .split('\n')
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .collect();
+    if line >= lines.len() {
+        return Vec::new();
+    }
+    let indent_unit = options.indent_unit();
+    let indent_width = |text: &str| -> usize {
+        text.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { options.tab_size } else { 1 })
+            .sum()
+    };
+
+    let mut layout = LineLayout::new(lines.len(), options);
+    let block = enclosing_block(tree.root_node(), line);
+    if let Some(block) = block {
+        let begin_line = block.start_position().row;
+        let depth = (indent_width(lines[begin_line]) / options.tab_size.max(1)) as i32;
+        assign_indentation(block, source, &mut layout, depth);
+        for level in layout
+            .levels
+            .iter_mut()
+            .take(block.end_position().row)
+            .skip(begin_line + 1)
+        {
+            level.get_or_insert(depth + 1);
+        }
+    }
+
+    let mut edits = Vec::new();
+    for target in line.saturating_sub(1)..=line {
+        let text = lines[target];
+        let trimmed = text.trim_start();
+        if is_directive_line(trimmed) {
+            continue;
+        }
+        let level = match layout.levels[target] {
+            Some(level) => level.max(0) as usize,
+            None if trimmed.is_empty() && target == line => {
+                // `begin` typed without its `end` yet: indent past it.
+                let Some(prev) = lines[..target].iter().rev().find(|l| !l.trim().is_empty()) else {
+                    continue;
+                };
+                if !prev.trim_end().to_ascii_lowercase().ends_with("begin") {
+                    continue;
+                }
+                indent_width(prev) / options.tab_size.max(1) + 1
+            }
+            None => continue,
+        };
+        let indent = indent_unit.repeat(level);
+        let current_len = text.len() - trimmed.len();
+        if text[..current_len] != indent {
+            edits.push(IndentEdit {
+                line: target,
+                current_len,
+                indent,
+            });
+        }
+    }
+    edits
+}
+
+/// Innermost `block` node whose span covers `line`.
+fn enclosing_block(node: Node, line: usize) -> Option<Node> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.start_position().row > line || child.end_position().row < line {
+            continue;
+        }
+        if let Some(inner) = enclosing_block(child, line) {
+            return Some(inner);
+        }
+        if child.kind() == "block" {
+            return Some(child);
+        }
+    }
+    None
+}
+
+/// Pair up groups of source lines with the formatted lines they produced.
+///
+/// Formatting only changes whitespace (and may join lines or drop blank
+/// ones), so groups are matched on their content with whitespace removed.
+fn align_formatted_lines(
+    source: &[&str],
+    formatted: &[&str],
+) -> Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> {
+    fn key(line: &str) -> String {
+        line.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    let mut groups = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < source.len() || j < formatted.len() {
+        let source_blank = source.get(i).map(|line| line.trim().is_empty());
+        let formatted_blank = formatted.get(j).map(|line| line.trim().is_empty());
+        match (source_blank, formatted_blank) {
+            (Some(true), Some(true)) => {
+                groups.push((i..i + 1, j..j + 1));
+                i += 1;
+                j += 1;
+                continue;
+            }
+            (Some(true), _) => {
+                groups.push((i..i + 1, j..j));
+                i += 1;
+                continue;
+            }
+            (_, Some(true)) => {
+                groups.push((i..i, j..j + 1));
+                j += 1;
+                continue;
+            }
+            (Some(false), Some(false)) => {}
+            _ => {
+                groups.push((i..source.len(), j..formatted.len()));
+                return groups;
+            }
+        }
+
+        let (source_start, formatted_start) = (i, j);
+        let mut source_key = key(source[i]);
+        let mut formatted_key = key(formatted[j]);
+        i += 1;
+        j += 1;
+        while source_key != formatted_key {
+            if source_key.len() < formatted_key.len() && i < source.len() {
+                source_key.push_str(&key(source[i]));
+                i += 1;
+            } else if formatted_key.len() < source_key.len() && j < formatted.len() {
+                formatted_key.push_str(&key(formatted[j]));
+                j += 1;
+            } else {
+                // Content diverged; treat everything left as one group.
+                groups.push((source_start..source.len(), formatted_start..formatted.len()));
+                return groups;
+            }
+        }
+        groups.push((source_start..i, formatted_start..j));
+    }
+    groups
+}
+
+/// Record, for each line after the first of a multi-line block comment, the
+/// comment's start line and column.
+fn collect_block_comment_lines(node: Node, lines: &mut [Option<(usize, usize)>]) {
+    if node.kind() == "block_comment" {
+        let start = node.start_position();
+        let end_line = node.end_position().row;
+        for line in lines.iter_mut().take(end_line + 1).skip(start.row + 1) {
+            *line = Some((start.row, start.column));
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_block_comment_lines(child, lines);
+    }
+}
+
+/// Per-line layout decisions collected while walking the CST.
+struct LineLayout {
+    begin_style: BeginStyle,
+    blank_line_before_begin: bool,
+    /// Indentation level of each line.
+    levels: Vec<Option<i32>>,
+    /// Lines whose content joins the previous line (e.g. `begin` after `do`).
+    join_to_prev: Vec<bool>,
+    /// Lines that are dropped (blank lines between `var` and `begin`).
+    remove_line: Vec<bool>,
+    /// Lines broken before a `begin`: its column and the depth of its new line.
+    begin_breaks: Vec<Option<(usize, i32)>>,
+}
+
+impl LineLayout {
+    fn new(line_count: usize, options: &FormatOptions) -> Self {
+        LineLayout {
+            begin_style: options.begin_style,
+            blank_line_before_begin: options.blank_line_before_begin,
+            levels: vec![None; line_count + 1],
+            join_to_prev: vec![false; line_count + 1],
+            remove_line: vec![false; line_count + 1],
+            begin_breaks: vec![None; line_count + 1],
+        }
+    }
+
+    /// Set the indent level for `line` only if it hasn't been set yet.
+    /// The first token to claim a line wins (leftmost on that line).
+    fn set_indent(&mut self, line: usize, depth: i32) {
+        if line < self.levels.len() && self.levels[line].is_none() {
+            self.levels[line] = Some(depth);
+        }
+    }
+}
+
+/// Line of the implicit `else` keyword in front of `node`: the node's own
+/// line when it starts with `else`, or the nearest non-blank line above it.
+fn else_keyword_line(source: &str, node: Node) -> Option<usize> {
+    let node_start = node.start_position().row;
+    let starts_with_else = |line: usize| {
+        source.lines().nth(line).is_some_and(|text| {
+            let text = text.trim_start();
+            text.get(..4)
+                .is_some_and(|word| word.eq_ignore_ascii_case("else"))
+                && !text[4..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+    };
+    if starts_with_else(node_start) {
+        return Some(node_start);
+    }
+    let line = (0..node_start)
+        .rev()
+        .find(|&line| !source.lines().nth(line).unwrap_or("").trim().is_empty())?;
+    starts_with_else(line).then_some(line)
+}
+
+/// Whether `node` directly follows an `else` on its own line (`else if`).
+fn follows_else_on_line(source: &str, node: Node) -> bool {
+    let position = node.start_position();
+    source
+        .lines()
+        .nth(position.row)
+        .and_then(|line| line.get(..position.column))
+        .and_then(|prefix| {
+            let prefix = prefix.trim_end();
+            let split = prefix.len().checked_sub(4)?;
+            Some((prefix.get(..split)?, prefix.get(split..)?))
+        })
+        .is_some_and(|(before, word)| {
+            word.eq_ignore_ascii_case("else")
+                && !before.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+}
+
+/// Place the `begin` of `block`, the body of an `if`/`for`/`while`/`with`
+/// at `depth`: join it to a `then`/`else`/`do` ending the line above, or
+/// break it onto its own line.
+fn place_body_begin(block: Node, source: &str, layout: &mut LineLayout, depth: i32) {
+    let begin = block.start_position();
+    let line = source.lines().nth(begin.row).unwrap_or_default();
+    let starts_line = line
+        .get(..begin.column)
+        .is_some_and(|prefix| prefix.trim().is_empty());
+
+    match layout.begin_style {
+        BeginStyle::SameLine => {
+            if !starts_line || begin.row == 0 || begin.row >= layout.join_to_prev.len() {
+                return;
+            }
+            // A comment between the keyword and `begin` keeps them apart
+            if block
+                .prev_sibling()
+                .is_some_and(|prev| matches!(prev.kind(), "line_comment" | "block_comment"))
+            {
+                return;
+            }
+            let previous = source
+                .lines()
+                .nth(begin.row - 1)
+                .unwrap_or_default()
+                .trim_end()
+                .to_ascii_lowercase();
+            let ends_with_keyword = ["then", "else", "do"].iter().any(|keyword| {
+                previous.strip_suffix(keyword).is_some_and(|rest| {
+                    !rest.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '"')
+                })
+            });
+            if ends_with_keyword {
+                layout.join_to_prev[begin.row] = true;
+            }
+        }
+        BeginStyle::NextLine => {
+            if starts_line {
+                layout.set_indent(begin.row, depth);
+            } else if begin.row < layout.begin_breaks.len() {
+                layout.begin_breaks[begin.row] = Some((begin.column, depth));
+            }
+        }
     }
 }
 
@@ -120,21 +631,36 @@ fn set_line_indent(levels: &mut [Option<i32>], line: usize, depth: i32) {
 ///
 /// `depth` is the indentation level of this node itself.
 /// The node decides what depth to pass to each of its children.
-fn assign_indentation(
-    node: Node,
-    source: &str,
-    levels: &mut [Option<i32>],
-    join_to_prev: &mut [bool],
-    remove_line: &mut [bool],
-    depth: i32,
-) {
+fn assign_indentation(node: Node, source: &str, layout: &mut LineLayout, depth: i32) {
     let kind = node.kind();
     let start_line = node.start_position().row;
     let end_line = node.end_position().row;
 
+    // Directives never claim a line; `format_document` leaves them in place
+    if matches!(
+        kind,
+        "region_directive" | "pragma_directive" | "preprocessor_directive"
+    ) {
+        return;
+    }
+
+    // Comments on their own line take the depth of the code around them; a
+    // trailing comment leaves its line to the code before it
+    if matches!(kind, "line_comment" | "block_comment") {
+        let line_prefix = source
+            .lines()
+            .nth(start_line)
+            .and_then(|line| line.get(..node.start_position().column))
+            .unwrap_or_default();
+        if line_prefix.trim().is_empty() {
+            layout.set_indent(start_line, depth);
+        }
+        return;
+    }
+
     // Leaf nodes: set the indent for their line
     if node.child_count() == 0 {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         return;
     }
 
@@ -145,13 +671,13 @@ fn assign_indentation(
     // We must set begin (start_line) and end (end_line) explicitly.
     if kind == "block" {
         // `begin` line at this depth
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         // `end` line at this depth
-        set_line_indent(levels, end_line, depth);
+        layout.set_indent(end_line, depth);
         // All children (statements, semicolons) are indented inside the block
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+            assign_indentation(child, source, layout, depth + 1);
         }
         return;
     }
@@ -160,11 +686,11 @@ fn assign_indentation(
     // `var` keyword is NOT a child node — it's implicit at the start of the span.
     if kind == "var_section" {
         // `var` line at this depth
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         // Variable declarations are indented
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+            assign_indentation(child, source, layout, depth + 1);
         }
         return;
     }
@@ -175,17 +701,17 @@ fn assign_indentation(
     // `{`/`}` at parent_depth, body members at parent_depth + 1.
     if is_object_declaration(kind) {
         // Set the object declaration's own start line (the keyword line)
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_line = child.start_position().row;
             let child_kind = child.kind();
             // Children on the same line as the object keyword (id, name, implements_clause)
             if child_line == start_line || child_kind == "{" || child_kind == "}" {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             } else {
                 // Body members: procedures, triggers, properties, sections
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+                assign_indentation(child, source, layout, depth + 1);
             }
         }
         return;
@@ -194,14 +720,14 @@ fn assign_indentation(
     // --- Braced sections: fields, keys, layout, actions, etc. ---
     // Section keyword is implicit at start_line.
     if is_braced_section(kind) {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_kind = child.kind();
             if child_kind == "{" || child_kind == "}" {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+                assign_indentation(child, source, layout, depth + 1);
             }
         }
         return;
@@ -209,15 +735,15 @@ fn assign_indentation(
 
     // --- Field/key/enum_value declarations with optional brace body ---
     if is_field_like_declaration(kind) {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_line = child.start_position().row;
             let child_kind = child.kind();
             if child_line == start_line || child_kind == "{" || child_kind == "}" {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+                assign_indentation(child, source, layout, depth + 1);
             }
         }
         return;
@@ -227,28 +753,38 @@ fn assign_indentation(
     // `procedure` keyword is implicit. All direct children at same depth.
     // Also: remove blank lines between var_section and block.
     if matches!(kind, "procedure_declaration" | "trigger_declaration") {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         let mut prev_child_end: Option<usize> = Some(start_line);
+        let mut prev_child_kind = None;
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_kind = child.kind();
             let child_start = child.start_position().row;
 
             // Remove blank lines between any parts of the procedure/trigger
-            // (signature → var, var → begin, signature → begin).
+            // (signature → var, var → begin, signature → begin), except
+            // for one between var and begin when `blank_line_before_begin`.
             if matches!(child_kind, "var_section" | "block") {
                 if let Some(prev_end) = prev_child_end {
+                    let mut keep_blank = layout.blank_line_before_begin
+                        && child_kind == "block"
+                        && prev_child_kind == Some("var_section");
                     for blank_line in (prev_end + 1)..child_start {
                         let line_text = source.lines().nth(blank_line).unwrap_or("");
-                        if line_text.trim().is_empty() && blank_line < remove_line.len() {
-                            remove_line[blank_line] = true;
+                        if line_text.trim().is_empty() && blank_line < layout.remove_line.len() {
+                            if keep_blank {
+                                keep_blank = false;
+                                continue;
+                            }
+                            layout.remove_line[blank_line] = true;
                         }
                     }
                 }
             }
 
             prev_child_end = Some(child.end_position().row);
-            assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+            prev_child_kind = Some(child_kind);
+            assign_indentation(child, source, layout, depth);
         }
         return;
     }
@@ -257,7 +793,7 @@ fn assign_indentation(
     // `if`, `then`, `else` keywords are implicit (not child nodes).
     // Children: condition expression, consequence block, [alternative block]
     if kind == "if_statement" {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         let consequence_id = node.child_by_field_name("consequence").map(|n| n.id());
         let alternative_id = node.child_by_field_name("alternative").map(|n| n.id());
         let mut cursor = node.walk();
@@ -266,76 +802,47 @@ fn assign_indentation(
             let is_consequence = consequence_id.is_some_and(|id| id == child.id());
             let is_alternative = alternative_id.is_some_and(|id| id == child.id());
 
-            if is_alternative {
-                // The `else` keyword is implicit and appears before the alternative node.
-                let alt_start = child.start_position().row;
-                let alt_line = source.lines().nth(alt_start).unwrap_or("");
-                if alt_line.trim().to_lowercase().starts_with("else") {
-                    set_line_indent(levels, alt_start, depth);
-                } else if alt_start > 0 {
-                    for else_line in (0..alt_start).rev() {
-                        let line_text = source.lines().nth(else_line).unwrap_or("");
-                        let trimmed = line_text.trim().to_lowercase();
-                        if trimmed.starts_with("else") {
-                            set_line_indent(levels, else_line, depth);
-                            break;
-                        }
-                        if !trimmed.is_empty() {
-                            break;
-                        }
-                    }
-                }
+            // The `else` keyword is implicit and appears before the alternative node.
+            let else_line = is_alternative
+                .then(|| else_keyword_line(source, child))
+                .flatten();
+            if let Some(line) = else_line {
+                layout.set_indent(line, depth);
             }
 
             if child_kind == "block" && (is_consequence || is_alternative) {
                 // For `if/else ... begin ... end`, keep `begin/end` aligned with `if/else`
                 // and indent only the statements inside the block by one level.
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                place_body_begin(child, source, layout, depth);
+                assign_indentation(child, source, layout, depth);
+            } else if child_kind == "if_statement" && follows_else_on_line(source, child) {
+                // `else if` continues the chain at the same depth
+                assign_indentation(child, source, layout, depth);
             } else if is_consequence || is_alternative {
                 // Single-statement consequence/alternative should still be indented.
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+                assign_indentation(child, source, layout, depth + 1);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             }
         }
         return;
     }
 
     // --- for/while/with statements ---
-    // When the body is a `block`, join `begin` to the statement line,
-    // put `end` at the statement's depth, body at depth + 2.
+    // A `block` body is placed per `begin_style`, with `end` at the
+    // statement's depth and the body at depth + 1.
+    // A single-statement body on its own line is indented one level.
     if matches!(kind, "for_statement" | "while_statement" | "with_statement") {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            let child_kind = child.kind();
-            if child_kind == "block" {
-                let block_start = child.start_position().row;
-                let block_end = child.end_position().row;
-
-                // If `begin` is on a different line from the statement, join it
-                if block_start != start_line && block_start < join_to_prev.len() {
-                    join_to_prev[block_start] = true;
-                }
-
-                // `end` at the for-statement's depth
-                set_line_indent(levels, block_end, depth);
-
-                // Body statements at depth + 2
-                // (begin is joined to for-line at depth, body needs extra indent)
-                let mut block_cursor = child.walk();
-                for block_child in child.children(&mut block_cursor) {
-                    assign_indentation(
-                        block_child,
-                        source,
-                        levels,
-                        join_to_prev,
-                        remove_line,
-                        depth + 2,
-                    );
-                }
+            if child.kind() == "block" {
+                place_body_begin(child, source, layout, depth);
+                assign_indentation(child, source, layout, depth);
+            } else if child.start_position().row > start_line {
+                assign_indentation(child, source, layout, depth + 1);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             }
         }
         return;
@@ -347,16 +854,16 @@ fn assign_indentation(
     // `repeat` (start_line) and `until` (end_line) at this depth,
     // body statements indented one level.
     if kind == "repeat_statement" {
-        set_line_indent(levels, start_line, depth);
-        set_line_indent(levels, end_line, depth);
+        layout.set_indent(start_line, depth);
+        layout.set_indent(end_line, depth);
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_start = child.start_position().row;
             if child_start == end_line {
                 // The condition expression is on the `until` line — same depth
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+                assign_indentation(child, source, layout, depth + 1);
             }
         }
         return;
@@ -364,31 +871,49 @@ fn assign_indentation(
 
     // --- case_statement ---
     if kind == "case_statement" {
-        set_line_indent(levels, start_line, depth);
-        set_line_indent(levels, end_line, depth); // `end` line
+        layout.set_indent(start_line, depth);
+        layout.set_indent(end_line, depth); // `end` line
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_kind = child.kind();
-            if child_kind == "case_branch" {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+            if matches!(child_kind, "case_branch" | "line_comment" | "block_comment") {
+                assign_indentation(child, source, layout, depth + 1);
+            } else if let Some(else_line) = (child.start_position().row > start_line)
+                .then(|| else_keyword_line(source, child))
+                .flatten()
+            {
+                // The `else` branch lines up with the other branches; its
+                // statement is indented like a branch body
+                layout.set_indent(else_line, depth + 1);
+                let body_depth = if child_kind == "block" && child.start_position().row == else_line
+                {
+                    depth + 1
+                } else {
+                    depth + 2
+                };
+                assign_indentation(child, source, layout, body_depth);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             }
         }
         return;
     }
 
     // --- case_branch ---
+    // `1, 2, 3:` — labels, including any continued on later lines, stay at
+    // the branch depth; the statement after `:` is indented one level.
     if kind == "case_branch" {
-        set_line_indent(levels, start_line, depth);
+        layout.set_indent(start_line, depth);
+        let mut after_colon = false;
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             let child_kind = child.kind();
-            if child_kind == "block" {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth + 1);
+            if after_colon && (child_kind == "block" || child.start_position().row > start_line) {
+                assign_indentation(child, source, layout, depth + 1);
             } else {
-                assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+                assign_indentation(child, source, layout, depth);
             }
+            after_colon |= child_kind == ":";
         }
         return;
     }
@@ -396,7 +921,131 @@ fn assign_indentation(
     // --- Default: pass depth through to children ---
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        assign_indentation(child, source, levels, join_to_prev, remove_line, depth);
+        assign_indentation(child, source, layout, depth);
+    }
+}
+
+/// Keywords lowercased wherever they appear as keyword tokens.
+const NORMALIZED_KEYWORDS: &[&str] = &[
+    "begin",
+    "end",
+    "if",
+    "then",
+    "else",
+    "for",
+    "to",
+    "downto",
+    "do",
+    "while",
+    "repeat",
+    "until",
+    "case",
+    "of",
+    "with",
+    "exit",
+    "var",
+    "procedure",
+    "trigger",
+    "local",
+    "internal",
+    "protected",
+    "not",
+    "and",
+    "or",
+    "xor",
+    "mod",
+    "div",
+    "in",
+    "array",
+    "temporary",
+];
+
+/// `source` with keyword tokens lowercased. Keywords are the source text the
+/// parse tree does not cover with a named child: identifiers, literals and
+/// comments are never touched, and neither is anything inside `ERROR` nodes.
+fn normalize_keyword_case(tree: &Tree, source: &str) -> String {
+    let mut ranges = Vec::new();
+    collect_keyword_ranges(tree.root_node(), source, &mut ranges);
+
+    let mut normalized = source.to_string();
+    for (start, end) in ranges {
+        // Keywords are ASCII, so lowercasing keeps every byte offset valid.
+        normalized[start..end].make_ascii_lowercase();
+    }
+    normalized
+}
+
+fn collect_keyword_ranges(node: Node, source: &str, ranges: &mut Vec<(usize, usize)>) {
+    let kind = node.kind();
+    if node.is_error()
+        || matches!(kind, "identifier" | "quoted_identifier")
+        || kind.ends_with("_literal")
+        || kind.ends_with("_comment")
+        || kind.ends_with("_directive")
+    {
+        return;
+    }
+
+    // The keyword that opens an object, section or field-like declaration
+    // (`Codeunit`, `Fields`, `Field`, `Area`) is lowercased whatever it is.
+    let mut lowercase_leading =
+        is_object_declaration(kind) || is_braced_section(kind) || is_field_like_declaration(kind);
+    let mut gap_start = node.start_byte();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_gap_keywords(
+            source,
+            gap_start,
+            child.start_byte(),
+            lowercase_leading,
+            ranges,
+        );
+        lowercase_leading = false;
+        collect_keyword_ranges(child, source, ranges);
+        gap_start = child.end_byte();
+    }
+    collect_gap_keywords(
+        source,
+        gap_start,
+        node.end_byte(),
+        lowercase_leading,
+        ranges,
+    );
+}
+
+/// Record the words in `source[start..end]` that are keywords not written
+/// in lowercase; with `lowercase_first`, the first word is taken regardless.
+fn collect_gap_keywords(
+    source: &str,
+    start: usize,
+    end: usize,
+    lowercase_first: bool,
+    ranges: &mut Vec<(usize, usize)>,
+) {
+    let Some(gap) = source.get(start..end) else {
+        return;
+    };
+    let bytes = gap.as_bytes();
+    let mut i = 0;
+    let mut first = true;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_alphabetic() {
+            i += 1;
+            continue;
+        }
+        let word_start = i;
+        while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+            i += 1;
+        }
+        let word = &gap[word_start..i];
+        let is_keyword = (first && lowercase_first)
+            || NORMALIZED_KEYWORDS
+                .iter()
+                .any(|keyword| keyword.eq_ignore_ascii_case(word));
+        if is_keyword && word.bytes().any(|b| b.is_ascii_uppercase()) {
+            ranges.push((start + word_start, start + i));
+        }
+        first = false;
     }
 }
 
@@ -445,6 +1094,63 @@ fn apply_spacing(line: &str) -> String {
             continue;
         }
 
+        // Quoted identifiers — preserve as-is
+        if ch == '"' {
+            result.push(ch);
+            i += 1;
+            while i < len && chars[i] != '"' {
+                result.push(chars[i]);
+                i += 1;
+            }
+            if i < len {
+                result.push(chars[i]); // closing quote
+                i += 1;
+            }
+            continue;
+        }
+
+        // Trailing comment — preserve as-is
+        if ch == '/' && i + 1 < len && chars[i + 1] == '/' {
+            result.extend(&chars[i..]);
+            break;
+        }
+
+        // Inline block comment — preserve as-is
+        if ch == '/' && i + 1 < len && chars[i + 1] == '*' {
+            let end = (i + 2..len.saturating_sub(1))
+                .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                .map_or(len, |j| j + 2);
+            result.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        // Member access `.` and enum/option access `::` bind tightly: no
+        // space on either side. A range `..` is passed through.
+        let scope = ch == ':' && chars.get(i + 1) == Some(&':');
+        let member = ch == '.' && chars.get(i + 1) != Some(&'.') && !result.ends_with('.');
+        if scope || member {
+            while result.ends_with(' ') {
+                result.pop();
+            }
+            result.push_str(if scope { "::" } else { "." });
+            i += if scope { 2 } else { 1 };
+            while i < len && chars[i] == ' ' {
+                i += 1;
+            }
+            continue;
+        }
+
+        // Lone colon (`X: Integer`, case label `1, 2:`): no space before
+        if ch == ':' && chars.get(i + 1) != Some(&'=') {
+            while result.ends_with(' ') {
+                result.pop();
+            }
+            result.push(':');
+            i += 1;
+            continue;
+        }
+
         // Assignment operator :=
         if ch == ':' && i + 1 < len && chars[i + 1] == '=' {
             // Ensure space before :=
@@ -509,6 +1215,28 @@ fn apply_spacing(line: &str) -> String {
             continue;
         }
 
+        // Comparison `=` and binary arithmetic operators; a sign (`-1`,
+        // `(+X)`) and an exponent sign (`1.5E-3`) are left attached
+        if ch == '='
+            || (matches!(ch, '+' | '-' | '*' | '/') && is_binary_operator_position(&result))
+        {
+            let exponent_sign = matches!(ch, '+' | '-')
+                && result.ends_with(['e', 'E'])
+                && result[..result.len() - 1].ends_with(|c: char| c.is_ascii_digit())
+                && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+            if !exponent_sign {
+                if !result.ends_with(' ') && !result.is_empty() {
+                    result.push(' ');
+                }
+                result.push(ch);
+                i += 1;
+                if i < len && chars[i] != ' ' {
+                    result.push(' ');
+                }
+                continue;
+            }
+        }
+
         // Comma: ensure space after, no space before
         if ch == ',' {
             while result.ends_with(' ') {
@@ -550,6 +1278,53 @@ fn apply_spacing(line: &str) -> String {
 
 // --- Helper predicates ---
 
+/// Whether a `+`/`-`/`*`/`/` following the already formatted `preceding`
+/// text is a binary operator: it follows an operand rather than the start of
+/// the line, an opening bracket, another operator or a keyword.
+fn is_binary_operator_position(preceding: &str) -> bool {
+    let preceding = preceding.trim_end();
+    let Some(last) = preceding.chars().last() else {
+        return false;
+    };
+    if matches!(last, ')' | ']' | '"' | '\'') {
+        return true;
+    }
+    if !(last.is_ascii_alphanumeric() || last == '_') {
+        return false;
+    }
+    let word_start = preceding
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let word = &preceding[word_start..];
+    !OPERAND_KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(word))
+}
+
+/// Keywords after which `+`/`-` can only be a sign.
+const OPERAND_KEYWORDS: &[&str] = &[
+    "and", "div", "do", "downto", "else", "exit", "if", "in", "mod", "not", "of", "or", "then",
+    "to", "until", "xor",
+];
+
+/// `#if`/`#elif`/`#else`/`#endif`/`#define`/`#undef`, `#pragma` and
+/// `#region`/`#endregion` lines.
+fn is_directive_line(trimmed: &str) -> bool {
+    let Some(rest) = trimmed.strip_prefix('#') else {
+        return false;
+    };
+    let word: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    matches!(
+        word.as_str(),
+        "if" | "elif" | "else" | "endif" | "define" | "undef" | "pragma" | "region" | "endregion"
+    )
+}
+
 fn is_object_declaration(kind: &str) -> bool {
     matches!(
         kind,
@@ -596,11 +1371,170 @@ mod tests {
         format_document(&tree, source, &options)
     }
 
+    fn format_with_begin_style(source: &str, begin_style: BeginStyle) -> String {
+        let tree = al_parser::parse(source).expect("failed to parse");
+        let options = FormatOptions {
+            begin_style,
+            ..Default::default()
+        };
+        format_document(&tree, source, &options)
+    }
+
+    fn on_type(source: &str, line: usize) -> Vec<(usize, String)> {
+        let tree = al_parser::parse(source).expect("failed to parse");
+        format_on_type(&tree, source, &FormatOptions::default(), line)
+            .into_iter()
+            .map(|edit| (edit.line, edit.indent))
+            .collect()
+    }
+
     #[test]
-    fn test_basic_codeunit_indentation() {
-        let input = r#"codeunit 50100 Test
-{
-procedure Hello()
+    fn test_on_type_indents_new_line_after_begin() {
+        let source = "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n\n    end;\n}";
+        assert_eq!(on_type(source, 4), vec![(4, " ".repeat(8))]);
+    }
+
+    #[test]
+    fn test_on_type_dedents_end() {
+        let source = "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n        Message('a');\n        end;\n}";
+        assert_eq!(on_type(source, 5), vec![(5, " ".repeat(4))]);
+    }
+
+    #[test]
+    fn test_on_type_indents_statement_in_nested_block() {
+        let source = "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n        if true then begin\n    Message('a');\n        end;\n    end;\n}";
+        assert_eq!(on_type(source, 5), vec![(5, " ".repeat(12))]);
+        // The previous line is re-indented too; `end;` itself is already right.
+        assert_eq!(on_type(source, 6), vec![(5, " ".repeat(12))]);
+    }
+
+    #[test]
+    fn test_indent_snippet_follows_insertion_column() {
+        let body = "trigger OnRun()\nbegin\n\t$0\nend;";
+        let spaces = FormatOptions {
+            tab_size: 2,
+            insert_spaces: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            indent_snippet(body, "    ", &spaces),
+            "trigger OnRun()\n    begin\n      $0\n    end;"
+        );
+        let tabs = FormatOptions {
+            tab_size: 4,
+            insert_spaces: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            indent_snippet(body, "\t", &tabs),
+            "trigger OnRun()\n\tbegin\n\t\t$0\n\tend;"
+        );
+    }
+
+    #[test]
+    fn test_on_type_after_unterminated_begin() {
+        let source = "codeunit 50100 Test\n{\n    procedure Run()\n    begin\n";
+        assert_eq!(on_type(source, 4), vec![(4, " ".repeat(8))]);
+    }
+
+    /// Samples for the idempotence check: the repo fixtures plus a few
+    /// shapes (loops, nested blocks, blank lines) that stress the formatter.
+    const IDEMPOTENCE_CORPUS: &[&str] = &[
+        include_str!("../../../test-fixtures/hello.al"),
+        include_str!("../../../test-fixtures/customer_table.al"),
+        include_str!("../../../test-fixtures/sample_page.al"),
+        include_str!("../../../test-fixtures/addressProvider_interface.al"),
+        r#"codeunit 50100 Test
+{
+    procedure HelloWorld()
+    var
+        Counter: Integer;
+    begin
+        for Counter := 1 to 5 do
+            begin
+                Counter += 1;
+            end;
+        while Counter > 0 do begin
+            Counter -= 1;
+        end;
+        while Counter < 5 do
+        Counter += 1;
+    end;
+}"#,
+        r#"codeunit 50100 Test
+{
+procedure DoWork()
+var
+X: Integer;
+
+begin
+if X = 1 then begin
+X := 2;
+end else
+X := 3;
+
+
+repeat
+X -= 1;
+until X = 0;
+end;
+}"#,
+        r#"codeunit 50100 Test
+{
+    procedure Outer()
+    begin
+        if true then
+            if false then begin
+                Message('a');
+            end;
+        case 1 of
+            1:
+                Message('b');
+        end;
+    end;
+}"#,
+        r#"codeunit 50100 Test
+{
+    procedure Branches()
+    begin
+        if X = 1 then begin
+            X := 2;
+        end else if X = 2 then
+            X := 3
+        else
+            X := 4;
+        case X of
+            1, 2:
+                begin
+                    X := 0;
+                end;
+            else
+                for X := 1 to 3 do
+                    while X > 0 do begin
+                        X -= 1;
+                    end;
+        end;
+    end;
+}"#,
+    ];
+
+    #[test]
+    fn test_formatting_is_idempotent() {
+        for (i, sample) in IDEMPOTENCE_CORPUS.iter().enumerate() {
+            let once = format(sample);
+            let twice = format(&once);
+            assert_eq!(
+                twice, once,
+                "formatting sample {i} twice changed the output"
+            );
+        }
+    }
+
+    #[test]
+    fn test_basic_codeunit_indentation() {
+        let input = r#"codeunit 50100 Test
+{
+procedure Hello()
 begin
 end;
 }"#;
@@ -663,12 +1597,10 @@ end;
 {
     procedure DoWork()
     begin
-        if x > 0 then
-        begin
+        if x > 0 then begin
             y := 1;
         end
-        else
-        begin
+        else begin
             y := 2;
         end;
     end;
@@ -751,6 +1683,169 @@ procedure GetAddress(): Text;
         assert!(result.contains("\n\n"), "blank lines should be preserved");
     }
 
+    #[test]
+    fn test_spacing_around_binary_operators() {
+        assert_eq!(apply_spacing("X:=A+B*C;"), "X := A + B * C;");
+        assert_eq!(apply_spacing("if X=1 then"), "if X = 1 then");
+        assert_eq!(apply_spacing("Total := (A-B)/2;"), "Total := (A - B) / 2;");
+        assert_eq!(apply_spacing("Rec.\"No.\"+Suffix"), "Rec.\"No.\" + Suffix");
+    }
+
+    #[test]
+    fn test_spacing_collapses_member_access() {
+        assert_eq!(apply_spacing("Rec . Field := 1;"), "Rec.Field := 1;");
+        assert_eq!(
+            apply_spacing("Total:=Rec .Amount+Line. \"Line Amount\";"),
+            "Total := Rec.Amount + Line.\"Line Amount\";"
+        );
+        assert_eq!(
+            apply_spacing("Rec . SetRange(X, 1);"),
+            "Rec.SetRange(X, 1);"
+        );
+        assert_eq!(apply_spacing("1..10:"), "1..10:");
+        assert_eq!(apply_spacing("X := 1.5;"), "X := 1.5;");
+    }
+
+    #[test]
+    fn test_spacing_collapses_enum_access() {
+        assert_eq!(
+            apply_spacing("Status := MyEnum :: Value;"),
+            "Status := MyEnum::Value;"
+        );
+        assert_eq!(
+            apply_spacing("if Status=\"My Enum\" ::\"Value A\" then"),
+            "if Status = \"My Enum\"::\"Value A\" then"
+        );
+        assert_eq!(
+            apply_spacing("X:=Database ::Customer+1;"),
+            "X := Database::Customer + 1;"
+        );
+    }
+
+    #[test]
+    fn test_spacing_leaves_unary_minus_and_literals() {
+        assert_eq!(apply_spacing("X := -1;"), "X := -1;");
+        assert_eq!(apply_spacing("exit(-1);"), "exit(-1);");
+        assert_eq!(apply_spacing("if X < -1 then"), "if X < -1 then");
+        assert_eq!(apply_spacing("Call(A, -B);"), "Call(A, -B);");
+        assert_eq!(apply_spacing("X := 1.5E-3;"), "X := 1.5E-3;");
+        assert_eq!(
+            apply_spacing("Message('A+B=C'); // a-b"),
+            "Message('A+B=C'); // a-b"
+        );
+        assert_eq!(apply_spacing("Rec.\"Sell-to No.\""), "Rec.\"Sell-to No.\"");
+    }
+
+    #[test]
+    fn test_comment_in_procedure_body_takes_body_indent() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        X: Integer;
+    begin
+  // leading comment
+        X := 1; // trailing a+b
+        case X of
+// branch comment
+            1: exit;
+        end;
+    end;
+}
+"#;
+        let expected = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        X: Integer;
+    begin
+        // leading comment
+        X := 1; // trailing a+b
+        case X of
+            // branch comment
+            1: exit;
+        end;
+    end;
+}
+"#;
+        assert_eq!(format(input), expected);
+    }
+
+    #[test]
+    fn test_multi_line_block_comment_keeps_shape() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure Run()
+    begin
+  /* Totals:
+       a+b := c,d
+
+     */
+        exit;
+    end;
+}
+"#;
+        let expected = r#"codeunit 50100 Test
+{
+    procedure Run()
+    begin
+        /* Totals:
+             a+b := c,d
+
+           */
+        exit;
+    end;
+}
+"#;
+        assert_eq!(format(input), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn test_strips_trailing_whitespace() {
+        let input = "codeunit 50100 Test \t\n{\n    // note \t \n    procedure Run()\t\n    begin  \n  \t\n        /* block \n           comment */  \n#if DEBUG  \n        exit;\t\n#endif \n    end;\n}  \n";
+        let result = format(input);
+        for line in result.lines() {
+            assert_eq!(line, line.trim_end(), "trailing whitespace in {line:?}");
+        }
+        assert!(result.contains("\n    // note\n"));
+        assert!(result.contains("\n           comment */\n"));
+    }
+
+    #[test]
+    fn test_preserves_crlf_line_endings() {
+        let input =
+            "codeunit 50100 Test\r\n{\r\nprocedure Run()\r\nbegin\r\nx:=1;\r\nend;\r\n}\r\n";
+        let expected = "codeunit 50100 Test\r\n{\r\n    procedure Run()\r\n    begin\r\n        x := 1;\r\n    end;\r\n}\r\n";
+        assert_eq!(format(input), expected);
+
+        let tree = al_parser::parse(input).unwrap();
+        let edits = format_range(&tree, input, &FormatOptions::default(), 4, 4);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "        x := 1;\r\n");
+    }
+
+    #[test]
+    fn test_line_ending_detection() {
+        assert_eq!(line_ending("a\r\nb\r\nc\n"), "\r\n");
+        assert_eq!(line_ending("a\nb\nc\r\n"), "\n");
+        assert_eq!(line_ending("a"), "\n");
+    }
+
+    #[test]
+    fn test_collapses_blank_line_runs() {
+        let input = "codeunit 50100 Test\n{\n    procedure Hello()\n    begin\n    end;\n\n\n\n\n    procedure World()\n    begin\n    end;\n}\n";
+        let expected = "codeunit 50100 Test\n{\n    procedure Hello()\n    begin\n    end;\n\n    procedure World()\n    begin\n    end;\n}\n";
+        assert_eq!(format(input), expected);
+
+        let tree = al_parser::parse(input).unwrap();
+        let options = FormatOptions {
+            max_blank_lines: 2,
+            ..Default::default()
+        };
+        assert!(format_document(&tree, input, &options).contains("end;\n\n\n    procedure World"));
+    }
+
     #[test]
     fn test_spacing_around_assignment() {
         let input = r#"codeunit 50100 Test
@@ -829,7 +1924,7 @@ end;
         Counter: Integer;
     begin
         for Counter := 1 to 5 do begin
-                Counter += 1;
+            Counter += 1;
         end;
     end;
 }
@@ -837,6 +1932,29 @@ end;
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_loop_single_statement_body_indented() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure Countdown(X: Integer)
+    begin
+        while X > 0 do
+        X -= 1;
+    end;
+}"#;
+        let result = format(input);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure Countdown(X: Integer)
+    begin
+        while X > 0 do
+            X -= 1;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+    }
+
     #[test]
     fn test_no_blank_lines_between_var_and_begin() {
         let input = r#"codeunit 50100 Test
@@ -863,6 +1981,68 @@ end;
         assert_eq!(result, expected);
     }
 
+    const BLANK_BEFORE_BEGIN_SAMPLE: &str = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+
+    var
+        X: Integer;
+
+
+
+    begin
+        X := 1;
+    end;
+}"#;
+
+    fn format_with_blank_line_before_begin(blank_line_before_begin: bool) -> String {
+        let tree = al_parser::parse(BLANK_BEFORE_BEGIN_SAMPLE).expect("failed to parse");
+        let options = FormatOptions {
+            max_blank_lines: 2,
+            blank_line_before_begin,
+            ..Default::default()
+        };
+        format_document(&tree, BLANK_BEFORE_BEGIN_SAMPLE, &options)
+    }
+
+    #[test]
+    fn test_blank_line_before_begin_off_removes_the_gap() {
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+    begin
+        X := 1;
+    end;
+}
+"#;
+        assert_eq!(format_with_blank_line_before_begin(false), expected);
+    }
+
+    #[test]
+    fn test_blank_line_before_begin_keeps_exactly_one() {
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        X: Integer;
+
+    begin
+        X := 1;
+    end;
+}
+"#;
+        let result = format_with_blank_line_before_begin(true);
+        assert_eq!(result, expected);
+        let tree = al_parser::parse(&result).expect("failed to parse");
+        let options = FormatOptions {
+            blank_line_before_begin: true,
+            ..Default::default()
+        };
+        assert_eq!(format_document(&tree, &result, &options), expected);
+    }
+
     #[test]
     fn test_no_blank_lines_between_procedure_and_var() {
         let input = r#"codeunit 50100 Test
@@ -953,6 +2133,53 @@ end;
         );
     }
 
+    #[test]
+    fn test_preprocessor_directives_stay_put() {
+        let input = r#"codeunit 50100 Test
+{
+#if not CLEAN22
+procedure Legacy()
+begin
+Message('x');
+end;
+#endif
+
+    procedure Current()
+    begin
+    #pragma warning disable AL0432
+        Message('y');
+#pragma warning restore AL0432
+    end;
+}"#;
+        let result = format(input);
+        let expected = r#"codeunit 50100 Test
+{
+#if not CLEAN22
+    procedure Legacy()
+    begin
+        Message('x');
+    end;
+#endif
+
+    procedure Current()
+    begin
+    #pragma warning disable AL0432
+        Message('y');
+#pragma warning restore AL0432
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+        assert_eq!(format(&result), result);
+    }
+
+    #[test]
+    fn test_on_type_skips_directive_line() {
+        let source = "codeunit 50100 Test\n{\n    procedure A()\n    begin\n#if CLEAN22\n        Message('x');\n#endif\n    end;\n}\n";
+        assert!(on_type(source, 4).is_empty());
+        assert!(on_type(source, 6).iter().all(|(line, _)| *line != 6));
+    }
+
     #[test]
     fn test_if_then_single_statement_else_block_indentation() {
         let input = r#"codeunit 50100 Test
@@ -991,4 +2218,388 @@ end;
 "#;
         assert_eq!(result, expected);
     }
+
+    const BEGIN_STYLE_SAMPLE: &str = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        I: Integer;
+    begin
+        if I = 0 then begin
+            I := 1;
+        end else
+        begin
+            I := 2;
+        end;
+        for I := 1 to 5 do
+        begin
+            Message('%1', I);
+        end;
+        while I > 0 do begin
+            I -= 1;
+        end;
+    end;
+}"#;
+
+    #[test]
+    fn test_begin_style_same_line() {
+        let result = format_with_begin_style(BEGIN_STYLE_SAMPLE, BeginStyle::SameLine);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        I: Integer;
+    begin
+        if I = 0 then begin
+            I := 1;
+        end else begin
+            I := 2;
+        end;
+        for I := 1 to 5 do begin
+            Message('%1', I);
+        end;
+        while I > 0 do begin
+            I -= 1;
+        end;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+        assert_eq!(
+            format_with_begin_style(&result, BeginStyle::SameLine),
+            result
+        );
+    }
+
+    #[test]
+    fn test_begin_style_next_line() {
+        let result = format_with_begin_style(BEGIN_STYLE_SAMPLE, BeginStyle::NextLine);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        I: Integer;
+    begin
+        if I = 0 then
+        begin
+            I := 1;
+        end else
+        begin
+            I := 2;
+        end;
+        for I := 1 to 5 do
+        begin
+            Message('%1', I);
+        end;
+        while I > 0 do
+        begin
+            I -= 1;
+        end;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+        assert_eq!(
+            format_with_begin_style(&result, BeginStyle::NextLine),
+            result
+        );
+    }
+
+    #[test]
+    fn test_begin_style_same_line_keeps_comment_before_begin() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        if true then // explain
+        begin
+            Message('a');
+        end;
+    end;
+}
+"#;
+        assert_eq!(format(input), input);
+    }
+
+    #[test]
+    fn test_case_multi_value_labels() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        case X of
+        1 ,2,3 :
+        Y := 1;
+        4,
+        5:
+        Y := 2;
+        end;
+    end;
+}"#;
+        let result = format(input);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        case X of
+            1, 2, 3:
+                Y := 1;
+            4,
+            5:
+                Y := 2;
+        end;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_case_branch_with_block() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        case X of
+            1, 2:
+            begin
+            Y := 1;
+            Z := 2;
+            end;
+        end;
+    end;
+}"#;
+        let result = format(input);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        case X of
+            1, 2:
+                begin
+                    Y := 1;
+                    Z := 2;
+                end;
+        end;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_else_if_chain_stays_at_if_depth() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        if X = 1 then begin
+            Y := 1;
+        end else if X = 2 then begin
+            Y := 2;
+        end else
+            if X = 3 then
+                Y := 3
+            else
+                Y := 4;
+    end;
+}"#;
+        let result = format(input);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        if X = 1 then begin
+            Y := 1;
+        end else if X = 2 then begin
+            Y := 2;
+        end else
+            if X = 3 then
+                Y := 3
+            else
+                Y := 4;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_case_else_lines_up_with_branches() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        case X of
+        1:
+        Y := 1;
+        else
+        Y := 2;
+        end;
+        case X of
+            1:
+                Y := 1;
+        else begin
+        Y := 2;
+        end;
+        end;
+    end;
+}"#;
+        let result = format(input);
+        let expected = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    begin
+        case X of
+            1:
+                Y := 1;
+            else
+                Y := 2;
+        end;
+        case X of
+            1:
+                Y := 1;
+            else begin
+                Y := 2;
+            end;
+        end;
+    end;
+}
+"#;
+        assert_eq!(result, expected);
+    }
+
+    fn apply_line_edits(source: &str, edits: &[LineEdit]) -> String {
+        let lines: Vec<&str> = source.split_inclusive('\n').collect();
+        let mut result = String::new();
+        let mut line = 0;
+        for edit in edits {
+            result.push_str(&lines[line..edit.start_line].concat());
+            result.push_str(&edit.new_text);
+            line = edit.end_line;
+        }
+        result.push_str(&lines[line..].concat());
+        result
+    }
+
+    #[test]
+    fn test_format_range_only_touches_selected_procedure() {
+        let input = r#"codeunit 50100 Test
+{
+procedure First()
+var
+x: Integer;
+begin
+x:=1;
+end;
+
+procedure Second()
+var
+y: Integer;
+begin
+y:=2;
+end;
+}"#;
+        let tree = al_parser::parse(input).unwrap();
+        // Lines 12..=13 hold the body of `Second`.
+        let edits = format_range(&tree, input, &FormatOptions::default(), 12, 13);
+        assert!(!edits.is_empty());
+        assert!(edits.iter().all(|e| e.start_line >= 12 && e.end_line <= 14));
+
+        let result = apply_line_edits(input, &edits);
+        let expected = r#"codeunit 50100 Test
+{
+procedure First()
+var
+x: Integer;
+begin
+x:=1;
+end;
+
+procedure Second()
+var
+y: Integer;
+    begin
+        y := 2;
+end;
+}"#;
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_format_range_handles_joined_lines() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure Loop()
+    var
+        i: Integer;
+    begin
+        for i := 1 to 10 do
+        begin
+            i:=i;
+        end;
+    end;
+}
+"#;
+        let tree = al_parser::parse(input).unwrap();
+        let edits = format_range(&tree, input, &FormatOptions::default(), 6, 9);
+        assert_eq!(apply_line_edits(input, &edits), format(input));
+    }
+
+    fn format_normalizing_keywords(source: &str) -> String {
+        let tree = al_parser::parse(source).expect("failed to parse");
+        let options = FormatOptions {
+            normalize_keyword_case: true,
+            ..Default::default()
+        };
+        format_document(&tree, source, &options)
+    }
+
+    #[test]
+    fn test_normalize_keyword_case_lowercases_keywords() {
+        let input = r#"Codeunit 50100 Test
+{
+    Procedure Run()
+    VAR
+        Total: Integer;
+    BEGIN
+        IF Total > 0 THEN
+            EXIT;
+        Total := 1;
+    End;
+}"#;
+        let expected = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        Total: Integer;
+    begin
+        if Total > 0 then
+            exit;
+        Total := 1;
+    end;
+}
+"#;
+        assert_eq!(format_normalizing_keywords(input), expected);
+        // Off by default.
+        assert!(format(input).contains("BEGIN"));
+    }
+
+    #[test]
+    fn test_normalize_keyword_case_leaves_strings_and_identifiers() {
+        let input = r#"codeunit 50100 Test
+{
+    procedure Run()
+    var
+        BeginDate: Date;
+    Begin
+        Message('Begin END If');
+        // Begin here
+        BeginDate := Today;
+    end;
+}
+"#;
+        let expected = input.replace("    Begin\n", "    begin\n");
+        assert_eq!(format_normalizing_keywords(input), expected);
+    }
 }
@@ -1,6 +1,6 @@
 use tree_sitter::{Node, Tree};
 
-use crate::ast::{extract_name, AlSymbol, AlSymbolKind};
+use crate::ast::{extract_name, split_namespace_qualifier, AlSymbol, AlSymbolKind};
 use crate::symbols::DocumentSymbolTable;
 
 /// Result of resolving a symbol at a position.
@@ -23,8 +23,13 @@ pub struct IdentifierContext<'a> {
 #[derive(Debug)]
 pub struct CallContext<'a> {
     pub function_name: String,
+    /// The first of `candidates`, for callers that don't care about overloads.
     pub symbol: Option<&'a AlSymbol>,
+    /// Every procedure/trigger in scope with the call's name.
+    pub candidates: Vec<&'a AlSymbol>,
     pub active_parameter: usize,
+    /// Number of arguments written so far (0 for an empty argument list).
+    pub argument_count: usize,
     _marker: std::marker::PhantomData<&'a ()>,
 }
 
@@ -41,6 +46,8 @@ pub struct FoldingArea {
     pub start_line: usize,
     pub end_line: usize,
     pub kind: FoldingAreaKind,
+    /// Text to show while folded, e.g. the name of a `#region`.
+    pub collapsed_text: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -306,6 +313,104 @@ fn collect_identifier_nodes<'a, F>(
     }
 }
 
+/// Whether the identifier spanning `start..end` is written to: the target of
+/// `:=` or a compound assignment (`+=`, ...), a `for` loop variable, or an
+/// argument passed to a `var` parameter of a procedure declared in this document.
+pub fn is_write_reference(
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    start: tree_sitter::Point,
+    end: tree_sitter::Point,
+) -> bool {
+    let Some(node) = tree.root_node().descendant_for_point_range(start, end) else {
+        return false;
+    };
+    if !matches!(node.kind(), "identifier" | "quoted_identifier") {
+        return false;
+    }
+
+    let mut expr = node;
+    while let Some(parent) = expr.parent() {
+        if parent.kind() != "primary_expression" {
+            break;
+        }
+        expr = parent;
+    }
+    let Some(parent) = expr.parent() else {
+        return false;
+    };
+
+    match parent.kind() {
+        "assignment_statement" | "compound_assignment_statement" => parent
+            .child_by_field_name("target")
+            .is_some_and(|target| target.id() == expr.id()),
+        "for_statement" => parent
+            .child_by_field_name("variable")
+            .is_some_and(|variable| variable.id() == expr.id()),
+        "argument_list" => is_var_argument(parent, expr, source, symbol_table, tree),
+        _ => false,
+    }
+}
+
+fn is_var_argument(
+    argument_list: Node,
+    argument: Node,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    tree: &Tree,
+) -> bool {
+    let Some(call) = argument_list.parent() else {
+        return false;
+    };
+    if call.kind() != "function_call" {
+        return false;
+    }
+    let Some(function) = call.child_by_field_name("function") else {
+        return false;
+    };
+    let mut cursor = argument_list.walk();
+    let Some(index) = argument_list
+        .named_children(&mut cursor)
+        .position(|arg| arg.id() == argument.id())
+    else {
+        return false;
+    };
+
+    let function_name = extract_name(function, source);
+    let Some(procedure) = symbol_table
+        .lookup_in_scope(&function_name, call.start_byte())
+        .into_iter()
+        .find(|sym| matches!(sym.kind, AlSymbolKind::Procedure))
+    else {
+        return false;
+    };
+    let Some(declaration) = tree
+        .root_node()
+        .descendant_for_byte_range(procedure.start_byte, procedure.end_byte)
+    else {
+        return false;
+    };
+    let Some(parameters) = declaration.child_by_field_name("parameters") else {
+        return false;
+    };
+
+    let mut cursor = parameters.walk();
+    let Some(parameter) = parameters
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "parameter")
+        .nth(index)
+    else {
+        return false;
+    };
+    let Some(name) = parameter.child_by_field_name("name") else {
+        return false;
+    };
+    source
+        .get(parameter.start_byte()..name.start_byte())
+        .is_some_and(|prefix| prefix.trim().eq_ignore_ascii_case("var"))
+}
+
 /// Walk up ancestors from the cursor position to find an enclosing function/method call.
 /// Count commas before the cursor to determine the active parameter index.
 pub fn find_call_context<'a>(
@@ -314,26 +419,19 @@ pub fn find_call_context<'a>(
     symbol_table: &'a DocumentSymbolTable,
     byte_offset: usize,
 ) -> Option<CallContext<'a>> {
-    let root = tree.root_node();
-    let mut node = find_deepest_node(root, byte_offset)?;
-
-    // Walk up to find function_call or method_call
-    loop {
-        let kind = node.kind();
-        if kind == "function_call" || kind == "method_call" {
-            break;
-        }
-        node = node.parent()?;
-    }
+    let node = enclosing_call_at_offset(tree, byte_offset)?;
 
     // Find the function name
     let name_node = node
-        .child_by_field_name("name")
+        .child_by_field_name("function")
+        .or_else(|| node.child_by_field_name("name"))
         .or_else(|| node.child_by_field_name("method"))?;
     let function_name = extract_name(name_node, source);
 
-    // Find argument_list and count commas before byte_offset
+    // Count the call's own commas before byte_offset; nested calls have theirs
+    // inside their own argument_list
     let mut active_parameter = 0;
+    let mut argument_count = 0;
     if let Some(args) = node.child_by_field_name("arguments") {
         let mut cursor = args.walk();
         for child in args.children(&mut cursor) {
@@ -341,22 +439,47 @@ pub fn find_call_context<'a>(
                 active_parameter += 1;
             }
         }
+        argument_count = args.named_child_count();
     }
 
-    // Try to resolve the function name to a symbol
-    let symbol = symbol_table
+    // Resolve the function name to every same-named procedure (overloads)
+    let candidates: Vec<&AlSymbol> = symbol_table
         .lookup_in_scope(&function_name, node.start_byte())
         .into_iter()
-        .find(|s| matches!(s.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger));
+        .filter(|s| matches!(s.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger))
+        .collect();
 
     Some(CallContext {
         function_name,
-        symbol,
+        symbol: candidates.first().copied(),
+        candidates,
         active_parameter,
+        argument_count,
         _marker: std::marker::PhantomData,
     })
 }
 
+/// Innermost `function_call`/`method_call` whose parentheses enclose
+/// `byte_offset`. A cursor on a call's name or right
+/// after its closing `)` belongs to the enclosing call, if any.
+pub fn enclosing_call_at_offset(tree: &Tree, byte_offset: usize) -> Option<Node<'_>> {
+    let mut node = find_deepest_node(tree.root_node(), byte_offset)?;
+    loop {
+        if matches!(node.kind(), "function_call" | "method_call") {
+            let mut cursor = node.walk();
+            let mut parens = node
+                .children(&mut cursor)
+                .filter(|child| matches!(child.kind(), "(" | ")"));
+            if let (Some(open), Some(close)) = (parens.next(), parens.last()) {
+                if open.end_byte() <= byte_offset && byte_offset <= close.start_byte() {
+                    return Some(node);
+                }
+            }
+        }
+        node = node.parent()?;
+    }
+}
+
 /// Detect whether the cursor is in a dot-member context and return the identifier
 /// immediately before the dot.
 pub fn dot_context_at_offset(
@@ -417,21 +540,16 @@ fn find_deepest_node(node: Node, byte_offset: usize) -> Option<Node> {
 
 /// Extract the object kind and object name from a type string.
 /// Example: `Record "Customer"` -> `("table", "Customer")`.
+/// `temporary`, `array[..] of` and `List`/`Dictionary` wrappers are looked
+/// through, so `array[5] of Codeunit X` -> `("codeunit", "X")`. Collection
+/// elements only resolve when they are objects: `List of [Text]` -> `None`.
 pub fn extract_type_object_name(type_info: &str) -> Option<(&'static str, &str)> {
-    let type_info = type_info.trim();
-
-    if type_info.to_ascii_lowercase().starts_with("list of [") {
-        return Some(("list", "List"));
-    }
-    if type_info
-        .to_ascii_lowercase()
-        .starts_with("dictionary of [")
-    {
-        return Some(("dictionary", "Dictionary"));
-    }
+    let (type_info, in_collection) = strip_type_wrappers(type_info);
 
-    if let Some((object_kind, name)) = builtin_data_type_object_kind(type_info) {
-        return Some((object_kind, name));
+    if !in_collection {
+        if let Some((object_kind, name)) = builtin_data_type_object_kind(type_info) {
+            return Some((object_kind, name));
+        }
     }
 
     let split_idx = type_info.find(char::is_whitespace)?;
@@ -462,6 +580,8 @@ pub fn extract_type_object_name(type_info: &str) -> Option<(&'static str, &str)>
         }
     }
 
+    // Namespace-qualified references resolve by their final segment.
+    let (_, name) = split_namespace_qualifier(name);
     let name = name.trim_matches('"').trim();
     if name.is_empty() {
         return None;
@@ -470,6 +590,96 @@ pub fn extract_type_object_name(type_info: &str) -> Option<(&'static str, &str)>
     Some((object_kind, name))
 }
 
+/// Namespace qualifier of an object type string, if any.
+/// Example: `Record MyCompany.Sales.Customer` -> `MyCompany.Sales`.
+pub fn extract_type_namespace(type_info: &str) -> Option<&str> {
+    let (type_info, _) = strip_type_wrappers(type_info);
+    let split_idx = type_info.find(char::is_whitespace)?;
+    let mut name = type_info[split_idx..].trim();
+    if let Some(without_temporary) = strip_ascii_case_suffix(name, "temporary") {
+        name = without_temporary.trim_end();
+    }
+    split_namespace_qualifier(name).0
+}
+
+/// Kind and name of the type whose methods a value of `type_info` exposes:
+/// `List` and `Dictionary` for collections, otherwise the same as
+/// [`extract_type_object_name`].
+pub fn extract_value_type_name(type_info: &str) -> Option<(&'static str, &str)> {
+    let lower = type_info.trim().to_ascii_lowercase();
+    if lower.starts_with("list of [") {
+        return Some(("list", "List"));
+    }
+    if lower.starts_with("dictionary of [") {
+        return Some(("dictionary", "Dictionary"));
+    }
+    extract_type_object_name(type_info)
+}
+
+/// Innermost element type of `type_info`, and whether it was found inside a
+/// `List` or `Dictionary` (whose value type is the element).
+fn strip_type_wrappers(type_info: &str) -> (&str, bool) {
+    let mut type_info = type_info.trim();
+    let mut in_collection = false;
+    loop {
+        if let Some(rest) = strip_ascii_case_keyword(type_info, "temporary") {
+            type_info = rest;
+        } else if let Some(rest) = strip_ascii_case_prefix(type_info, "array[") {
+            let Some(close) = rest.find(']') else {
+                return (type_info, in_collection);
+            };
+            match strip_ascii_case_keyword(rest[close + 1..].trim_start(), "of") {
+                Some(element) => type_info = element,
+                None => return (type_info, in_collection),
+            }
+        } else if let Some(elements) = strip_ascii_case_prefix(type_info, "list of [")
+            .or_else(|| strip_ascii_case_prefix(type_info, "dictionary of ["))
+        {
+            let Some(elements) = elements.strip_suffix(']') else {
+                return (type_info, in_collection);
+            };
+            let value_type = match top_level_comma(elements) {
+                Some(comma) => &elements[comma + 1..],
+                None => elements,
+            };
+            type_info = value_type.trim();
+            in_collection = true;
+        } else {
+            return (type_info, in_collection);
+        }
+    }
+}
+
+fn strip_ascii_case_prefix<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &value[prefix.len()..])
+}
+
+/// `value` without a leading `keyword` that is followed by whitespace.
+fn strip_ascii_case_keyword<'a>(value: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = strip_ascii_case_prefix(value, keyword)?;
+    rest.starts_with(char::is_whitespace)
+        .then(|| rest.trim_start())
+}
+
+/// Byte index of the last comma in `value` that is not nested in brackets or quotes.
+fn top_level_comma(value: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    let mut comma = None;
+    for (idx, ch) in value.char_indices() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => depth += 1,
+            ']' if !in_quotes => depth = depth.saturating_sub(1),
+            ',' if !in_quotes && depth == 0 => comma = Some(idx),
+            _ => {}
+        }
+    }
+    comma
+}
+
 fn builtin_data_type_object_kind(type_info: &str) -> Option<(&'static str, &str)> {
     let head = type_info
         .split_whitespace()
@@ -584,14 +794,91 @@ fn builtin_data_type_object_kind(type_info: &str) -> Option<(&'static str, &str)
     Some((object_kind, base))
 }
 
+/// Collect the selection ranges enclosing `byte_offset`, innermost first.
+/// Ranges snap to AL units: identifier → member access chain → call →
+/// statement → block → procedure/trigger → section → object.
+pub fn collect_selection_ranges(
+    tree: &Tree,
+    byte_offset: usize,
+) -> Vec<(tree_sitter::Point, tree_sitter::Point)> {
+    let mut ranges: Vec<(tree_sitter::Point, tree_sitter::Point)> = Vec::new();
+    let mut current = node_at_offset(tree, byte_offset);
+    while let Some(node) = current {
+        if is_selection_unit(node.kind()) {
+            let range = (node.start_position(), node.end_position());
+            if ranges.last() != Some(&range) {
+                ranges.push(range);
+            }
+        }
+        current = node.parent();
+    }
+    ranges
+}
+
+fn is_selection_unit(kind: &str) -> bool {
+    matches!(
+        kind,
+        "identifier"
+            | "quoted_identifier"
+            | "string_literal"
+            | "integer_literal"
+            | "decimal_literal"
+            | "member_access"
+            | "method_call"
+            | "function_call"
+            | "argument_list"
+            | "qualified_enum_value"
+            | "parenthesized_expression"
+            | "block"
+            | "var_section"
+            | "variable_declaration"
+            | "parameter"
+            | "parameter_list"
+            | "property"
+            | "source_file"
+    ) || kind.ends_with("_statement")
+        || kind.ends_with("_declaration")
+        || kind.ends_with("_section")
+}
+
 /// Collect folding ranges from structural nodes in the parse tree.
-/// Returns ranges for objects, procedures, blocks, sections, and multi-line comments.
-pub fn collect_folding_ranges(tree: &Tree) -> Vec<FoldingArea> {
+/// Returns ranges for objects, procedures, blocks, sections, multi-line comments,
+/// and `#region`/`#endregion` pairs.
+pub fn collect_folding_ranges(tree: &Tree, source: &str) -> Vec<FoldingArea> {
     let mut ranges = Vec::new();
     collect_folding_ranges_recursive(tree.root_node(), &mut ranges);
+    collect_region_folding_ranges(source, &mut ranges);
     ranges
 }
 
+/// Pair `#region NAME` lines with their `#endregion`. Directives are matched on
+/// raw source lines since the parser does not model them; unmatched lines are ignored.
+fn collect_region_folding_ranges(source: &str, ranges: &mut Vec<FoldingArea>) {
+    let mut open: Vec<(usize, Option<String>)> = Vec::new();
+    for (line_idx, line) in source.lines().enumerate() {
+        let Some(rest) = line.trim().strip_prefix('#') else {
+            continue;
+        };
+        let word_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (word, name) = rest.split_at(word_len);
+        if word.eq_ignore_ascii_case("region") {
+            let name = name.trim();
+            open.push((line_idx, (!name.is_empty()).then(|| name.to_string())));
+        } else if word.eq_ignore_ascii_case("endregion") {
+            if let Some((start_line, name)) = open.pop() {
+                ranges.push(FoldingArea {
+                    start_line,
+                    end_line: line_idx,
+                    kind: FoldingAreaKind::Region,
+                    collapsed_text: name,
+                });
+            }
+        }
+    }
+}
+
 fn collect_folding_ranges_recursive(node: Node, ranges: &mut Vec<FoldingArea>) {
     let kind = node.kind();
 
@@ -615,11 +902,15 @@ fn collect_folding_ranges_recursive(node: Node, ranges: &mut Vec<FoldingArea>) {
             | "var_section"
             | "fields_section"
             | "keys_section"
+            | "requestpage_section"
+            | "layout_section"
+            | "actions_section"
             | "if_statement"
             | "for_statement"
             | "while_statement"
             | "repeat_statement"
             | "case_statement"
+            | "case_branch"
             | "with_statement"
     );
 
@@ -637,6 +928,7 @@ fn collect_folding_ranges_recursive(node: Node, ranges: &mut Vec<FoldingArea>) {
                 } else {
                     FoldingAreaKind::Region
                 },
+                collapsed_text: None,
             });
         }
     }
@@ -905,8 +1197,103 @@ fn collect_codeunit_method_calls(
     }
 }
 
+/// Kind of object a method call site resolves to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallSiteKind {
+    Interface,
+    Codeunit,
+}
+
+/// A qualified or unqualified procedure call resolved to its target object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodCallSite {
+    pub kind: CallSiteKind,
+    /// Lowercased name of the interface or codeunit.
+    pub object_name: String,
+    /// Lowercased name of the called method.
+    pub method_name: String,
+    pub start: tree_sitter::Point,
+    pub end: tree_sitter::Point,
+}
+
+/// Collect every call site `find_interface_method_calls` and
+/// `find_codeunit_method_calls` would report for any interface/codeunit
+/// method, in a single walk of the tree. Used to build workspace call indexes.
+pub fn collect_method_call_sites(
+    tree: &Tree,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+) -> Vec<MethodCallSite> {
+    let mut results = Vec::new();
+    collect_all_method_call_sites(tree.root_node(), source, symbol_table, &mut results);
+    results
+}
+
+fn collect_all_method_call_sites(
+    node: Node,
+    source: &str,
+    symbol_table: &DocumentSymbolTable,
+    results: &mut Vec<MethodCallSite>,
+) {
+    if matches!(node.kind(), "method_call" | "member_access") {
+        let method_field = if node.kind() == "method_call" {
+            "method"
+        } else {
+            "member"
+        };
+        if let (Some(method_node), Some(object_node)) = (
+            node.child_by_field_name(method_field),
+            node.child_by_field_name("object"),
+        ) {
+            let target =
+                resolve_object_type_info(symbol_table, source, object_node, node.start_byte())
+                    .and_then(|type_info| {
+                        interface_type_name(type_info)
+                            .map(|name| (CallSiteKind::Interface, name))
+                            .or_else(|| {
+                                extract_codeunit_type_name(type_info)
+                                    .map(|name| (CallSiteKind::Codeunit, name.to_lowercase()))
+                            })
+                    });
+            if let Some((kind, object_name)) = target {
+                results.push(MethodCallSite {
+                    kind,
+                    object_name,
+                    method_name: extract_name(method_node, source).to_lowercase(),
+                    start: method_node.start_position(),
+                    end: method_node.end_position(),
+                });
+            }
+        }
+    }
+
+    if node.kind() == "function_call" {
+        if let Some(name_node) = node.child_by_field_name("name") {
+            if let Some(object_name) = enclosing_object_name(node, source) {
+                results.push(MethodCallSite {
+                    kind: CallSiteKind::Codeunit,
+                    object_name,
+                    method_name: extract_name(name_node, source).to_lowercase(),
+                    start: name_node.start_position(),
+                    end: name_node.end_position(),
+                });
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_all_method_call_sites(child, source, symbol_table, results);
+    }
+}
+
 /// Check if a node is inside an object (codeunit, table, etc.) with the given name.
 fn is_inside_object(node: Node, source: &str, object_name_lower: &str) -> bool {
+    enclosing_object_name(node, source).is_some_and(|name| name == object_name_lower)
+}
+
+/// Lowercased name of the innermost object declaration containing `node`.
+fn enclosing_object_name(node: Node, source: &str) -> Option<String> {
     let mut current = Some(node);
     while let Some(n) = current {
         let kind = n.kind();
@@ -919,23 +1306,21 @@ fn is_inside_object(node: Node, source: &str, object_name_lower: &str) -> bool {
             && kind != "key_declaration"
             && kind != "enum_value_declaration"
         {
-            // This is an object declaration — check its name
+            // This is an object declaration — return its name
             if let Some(name_node) = n.child_by_field_name("name") {
-                let name = extract_name(name_node, source);
-                return name.to_lowercase() == object_name_lower;
+                return Some(extract_name(name_node, source).to_lowercase());
             }
             // For objects with integer + name pattern, find the identifier child
             let mut cursor = n.walk();
             for child in n.named_children(&mut cursor) {
                 if child.kind() == "identifier" || child.kind() == "quoted_identifier" {
-                    let name = extract_name(child, source);
-                    return name.to_lowercase() == object_name_lower;
+                    return Some(extract_name(child, source).to_lowercase());
                 }
             }
         }
         current = n.parent();
     }
-    false
+    None
 }
 
 /// Check if a type_info string refers to a specific codeunit (case-insensitive).
@@ -949,12 +1334,14 @@ fn is_codeunit_type(type_info: &str, cu_name_lower: &str) -> bool {
 /// Check if a type_info string refers to a specific interface (case-insensitive).
 /// Matches "Interface IFoo" against interface name "IFoo".
 fn is_interface_type(type_info: &str, iface_name_lower: &str) -> bool {
+    interface_type_name(type_info).is_some_and(|name| name == iface_name_lower)
+}
+
+/// Lowercased interface name of a type_info string like "Interface IFoo".
+fn interface_type_name(type_info: &str) -> Option<String> {
     let lower = type_info.trim().to_lowercase();
-    if let Some(rest) = lower.strip_prefix("interface ") {
-        let name = rest.trim().trim_matches('"');
-        return name == iface_name_lower;
-    }
-    false
+    let rest = lower.strip_prefix("interface ")?;
+    Some(rest.trim().trim_matches('"').to_string())
 }
 
 fn resolve_object_type_info<'a>(
@@ -1150,6 +1537,59 @@ mod tests {
         assert_eq!(r.name, "Hello");
     }
 
+    #[test]
+    fn test_resolve_call_prefers_procedure_of_enclosing_object() {
+        let source = r#"codeunit 50101 "Codeunit B"
+{
+    procedure DoWork()
+    begin
+    end;
+
+    procedure OnlyInB()
+    begin
+    end;
+}
+
+codeunit 50100 "Codeunit A"
+{
+    procedure Run()
+    var
+        DoWork: Integer;
+    begin
+    end;
+
+    procedure Start()
+    begin
+        DoWork();
+        OnlyInB();
+    end;
+
+    procedure DoWork()
+    begin
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+        let a_start = source.find("codeunit 50100").unwrap();
+
+        let call_offset = source.find("DoWork();").unwrap();
+        let resolved =
+            resolve_at_offset(&tree, source, &table, call_offset).expect("should resolve DoWork");
+        assert_eq!(resolved.symbol.kind, AlSymbolKind::Procedure);
+        assert_eq!(
+            resolved.symbol.start_byte,
+            source.rfind("procedure DoWork").unwrap()
+        );
+        assert!(resolved.symbol.start_byte > a_start);
+
+        // Nothing in A declares it, so the call falls back to B's procedure.
+        let call_offset = source.find("OnlyInB();").unwrap();
+        let resolved =
+            resolve_at_offset(&tree, source, &table, call_offset).expect("should resolve OnlyInB");
+        assert!(resolved.symbol.start_byte < a_start);
+    }
+
     #[test]
     fn test_no_resolve_on_keyword() {
         let source = r#"codeunit 50100 Test
@@ -1256,6 +1696,32 @@ mod tests {
         assert_eq!(refs_no_decl.len(), refs.len() - 1);
     }
 
+    #[test]
+    fn test_extract_type_object_name_through_wrappers() {
+        assert_eq!(
+            extract_type_object_name("temporary Record Customer"),
+            Some(("table", "Customer"))
+        );
+        assert_eq!(
+            extract_type_object_name("array[5] of Codeunit X"),
+            Some(("codeunit", "X"))
+        );
+        assert_eq!(
+            extract_type_object_name("array[10] of Record \"Sales Line\" temporary"),
+            Some(("table", "Sales Line"))
+        );
+        assert_eq!(
+            extract_type_object_name("Dictionary of [Code[20], Interface IFoo]"),
+            Some(("interface", "IFoo"))
+        );
+        assert_eq!(extract_type_object_name("List of [Text]"), None);
+        assert_eq!(extract_type_object_name("Dictionary of [Text, Text]"), None);
+        assert_eq!(
+            extract_type_namespace("array[2] of Record MyCompany.Sales.Customer"),
+            Some("MyCompany.Sales")
+        );
+    }
+
     #[test]
     fn test_extract_type_object_name() {
         assert_eq!(
@@ -1275,11 +1741,11 @@ mod tests {
             Some(("table", "Customer"))
         );
         assert_eq!(
-            extract_type_object_name("Dictionary of [Text, Text]"),
+            extract_value_type_name("Dictionary of [Text, Text]"),
             Some(("dictionary", "Dictionary"))
         );
         assert_eq!(
-            extract_type_object_name("List of [Text]"),
+            extract_value_type_name("List of [Text]"),
             Some(("list", "List"))
         );
         assert_eq!(
@@ -1287,6 +1753,19 @@ mod tests {
             Some(("text", "Text"))
         );
         assert_eq!(extract_type_object_name("Code[20]"), Some(("code", "Code")));
+        assert_eq!(
+            extract_type_object_name("Record MyCompany.Sales.Customer"),
+            Some(("table", "Customer"))
+        );
+        assert_eq!(
+            extract_type_object_name("Codeunit MyCompany.\"Sales Mgt.\""),
+            Some(("codeunit", "Sales Mgt."))
+        );
+        assert_eq!(
+            extract_type_namespace("Record MyCompany.Sales.Customer temporary"),
+            Some("MyCompany.Sales")
+        );
+        assert_eq!(extract_type_namespace("Record Customer"), None);
         assert_eq!(
             extract_type_object_name("ControlAddIn \"Demo AddIn\""),
             Some(("controladdin", "Demo AddIn"))
@@ -1487,7 +1966,7 @@ mod tests {
     end;
 }"#;
         let tree = al_parser::parse(source).unwrap();
-        let ranges = collect_folding_ranges(&tree);
+        let ranges = collect_folding_ranges(&tree, source);
         // At minimum: the codeunit and the two procedures
         assert!(
             ranges.len() >= 3,
@@ -1497,6 +1976,115 @@ mod tests {
         assert!(ranges.iter().all(|r| r.end_line > r.start_line));
     }
 
+    #[test]
+    fn test_collect_folding_ranges_report_request_page() {
+        let source = r#"report 50100 "Customer List"
+{
+    requestpage
+    {
+        layout
+        {
+            area(Content)
+            {
+                field(StartDate; StartDate)
+                {
+                }
+            }
+        }
+
+        trigger OnOpenPage()
+        begin
+        end;
+    }
+
+    var
+        StartDate: Date;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let ranges = collect_folding_ranges(&tree, source);
+        let lines: Vec<_> = ranges.iter().map(|r| (r.start_line, r.end_line)).collect();
+        assert!(
+            lines.contains(&(2, 18)),
+            "expected request page fold, got {lines:?}"
+        );
+        assert!(
+            lines.contains(&(4, 12)),
+            "expected layout fold, got {lines:?}"
+        );
+        assert!(
+            lines.contains(&(14, 16)),
+            "expected trigger fold, got {lines:?}"
+        );
+    }
+
+    #[test]
+    fn test_collect_folding_ranges_case_branches() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Run(X: Integer)
+    begin
+        case X of
+            1, 2, 3:
+                Message('low');
+            4:
+                begin
+                    Message('four');
+                end;
+            5: Message('five');
+        end;
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let lines: Vec<_> = collect_folding_ranges(&tree, source)
+            .iter()
+            .map(|r| (r.start_line, r.end_line))
+            .collect();
+        assert!(
+            lines.contains(&(4, 12)),
+            "expected case fold, got {lines:?}"
+        );
+        assert!(
+            lines.contains(&(5, 6)),
+            "expected multi-value branch fold, got {lines:?}"
+        );
+        assert!(
+            lines.contains(&(7, 10)),
+            "expected block branch fold, got {lines:?}"
+        );
+        assert!(!lines.iter().any(|&(start, _)| start == 11));
+    }
+
+    #[test]
+    fn test_collect_folding_ranges_nested_regions() {
+        let source = r#"codeunit 50100 Test
+{
+    #region Public API
+    procedure Hello()
+    begin
+    end;
+
+    #region Helpers
+    procedure World()
+    begin
+    end;
+    #endregion
+    #endregion
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let regions: Vec<_> = collect_folding_ranges(&tree, source)
+            .into_iter()
+            .filter(|r| r.collapsed_text.is_some())
+            .map(|r| (r.start_line, r.end_line, r.collapsed_text.unwrap()))
+            .collect();
+        assert_eq!(
+            regions,
+            vec![
+                (7, 11, "Helpers".to_string()),
+                (2, 12, "Public API".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_interface_method_call_at_offset_without_parentheses() {
         let source = r#"interface IAddressProvider
@@ -1614,4 +2202,46 @@ codeunit 50100 Test
         );
         assert_eq!(calls.len(), 1, "expected one codeunit call, got {calls:?}");
     }
+
+    #[test]
+    fn test_selection_ranges_expand_member_access_chain() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure DoWork()
+    var
+        Rec: Record Customer;
+    begin
+        if true then begin
+            X := Rec.Field.SubField;
+        end;
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let offset = source.find("Field.SubField").unwrap() + 1;
+        let texts: Vec<String> = collect_selection_ranges(&tree, offset)
+            .into_iter()
+            .map(|(start, end)| {
+                let line_start = |p: tree_sitter::Point| {
+                    source
+                        .split_inclusive('\n')
+                        .take(p.row)
+                        .map(str::len)
+                        .sum::<usize>()
+                        + p.column
+                };
+                source[line_start(start)..line_start(end)].to_string()
+            })
+            .collect();
+
+        assert_eq!(texts[0], "Field");
+        assert_eq!(texts[1], "Rec.Field");
+        assert_eq!(texts[2], "Rec.Field.SubField");
+        assert_eq!(texts[3], "X := Rec.Field.SubField");
+        assert!(texts[4].starts_with("begin") && texts[4].ends_with("end;"));
+        assert!(texts[5].starts_with("if true then"));
+        assert!(texts
+            .iter()
+            .any(|t| t.starts_with("procedure DoWork()") && t.ends_with("end;")));
+        assert!(texts.iter().any(|t| t.starts_with("codeunit 50100 Test")));
+    }
 }
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::ast::{AlObjectKind, AlSymbol, AlSymbolKind};
+use crate::ast::{parse_sized_type, AlObjectKind, AlSymbol, AlSymbolKind, NamespaceInfo};
 
 /// A case-insensitive index of symbols within a document.
 #[derive(Debug, Default)]
@@ -9,6 +9,8 @@ pub struct DocumentSymbolTable {
     pub symbols: Vec<AlSymbol>,
     /// Flat index: lowercase name → list of symbol references.
     index: HashMap<String, Vec<SymbolRef>>,
+    /// The document's `namespace` declaration and `using` directives.
+    pub namespaces: NamespaceInfo,
 }
 
 /// A reference into the symbol tree.
@@ -27,7 +29,17 @@ impl DocumentSymbolTable {
         for (obj_idx, sym) in symbols.iter().enumerate() {
             insert_into_index(&mut index, sym, obj_idx, &[]);
         }
-        DocumentSymbolTable { symbols, index }
+        DocumentSymbolTable {
+            symbols,
+            index,
+            namespaces: NamespaceInfo::default(),
+        }
+    }
+
+    /// Attach the document's namespace declaration and `using` directives.
+    pub fn with_namespaces(mut self, namespaces: NamespaceInfo) -> Self {
+        self.namespaces = namespaces;
+        self
     }
 
     /// Lookup symbols by name (case-insensitive).
@@ -71,6 +83,12 @@ impl DocumentSymbolTable {
                 continue;
             };
             if Some(sym_ref.object_idx) == scope_object_idx {
+                // Parameters and locals of the object's other procedures are
+                // out of scope, so a call like `DoWork()` must not resolve to
+                // a variable of that name declared elsewhere in the object.
+                if self.is_local_of_other_body(sym_ref, scope_byte) {
+                    continue;
+                }
                 if let Some(child_idx) = scope_child_idx {
                     if sym_ref.child_path.len() >= 2 && sym_ref.child_path[0] == child_idx {
                         local_results.push(symbol);
@@ -94,6 +112,66 @@ impl DocumentSymbolTable {
         global_results
     }
 
+    /// Replace the object at `object_idx` after an edit inside it, re-indexing
+    /// only its names. Objects after it move by `byte_delta` bytes and
+    /// `row_delta` lines; they must start on a line after the edit, so no
+    /// column changes.
+    pub fn replace_object(
+        &mut self,
+        object_idx: usize,
+        symbol: AlSymbol,
+        byte_delta: isize,
+        row_delta: isize,
+    ) {
+        let mut keys = HashSet::new();
+        collect_index_keys(&self.symbols[object_idx], &mut keys);
+        collect_index_keys(&symbol, &mut keys);
+
+        self.symbols[object_idx] = symbol;
+        for later in &mut self.symbols[object_idx + 1..] {
+            shift_symbol(later, byte_delta, row_delta);
+        }
+
+        for key in &keys {
+            if let Some(refs) = self.index.get_mut(key) {
+                refs.retain(|r| r.object_idx != object_idx);
+            }
+        }
+        insert_into_index(&mut self.index, &self.symbols[object_idx], object_idx, &[]);
+        // Keep each name's references in tree order, as a full build does
+        for key in &keys {
+            if let Some(refs) = self.index.get_mut(key) {
+                refs.sort_by(|a, b| {
+                    (a.object_idx, &a.child_path).cmp(&(b.object_idx, &b.child_path))
+                });
+                if refs.is_empty() {
+                    self.index.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Whether `sym_ref` is declared inside a procedure or trigger that does
+    /// not contain `scope_byte`.
+    fn is_local_of_other_body(&self, sym_ref: &SymbolRef, scope_byte: usize) -> bool {
+        let Some(mut current) = self.symbols.get(sym_ref.object_idx) else {
+            return false;
+        };
+        let ancestors = sym_ref.child_path.len().saturating_sub(1);
+        for &idx in &sym_ref.child_path[..ancestors] {
+            let Some(child) = current.children.get(idx) else {
+                return false;
+            };
+            if matches!(child.kind, AlSymbolKind::Procedure | AlSymbolKind::Trigger)
+                && !(child.start_byte <= scope_byte && scope_byte <= child.end_byte)
+            {
+                return true;
+            }
+            current = child;
+        }
+        false
+    }
+
     fn resolve_ref(&self, sym_ref: &SymbolRef) -> Option<&AlSymbol> {
         let mut current = self.symbols.get(sym_ref.object_idx)?;
         for &idx in &sym_ref.child_path {
@@ -104,8 +182,10 @@ impl DocumentSymbolTable {
 
     /// Get all symbols reachable from a given byte offset.
     /// Returns locals (if inside a procedure) → object-level members → top-level objects.
+    /// Locals of other procedures are never included, and of symbols sharing a
+    /// name and kind only the innermost is kept.
     pub fn reachable_symbols(&self, byte_offset: usize) -> Vec<&AlSymbol> {
-        let mut result = Vec::new();
+        let mut result: Vec<&AlSymbol> = Vec::new();
 
         if let Some(object) = self
             .symbols
@@ -123,9 +203,20 @@ impl DocumentSymbolTable {
         }
 
         result.extend(self.symbols.iter());
+
+        let mut seen = HashSet::new();
+        result.retain(|sym| seen.insert((sym.name.to_lowercase(), sym.kind)));
         result
     }
 
+    /// Top-level objects of `kind`, in declaration order.
+    pub fn objects_of_kind(&self, kind: AlObjectKind) -> Vec<&AlSymbol> {
+        self.symbols
+            .iter()
+            .filter(|sym| sym.kind == AlSymbolKind::Object(kind))
+            .collect()
+    }
+
     /// Find a top-level object by name (case-insensitive).
     pub fn find_object_by_name(&self, name: &str) -> Option<&AlSymbol> {
         let lower = name.to_lowercase();
@@ -137,10 +228,7 @@ impl DocumentSymbolTable {
     /// If the symbol at `byte_offset` is a procedure that belongs to an interface object,
     /// returns `Some((interface_name, procedure_name))`.
     pub fn interface_method_at(&self, byte_offset: usize) -> Option<(&str, &str)> {
-        for sym in &self.symbols {
-            if !matches!(sym.kind, AlSymbolKind::Object(AlObjectKind::Interface)) {
-                continue;
-            }
+        for sym in self.objects_of_kind(AlObjectKind::Interface) {
             if sym.start_byte <= byte_offset && byte_offset <= sym.end_byte {
                 for child in &sym.children {
                     if matches!(child.kind, AlSymbolKind::Procedure)
@@ -266,10 +354,7 @@ impl DocumentSymbolTable {
         let iface_lower = interface_name.to_lowercase();
         let method_lower = method_name.to_lowercase();
 
-        for sym in &self.symbols {
-            if !matches!(sym.kind, AlSymbolKind::Object(AlObjectKind::Interface)) {
-                continue;
-            }
+        for sym in self.objects_of_kind(AlObjectKind::Interface) {
             if sym.name.to_lowercase() != iface_lower {
                 continue;
             }
@@ -285,6 +370,28 @@ impl DocumentSymbolTable {
     }
 }
 
+fn collect_index_keys(sym: &AlSymbol, keys: &mut HashSet<String>) {
+    keys.insert(sym.name.to_lowercase());
+    for child in &sym.children {
+        collect_index_keys(child, keys);
+    }
+}
+
+fn shift_symbol(sym: &mut AlSymbol, byte_delta: isize, row_delta: isize) {
+    let shift_row = |point: &mut tree_sitter::Point| {
+        point.row = point.row.saturating_add_signed(row_delta);
+    };
+    sym.start_byte = sym.start_byte.saturating_add_signed(byte_delta);
+    sym.end_byte = sym.end_byte.saturating_add_signed(byte_delta);
+    shift_row(&mut sym.start_point);
+    shift_row(&mut sym.end_point);
+    shift_row(&mut sym.name_start_point);
+    shift_row(&mut sym.name_end_point);
+    for child in &mut sym.children {
+        shift_symbol(child, byte_delta, row_delta);
+    }
+}
+
 fn insert_into_index(
     index: &mut HashMap<String, Vec<SymbolRef>>,
     sym: &AlSymbol,
@@ -359,10 +466,24 @@ pub fn al_keywords() -> &'static [&'static str] {
     ]
 }
 
-/// Format symbol info as Markdown for hover display.
+/// Display form of a symbol's `type_info`; sized types are normalized to
+/// `Base[Length]` (e.g. `Code [20]` becomes `Code[20]`).
+pub fn format_type_info(type_info: &str) -> String {
+    match parse_sized_type(type_info) {
+        Some((base, length)) => format!("{base}[{length}]"),
+        None => type_info.to_string(),
+    }
+}
+
+/// Format symbol info as Markdown for hover display. Procedures include their
+/// parameter list, e.g. `(procedure) Add(A: Integer; B: Integer): Integer`, and
+/// objects their ID, e.g. `(codeunit 50100) Test`.
 pub fn format_hover(sym: &AlSymbol) -> String {
     let kind_label = match &sym.kind {
-        AlSymbolKind::Object(ok) => ok.label().to_string(),
+        AlSymbolKind::Object(ok) => {
+            let id = sym.object_id.map(|id| format!(" {id}")).unwrap_or_default();
+            return format!("```al\n({}{id}) {}\n```", ok.label(), sym.name);
+        }
         AlSymbolKind::Procedure => "procedure".to_string(),
         AlSymbolKind::Trigger => "trigger".to_string(),
         AlSymbolKind::Variable => "variable".to_string(),
@@ -375,10 +496,27 @@ pub fn format_hover(sym: &AlSymbol) -> String {
     let type_str = sym
         .type_info
         .as_deref()
-        .map(|t| format!(": {t}"))
+        .map(|t| format!(": {}", format_type_info(t)))
         .unwrap_or_default();
 
-    format!("```al\n({kind_label}) {}{type_str}\n```", sym.name)
+    let params_str = if matches!(sym.kind, AlSymbolKind::Procedure) {
+        let params = sym
+            .parameters()
+            .map(|p| match p.type_info.as_deref() {
+                Some(t) => format!("{}: {}", p.name, format_type_info(t)),
+                None => p.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        format!("({params})")
+    } else {
+        String::new()
+    };
+
+    format!(
+        "```al\n({kind_label}) {}{params_str}{type_str}\n```",
+        sym.name
+    )
 }
 
 #[cfg(test)]
@@ -386,6 +524,116 @@ mod tests {
     use super::*;
     use crate::ast::extract_symbols;
 
+    #[test]
+    fn test_objects_of_kind_lists_every_matching_object() {
+        let source = r#"interface "Payment Method"
+{
+    procedure Pay();
+}
+
+codeunit 50100 "Cash Payment" implements "Payment Method"
+{
+    procedure Pay()
+    begin
+    end;
+}
+
+interface Shipping
+{
+    procedure Ship();
+}
+
+codeunit 50101 "Card Payment" implements "Payment Method"
+{
+    procedure Pay()
+    begin
+    end;
+}
+
+table 50100 Shipment
+{
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let table = DocumentSymbolTable::new(extract_symbols(&tree, source));
+        let names = |kind| {
+            table
+                .objects_of_kind(kind)
+                .into_iter()
+                .map(|sym| sym.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(AlObjectKind::Interface),
+            vec!["Payment Method", "Shipping"]
+        );
+        assert_eq!(
+            names(AlObjectKind::Codeunit),
+            vec!["Cash Payment", "Card Payment"]
+        );
+        assert_eq!(names(AlObjectKind::Table), vec!["Shipment"]);
+        assert!(names(AlObjectKind::Page).is_empty());
+    }
+
+    #[test]
+    fn test_format_hover_normalizes_sized_type() {
+        let source = r#"codeunit 50100 Test
+{
+    var
+        CustomerNo: Code [20];
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+        let variable = table.lookup("CustomerNo")[0];
+        assert_eq!(variable.sized_type(), Some(("Code", 20)));
+        assert_eq!(
+            format_hover(variable),
+            "```al\n(variable) CustomerNo: Code[20]\n```"
+        );
+    }
+
+    #[test]
+    fn test_format_hover_procedure_signature() {
+        let source = r#"codeunit 50100 Test
+{
+    procedure Add(A: Integer; B: Code[20]): Decimal
+    var
+        X: Integer;
+    begin
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+        assert_eq!(
+            format_hover(table.lookup("Add")[0]),
+            "```al\n(procedure) Add(A: Integer; B: Code[20]): Decimal\n```"
+        );
+        assert_eq!(
+            format_hover(table.lookup("X")[0]),
+            "```al\n(variable) X: Integer\n```"
+        );
+    }
+
+    #[test]
+    fn test_format_hover_object_id() {
+        let source = r#"codeunit 50100 Test
+{
+}
+
+interface IGreeter
+{
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let table = DocumentSymbolTable::new(extract_symbols(&tree, source));
+        let codeunit = table.lookup("Test")[0];
+        assert_eq!(codeunit.object_id, Some(50100));
+        assert_eq!(format_hover(codeunit), "```al\n(codeunit 50100) Test\n```");
+        let interface = table.lookup("IGreeter")[0];
+        assert_eq!(interface.object_id, None);
+        assert_eq!(format_hover(interface), "```al\n(interface) IGreeter\n```");
+    }
+
     #[test]
     fn test_case_insensitive_lookup() {
         let source = r#"codeunit 50100 Test
@@ -432,6 +680,47 @@ mod tests {
         assert!(names.contains(&"Test"), "should contain Test object");
     }
 
+    #[test]
+    fn test_reachable_symbols_only_include_enclosing_procedure_locals() {
+        let source = r#"codeunit 50100 Test
+{
+    var
+        Counter: Integer;
+
+    procedure First()
+    var
+        Temp: Integer;
+        Counter: Integer;
+    begin
+    end;
+
+    procedure Second()
+    var
+        Temp: Text;
+    begin
+    end;
+}"#;
+        let tree = al_parser::parse(source).unwrap();
+        let symbols = extract_symbols(&tree, source);
+        let table = DocumentSymbolTable::new(symbols);
+
+        let first = &table.symbols[0].children[1];
+        let reachable = table.reachable_symbols(first.end_byte - 1);
+        let temps: Vec<_> = reachable.iter().filter(|s| s.name == "Temp").collect();
+        assert_eq!(temps.len(), 1);
+        assert_eq!(temps[0].type_info.as_deref(), Some("Integer"));
+        // The local shadows the global of the same name
+        let counters: Vec<_> = reachable.iter().filter(|s| s.name == "Counter").collect();
+        assert_eq!(counters.len(), 1);
+        assert!(counters[0].start_byte > first.start_byte);
+
+        let second = &table.symbols[0].children[2];
+        let reachable = table.reachable_symbols(second.end_byte - 1);
+        let temps: Vec<_> = reachable.iter().filter(|s| s.name == "Temp").collect();
+        assert_eq!(temps.len(), 1);
+        assert_eq!(temps[0].type_info.as_deref(), Some("Text"));
+    }
+
     #[test]
     fn test_find_object_by_name() {
         let source = r#"codeunit 50100 "My Codeunit"
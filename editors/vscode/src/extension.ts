@@ -1,10 +1,12 @@
 import * as path from "path";
-import { workspace, ExtensionContext } from "vscode";
+import { commands, workspace, ExtensionContext, Uri } from "vscode";
 import {
   LanguageClient,
   LanguageClientOptions,
   ServerOptions,
   Executable,
+  Location,
+  Position,
 } from "vscode-languageclient/node";
 
 let client: LanguageClient;
@@ -28,6 +30,13 @@ export function activate(context: ExtensionContext) {
     synchronize: {
       fileEvents: workspace.createFileSystemWatcher("**/*.al"),
     },
+    initializationOptions: {
+      treatWarningsAsErrors: config.get<boolean>("treatWarningsAsErrors") ?? false,
+      normalizeKeywordCase: config.get<boolean>("normalizeKeywordCase") ?? false,
+      beginStyle: config.get<string>("beginStyle") ?? "sameLine",
+      blankLineBeforeBegin: config.get<boolean>("blankLineBeforeBegin") ?? false,
+      maxBlankLines: config.get<number>("maxBlankLines") ?? 1,
+    },
   };
 
   client = new LanguageClient(
@@ -37,6 +46,21 @@ export function activate(context: ExtensionContext) {
     clientOptions
   );
 
+  // Code lens reference counts carry LSP-typed arguments; convert them
+  // before handing off to the built-in references peek view.
+  context.subscriptions.push(
+    commands.registerCommand(
+      "al-lsp.showReferences",
+      (uri: string, position: Position, locations: Location[]) =>
+        commands.executeCommand(
+          "editor.action.showReferences",
+          Uri.parse(uri),
+          client.protocol2CodeConverter.asPosition(position),
+          locations.map(client.protocol2CodeConverter.asLocation)
+        )
+    )
+  );
+
   client.start();
 }
 